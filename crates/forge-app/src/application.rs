@@ -13,11 +13,12 @@ use crate::modes::UiMode;
 use crate::organism::{self, SharedOrganismState};
 use crate::rect_renderer::RectRenderer;
 use crate::scrollbar::Scrollbar;
-use crate::status_bar::StatusBar;
+use crate::status_bar::{StatusAction, StatusBar};
 use crate::tab_bar::TabBar;
 use crate::tab_manager::TabManager;
 use crate::ui::{LayoutConstants, LayoutZones};
 
+use forge_core::motion::{Motion, Scope, TextObject, Unit};
 use forge_agent::agent::{Agent, AgentRequest, AgentResponse, AgentStatus, EditorContext};
 use forge_lsp::{LspClient, LspServer};
 use std::sync::Arc;
@@ -53,6 +54,11 @@ const BG_COLOR: wgpu::Color = wgpu::Color {
     a: 1.0,
 };
 
+/// Shown instead of undo tree navigation in a shared tab, where each
+/// collaborator's undo steps are kept by the session
+const SHARED_UNDO_NOTICE: &str =
+    "The undo tree isn't available while sharing; Undo and Redo only revert your own edits";
+
 // ─── Performance ───
 
 pub struct FrameTimer {
@@ -125,6 +131,8 @@ impl Default for RenderBatch {
 #[derive(Debug)]
 pub enum UserEvent {
     AccessKitEvent(accesskit_winit::Event),
+    /// The relay sent something for the collaboration session
+    Collab,
 }
 
 impl From<accesskit_winit::Event> for UserEvent {
@@ -147,6 +155,8 @@ pub struct Application {
     find_bar: crate::find_bar::FindBar,
     replace_bar: crate::replace_bar::ReplaceBar,
     go_to_line: crate::go_to_line::GoToLine,
+    undo_tree_panel: crate::undo_tree_panel::UndoTreePanel,
+    recovery_dialog: crate::recovery_dialog::RecoveryDialog,
     command_palette: crate::command_palette::CommandPalette,
     bottom_panel: crate::bottom_panel::BottomPanel,
     notifications: crate::notifications::NotificationManager,
@@ -168,10 +178,14 @@ pub struct Application {
     plugin_runtime: Option<forge_plugin::PluginRuntime>,
     extension_host: Option<forge_extension_host::ExtensionHost>,
     search_panel: crate::search_panel::SearchPanel,
+    problems_panel: crate::problems_panel::ProblemsPanel,
 
     // Phase 4: Intelligence Layer
     ghost_tabs: forge_anticipation::GhostTabsEngine,
     anomaly_detector: forge_immune::AnomalyDetector,
+
+    // Collaboration
+    collab: Option<crate::collab::Collaboration>,
 }
 
 /// Unified application state
@@ -204,6 +218,8 @@ struct AppState {
     // Editor & File Management
     tab_manager: TabManager,
     file_explorer: crate::file_explorer::FileExplorer,
+    /// Gitignore-aware index of the working directory and its sub-projects
+    project: Option<forge_core::ProjectWatcher>,
 
     // Terminal
     terminal: Option<forge_terminal::Terminal>,
@@ -261,6 +277,7 @@ struct AppState {
 #[derive(Debug)]
 pub enum AppEvent {
     GoToLocation(lsp_types::Location),
+    References(Vec<lsp_types::Location>),
 }
 
 impl Application {
@@ -271,11 +288,39 @@ impl Application {
         debug_zones: bool,
     ) -> Self {
         let config = forge_config::ForgeConfig::default();
+        // TextMate grammars contributed by extensions, for languages without
+        // a tree-sitter grammar
+        let textmate = forge_syntax::TextMateGrammars::global();
+        let extensions = crate::extensions::ExtensionRegistry::new().store_path;
+        for entry in std::fs::read_dir(extensions).into_iter().flatten().flatten() {
+            if entry.path().join("package.json").exists() {
+                if let Err(e) = textmate.load_extension(&entry.path()) {
+                    tracing::warn!("{:#}", e);
+                }
+            }
+        }
+        // Grammars beyond the built-in ones, and user highlight and injection
+        // queries and TextMate grammars overriding the bundled ones
+        if let Some(config_dir) = forge_config::ForgeConfig::config_path().parent() {
+            forge_syntax::Queries::global().set_user_dir(Some(config_dir.join("queries")));
+            forge_syntax::Grammars::global().set_dir(Some(config_dir.join("grammars")));
+            textmate.load_dir(&config_dir.join("syntaxes"));
+        }
+        // The user's language settings; the workspace's are added once the
+        // opened folder is known
+        if let Some(dir) = forge_config::ForgeConfig::config_path().parent() {
+            let path = dir.join("languages.toml");
+            if let Err(e) = forge_syntax::LanguageConfigs::global().load_file(&path) {
+                tracing::warn!("{:#}", e);
+            }
+        }
         let theme = forge_theme::Theme::default_dark();
 
         let find_bar = crate::find_bar::FindBar::default();
         let replace_bar = crate::replace_bar::ReplaceBar::default();
         let go_to_line = crate::go_to_line::GoToLine::default();
+        let undo_tree_panel = crate::undo_tree_panel::UndoTreePanel::default();
+        let recovery_dialog = crate::recovery_dialog::RecoveryDialog::default();
         let command_palette = crate::command_palette::CommandPalette::default();
         let bottom_panel = crate::bottom_panel::BottomPanel::default();
         let notifications = crate::notifications::NotificationManager::default();
@@ -287,6 +332,7 @@ impl Application {
         let debug_ui = Some(crate::debug_ui::DebugUi::new());
 
         let search_panel = crate::search_panel::SearchPanel::new();
+        let problems_panel = crate::problems_panel::ProblemsPanel::new();
 
         // Phase 5: Plugin runtime (sync init)
         let plugin_runtime = match forge_plugin::PluginRuntime::new() {
@@ -356,6 +402,8 @@ impl Application {
             find_bar,
             replace_bar,
             go_to_line,
+            undo_tree_panel,
+            recovery_dialog,
             command_palette,
             bottom_panel,
             notifications,
@@ -366,8 +414,10 @@ impl Application {
             plugin_runtime,
             extension_host,
             search_panel,
+            problems_panel,
             ghost_tabs,
             anomaly_detector,
+            collab: None,
         }
     }
 
@@ -386,7 +436,55 @@ impl Application {
 
         // Init tab manager & file explorer
         let mut tab_manager = TabManager::new();
+        if self.config.editor.persistent_undo {
+            let editor_config = &self.config.editor;
+            let store = forge_core::UndoStore::new(forge_core::UndoStoreLimits {
+                max_bytes: editor_config.undo_history_max_kb * 1024,
+                max_age: std::time::Duration::from_secs(
+                    editor_config.undo_history_max_age_days * 24 * 60 * 60,
+                ),
+            });
+            if let Err(e) = store.prune() {
+                tracing::warn!("Failed to prune undo history: {}", e);
+            }
+            tab_manager.set_undo_store(store);
+        }
+        tab_manager.set_large_file_limits(forge_core::LargeFileLimits {
+            threshold_bytes: self.config.editor.large_file_threshold_mb * 1024 * 1024,
+            mmap_threshold_bytes: self.config.editor.large_file_mmap_threshold_mb * 1024 * 1024,
+        });
+        if self.config.editor.crash_recovery {
+            tab_manager.set_recovery(forge_core::RecoveryManager::new());
+            self.recovery_dialog.open(tab_manager.recoverable_journals());
+        }
         tab_manager.open_scratch(); // Ensure keyboard input works from launch
+        // A folder given on the command line is opened as the project
+        let folder = self
+            .file_path
+            .as_deref()
+            .map(std::path::Path::new)
+            .filter(|path| path.is_dir())
+            .map(std::path::Path::to_path_buf);
+        if folder.is_some() {
+            self.file_path = None;
+        }
+        // Workspace language settings override the user's
+        let languages = forge_syntax::LanguageConfigs::global();
+        if let Some(folder) = &folder {
+            let path = folder.join(".forge").join("languages.toml");
+            if let Err(e) = languages.load_file(&path) {
+                tracing::warn!("{:#}", e);
+            }
+        }
+        let language_names: Vec<(String, String)> = languages
+            .ids()
+            .into_iter()
+            .map(|id| {
+                let name = languages.get(&id).display_name().to_string();
+                (id, name)
+            })
+            .collect();
+        self.command_palette.set_languages(&language_names);
         if let Some(ref path) = self.file_path {
             if let Err(e) = tab_manager.open_file(path) {
                 tracing::warn!("Failed to open {}: {}", path, e);
@@ -396,7 +494,17 @@ impl Application {
         }
 
         let mut file_explorer = crate::file_explorer::FileExplorer::new();
-        let cwd = std::env::current_dir().unwrap_or_default();
+        // Only an explicitly opened folder is indexed and watched; a single
+        // file doesn't make its working directory a project
+        let project = folder.and_then(|folder| {
+            forge_core::ProjectWatcher::start(
+                folder,
+                forge_core::ProjectLimits::default(),
+                forge_core::project::DEFAULT_REFRESH_INTERVAL,
+            )
+            .map_err(|e| tracing::warn!("Project indexing disabled: {}", e))
+            .ok()
+        });
         // Don't auto-scan at startup — VS Code only shows files when user opens Explorer
 
         // LSP: Send didOpen if file is opened
@@ -571,6 +679,7 @@ impl Application {
             overlay_buffer,
             tab_manager,
             file_explorer,
+            project,
             terminal: None,
             bottom_panel_focused: false,
             agent: Some(Agent::start()),
@@ -603,8 +712,24 @@ impl Application {
         });
     }
 
+    /// Open `hits` as excerpts in a multi-buffer tab
+    fn open_multi_buffer(
+        state: &mut AppState,
+        notifications: &mut crate::notifications::NotificationManager,
+        title: &str,
+        hits: &[(String, usize)],
+    ) {
+        if let Err(e) = state.tab_manager.open_multi_buffer(title, hits) {
+            notifications.show(
+                &format!("{}: {}", title, e),
+                crate::notifications::Level::Info,
+            );
+        }
+        state.window.request_redraw();
+    }
+
     /// Handle application events (async callbacks)
-    fn handle_app_events(state: &mut AppState) {
+    fn handle_app_events(state: &mut AppState, lsp_unit: forge_core::ColumnUnit) {
         while let Ok(event) = state.event_rx.try_recv() {
             match event {
                 AppEvent::GoToLocation(loc) => {
@@ -619,10 +744,10 @@ impl Application {
                                 // Move cursor
                                 let line = loc.range.start.line as usize;
                                 let col = loc.range.start.character as usize;
-                                let offset = ed.buffer.line_col_to_offset(line, col);
-                                ed.buffer.set_selection(forge_core::Selection::point(
-                                    forge_core::Position::new(offset),
-                                ));
+                                let pos = ed
+                                    .buffer
+                                    .line_col_to_position(forge_core::LineCol::new(line, col), lsp_unit);
+                                ed.buffer.set_selection(forge_core::Selection::point(pos));
                                 ed.set_scroll_top(line.saturating_sub(5));
                                 tracing::info!("Jumped to definition: {}:{}", path_str, line);
                             }
@@ -633,10 +758,101 @@ impl Application {
                         tracing::warn!("GoToDef: Invalid URI: {}", uri_str);
                     }
                 }
+                AppEvent::References(locations) => {
+                    let hits: Vec<(String, usize)> = locations
+                        .iter()
+                        .filter_map(|loc| {
+                            let path = url::Url::parse(loc.uri.as_str()).ok()?.to_file_path().ok()?;
+                            Some((path.to_string_lossy().to_string(), loc.range.start.line as usize))
+                        })
+                        .collect();
+                    if let Err(e) = state.tab_manager.open_multi_buffer("References", &hits) {
+                        tracing::info!("No references shown: {}", e);
+                    }
+                }
             }
         }
     }
 
+    /// The collaboration, connecting to the relay first if there is none
+    fn collab_connection<'a>(
+        collab: &'a mut Option<crate::collab::Collaboration>,
+        rt: &Runtime,
+        config: &forge_config::CollabConfig,
+        proxy: &EventLoopProxy<UserEvent>,
+    ) -> anyhow::Result<&'a mut crate::collab::Collaboration> {
+        let connected = match collab.take() {
+            Some(connected) => connected,
+            None => {
+                let proxy = proxy.clone();
+                crate::collab::Collaboration::connect(rt, config, move || {
+                    let _ = proxy.send_event(UserEvent::Collab);
+                })?
+            }
+        };
+        Ok(collab.insert(connected))
+    }
+
+    /// Exchange edits with the relay and act on what the session reports
+    fn poll_collab(
+        collab: &mut Option<crate::collab::Collaboration>,
+        state: &mut AppState,
+        notifications: &mut crate::notifications::NotificationManager,
+        command_palette: &mut crate::command_palette::CommandPalette,
+        rt: &Arc<Runtime>,
+        lsp_client: &Option<Arc<LspClient>>,
+    ) {
+        let Some(connected) = collab.as_mut() else {
+            return;
+        };
+        let mut events = Vec::new();
+        let changed = connected.poll(&mut state.tab_manager, &mut events);
+        for event in events {
+            match event {
+                crate::collab::CollabEvent::Rooms(rooms) if rooms.is_empty() => {
+                    notifications.show(
+                        "Nobody is sharing a file on the relay",
+                        crate::notifications::Level::Info,
+                    );
+                }
+                crate::collab::CollabEvent::Rooms(rooms) => {
+                    command_palette.set_collab_rooms(&rooms);
+                    command_palette.open(crate::command_palette::PaletteMode::Commands);
+                    for c in "Collaboration: Join Room: ".chars() {
+                        command_palette.type_char(c);
+                    }
+                }
+                crate::collab::CollabEvent::Notice(message) => {
+                    notifications.show(&message, crate::notifications::Level::Info);
+                }
+                crate::collab::CollabEvent::Ended(message) => {
+                    state.tab_manager.unshare();
+                    *collab = None;
+                    notifications.show(&message, crate::notifications::Level::Warning);
+                }
+            }
+        }
+
+        // Follow mode: keep the followed collaborator's cursor on screen
+        let visible_lines = (state.layout.editor.height / LayoutConstants::LINE_HEIGHT) as usize;
+        if let Some((session, ed)) = state.tab_manager.active_session_mut() {
+            let head = session
+                .following()
+                .and_then(|peer| peer.selection.as_ref())
+                .map(|selection| selection.primary().head);
+            if let Some(head) = head {
+                let row = ed.buffer.to_display(head).row;
+                let top = ed.scroll_top();
+                if row < top || row >= top + visible_lines {
+                    ed.set_scroll_row(row.saturating_sub(visible_lines / 2));
+                }
+            }
+        }
+        if changed {
+            Self::notify_lsp(state, rt, lsp_client);
+        }
+    }
+
     /// Main render function
     fn render(
         extension_host: &mut Option<forge_extension_host::ExtensionHost>,
@@ -647,6 +863,8 @@ impl Application {
         find_bar: &crate::find_bar::FindBar,
         replace_bar: &crate::replace_bar::ReplaceBar,
         go_to_line: &crate::go_to_line::GoToLine,
+        undo_tree_panel: &crate::undo_tree_panel::UndoTreePanel,
+        recovery_dialog: &crate::recovery_dialog::RecoveryDialog,
         command_palette: &crate::command_palette::CommandPalette,
         search_panel: &mut crate::search_panel::SearchPanel,
         settings_ui: &crate::settings_ui::SettingsUi,
@@ -656,7 +874,6 @@ impl Application {
     ) {
         state.frame_timer.begin_frame();
         notifications.tick();
-        Self::handle_app_events(state);
 
         // Update accessibility tree
         let (editor_text, cursor_line, cursor_col) = if let Some(ed) = state.tab_manager.active_editor() {
@@ -672,6 +889,26 @@ impl Application {
         };
         state.accessibility_manager.update(acc_state);
 
+        state.tab_manager.poll_loading();
+        state.tab_manager.poll_external_changes();
+        state.tab_manager.poll_journals();
+        if let Some(project) = &state.project {
+            // Keep an already-shown explorer in step with the index
+            let generation = project.generation();
+            let shown = state.file_explorer.generation != 0 || state.file_explorer.indexing;
+            if shown && generation != 0 && state.file_explorer.generation != generation {
+                state.file_explorer.populate(&project.project());
+                state.file_explorer.generation = generation;
+            }
+        }
+        if state.tab_manager.is_loading() || state.file_explorer.indexing {
+            // Keep the progress display moving
+            state.window.request_redraw();
+        }
+        for notice in state.tab_manager.take_notices() {
+            notifications.show(&notice, crate::notifications::Level::Warning);
+        }
+
         // Poll Extension Host
         if let Some(host) = extension_host {
             for msg in host.poll_messages() {
@@ -741,11 +978,13 @@ impl Application {
         // Gutter
         if mode_config.gutter {
             if let Some(editor) = state.tab_manager.active_editor() {
-                state.gutter.scroll_top = editor.scroll_top();
+                let top = editor.scroll_top();
+                let visible = Gutter::visible_lines(&state.layout.gutter);
+                state.gutter.rows = editor.row_lines(top..top + visible);
                 state.gutter.total_lines = editor.total_lines();
                 state.gutter.cursor_line = editor.cursor_line();
             } else {
-                state.gutter.scroll_top = 0;
+                state.gutter.rows = vec![Some(0)];
                 state.gutter.total_lines = 1;
                 state.gutter.cursor_line = 0;
             }
@@ -756,7 +995,7 @@ impl Application {
         // Current line highlight
         if let Some(editor) = state.tab_manager.active_editor() {
             if let Some(hl_rect) = state.cursor_renderer.current_line_rect(
-                editor.cursor_line(),
+                editor.cursor_display_point().row,
                 editor.scroll_top(),
                 &state.layout.editor,
             ) {
@@ -769,9 +1008,10 @@ impl Application {
             state.cursor_renderer.update();
         }
         if let Some(editor) = state.tab_manager.active_editor() {
+            let cursor = editor.cursor_display_point();
             if let Some(cursor_rect) = state.cursor_renderer.render_rect(
-                editor.cursor_line(),
-                editor.cursor_col(),
+                cursor.row,
+                cursor.col,
                 editor.scroll_top(),
                 &state.layout.editor,
             ) {
@@ -779,16 +1019,68 @@ impl Application {
             }
         }
 
+        // Collaborators' selections and cursors
+        if let (Some(session), Some(editor)) = (
+            state.tab_manager.active_session(),
+            state.tab_manager.active_editor(),
+        ) {
+            let buffer = &editor.buffer;
+            let scroll_top = editor.scroll_top();
+            for peer in session.peers() {
+                let Some(selection) = &peer.selection else {
+                    continue;
+                };
+                let color = crate::collab::peer_color(peer.id);
+                for range in selection.ranges() {
+                    let anchor = buffer.to_display(range.anchor);
+                    let head = buffer.to_display(range.head);
+                    if !range.is_point() {
+                        // Only the widths of rows on screen are used
+                        let last_row = anchor.row.max(head.row);
+                        let mut row_widths = vec![0; scroll_top.min(last_row + 1)];
+                        row_widths.extend(
+                            buffer
+                                .display_rows(scroll_top..last_row + 1)
+                                .iter()
+                                .map(|row| row.width),
+                        );
+                        for mut rect in state.cursor_renderer.selection_rects(
+                            anchor.row,
+                            anchor.col,
+                            head.row,
+                            head.col,
+                            scroll_top,
+                            &row_widths,
+                            &state.layout.editor,
+                        ) {
+                            rect.color = [color[0], color[1], color[2], 0.25];
+                            state.render_batch.push(rect);
+                        }
+                    }
+                    if let Some(mut rect) = state.cursor_renderer.render_rect(
+                        head.row,
+                        head.col,
+                        scroll_top,
+                        &state.layout.editor,
+                    ) {
+                        rect.width = 2.0;
+                        rect.color = color;
+                        state.render_batch.push(rect);
+                    }
+                }
+            }
+        }
+
         // Scrollbar
         let visible_lines = (state.layout.editor.height / LayoutConstants::LINE_HEIGHT) as usize;
-        let (total_lines, scroll_top) = state
+        let (total_rows, scroll_top) = state
             .tab_manager
             .active_editor()
-            .map(|e| (e.total_lines(), e.scroll_top()))
+            .map(|e| (e.total_rows(), e.scroll_top()))
             .unwrap_or((1, 0));
         let sb_rects = state.scrollbar.render_rect(
             &state.layout.scrollbar_v,
-            total_lines,
+            total_rows,
             visible_lines,
             scroll_top,
         );
@@ -808,11 +1100,20 @@ impl Application {
             let cursor_col = editor.cursor_col();
             let text = editor.buffer.text();
             if let Some((match_line, match_col)) =
-                crate::bracket_match::BracketMatcher::find_match(&text, cursor_line, cursor_col)
+                crate::bracket_match::BracketMatcher::find_match(
+                    &text,
+                    cursor_line,
+                    cursor_col,
+                    &editor.language_config(),
+                )
             {
+                let at = editor.buffer.to_display(editor.buffer.line_col_to_position(
+                    forge_core::LineCol::new(match_line, match_col),
+                    forge_core::ColumnUnit::Char,
+                ));
                 if let Some(rect) = state.cursor_renderer.render_rect(
-                    match_line,
-                    match_col,
+                    at.row,
+                    at.col,
                     editor.scroll_top(),
                     &state.layout.editor,
                 ) {
@@ -827,15 +1128,35 @@ impl Application {
         if find_bar.visible && !find_bar.matches.is_empty() {
             if let Some(editor) = state.tab_manager.active_editor() {
                 let scroll_top = editor.scroll_top();
+                let last_row = (scroll_top + visible_lines).saturating_sub(1);
+                let visible = editor
+                    .buffer
+                    .from_display(forge_core::DisplayPoint::new(scroll_top, 0))
+                    .offset
+                    ..editor
+                        .buffer
+                        .from_display(forge_core::DisplayPoint::new(last_row, usize::MAX))
+                        .offset;
                 for (i, m) in find_bar.matches.iter().enumerate() {
+                    if m.end < visible.start || m.start > visible.end {
+                        continue;
+                    }
+                    let start = editor.buffer.to_display(forge_core::Position::new(m.start));
                     // Only highlight matches in visible viewport
-                    if m.line >= scroll_top && m.line < scroll_top + visible_lines {
-                        let rel_line = m.line - scroll_top;
-                        let char_w = 9.2f32; // approx monospace char width
-                        let match_x = state.layout.editor.x + (m.start_col as f32 * char_w);
+                    if start.row >= scroll_top && start.row < scroll_top + visible_lines {
+                        let end = editor.buffer.to_display(forge_core::Position::new(m.end));
+                        // Matches spanning rows are highlighted on their first
+                        let end_col = if end.row == start.row {
+                            end.col
+                        } else {
+                            editor.buffer.display_rows(start.row..start.row + 1)[0].width
+                        };
+                        let rel_row = start.row - scroll_top;
+                        let char_w = LayoutConstants::CHAR_WIDTH;
+                        let match_x = state.layout.editor.x + (start.col as f32 * char_w);
                         let match_y = state.layout.editor.y
-                            + (rel_line as f32 * LayoutConstants::LINE_HEIGHT);
-                        let match_w = ((m.end_col - m.start_col) as f32) * char_w;
+                            + (rel_row as f32 * LayoutConstants::LINE_HEIGHT);
+                        let match_w = (end_col.saturating_sub(start.col).max(1) as f32) * char_w;
 
                         let is_current = find_bar.current_match == Some(i);
                         let color = if is_current {
@@ -910,6 +1231,42 @@ impl Application {
             });
         }
 
+        // Recover Unsaved Work Overlay
+        if recovery_dialog.visible {
+            let rd_width = 560.0;
+            let rd_height = 420.0;
+            let rd_x = state.layout.editor.x + (state.layout.editor.width - rd_width) / 2.0;
+            let rd_y = state.layout.editor.y + 40.0;
+            let bg = theme
+                .color("editorWidget.background")
+                .unwrap_or([0.18, 0.20, 0.26, 0.98]);
+            state.render_batch.push(crate::rect_renderer::Rect {
+                x: rd_x,
+                y: rd_y,
+                width: rd_width,
+                height: rd_height,
+                color: bg,
+            });
+        }
+
+        // Undo Tree Overlay
+        if undo_tree_panel.visible {
+            let ut_width = 360.0;
+            let ut_height = 400.0;
+            let ut_x = state.layout.editor.x + state.layout.editor.width - ut_width - 20.0;
+            let ut_y = state.layout.editor.y + 4.0;
+            let bg = theme
+                .color("editorWidget.background")
+                .unwrap_or([0.18, 0.20, 0.26, 0.98]);
+            state.render_batch.push(crate::rect_renderer::Rect {
+                x: ut_x,
+                y: ut_y,
+                width: ut_width,
+                height: ut_height,
+                color: bg,
+            });
+        }
+
         // Command Palette Overlay
         if command_palette.visible {
             let cp_width = 500.0;
@@ -1024,25 +1381,38 @@ impl Application {
         // 1. Editor text
         let vis_lines = (state.layout.editor.height / LayoutConstants::LINE_HEIGHT) as usize + 1;
         let mut editor_text = String::new();
+        // Rows of a laid out buffer, styled chunk by chunk below
+        let mut display_rows = Vec::new();
+        let mut gutter_lines = Vec::new();
 
         if let Some(editor) = state.tab_manager.active_editor() {
-            if editor.buffer.is_loading {
-                editor_text = "\n\n   Loading large file...".to_string();
-            } else {
-                let scroll_top = editor.scroll_top();
+            let scroll_top = editor.scroll_top();
+            if let Some(progress) = editor.buffer.load_progress() {
+                editor_text = format!(
+                    "\n\n   Loading large file... {:.0}%",
+                    progress * 100.0
+                );
+            } else if editor.mapped.is_some() {
                 let total_lines = editor.total_lines();
                 for i in 0..vis_lines {
                     let line_idx = scroll_top + i;
                     if line_idx >= total_lines {
                         break;
                     }
-                    let line = Guard::get_line(editor.buffer.rope(), line_idx);
+                    let line = editor.display_line(line_idx);
                     editor_text.push_str(&line);
                     if !line.ends_with('\n') {
                         editor_text.push('\n');
                     }
                 }
+            } else {
+                display_rows = editor.buffer.display_rows(scroll_top..scroll_top + vis_lines);
+                for row in &display_rows {
+                    editor_text.push_str(&row.text);
+                    editor_text.push('\n');
+                }
             }
+            gutter_lines = editor.row_lines(scroll_top..scroll_top + vis_lines);
         } else {
             editor_text = "\n\n\n\
                 \t\t\t🔥 FORGE EDITOR\n\n\
@@ -1073,69 +1443,64 @@ impl Application {
         // ─── SYNTAX HIGHLIGHTING via set_rich_text ───
         // Build per-span colored text chunks from the editor's highlight_spans
         let base_attrs = Attrs::new().family(Family::Monospace).color(text_color);
-        let has_highlights = state
-            .tab_manager
-            .active_editor()
-            .map(|e| !e.highlight_spans.is_empty())
-            .unwrap_or(false);
 
-        if has_highlights {
-            // Get the byte offset of the first visible line so we can map spans
-            let scroll_top = state
+        if !display_rows.is_empty() {
+            let spans: &[forge_syntax::HighlightSpan] = state
                 .tab_manager
                 .active_editor()
-                .map(|e| e.scroll_top())
-                .unwrap_or(0);
-            let (vis_byte_start, spans_clone) = {
-                if let Some(editor) = state.tab_manager.active_editor() {
-                    let full_text = editor.buffer.text();
-                    let byte_start: usize = full_text
-                        .lines()
-                        .take(scroll_top)
-                        .map(|l| l.len() + 1) // +1 for newline
-                        .sum();
-                    (byte_start, editor.highlight_spans.clone())
-                } else {
-                    (0, Vec::new())
-                }
-            };
-            let vis_byte_end = vis_byte_start + editor_text.len();
-
-            // Build rich text spans
+                .map(|e| e.highlight_spans.as_slice())
+                .unwrap_or_default();
+            let hint_attrs = base_attrs
+                .color(GlyphonColor::rgb(136, 136, 136))
+                .style(glyphon::Style::Italic);
+            let fold_attrs = base_attrs.color(GlyphonColor::rgb(133, 133, 133));
+
+            // Build rich text spans: buffer text colored by its highlight
+            // spans, inlays (hints, ghost text) and fold placeholders dimmed
             let mut rich_spans: Vec<(String, Attrs)> = Vec::new();
-            let mut pos = 0usize; // position within editor_text
-
-            for span in &spans_clone {
-                // Skip spans before visible area
-                if span.end_byte <= vis_byte_start || span.start_byte >= vis_byte_end {
-                    continue;
-                }
-                // Clamp to visible area
-                let s = span.start_byte.max(vis_byte_start) - vis_byte_start;
-                let e = span.end_byte.min(vis_byte_end) - vis_byte_start;
-                let s = s.min(editor_text.len());
-                let e = e.min(editor_text.len());
-                if s < e {
-                    // Push plain text before this span
-                    if pos < s {
-                        rich_spans.push((editor_text[pos..s].to_string(), base_attrs));
+            for row in &display_rows {
+                for chunk in &row.chunks {
+                    let text = &row.text[chunk.range.clone()];
+                    match &chunk.source {
+                        forge_core::display_map::ChunkSource::Text(range) => {
+                            let local = |offset: usize| offset - range.start;
+                            let mut pos = range.start;
+                            let first = spans.partition_point(|span| span.end_byte <= range.start);
+                            for span in &spans[first..] {
+                                if span.start_byte >= range.end {
+                                    break;
+                                }
+                                let s = span.start_byte.max(pos);
+                                let e = span.end_byte.min(range.end);
+                                if s >= e {
+                                    continue;
+                                }
+                                // Push plain text before this span
+                                if pos < s {
+                                    rich_spans.push((text[local(pos)..local(s)].to_string(), base_attrs));
+                                }
+                                // Push colored span
+                                let [r, g, b] = default_color(span.token_type);
+                                let color_attrs = base_attrs.color(GlyphonColor::rgb(r, g, b));
+                                rich_spans.push((text[local(s)..local(e)].to_string(), color_attrs));
+                                pos = e;
+                            }
+                            if pos < range.end {
+                                rich_spans.push((text[local(pos)..].to_string(), base_attrs));
+                            }
+                        }
+                        forge_core::display_map::ChunkSource::Tab(_) => {
+                            rich_spans.push((text.to_string(), base_attrs));
+                        }
+                        forge_core::display_map::ChunkSource::Inlay(_) => {
+                            rich_spans.push((text.to_string(), hint_attrs));
+                        }
+                        forge_core::display_map::ChunkSource::Fold(_) => {
+                            rich_spans.push((text.to_string(), fold_attrs));
+                        }
                     }
-                    // Push colored span
-                    let [r, g, b] = default_color(span.token_type);
-                    let color_attrs = base_attrs.color(GlyphonColor::rgb(r, g, b));
-                    rich_spans.push((editor_text[s..e].to_string(), color_attrs));
-                    pos = e;
                 }
-            }
-            // Push remaining plain text
-            if pos < editor_text.len() {
-                rich_spans.push((editor_text[pos..].to_string(), base_attrs));
-            }
-
-            // Ghost Text (inline diff streaming)
-            if let Some(ghost) = &state.tab_manager.active_editor().and_then(|e| e.ghost_text.as_ref()) {
-                let ghost_attrs = base_attrs.color(GlyphonColor::rgb(136, 136, 136)).style(glyphon::Style::Italic);
-                rich_spans.push((ghost.to_string(), ghost_attrs));
+                rich_spans.push(("\n".to_string(), base_attrs));
             }
 
             let rich_ref: Vec<(&str, Attrs)> =
@@ -1160,25 +1525,12 @@ impl Application {
 
         // 2. Gutter (line numbers)
         let mut gutter_text = String::new();
-        let scroll_top = state
-            .tab_manager
-            .active_editor()
-            .map(|e| e.scroll_top())
-            .unwrap_or(0);
-        let total_lines = state
-            .tab_manager
-            .active_editor()
-            .map(|e| e.total_lines())
-            .unwrap_or(1);
-
-        for i in 0..vis_lines {
-            let line_idx = scroll_top + i;
-            if line_idx >= total_lines {
-                break;
+        for line in &gutter_lines {
+            // Right-align line numbers in gutter; wrapped rows have none
+            match line {
+                Some(line_idx) => gutter_text.push_str(&format!("{:>4}\n", line_idx + 1)),
+                None => gutter_text.push('\n'),
             }
-            let line_num = line_idx + 1;
-            // Right-align line numbers in gutter
-            gutter_text.push_str(&format!("{:>4}\n", line_num));
         }
 
         state.gutter_buffer.set_size(
@@ -1215,9 +1567,18 @@ impl Application {
         // Update status state first
         if let Some(ed) = state.tab_manager.active_editor() {
             state.status_bar_state.cursor_line = ed.cursor_line() + 1;
-            state.status_bar_state.cursor_col = ed.cursor_col() + 1;
-            state.status_bar_state.language = format!("{:?}", ed.language);
+            state.status_bar_state.cursor_col = ed.cursor_display_line_col().col + 1;
+            state.status_bar_state.language = ed.language_config().display_name().to_string();
+            state.status_bar_state.encoding = ed.buffer.encoding_label();
+            state.status_bar_state.line_ending = ed.buffer.line_ending().label().to_string();
+            state.status_bar_state.mixed_line_endings = ed.mixed_line_endings;
         }
+        let session = state.tab_manager.active_session();
+        state.status_bar_state.collaborators = session
+            .map(|s| s.peers().iter().map(|p| p.name.clone()).collect())
+            .unwrap_or_default();
+        state.status_bar_state.following =
+            session.and_then(|s| s.following()).map(|p| p.name.clone());
         if state.frame_timer.avg_frame_time_ms > 0.0 {
             state.status_bar_state.frame_time_ms = state.frame_timer.avg_frame_time_ms;
         }
@@ -1366,6 +1727,9 @@ impl Application {
                 }
                 crate::ui::SidebarMode::Explorer => {
                     rich_spans.push(("  EXPLORER\n\n".to_string(), header_attrs));
+                    if state.file_explorer.indexing {
+                        rich_spans.push(("  Indexing\u{2026}\n".to_string(), header_attrs));
+                    }
 
                     if let Some(sidebar_zone) = &state.layout.sidebar {
                         let rects = state.file_explorer.ui.render_rects(
@@ -1557,6 +1921,56 @@ impl Application {
             dynamic_meta.push((g_x + 10.0, g_y + 10.0, g_width - 20.0, g_height - 8.0));
         }
 
+        if recovery_dialog.visible {
+            let mut buf = GlyphonBuffer::new(
+                &mut state.font_system,
+                Metrics::new(
+                    LayoutConstants::SMALL_FONT_SIZE,
+                    LayoutConstants::LINE_HEIGHT,
+                ),
+            );
+            buf.set_text(
+                &mut state.font_system,
+                &recovery_dialog.render_text(),
+                Attrs::new()
+                    .family(Family::Monospace)
+                    .color(GlyphonColor::rgb(220, 220, 220)),
+                Shaping::Advanced,
+            );
+            buf.shape_until_scroll(&mut state.font_system, false);
+            let rd_width = 560.0;
+            let rd_height = 420.0;
+            let rd_x = state.layout.editor.x + (state.layout.editor.width - rd_width) / 2.0;
+            let rd_y = state.layout.editor.y + 40.0;
+            dynamic_buffers.push(buf);
+            dynamic_meta.push((rd_x + 10.0, rd_y + 10.0, rd_width - 20.0, rd_height - 20.0));
+        }
+
+        if undo_tree_panel.visible {
+            let mut buf = GlyphonBuffer::new(
+                &mut state.font_system,
+                Metrics::new(
+                    LayoutConstants::SMALL_FONT_SIZE,
+                    LayoutConstants::LINE_HEIGHT,
+                ),
+            );
+            buf.set_text(
+                &mut state.font_system,
+                &undo_tree_panel.render_text(),
+                Attrs::new()
+                    .family(Family::Monospace)
+                    .color(GlyphonColor::rgb(220, 220, 220)),
+                Shaping::Advanced,
+            );
+            buf.shape_until_scroll(&mut state.font_system, false);
+            let ut_width = 360.0;
+            let ut_height = 400.0;
+            let ut_x = state.layout.editor.x + state.layout.editor.width - ut_width - 20.0;
+            let ut_y = state.layout.editor.y + 4.0;
+            dynamic_buffers.push(buf);
+            dynamic_meta.push((ut_x + 10.0, ut_y + 10.0, ut_width - 20.0, ut_height - 20.0));
+        }
+
         if command_palette.visible {
             let mut cp_text = format!("> {}\n\n", command_palette.query);
             match command_palette.mode {
@@ -2170,6 +2584,13 @@ impl Application {
         state.frame_timer.end_frame();
     }
 
+    /// The sub-project containing the active file
+    fn active_member(state: &AppState) -> Option<forge_core::SubProject> {
+        let path = state.tab_manager.active_editor()?.buffer.path()?;
+        let path = std::path::absolute(path).ok()?;
+        state.project.as_ref()?.project().member_for(&path).cloned()
+    }
+
     fn notify_lsp(state: &AppState, rt: &Arc<Runtime>, lsp_client: &Option<Arc<LspClient>>) {
         if let Some(client) = lsp_client {
             if let Some(tab) = state.tab_manager.tabs.get(state.tab_manager.active) {
@@ -2275,10 +2696,31 @@ impl Application {
                                                     crate::ui::SidebarMode::Explorer;
                                                 // Lazy-load files on first Explorer open
                                                 if state.file_explorer.nodes.is_empty() {
-                                                    let cwd =
-                                                        std::env::current_dir().unwrap_or_default();
-                                                    let _ =
-                                                        state.file_explorer.scan_directory(&cwd);
+                                                    match &state.project {
+                                                        Some(project) if project.generation() > 0 => {
+                                                            let index = project.project();
+                                                            if index.is_truncated() {
+                                                                tracing::warn!(
+                                                                    "{} is too large to index completely",
+                                                                    index.root.display()
+                                                                );
+                                                            }
+                                                            state.file_explorer.populate(&index);
+                                                            state.file_explorer.generation =
+                                                                project.generation();
+                                                        }
+                                                        // Listed once the first scan finishes
+                                                        Some(_) => {
+                                                            state.file_explorer.indexing = true;
+                                                        }
+                                                        None => {
+                                                            let cwd = std::env::current_dir()
+                                                                .unwrap_or_default();
+                                                            let _ = state
+                                                                .file_explorer
+                                                                .scan_directory(&cwd);
+                                                        }
+                                                    }
                                                 }
                                             }
                                             crate::activity_bar::ActivityItem::Search => {
@@ -2357,29 +2799,33 @@ impl Application {
                                     .map(|e| e.scroll_top())
                                     .unwrap_or(0);
 
-                                let clicked_line = scroll_top
-                                    + (rel_y / crate::ui::LayoutConstants::LINE_HEIGHT) as usize;
-                                let clicked_col =
-                                    (rel_x / crate::ui::LayoutConstants::CHAR_WIDTH) as usize;
+                                let point = forge_core::DisplayPoint::new(
+                                    scroll_top
+                                        + (rel_y / crate::ui::LayoutConstants::LINE_HEIGHT) as usize,
+                                    (rel_x / crate::ui::LayoutConstants::CHAR_WIDTH) as usize,
+                                );
 
                                 if let Some(ed) = state.tab_manager.active_editor_mut() {
-                                    let max_line = ed.total_lines().saturating_sub(1);
-                                    let target_line = clicked_line.min(max_line);
-
-                                    let text = ed.buffer.text();
-                                    let line_text = text.lines().nth(target_line).unwrap_or("");
-                                    let target_col = clicked_col.min(line_text.len());
-
-                                    let offset =
-                                        ed.buffer.line_col_to_offset(target_line, target_col);
-
-                                    if modifiers.alt_key() {
-                                        ed.add_cursor_at_point(target_line, target_col);
+                                    let offset = ed.buffer.from_display(point).offset;
+                                    let on_fold = ed.buffer.display_map().is_some_and(|map| {
+                                        map.folds().iter().any(|fold| fold.start == offset)
+                                    }) && ed.buffer.to_display(forge_core::Position::new(offset))
+                                        == point;
+
+                                    if on_fold {
+                                        // Clicking a fold placeholder unfolds it
+                                        ed.buffer.unfold(offset..offset);
+                                    } else if modifiers.alt_key() {
+                                        ed.add_cursor_at_point(point);
                                     } else {
                                         ed.buffer.set_selection(forge_core::Selection::point(
                                             forge_core::Position::new(offset),
                                         ));
                                     }
+                                    // Clicking an excerpt header shows more of it
+                                    if state.tab_manager.is_excerpt_header(offset) {
+                                        state.tab_manager.expand_excerpt_at(offset);
+                                    }
                                 }
                                 state.window.request_redraw();
                             } else if state.sidebar_open
@@ -2495,19 +2941,19 @@ impl Application {
                 if state.scrollbar.dragging {
                     let visible =
                         (state.layout.editor.height / LayoutConstants::LINE_HEIGHT) as usize;
-                    let total_lines = state
+                    let total_rows = state
                         .tab_manager
                         .active_editor()
-                        .map(|e| e.total_lines())
+                        .map(|e| e.total_rows())
                         .unwrap_or(1);
                     let new_scroll = state.scrollbar.update_drag(
                         my,
                         &state.layout.scrollbar_v,
-                        total_lines,
+                        total_rows,
                         visible,
                     );
                     if let Some(editor) = state.tab_manager.active_editor_mut() {
-                        editor.set_scroll_top(new_scroll);
+                        editor.set_scroll_row(new_scroll);
                     }
                 }
             }
@@ -2538,6 +2984,9 @@ impl ApplicationHandler<UserEvent> for Application {
         // Actually, checking standard implementation:
         // If we passed the proxy to adapter, it will send events. We need to handle them?
         // Let's look at accessibility.rs again.
+        if let (UserEvent::Collab, Some(state)) = (event, &self.state) {
+            state.window.request_redraw();
+        }
     }
 
     fn window_event(
@@ -2556,6 +3005,7 @@ impl ApplicationHandler<UserEvent> for Application {
         match event {
             WindowEvent::CloseRequested => {
                 info!("Goodbye from Forge 🔥");
+                state.tab_manager.persist_all_undo();
                 event_loop.exit();
             }
 
@@ -2642,14 +3092,21 @@ impl ApplicationHandler<UserEvent> for Application {
                             self.find_bar.close();
                             self.replace_bar.close();
                             self.go_to_line.cancel();
-                            // Collect files from file_explorer
-                            // FileExplorer.paths contains all paths
-                            let files: Vec<String> = state
-                                .file_explorer
-                                .paths
-                                .iter()
-                                .map(|p| p.to_string_lossy().to_string())
-                                .collect();
+                            // Every indexed file, or what the explorer has listed so far
+                            let files: Vec<String> = match &state.project {
+                                Some(project) if project.generation() > 0 => project
+                                    .project()
+                                    .files
+                                    .iter()
+                                    .map(|p| p.to_string_lossy().to_string())
+                                    .collect(),
+                                _ => state
+                                    .file_explorer
+                                    .paths
+                                    .iter()
+                                    .map(|p| p.to_string_lossy().to_string())
+                                    .collect(),
+                            };
                             self.command_palette.set_files(files);
                             self.command_palette
                                 .open(crate::command_palette::PaletteMode::Files);
@@ -2794,27 +3251,16 @@ impl ApplicationHandler<UserEvent> for Application {
                             state.window.request_redraw();
                         }
                         "s" => {
-                            // Atomic save via forge-core FileIO
-                            if let Some(tab) = state.tab_manager.tabs.get(state.tab_manager.active)
-                            {
-                                if let Some(ref path) = tab.path {
-                                    if let Some(ed) = state.tab_manager.active_editor() {
-                                        let text = ed.buffer.text();
-                                        if let Err(e) =
-                                            forge_core::file_io::FileIO::save_atomic(path, &text)
-                                        {
-                                            tracing::error!("Save failed: {}", e);
-                                        } else {
-                                            tracing::info!("Saved: {}", path.display());
-                                        }
-                                    }
-                                }
-                            }
-                            // Mark tab as not modified after save
-                            if let Some(tab) =
-                                state.tab_manager.tabs.get_mut(state.tab_manager.active)
-                            {
-                                tab.is_modified = false;
+                            // Atomic save via forge-core FileIO, in the buffer's encoding
+                            let options = forge_core::SaveOptions {
+                                backup: self.config.editor.backup_on_save,
+                            };
+                            if let Err(e) = state.tab_manager.save_active(&options) {
+                                tracing::error!("Save failed: {}", e);
+                                self.notifications.show(
+                                    &format!("Save failed: {}", e),
+                                    crate::notifications::Level::Error,
+                                );
                             }
                         }
                         "w" => {
@@ -2822,14 +3268,22 @@ impl ApplicationHandler<UserEvent> for Application {
                             state.window.request_redraw();
                         }
                         "z" if shift => {
-                            if let Some(ed) = state.tab_manager.active_editor_mut() {
+                            // In a shared tab, only redo what this user undid
+                            if let Some((session, ed)) = state.tab_manager.active_session_mut() {
+                                session.redo(&mut ed.buffer);
+                                ed.rehighlight();
+                            } else if let Some(ed) = state.tab_manager.active_editor_mut() {
                                 ed.buffer.redo();
                                 ed.rehighlight();
                             }
                             Self::notify_lsp(state, &self.rt, &self.lsp_client);
                         }
                         "z" => {
-                            if let Some(ed) = state.tab_manager.active_editor_mut() {
+                            // In a shared tab, collaborators' edits are left alone
+                            if let Some((session, ed)) = state.tab_manager.active_session_mut() {
+                                session.undo(&mut ed.buffer);
+                                ed.rehighlight();
+                            } else if let Some(ed) = state.tab_manager.active_editor_mut() {
                                 ed.buffer.undo();
                                 ed.rehighlight(); // Ensure syntax highlighting is updated
                             }
@@ -2860,7 +3314,11 @@ impl ApplicationHandler<UserEvent> for Application {
                             state.window.request_redraw();
                         }
                         "y" => {
-                            if let Some(ed) = state.tab_manager.active_editor_mut() {
+                            // In a shared tab, only redo what this user undid
+                            if let Some((session, ed)) = state.tab_manager.active_session_mut() {
+                                session.redo(&mut ed.buffer);
+                                ed.rehighlight();
+                            } else if let Some(ed) = state.tab_manager.active_editor_mut() {
                                 ed.buffer.redo();
                                 ed.rehighlight(); // Ensure syntax highlighting is updated
                             }
@@ -2889,9 +3347,7 @@ impl ApplicationHandler<UserEvent> for Application {
                             if let Ok(mut clipboard) = arboard::Clipboard::new() {
                                 if let Ok(text) = clipboard.get_text() {
                                     if let Some(ed) = state.tab_manager.active_editor_mut() {
-                                        for ch in text.chars() {
-                                            ed.insert_char(ch);
-                                        }
+                                        ed.insert_text(&text);
                                         ed.rehighlight();
                                     }
                                     state.tab_manager.mark_active_modified();
@@ -2916,6 +3372,14 @@ impl ApplicationHandler<UserEvent> for Application {
                             state.tab_manager.mark_active_modified();
                             Self::notify_lsp(state, &self.rt, &self.lsp_client);
                         }
+                        "/" => {
+                            if let Some(ed) = state.tab_manager.active_editor_mut() {
+                                ed.toggle_comment();
+                                ed.rehighlight();
+                            }
+                            state.tab_manager.mark_active_modified();
+                            Self::notify_lsp(state, &self.rt, &self.lsp_client);
+                        }
                         "o" => {
                             // Open file dialog (simple native dialog)
                             #[cfg(target_os = "windows")]
@@ -2930,10 +3394,12 @@ impl ApplicationHandler<UserEvent> for Application {
                         "g" if shift => {
                             // Mock AI Ghost Text Trigger (/ghost)
                             if let Some(ed) = state.tab_manager.active_editor_mut() {
-                                if ed.ghost_text.is_some() {
-                                    ed.ghost_text = None;
+                                if ed.ghost_text().is_some() {
+                                    ed.set_ghost_text(None);
                                 } else {
-                                    ed.ghost_text = Some(" // AI Suggestion: Optimize this loop".to_string());
+                                    ed.set_ghost_text(Some(
+                                        " // AI Suggestion: Optimize this loop".to_string(),
+                                    ));
                                 }
                             }
                             state.window.request_redraw();
@@ -2943,7 +3409,8 @@ impl ApplicationHandler<UserEvent> for Application {
                             if let Some(tab) = state.tab_manager.tabs.get(state.tab_manager.active)
                             {
                                 if let Some(ref path) = tab.path {
-                                    let (line, col) = tab.editor.cursor_line_col();
+                                    let unit = crate::go_to_def::column_unit(&self.lsp_client);
+                                    let (line, col) = tab.editor.cursor_line_col_in(unit);
                                     crate::go_to_def::GoToDef::execute(
                                         &self.rt,
                                         &self.lsp_client,
@@ -3002,21 +3469,41 @@ impl ApplicationHandler<UserEvent> for Application {
 
                     Key::Named(NamedKey::ArrowLeft) => {
                         if let Some(ed) = state.tab_manager.active_editor_mut() {
-                            ed.move_left();
+                            if shift && self.modifiers.alt_key() {
+                                ed.shrink_selection();
+                            } else if ctrl {
+                                ed.move_by(Motion::PrevStart(Unit::Word), shift);
+                            } else {
+                                ed.move_left();
+                            }
                         }
                     }
                     Key::Named(NamedKey::ArrowRight) => {
                         if let Some(ed) = state.tab_manager.active_editor_mut() {
-                            ed.move_right();
+                            if shift && self.modifiers.alt_key() {
+                                ed.expand_selection();
+                            } else if ctrl {
+                                ed.move_by(Motion::NextEnd(Unit::Word), shift);
+                            } else {
+                                ed.move_right();
+                            }
                         }
                     }
                     Key::Named(NamedKey::ArrowUp) => {
-                        if let Some(ed) = state.tab_manager.active_editor_mut() {
+                        if self.recovery_dialog.visible {
+                            self.recovery_dialog.select_prev();
+                        } else if self.undo_tree_panel.visible {
+                            self.undo_tree_panel.select_prev();
+                        } else if let Some(ed) = state.tab_manager.active_editor_mut() {
                             ed.move_up();
                         }
                     }
                     Key::Named(NamedKey::ArrowDown) => {
-                        if let Some(ed) = state.tab_manager.active_editor_mut() {
+                        if self.recovery_dialog.visible {
+                            self.recovery_dialog.select_next();
+                        } else if self.undo_tree_panel.visible {
+                            self.undo_tree_panel.select_next();
+                        } else if let Some(ed) = state.tab_manager.active_editor_mut() {
                             ed.move_down();
                         }
                     }
@@ -3052,10 +3539,9 @@ impl ApplicationHandler<UserEvent> for Application {
                             self.replace_bar.replace_text.pop();
                         } else if self.find_bar.visible {
                             self.find_bar.query.pop();
-                            if let Some(ed) = state.tab_manager.active_editor() {
-                                let text = ed.buffer.text();
+                            if let Some(ed) = state.tab_manager.active_editor_mut() {
                                 let query = self.find_bar.query.clone();
-                                self.find_bar.search(&text, &query);
+                                self.find_bar.search(&mut ed.buffer, &query);
                             }
                         } else {
                             if let Some(ed) = state.tab_manager.active_editor_mut() {
@@ -3066,6 +3552,19 @@ impl ApplicationHandler<UserEvent> for Application {
                             Self::notify_lsp(state, &self.rt, &self.lsp_client);
                         }
                     }
+                    Key::Named(NamedKey::Delete) if self.recovery_dialog.visible => {
+                        if let Some(journal) = self.recovery_dialog.selected_journal() {
+                            match state.tab_manager.discard_journal(journal) {
+                                Ok(()) => self.recovery_dialog.remove_selected(),
+                                Err(e) => {
+                                    self.notifications.show(
+                                        &format!("Failed to discard: {}", e),
+                                        crate::notifications::Level::Error,
+                                    );
+                                }
+                            }
+                        }
+                    }
                     Key::Named(NamedKey::Delete) => {
                         if let Some(ed) = state.tab_manager.active_editor_mut() {
                             ed.delete();
@@ -3075,7 +3574,22 @@ impl ApplicationHandler<UserEvent> for Application {
                         Self::notify_lsp(state, &self.rt, &self.lsp_client);
                     }
                     Key::Named(NamedKey::Enter) => {
-                        if self.command_palette.visible {
+                        if self.recovery_dialog.visible {
+                            if let Some(journal) = self.recovery_dialog.selected_journal().cloned() {
+                                match state.tab_manager.recover(&journal) {
+                                    Ok(()) => {
+                                        self.recovery_dialog.remove_selected();
+                                        Self::notify_lsp(state, &self.rt, &self.lsp_client);
+                                    }
+                                    Err(e) => {
+                                        self.notifications.show(
+                                            &format!("Failed to recover {}: {}", journal.path, e),
+                                            crate::notifications::Level::Error,
+                                        );
+                                    }
+                                }
+                            }
+                        } else if self.command_palette.visible {
                             match self.command_palette.mode {
                                 crate::command_palette::PaletteMode::Commands => {
                                     if let Some(cmd) = self.command_palette.select_command(0) {
@@ -3083,11 +3597,247 @@ impl ApplicationHandler<UserEvent> for Application {
                                         let cmd_label = cmd.label.clone();
                                         self.command_palette.close();
                                         match cmd_id.as_str() {
+                                            "selection.word"
+                                            | "selection.paragraph"
+                                            | "selection.inside_brackets"
+                                            | "selection.around_brackets"
+                                            | "selection.inside_quotes"
+                                            | "selection.function_body"
+                                            | "selection.argument" => {
+                                                let (object, scope) = match cmd_id.as_str() {
+                                                    "selection.word" => {
+                                                        (TextObject::Word, Scope::Inside)
+                                                    }
+                                                    "selection.paragraph" => {
+                                                        (TextObject::Paragraph, Scope::Inside)
+                                                    }
+                                                    "selection.inside_brackets" => {
+                                                        (TextObject::AnyBracket, Scope::Inside)
+                                                    }
+                                                    "selection.around_brackets" => {
+                                                        (TextObject::AnyBracket, Scope::Around)
+                                                    }
+                                                    "selection.inside_quotes" => {
+                                                        (TextObject::AnyQuote, Scope::Inside)
+                                                    }
+                                                    "selection.function_body" => {
+                                                        (TextObject::Function, Scope::Inside)
+                                                    }
+                                                    _ => (TextObject::Argument, Scope::Inside),
+                                                };
+                                                if let Some(ed) =
+                                                    state.tab_manager.active_editor_mut()
+                                                {
+                                                    ed.select_text_object(object, scope);
+                                                }
+                                            }
+                                            "selection.expand"
+                                            | "selection.shrink"
+                                            | "selection.next_sibling"
+                                            | "selection.prev_sibling"
+                                            | "selection.enclosing_function"
+                                            | "selection.enclosing_class" => {
+                                                use forge_core::syntax_selection::Enclosing;
+                                                if let Some(ed) =
+                                                    state.tab_manager.active_editor_mut()
+                                                {
+                                                    match cmd_id.as_str() {
+                                                        "selection.expand" => ed.expand_selection(),
+                                                        "selection.shrink" => ed.shrink_selection(),
+                                                        "selection.next_sibling" => {
+                                                            ed.select_sibling(true)
+                                                        }
+                                                        "selection.prev_sibling" => {
+                                                            ed.select_sibling(false)
+                                                        }
+                                                        "selection.enclosing_function" => {
+                                                            ed.select_enclosing(Enclosing::Function)
+                                                        }
+                                                        _ => ed.select_enclosing(Enclosing::Class),
+                                                    }
+                                                }
+                                            }
                                             "edit.find" => {
                                                 self.find_bar.open();
                                                 self.go_to_line.cancel();
                                                 self.replace_bar.close();
                                             }
+                                            "search.find_in_member" => {
+                                                let member = Self::active_member(state);
+                                                let root = member.as_ref().map_or_else(
+                                                    || std::env::current_dir().unwrap_or_default(),
+                                                    |m| m.root.clone(),
+                                                );
+                                                state.sidebar_mode = crate::ui::SidebarMode::Search;
+                                                state.sidebar_open = true;
+                                                self.search_panel.visible = true;
+                                                self.search_panel.search(&root);
+                                                if let Some(member) = member {
+                                                    self.notifications.show(
+                                                        &format!("Searching in {}", member.name),
+                                                        crate::notifications::Level::Info,
+                                                    );
+                                                }
+                                                state.window.request_redraw();
+                                            }
+                                            "search.open_in_multibuffer" => {
+                                                let hits: Vec<(String, usize)> = self
+                                                    .search_panel
+                                                    .results
+                                                    .iter()
+                                                    .map(|r| (r.file.clone(), r.line.saturating_sub(1)))
+                                                    .collect();
+                                                Self::open_multi_buffer(
+                                                    state,
+                                                    &mut self.notifications,
+                                                    "Search Results",
+                                                    &hits,
+                                                );
+                                            }
+                                            "problems.open_in_multibuffer" => {
+                                                let hits = self.problems_panel.locations();
+                                                Self::open_multi_buffer(
+                                                    state,
+                                                    &mut self.notifications,
+                                                    "Problems",
+                                                    &hits,
+                                                );
+                                            }
+                                            "editor.find_references" => {
+                                                if let Some(tab) =
+                                                    state.tab_manager.tabs.get(state.tab_manager.active)
+                                                {
+                                                    if let Some(ref path) = tab.path {
+                                                        let unit =
+                                                            crate::go_to_def::column_unit(&self.lsp_client);
+                                                        let (line, col) = tab.editor.cursor_line_col_in(unit);
+                                                        crate::references::References::execute(
+                                                            &self.rt,
+                                                            &self.lsp_client,
+                                                            &path.to_string_lossy(),
+                                                            line as u32,
+                                                            col as u32,
+                                                            &mut self.notifications,
+                                                            state.event_tx.clone(),
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                            "multibuffer.expand_excerpt" => {
+                                                let offset = state
+                                                    .tab_manager
+                                                    .active_editor()
+                                                    .map(|ed| ed.buffer.selection().primary().head.offset);
+                                                if let Some(offset) = offset {
+                                                    state.tab_manager.expand_excerpt_at(offset);
+                                                }
+                                            }
+                                            "multibuffer.open_excerpt" => {
+                                                state.tab_manager.open_excerpt_at_cursor();
+                                            }
+                                            "collab.share" | "collab.join" => {
+                                                let shareable = state
+                                                    .tab_manager
+                                                    .tabs
+                                                    .get(state.tab_manager.active)
+                                                    .filter(|tab| tab.multi_buffer.is_none())
+                                                    .and_then(|tab| {
+                                                        let path = tab.path.clone()?;
+                                                        Some((tab.title.clone(), path, tab.editor.buffer.text()))
+                                                    });
+                                                let result = if self.collab.as_ref().is_some_and(|c| c.is_active()) {
+                                                    Err(anyhow::anyhow!("leave the current session first"))
+                                                } else if cmd_id == "collab.join" {
+                                                    Self::collab_connection(
+                                                        &mut self.collab,
+                                                        &self.rt,
+                                                        &self.config.collab,
+                                                        &self.proxy,
+                                                    )
+                                                    .map(|collab| collab.browse())
+                                                } else if let Some((title, path, text)) = shareable {
+                                                    Self::collab_connection(
+                                                        &mut self.collab,
+                                                        &self.rt,
+                                                        &self.config.collab,
+                                                        &self.proxy,
+                                                    )
+                                                    .map(|collab| collab.host(&title, path, text))
+                                                } else {
+                                                    Err(anyhow::anyhow!("only files saved to disk can be shared"))
+                                                };
+                                                if let Err(e) = result {
+                                                    self.notifications.show(
+                                                        &format!("{}: {}", cmd_label, e),
+                                                        crate::notifications::Level::Error,
+                                                    );
+                                                }
+                                            }
+                                            "collab.follow" => {
+                                                let msg = match state.tab_manager.active_session_mut() {
+                                                    Some((session, _)) => {
+                                                        // Cycle through the collaborators, then stop
+                                                        let next = match session.following() {
+                                                            Some(current) => session
+                                                                .peers()
+                                                                .iter()
+                                                                .skip_while(|p| p.id != current.id)
+                                                                .nth(1),
+                                                            None => session.peers().first(),
+                                                        }
+                                                        .map(|p| (p.id, p.name.clone()));
+                                                        session.follow(next.as_ref().map(|(id, _)| *id));
+                                                        match next {
+                                                            Some((_, name)) => format!("Following {}", name),
+                                                            None => "Stopped following".to_string(),
+                                                        }
+                                                    }
+                                                    None => "The current tab isn't shared".to_string(),
+                                                };
+                                                self.notifications
+                                                    .show(&msg, crate::notifications::Level::Info);
+                                            }
+                                            "collab.leave" => {
+                                                if self.collab.take().is_some() {
+                                                    state.tab_manager.unshare();
+                                                    self.notifications.show(
+                                                        "Left the shared session",
+                                                        crate::notifications::Level::Info,
+                                                    );
+                                                }
+                                            }
+                                            "task.build_member" | "task.test_member" => {
+                                                let group = if cmd_id == "task.build_member" {
+                                                    "build"
+                                                } else {
+                                                    "test"
+                                                };
+                                                let task = Self::active_member(state).and_then(|member| {
+                                                    let project = state.project.as_ref()?.project();
+                                                    crate::task_runner::TaskRunner::member_tasks(&project)
+                                                        .into_iter()
+                                                        .find(|t| {
+                                                            t.cwd.as_ref() == Some(&member.root)
+                                                                && t.group.as_deref() == Some(group)
+                                                        })
+                                                });
+                                                let msg = match task {
+                                                    Some(task) => {
+                                                        let label = task.label.clone();
+                                                        let mut runner = crate::task_runner::TaskRunner::new();
+                                                        runner.load_tasks(crate::task_runner::TaskConfig {
+                                                            tasks: vec![task],
+                                                        });
+                                                        match runner.run_task(&label) {
+                                                            Ok(()) => format!("Started {}", label),
+                                                            Err(e) => format!("Failed to start {}: {}", label, e),
+                                                        }
+                                                    }
+                                                    None => format!("No {} task for the current file", group),
+                                                };
+                                                self.notifications
+                                                    .show(&msg, crate::notifications::Level::Info);
+                                            }
                                             "edit.replace" => {
                                                 self.find_bar.open();
                                                 self.go_to_line.cancel();
@@ -3139,37 +3889,244 @@ impl ApplicationHandler<UserEvent> for Application {
                                                     self.bottom_panel.visible,
                                                 );
                                             }
-                                            "file.save" => {
-                                                if let Some(tab) = state
-                                                    .tab_manager
-                                                    .tabs
-                                                    .get(state.tab_manager.active)
+                                            "view.word_wrap" => {
+                                                self.config.editor.word_wrap =
+                                                    !self.config.editor.word_wrap;
+                                            }
+                                            "editor.comment" => {
+                                                if let Some(ed) =
+                                                    state.tab_manager.active_editor_mut()
                                                 {
-                                                    if let Some(ref path) = tab.path {
-                                                        if let Some(ed) =
-                                                            state.tab_manager.active_editor()
-                                                        {
-                                                            let text = ed.buffer.text();
-                                                            if let Err(e) =
-                                                        forge_core::file_io::FileIO::save_atomic(
-                                                            path, &text,
-                                                        )
-                                                    {
-                                                        tracing::error!("Save failed: {}", e);
+                                                    ed.toggle_comment();
+                                                    ed.rehighlight();
+                                                }
+                                                state.tab_manager.mark_active_modified();
+                                                Self::notify_lsp(
+                                                    state,
+                                                    &self.rt,
+                                                    &self.lsp_client,
+                                                );
+                                            }
+                                            "editor.reindent_selection"
+                                            | "editor.reindent_file" => {
+                                                if let Some(ed) =
+                                                    state.tab_manager.active_editor_mut()
+                                                {
+                                                    if cmd_id == "editor.reindent_file" {
+                                                        ed.reindent_file();
+                                                    } else {
+                                                        ed.reindent_selection();
                                                     }
+                                                    ed.rehighlight();
+                                                }
+                                                state.tab_manager.mark_active_modified();
+                                                Self::notify_lsp(
+                                                    state,
+                                                    &self.rt,
+                                                    &self.lsp_client,
+                                                );
+                                            }
+                                            "editor.fold" => {
+                                                if let Some(ed) =
+                                                    state.tab_manager.active_editor_mut()
+                                                {
+                                                    ed.fold_at_cursor();
+                                                }
+                                            }
+                                            "editor.unfold" => {
+                                                if let Some(ed) =
+                                                    state.tab_manager.active_editor_mut()
+                                                {
+                                                    ed.unfold_at_cursor();
+                                                }
+                                            }
+                                            "file.save" => {
+                                                let options = forge_core::SaveOptions {
+                                                    backup: self.config.editor.backup_on_save,
+                                                };
+                                                if let Err(e) =
+                                                    state.tab_manager.save_active(&options)
+                                                {
+                                                    tracing::error!("Save failed: {}", e);
+                                                    self.notifications.show(
+                                                        &format!("Save failed: {}", e),
+                                                        crate::notifications::Level::Error,
+                                                    );
+                                                }
+                                            }
+                                            "file.close" => {
+                                                state.tab_manager.close_current();
+                                            }
+                                            id if id.starts_with("file.reopen_with_encoding.") => {
+                                                let enc = forge_core::Encoding::from_id(
+                                                    &id["file.reopen_with_encoding.".len()..],
+                                                );
+                                                if let (Some(enc), Some(ed)) =
+                                                    (enc, state.tab_manager.active_editor_mut())
+                                                {
+                                                    match ed.reopen_with_encoding(enc) {
+                                                        Ok(()) => {
+                                                            if let Some(tab) = state
+                                                                .tab_manager
+                                                                .tabs
+                                                                .get_mut(state.tab_manager.active)
+                                                            {
+                                                                tab.is_modified = false;
+                                                            }
+                                                        }
+                                                        Err(e) => {
+                                                            self.notifications.show(
+                                                                &format!(
+                                                                    "Could not reopen as {}: {}",
+                                                                    enc, e
+                                                                ),
+                                                                crate::notifications::Level::Error,
+                                                            );
                                                         }
                                                     }
                                                 }
-                                                if let Some(tab) = state
+                                            }
+                                            "merge.keep_buffer" | "merge.take_disk" => {
+                                                let side = if cmd_id == "merge.keep_buffer" {
+                                                    forge_core::ConflictSide::Buffer
+                                                } else {
+                                                    forge_core::ConflictSide::Disk
+                                                };
+                                                let resolved = state
                                                     .tab_manager
-                                                    .tabs
-                                                    .get_mut(state.tab_manager.active)
+                                                    .active_editor_mut()
+                                                    .is_some_and(|ed| {
+                                                        let offset = ed.cursor_offset();
+                                                        let resolved =
+                                                            ed.buffer.resolve_conflict_at(offset, side);
+                                                        ed.rehighlight();
+                                                        resolved
+                                                    });
+                                                if resolved {
+                                                    state.tab_manager.mark_active_modified();
+                                                    Self::notify_lsp(state, &self.rt, &self.lsp_client);
+                                                } else {
+                                                    self.notifications.show(
+                                                        "No merge conflict at the cursor",
+                                                        crate::notifications::Level::Info,
+                                                    );
+                                                }
+                                            }
+                                            id if state.tab_manager.active_session().is_some()
+                                                && (id == "edit.undo_tree"
+                                                    || id.starts_with("edit.redo_branch.")
+                                                    || id.starts_with("edit.earlier.")
+                                                    || id.starts_with("edit.later.")) =>
+                                            {
+                                                self.notifications.show(
+                                                    SHARED_UNDO_NOTICE,
+                                                    crate::notifications::Level::Info,
+                                                );
+                                            }
+                                            "edit.undo_tree" => {
+                                                if let Some(ed) = state.tab_manager.active_editor() {
+                                                    self.undo_tree_panel.open(ed.buffer.history());
+                                                }
+                                            }
+                                            "edit.redo_branch.next" | "edit.redo_branch.prev" => {
+                                                let forward = cmd_id == "edit.redo_branch.next";
+                                                let cycled = state
+                                                    .tab_manager
+                                                    .active_editor_mut()
+                                                    .and_then(|ed| ed.cycle_redo_branch(forward));
+                                                let msg = match cycled {
+                                                    Some((idx, count)) => {
+                                                        format!("Redo branch {} of {}", idx + 1, count)
+                                                    }
+                                                    None => "Nothing to redo".to_string(),
+                                                };
+                                                self.notifications
+                                                    .show(&msg, crate::notifications::Level::Info);
+                                            }
+                                            id if id.starts_with("edit.earlier.")
+                                                || id.starts_with("edit.later.") =>
+                                            {
+                                                let (earlier, secs) = match id.strip_prefix("edit.earlier.") {
+                                                    Some(secs) => (true, secs),
+                                                    None => (false, &id["edit.later.".len()..]),
+                                                };
+                                                let duration = std::time::Duration::from_secs(
+                                                    secs.parse().unwrap_or(0),
+                                                );
+                                                if let Some(ed) = state.tab_manager.active_editor_mut() {
+                                                    let changed = if earlier {
+                                                        ed.buffer.earlier(duration)
+                                                    } else {
+                                                        ed.buffer.later(duration)
+                                                    };
+                                                    if changed {
+                                                        ed.rehighlight();
+                                                        state.tab_manager.mark_active_modified();
+                                                        Self::notify_lsp(
+                                                            state,
+                                                            &self.rt,
+                                                            &self.lsp_client,
+                                                        );
+                                                    }
+                                                }
+                                            }
+                                            id if id.starts_with("editor.eol.convert.") => {
+                                                let target = match &id["editor.eol.convert.".len()..] {
+                                                    "crlf" => forge_core::LineEnding::CRLF,
+                                                    _ => forge_core::LineEnding::LF,
+                                                };
+                                                if let Some(ed) =
+                                                    state.tab_manager.active_editor_mut()
                                                 {
-                                                    tab.is_modified = false;
+                                                    if ed.buffer.convert_line_endings(target) {
+                                                        ed.rehighlight();
+                                                        state.tab_manager.mark_active_modified();
+                                                    }
                                                 }
                                             }
-                                            "file.close" => {
-                                                state.tab_manager.close_current();
+                                            id if id.starts_with("editor.eol.policy.") => {
+                                                let policy = forge_core::LineEndingPolicy::from_id(
+                                                    &id["editor.eol.policy.".len()..],
+                                                );
+                                                if let (Some(policy), Some(ed)) =
+                                                    (policy, state.tab_manager.active_editor_mut())
+                                                {
+                                                    ed.buffer.set_line_ending_policy(policy);
+                                                }
+                                            }
+                                            id if id.starts_with("language.set.") => {
+                                                if let Some(ed) =
+                                                    state.tab_manager.active_editor_mut()
+                                                {
+                                                    ed.set_language(&id["language.set.".len()..]);
+                                                }
+                                            }
+                                            id if id.starts_with("collab.join.") => {
+                                                if let Some(collab) = self.collab.as_mut() {
+                                                    collab.join(&id["collab.join.".len()..]);
+                                                }
+                                            }
+                                            id if id.starts_with("file.save_with_encoding.") => {
+                                                let enc = forge_core::Encoding::from_id(
+                                                    &id["file.save_with_encoding.".len()..],
+                                                );
+                                                if let Some(enc) = enc {
+                                                    let options = forge_core::SaveOptions {
+                                                        backup: self.config.editor.backup_on_save,
+                                                    };
+                                                    if let Err(e) = state
+                                                        .tab_manager
+                                                        .save_active_with_encoding(enc, &options)
+                                                    {
+                                                        self.notifications.show(
+                                                            &format!(
+                                                                "Could not save as {}: {}",
+                                                                enc, e
+                                                            ),
+                                                            crate::notifications::Level::Error,
+                                                        );
+                                                    }
+                                                }
                                             }
                                             _ => {
                                                 self.notifications.show(
@@ -3200,23 +4157,43 @@ impl ApplicationHandler<UserEvent> for Application {
                                     }
                                 }
                             }
+                        } else if self.undo_tree_panel.visible
+                            && state.tab_manager.active_session().is_some()
+                        {
+                            // The tab was shared after the panel opened
+                            self.undo_tree_panel.close();
+                            self.notifications
+                                .show(SHARED_UNDO_NOTICE, crate::notifications::Level::Info);
+                        } else if self.undo_tree_panel.visible {
+                            // Restore the highlighted state; the panel stays open to keep browsing
+                            if let (Some(node), Some(ed)) = (
+                                self.undo_tree_panel.selected_node(),
+                                state.tab_manager.active_editor_mut(),
+                            ) {
+                                if ed.buffer.goto_history_state(node) {
+                                    ed.rehighlight();
+                                    self.undo_tree_panel.refresh(ed.buffer.history());
+                                    state.tab_manager.mark_active_modified();
+                                    Self::notify_lsp(state, &self.rt, &self.lsp_client);
+                                }
+                            }
                         } else if self.go_to_line.visible {
                             if let Some((line, col_opt)) = self.go_to_line.confirm() {
                                 if let Some(ed) = state.tab_manager.active_editor_mut() {
                                     let max_line = ed.total_lines().saturating_sub(1);
                                     let target_line = line.min(max_line);
-                                    let target_col = col_opt.unwrap_or(0);
-                                    let line_start = ed.buffer.line_col_to_offset(target_line, 0);
-                                    let text = ed.buffer.text();
-                                    let line_len =
-                                        text[line_start..].lines().next().unwrap_or("").len();
-                                    let target_col = target_col.min(line_len);
-                                    let offset =
-                                        ed.buffer.line_col_to_offset(target_line, target_col);
-                                    ed.buffer.set_selection(forge_core::Selection::point(
-                                        forge_core::Position::new(offset),
-                                    ));
-                                    ed.set_scroll_top(target_line.saturating_sub(5));
+                                    if ed.mapped.is_some() {
+                                        // Read-only view: there is no cursor to move
+                                        ed.set_scroll_top(target_line.saturating_sub(5));
+                                    } else {
+                                        let target_col = col_opt.unwrap_or(0);
+                                        let pos = ed.buffer.line_col_to_position(
+                                            forge_core::LineCol::new(target_line, target_col),
+                                            forge_core::ColumnUnit::Grapheme,
+                                        );
+                                        ed.buffer.set_selection(forge_core::Selection::point(pos));
+                                        ed.set_scroll_top(target_line.saturating_sub(5));
+                                    }
                                 }
                             }
                             self.go_to_line.cancel();
@@ -3227,8 +4204,7 @@ impl ApplicationHandler<UserEvent> for Application {
                                 .and_then(|idx| self.find_bar.matches.get(idx).cloned());
                             if let Some(m) = current_match {
                                 if let Some(ed) = state.tab_manager.active_editor_mut() {
-                                    let start = ed.buffer.line_col_to_offset(m.line, m.start_col);
-                                    let end = ed.buffer.line_col_to_offset(m.line, m.end_col);
+                                    let (start, end) = (m.start, m.end);
                                     let replacement = self.replace_bar.replace_text.clone();
                                     let change = forge_core::Change::replace(
                                         forge_core::Position::new(start),
@@ -3243,9 +4219,7 @@ impl ApplicationHandler<UserEvent> for Application {
                                     );
                                     ed.buffer.apply(tx);
                                     ed.rehighlight();
-                                    let text = ed.buffer.text();
-                                    let query = self.find_bar.query.clone();
-                                    self.find_bar.search(&text, &query);
+                                    self.find_bar.sync(&mut ed.buffer);
                                 }
                                 if let Some(tab) =
                                     state.tab_manager.tabs.get_mut(state.tab_manager.active)
@@ -3260,8 +4234,22 @@ impl ApplicationHandler<UserEvent> for Application {
                                 );
                             }
                         } else if self.find_bar.visible {
-                            // Navigate to next match when Enter is pressed in find bar
-                            if let Some(m) = self.find_bar.next_match() {
+                            let mapped = state
+                                .tab_manager
+                                .active_editor()
+                                .and_then(|ed| ed.mapped.clone());
+                            if let Some(mapped) = mapped {
+                                // Too big to list every match; step to the next one in
+                                // the background
+                                if !self.find_bar.find_next_mapped(&mapped) {
+                                    self.notifications.show(
+                                        "No matches",
+                                        crate::notifications::Level::Info,
+                                    );
+                                }
+                                state.window.request_redraw();
+                            } else if let Some(m) = self.find_bar.next_match() {
+                                // Navigate to next match when Enter is pressed in find bar
                                 let target_line = m.line;
                                 if let Some(ed) = state.tab_manager.active_editor_mut() {
                                     let offset = ed.buffer.line_col_to_offset(target_line, 0);
@@ -3281,6 +4269,10 @@ impl ApplicationHandler<UserEvent> for Application {
                         }
                     }
                     Key::Named(NamedKey::Escape) => {
+                        // Journals stay on disk and are offered again next launch
+                        if self.recovery_dialog.visible {
+                            self.recovery_dialog.close();
+                        }
                         if self.find_bar.visible {
                             self.find_bar.close();
                         }
@@ -3293,15 +4285,16 @@ impl ApplicationHandler<UserEvent> for Application {
                         if self.command_palette.visible {
                             self.command_palette.close();
                         }
+                        if self.undo_tree_panel.visible {
+                            self.undo_tree_panel.close();
+                        }
                         if self.settings_ui.visible {
                             self.settings_ui.toggle();
                         }
                     }
                     Key::Named(NamedKey::Tab) => {
                         if let Some(ed) = state.tab_manager.active_editor_mut() {
-                            for _ in 0..4 {
-                                ed.insert_char(' ');
-                            }
+                            ed.insert_text("    ");
                             ed.rehighlight();
                         }
                         state.tab_manager.mark_active_modified();
@@ -3309,7 +4302,21 @@ impl ApplicationHandler<UserEvent> for Application {
                     }
 
                     Key::Character(ref c) if !ctrl => {
-                        if self.command_palette.visible {
+                        if self.recovery_dialog.visible {
+                            if c.eq_ignore_ascii_case("d") {
+                                if let Some(journal) = self.recovery_dialog.selected_journal() {
+                                    match state.tab_manager.recovery_diff(journal) {
+                                        Ok(hunks) => self.recovery_dialog.diff = Some(hunks),
+                                        Err(e) => {
+                                            self.notifications.show(
+                                                &format!("Cannot diff: {}", e),
+                                                crate::notifications::Level::Error,
+                                            );
+                                        }
+                                    }
+                                }
+                            }
+                        } else if self.command_palette.visible {
                             for ch in c.chars() {
                                 self.command_palette.type_char(ch);
                             }
@@ -3324,16 +4331,13 @@ impl ApplicationHandler<UserEvent> for Application {
                         } else if self.find_bar.visible {
                             self.find_bar.query.push_str(c);
                             // Live search as user types
-                            if let Some(ed) = state.tab_manager.active_editor() {
-                                let text = ed.buffer.text();
+                            if let Some(ed) = state.tab_manager.active_editor_mut() {
                                 let query = self.find_bar.query.clone();
-                                self.find_bar.search(&text, &query);
+                                self.find_bar.search(&mut ed.buffer, &query);
                             }
                         } else {
                             if let Some(ed) = state.tab_manager.active_editor_mut() {
-                                for ch in c.chars() {
-                                    ed.insert_char(ch);
-                                }
+                                ed.type_text(c);
                                 ed.rehighlight();
                             }
                             // Mark tab as modified
@@ -3403,6 +4407,43 @@ impl ApplicationHandler<UserEvent> for Application {
             }
 
             WindowEvent::RedrawRequested => {
+                Self::handle_app_events(state, crate::go_to_def::column_unit(&self.lsp_client));
+                Self::poll_collab(
+                    &mut self.collab,
+                    state,
+                    &mut self.notifications,
+                    &mut self.command_palette,
+                    &self.rt,
+                    &self.lsp_client,
+                );
+                let display = forge_core::DisplayOptions {
+                    tab_size: self.config.editor.tab_size,
+                    wrap_width: self.config.editor.word_wrap.then(|| {
+                        (state.layout.editor.width / LayoutConstants::CHAR_WIDTH) as usize
+                    }),
+                };
+                if let Some(ed) = state.tab_manager.active_editor_mut() {
+                    self.find_bar.sync(&mut ed.buffer);
+                    ed.set_display_options(display);
+                    ed.set_indent(
+                        self.config.editor.tab_size,
+                        self.config.editor.insert_spaces,
+                    );
+                    if let Some(mapped) = ed.mapped.clone() {
+                        match self.find_bar.poll_mapped(&mapped) {
+                            Some(Some(m)) => ed.set_scroll_top(m.line.saturating_sub(5)),
+                            Some(None) => {
+                                self.notifications
+                                    .show("No matches", crate::notifications::Level::Info);
+                            }
+                            None => {}
+                        }
+                    }
+                }
+                if self.find_bar.is_searching_mapped() {
+                    // Keep polling until the background search finishes
+                    state.window.request_redraw();
+                }
                 Self::render(
                     &mut self.extension_host,
                     state,
@@ -3412,6 +4453,8 @@ impl ApplicationHandler<UserEvent> for Application {
                     &self.find_bar,
                     &self.replace_bar,
                     &self.go_to_line,
+                    &self.undo_tree_panel,
+                    &self.recovery_dialog,
                     &self.command_palette,
                     &mut self.search_panel,
                     &self.settings_ui,
@@ -3422,6 +4465,39 @@ impl ApplicationHandler<UserEvent> for Application {
             }
 
             _ => {
+                // Status bar items open the matching picker in the command palette
+                if let WindowEvent::MouseInput {
+                    state: ElementState::Pressed,
+                    button: winit::event::MouseButton::Left,
+                    ..
+                } = event
+                {
+                    if let Some((mx, my)) = state.last_mouse_position {
+                        if state.layout.status_bar.contains(mx, my) {
+                            let action = state.status_bar_state.action_at(
+                                mx,
+                                &state.layout.status_bar,
+                                &self.theme,
+                            );
+                            let query = match action {
+                                Some(StatusAction::SelectEncoding) => Some("Encoding"),
+                                Some(StatusAction::SelectLineEnding) => Some("Line Ending"),
+                                Some(StatusAction::SelectLanguage) => Some("Change Language Mode"),
+                                Some(StatusAction::OpenCommandPalette) => Some(""),
+                                _ => None,
+                            };
+                            if let Some(query) = query {
+                                self.command_palette
+                                    .open(crate::command_palette::PaletteMode::Commands);
+                                for c in query.chars() {
+                                    self.command_palette.type_char(c);
+                                }
+                                state.window.request_redraw();
+                                return;
+                            }
+                        }
+                    }
+                }
                 Self::handle_input(
                     self.modifiers,
                     state,
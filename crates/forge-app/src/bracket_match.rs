@@ -1,161 +1,165 @@
+use forge_syntax::LanguageConfig;
+
 pub struct BracketMatcher;
 
-impl BracketMatcher {
-    pub fn find_match(text: &str, line: usize, col: usize) -> Option<(usize, usize)> {
-        // Identify the bracket at the target position to ensure it is a bracket
-        // This is a bit inefficient since we scan everything anyway, but good for early exit?
-        // Actually, we can just run the full scan and check if we find a pair involving (line, col).
+#[derive(Clone, Copy, PartialEq)]
+enum State<'a> {
+    Code,
+    LineComment,
+    /// In a block comment, ended by the token
+    BlockComment(&'a str),
+    /// In a string, ended by the quote
+    String(char),
+}
 
+impl BracketMatcher {
+    /// The bracket matching the one at `line`, `col` (in chars), skipping
+    /// brackets in the language's comments and strings
+    pub fn find_match(
+        text: &str,
+        line: usize,
+        col: usize,
+        config: &LanguageConfig,
+    ) -> Option<(usize, usize)> {
         let target_pos = (line, col);
+        // Only single-character pairs are matched; quotes are the
+        // auto-closing pairs that close with the same character
+        let brackets: Vec<(char, char)> = config
+            .brackets
+            .iter()
+            .filter_map(|(open, close)| Some((Self::single(open)?, Self::single(close)?)))
+            .collect();
+        let quotes: Vec<char> = config
+            .auto_closing_pairs
+            .iter()
+            .filter(|(open, close)| open == close)
+            .filter_map(|(open, _)| Self::single(open))
+            .collect();
+        let line_comment = config.line_comment.as_deref();
+        let block_comment = config.block_comment.as_ref();
 
         // Stack stores (char, (line, col))
         let mut stack: Vec<(char, (usize, usize))> = Vec::new();
-
         let mut current_line = 0;
         let mut current_col = 0;
+        let mut state = State::Code;
+        let mut i = 0;
 
-        let mut chars = text.char_indices().peekable();
-
-        let mut in_string = false;
-        let mut in_line_comment = false;
-        let mut in_block_comment = false;
-
-        // Helper to check if we match the target
-        let is_target = |l, c| l == target_pos.0 && c == target_pos.1;
-
-        while let Some((_, c)) = chars.next() {
+        while let Some(c) = text[i..].chars().next() {
+            let rest = &text[i..];
             // Handle newlines for line/col tracking
             if c == '\n' {
                 current_line += 1;
                 current_col = 0;
-                in_line_comment = false;
-                // Keep in_string state for multiline strings
-                continue;
-            }
-
-            let pos = (current_line, current_col);
-            current_col += 1; // Advance col for NEXT char? No, current char is at current_col.
-                              // Actually, if I increment after processing, then (line, col) matches my logic.
-                              // Let's assume 0-indexed cols.
-
-            // State machine update
-            if in_line_comment {
-                continue;
-            }
-
-            if in_block_comment {
-                if c == '*' {
-                    if let Some((_, '/')) = chars.peek() {
-                        chars.next(); // consume '/'
-                        current_col += 1;
-                        in_block_comment = false;
-                    }
+                i += 1;
+                if state == State::LineComment {
+                    state = State::Code;
                 }
+                // Keep string state for multiline strings
                 continue;
             }
 
-            if in_string {
-                if c == '\\' {
-                    // Escape next char
-                    if let Some(_) = chars.next() {
-                        current_col += 1;
+            let pos = (current_line, current_col);
+            let mut len = c.len_utf8();
+            match state {
+                State::LineComment => {}
+                State::BlockComment(close) => {
+                    if rest.starts_with(close) {
+                        len = close.len();
+                        state = State::Code;
                     }
-                } else if c == '"' {
-                    in_string = false;
                 }
-                continue;
-            }
-
-            // Normal state
-            match c {
-                '/' => {
-                    if let Some((_, '/')) = chars.peek() {
-                        chars.next();
-                        current_col += 1;
-                        in_line_comment = true;
-                        continue;
-                    } else if let Some((_, '*')) = chars.peek() {
-                        chars.next();
-                        current_col += 1;
-                        in_block_comment = true;
-                        continue;
+                State::String(quote) => {
+                    if c == '\\' {
+                        // Escape next char
+                        if let Some(escaped) = rest[1..].chars().next().filter(|&e| e != '\n') {
+                            len += escaped.len_utf8();
+                        }
+                    } else if c == quote {
+                        state = State::Code;
                     }
                 }
-                '"' => {
-                    in_string = true;
-                    continue;
-                }
-                '(' | '[' | '{' => {
-                    stack.push((c, pos));
-                }
-                ')' | ']' | '}' => {
-                    if let Some((open_char, open_pos)) = stack.pop() {
-                        if !Self::matches(open_char, c) {
-                            // Mismatched bracket, ignore or handle?
-                            // For simple matching, maybe we just assume code is well-formed or we ignore mismatches.
-                            // But if we popped, we consumed the open bracket.
-                            // If it doesn't match, strictly speaking, it's a syntax error.
-                            // But let's proceed.
-                        } else {
-                            // Check if this pair involves our target
-                            if is_target(open_pos.0, open_pos.1) {
-                                return Some(pos);
-                            }
-                            if is_target(pos.0, pos.1) {
-                                return Some(open_pos);
+                State::Code => {
+                    if let Some(token) = line_comment.filter(|token| rest.starts_with(token)) {
+                        len = token.len();
+                        state = State::LineComment;
+                    } else if let Some((open, close)) =
+                        block_comment.filter(|(open, _)| rest.starts_with(open.as_str()))
+                    {
+                        len = open.len();
+                        state = State::BlockComment(close);
+                    } else if quotes.contains(&c) {
+                        state = State::String(c);
+                    } else if brackets.iter().any(|&(open, _)| open == c) {
+                        stack.push((c, pos));
+                    } else if let Some(&(open, _)) = brackets.iter().find(|&&(_, close)| close == c)
+                    {
+                        // A mismatched bracket is a syntax error; it still
+                        // consumes the open one
+                        if let Some((open_char, open_pos)) = stack.pop() {
+                            if open_char == open {
+                                // Check if this pair involves our target
+                                if open_pos == target_pos {
+                                    return Some(pos);
+                                }
+                                if pos == target_pos {
+                                    return Some(open_pos);
+                                }
                             }
                         }
                     }
                 }
-                _ => {}
             }
+            current_col += rest[..len].chars().count();
+            i += len;
         }
 
         None
     }
 
-    fn matches(open: char, close: char) -> bool {
-        match (open, close) {
-            ('(', ')') => true,
-            ('[', ']') => true,
-            ('{', '}') => true,
-            _ => false,
-        }
+    fn single(token: &str) -> Option<char> {
+        let mut chars = token.chars();
+        let c = chars.next()?;
+        chars.next().is_none().then_some(c)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use forge_syntax::LanguageConfigs;
 
     #[test]
     fn test_match_basic() {
+        let rust = LanguageConfigs::global().get("rust");
         let text = "fn foo() { }";
         // ( is at 0:6
         // ) is at 0:7
         // { is at 0:9
         // } is at 0:11
 
-        assert_eq!(BracketMatcher::find_match(text, 0, 6), Some((0, 7)));
-        assert_eq!(BracketMatcher::find_match(text, 0, 7), Some((0, 6)));
-        assert_eq!(BracketMatcher::find_match(text, 0, 9), Some((0, 11)));
-        assert_eq!(BracketMatcher::find_match(text, 0, 11), Some((0, 9)));
+        assert_eq!(BracketMatcher::find_match(text, 0, 6, &rust), Some((0, 7)));
+        assert_eq!(BracketMatcher::find_match(text, 0, 7, &rust), Some((0, 6)));
+        assert_eq!(BracketMatcher::find_match(text, 0, 9, &rust), Some((0, 11)));
+        assert_eq!(BracketMatcher::find_match(text, 0, 11, &rust), Some((0, 9)));
     }
 
     #[test]
     fn test_match_nested() {
+        let rust = LanguageConfigs::global().get("rust");
         let text = "(( ))";
         // Outer ( at 0
         // Inner ( at 1
         // Inner ) at 3
         // Outer ) at 4
 
-        assert_eq!(BracketMatcher::find_match(text, 0, 0), Some((0, 4)));
-        assert_eq!(BracketMatcher::find_match(text, 0, 1), Some((0, 3)));
+        assert_eq!(BracketMatcher::find_match(text, 0, 0, &rust), Some((0, 4)));
+        assert_eq!(BracketMatcher::find_match(text, 0, 1, &rust), Some((0, 3)));
     }
 
     #[test]
     fn test_skip_strings() {
+        let rust = LanguageConfigs::global().get("rust");
         let text = "let s = \"(\"; )";
         // "(\" is a string. The ( inside should be ignored.
         // The last ) at 13 has no match (or matches something before?).
@@ -164,7 +168,7 @@ mod tests {
         // let s = "("; )
 
         // The ( inside string is at 9.
-        assert_eq!(BracketMatcher::find_match(text, 0, 9), None);
+        assert_eq!(BracketMatcher::find_match(text, 0, 9, &rust), None);
 
         // If we had a real pair outside:
         let text2 = "( \" ) \" )";
@@ -175,16 +179,27 @@ mod tests {
         // 8: )
 
         // The ( at 0 matches ) at 8. ) at 4 is ignored.
-        assert_eq!(BracketMatcher::find_match(text2, 0, 0), Some((0, 8)));
+        assert_eq!(BracketMatcher::find_match(text2, 0, 0, &rust), Some((0, 8)));
     }
 
     #[test]
     fn test_skip_comments() {
+        let rust = LanguageConfigs::global().get("rust");
         let text = "( // ) \n )";
         // ( at 0
         // ) at 5 is in comment
         // ) at 9 (line 1, col 1) is real match
 
-        assert_eq!(BracketMatcher::find_match(text, 0, 0), Some((1, 1)));
+        assert_eq!(BracketMatcher::find_match(text, 0, 0, &rust), Some((1, 1)));
+    }
+
+    #[test]
+    fn test_comment_tokens_follow_the_language() {
+        let python = LanguageConfigs::global().get("python");
+        let text = "( # )\n ')' )";
+        assert_eq!(BracketMatcher::find_match(text, 0, 0, &python), Some((1, 5)));
+        // `#` isn't a comment in Rust
+        let rust = LanguageConfigs::global().get("rust");
+        assert_eq!(BracketMatcher::find_match("( # )", 0, 0, &rust), Some((0, 4)));
     }
 }
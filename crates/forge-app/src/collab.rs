@@ -0,0 +1,263 @@
+use crate::tab_manager::TabManager;
+use anyhow::Result;
+use forge_collab::Connection;
+use forge_core::collab::{ClientMessage, PeerId, ServerMessage};
+use forge_core::Session;
+use std::path::PathBuf;
+use tokio::runtime::Runtime;
+
+/// Colors of collaborators' cursors and selections, picked by peer id
+const PEER_COLORS: [[f32; 4]; 6] = [
+    [0.95, 0.55, 0.25, 1.0],
+    [0.40, 0.80, 0.45, 1.0],
+    [0.80, 0.45, 0.90, 1.0],
+    [0.30, 0.75, 0.95, 1.0],
+    [0.95, 0.80, 0.30, 1.0],
+    [0.95, 0.40, 0.55, 1.0],
+];
+
+pub fn peer_color(peer: PeerId) -> [f32; 4] {
+    PEER_COLORS[peer as usize % PEER_COLORS.len()]
+}
+
+/// What a collaboration is waiting for or doing
+enum Stage {
+    /// Asked the relay which rooms are open
+    Browsing,
+    /// Opening a room for the file at `path`
+    Hosting {
+        room: String,
+        path: PathBuf,
+    },
+    Joining(String),
+    /// In this room, through the shared tab
+    Active(String),
+}
+
+/// Things the application has to act on
+#[derive(Debug, PartialEq)]
+pub enum CollabEvent {
+    /// The rooms open on the relay, to offer for joining
+    Rooms(Vec<String>),
+    Notice(String),
+    /// The session is over; drop the collaboration
+    Ended(String),
+}
+
+/// A connection to the relay and the session it carries
+pub struct Collaboration {
+    connection: Connection,
+    name: String,
+    stage: Stage,
+}
+
+impl Collaboration {
+    /// Connect to the relay; `wake` gets the UI to call [`Collaboration::poll`]
+    pub fn connect(
+        rt: &Runtime,
+        config: &forge_config::CollabConfig,
+        wake: impl Fn() + Send + 'static,
+    ) -> Result<Self> {
+        let connection = Connection::connect(rt, &config.relay, wake)?;
+        Ok(Self {
+            connection,
+            name: config.display_name(),
+            stage: Stage::Browsing,
+        })
+    }
+
+    /// Open a room for the file at `path`, named `room`, holding `text`
+    pub fn host(&mut self, room: &str, path: PathBuf, text: String) {
+        self.connection.send(ClientMessage::Join {
+            room: room.to_string(),
+            name: self.name.clone(),
+            text: Some(text),
+        });
+        self.stage = Stage::Hosting {
+            room: room.to_string(),
+            path,
+        };
+    }
+
+    /// Ask for the rooms that can be joined
+    pub fn browse(&mut self) {
+        self.connection.send(ClientMessage::ListRooms);
+        self.stage = Stage::Browsing;
+    }
+
+    pub fn join(&mut self, room: &str) {
+        self.connection.send(ClientMessage::Join {
+            room: room.to_string(),
+            name: self.name.clone(),
+            text: None,
+        });
+        self.stage = Stage::Joining(room.to_string());
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self.stage, Stage::Active(_))
+    }
+
+    /// Feed the relay's messages to the shared tab and send its edits.
+    /// Returns whether the shared tab's text changed.
+    pub fn poll(&mut self, tabs: &mut TabManager, events: &mut Vec<CollabEvent>) -> bool {
+        let mut changed = false;
+        while let Some(message) = self.connection.try_recv() {
+            match message {
+                ServerMessage::Rooms { rooms } => events.push(CollabEvent::Rooms(rooms)),
+                ServerMessage::Welcome {
+                    peer,
+                    revision,
+                    text,
+                    peers,
+                } => {
+                    let session = Session::new(peer, revision, &text, peers);
+                    match std::mem::replace(&mut self.stage, Stage::Browsing) {
+                        Stage::Hosting { room, path } => {
+                            if let Err(e) = tabs.share(&path, session) {
+                                events.push(CollabEvent::Ended(e.to_string()));
+                                return changed;
+                            }
+                            events.push(CollabEvent::Notice(format!(
+                                "Sharing {}; others can join it from the command palette",
+                                room
+                            )));
+                            self.stage = Stage::Active(room);
+                        }
+                        Stage::Joining(room) => {
+                            tabs.open_shared(&room, &text, session);
+                            events.push(CollabEvent::Notice(format!("Joined {}", room)));
+                            self.stage = Stage::Active(room);
+                        }
+                        stage => self.stage = stage,
+                    }
+                }
+                ServerMessage::Error { message } => {
+                    if self.is_active() && !message.starts_with("Disconnected") {
+                        events.push(CollabEvent::Notice(message));
+                    } else {
+                        events.push(CollabEvent::Ended(message));
+                        return changed;
+                    }
+                }
+                message => {
+                    let Some(tab) = tabs.shared_tab_mut() else {
+                        continue;
+                    };
+                    let Some(session) = tab.collab.as_mut() else {
+                        continue;
+                    };
+                    match &message {
+                        ServerMessage::Joined { peer } => {
+                            events.push(CollabEvent::Notice(format!("{} joined", peer.name)));
+                        }
+                        ServerMessage::Left { peer } => {
+                            if let Some(info) = session.peer(*peer) {
+                                events.push(CollabEvent::Notice(format!("{} left", info.name)));
+                            }
+                        }
+                        ServerMessage::Edit { peer, .. } if *peer != session.id() => {
+                            changed = true;
+                            tab.is_modified = true;
+                        }
+                        _ => {}
+                    }
+                    session.receive(message, &mut tab.editor.buffer);
+                }
+            }
+        }
+
+        if let Stage::Active(room) = &self.stage {
+            let Some(tab) = tabs.shared_tab_mut() else {
+                events.push(CollabEvent::Ended(format!("Left {}", room)));
+                return changed;
+            };
+            if let Some(session) = tab.collab.as_mut() {
+                session.sync(&mut tab.editor.buffer);
+                for message in session.take_outgoing() {
+                    self.connection.send(message);
+                }
+            }
+            if changed {
+                tab.editor.rehighlight();
+            }
+        }
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge_core::{Change, Position, Transaction};
+    use std::time::{Duration, Instant};
+
+    fn poll_until(
+        collab: &mut Collaboration,
+        tabs: &mut TabManager,
+        mut done: impl FnMut(&TabManager, &[CollabEvent]) -> bool,
+    ) -> Vec<CollabEvent> {
+        let start = Instant::now();
+        let mut events = Vec::new();
+        loop {
+            collab.poll(tabs, &mut events);
+            if done(tabs, &events) {
+                return events;
+            }
+            assert!(start.elapsed() < Duration::from_secs(5), "{:?}", events);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn test_guest_edits_reach_the_shared_file() {
+        let rt = Runtime::new().unwrap();
+        let listener = rt
+            .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+            .unwrap();
+        let config = forge_config::CollabConfig {
+            relay: listener.local_addr().unwrap().to_string(),
+            name: Some("host".into()),
+        };
+        rt.spawn(forge_collab::serve(listener));
+
+        let path = std::env::temp_dir().join("forge_collab_shared.txt");
+        std::fs::write(&path, "fn main() {}\n").unwrap();
+        let mut host_tabs = TabManager::new();
+        host_tabs.open_file(&path.to_string_lossy()).unwrap();
+        let mut host = Collaboration::connect(&rt, &config, || {}).unwrap();
+        host.host("shared.txt", path.clone(), "fn main() {}\n".into());
+        poll_until(&mut host, &mut host_tabs, |tabs, _| {
+            tabs.shared_tab().is_some()
+        });
+
+        let mut guest_tabs = TabManager::new();
+        let mut guest = Collaboration::connect(&rt, &config, || {}).unwrap();
+        guest.browse();
+        let events = poll_until(&mut guest, &mut guest_tabs, |_, events| !events.is_empty());
+        assert_eq!(events, vec![CollabEvent::Rooms(vec!["shared.txt".into()])]);
+        guest.join("shared.txt");
+        poll_until(&mut guest, &mut guest_tabs, |tabs, _| {
+            tabs.shared_tab().is_some()
+        });
+
+        let (_, editor) = guest_tabs.active_session_mut().unwrap();
+        let change = Change::insert(Position::new(11), " todo!() ".into());
+        editor.buffer.apply(Transaction::from_change(change));
+        poll_until(&mut guest, &mut guest_tabs, |tabs, _| {
+            tabs.shared_tab()
+                .and_then(|t| t.collab.as_ref())
+                .is_some_and(|s| s.is_synced())
+        });
+        poll_until(&mut host, &mut host_tabs, |tabs, _| {
+            tabs.shared_tab().unwrap().editor.buffer.text() == "fn main() { todo!() }\n"
+        });
+        assert!(host_tabs.shared_tab().unwrap().is_modified);
+
+        // Closing the shared tab ends the session for the guest
+        guest_tabs.close_current();
+        let events = poll_until(&mut guest, &mut guest_tabs, |_, events| !events.is_empty());
+        assert!(matches!(events[..], [CollabEvent::Ended(_)]));
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -78,6 +78,31 @@ impl CommandPalette {
             ("file.quit", "File: Quit", Some("Ctrl+Q"), "File"),
             ("edit.undo", "Edit: Undo", Some("Ctrl+Z"), "Edit"),
             ("edit.redo", "Edit: Redo", Some("Ctrl+Y"), "Edit"),
+            ("edit.undo_tree", "Edit: Show Undo Tree", None, "Edit"),
+            (
+                "edit.redo_branch.next",
+                "Edit: Next Redo Branch",
+                None,
+                "Edit",
+            ),
+            (
+                "edit.redo_branch.prev",
+                "Edit: Previous Redo Branch",
+                None,
+                "Edit",
+            ),
+            (
+                "merge.keep_buffer",
+                "Merge: Keep Buffer Version of Conflict",
+                None,
+                "Edit",
+            ),
+            (
+                "merge.take_disk",
+                "Merge: Take Disk Version of Conflict",
+                None,
+                "Edit",
+            ),
             ("edit.cut", "Edit: Cut", Some("Ctrl+X"), "Edit"),
             ("edit.copy", "Edit: Copy", Some("Ctrl+C"), "Edit"),
             ("edit.paste", "Edit: Paste", Some("Ctrl+V"), "Edit"),
@@ -89,6 +114,156 @@ impl CommandPalette {
                 "Edit",
             ),
             ("edit.replace", "Edit: Replace", Some("Ctrl+H"), "Edit"),
+            (
+                "selection.expand",
+                "Selection: Expand Selection",
+                Some("Shift+Alt+Right"),
+                "Selection",
+            ),
+            (
+                "selection.shrink",
+                "Selection: Shrink Selection",
+                Some("Shift+Alt+Left"),
+                "Selection",
+            ),
+            (
+                "selection.next_sibling",
+                "Selection: Select Next Sibling Node",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.prev_sibling",
+                "Selection: Select Previous Sibling Node",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.enclosing_function",
+                "Selection: Select Enclosing Function",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.enclosing_class",
+                "Selection: Select Enclosing Class",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.word",
+                "Selection: Select Word",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.paragraph",
+                "Selection: Select Paragraph",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.inside_brackets",
+                "Selection: Select Inside Brackets",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.around_brackets",
+                "Selection: Select Around Brackets",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.inside_quotes",
+                "Selection: Select Inside Quotes",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.function_body",
+                "Selection: Select Function Body",
+                None,
+                "Selection",
+            ),
+            (
+                "selection.argument",
+                "Selection: Select Argument",
+                None,
+                "Selection",
+            ),
+            (
+                "search.find_in_member",
+                "Search: Find in Current Sub-project",
+                None,
+                "Edit",
+            ),
+            (
+                "search.open_in_multibuffer",
+                "Search: Open Results in Multi-Buffer",
+                None,
+                "Edit",
+            ),
+            (
+                "editor.find_references",
+                "Editor: Find All References",
+                None,
+                "Editor",
+            ),
+            (
+                "problems.open_in_multibuffer",
+                "Problems: Open in Multi-Buffer",
+                None,
+                "View",
+            ),
+            (
+                "multibuffer.expand_excerpt",
+                "Multi-Buffer: Show More Context",
+                None,
+                "Editor",
+            ),
+            (
+                "multibuffer.open_excerpt",
+                "Multi-Buffer: Open Excerpt in Editor",
+                None,
+                "Editor",
+            ),
+            (
+                "collab.share",
+                "Collaboration: Share Current File",
+                None,
+                "Collaboration",
+            ),
+            (
+                "collab.join",
+                "Collaboration: Join Session",
+                None,
+                "Collaboration",
+            ),
+            (
+                "collab.follow",
+                "Collaboration: Follow Collaborator",
+                None,
+                "Collaboration",
+            ),
+            (
+                "collab.leave",
+                "Collaboration: Leave Session",
+                None,
+                "Collaboration",
+            ),
+            (
+                "task.build_member",
+                "Tasks: Build Current Sub-project",
+                None,
+                "Tasks",
+            ),
+            (
+                "task.test_member",
+                "Tasks: Test Current Sub-project",
+                None,
+                "Tasks",
+            ),
             (
                 "view.command_palette",
                 "View: Command Palette",
@@ -108,6 +283,7 @@ impl CommandPalette {
                 "View",
             ),
             ("view.minimap", "View: Toggle Minimap", None, "View"),
+            ("view.word_wrap", "View: Toggle Word Wrap", None, "View"),
             ("help.about", "Help: About", None, "Help"),
             // Add more to reach 30+
             (
@@ -134,6 +310,13 @@ impl CommandPalette {
                 Some("Ctrl+/"),
                 "Editor",
             ),
+            (
+                "editor.reindent_selection",
+                "Editor: Reindent Selected Lines",
+                None,
+                "Editor",
+            ),
+            ("editor.reindent_file", "Editor: Reindent File", None, "Editor"),
             ("git.commit", "Git: Commit", None, "Git"),
             ("git.push", "Git: Push", None, "Git"),
             ("git.pull", "Git: Pull", None, "Git"),
@@ -162,6 +345,86 @@ impl CommandPalette {
                 category: Some(category.to_string()),
             });
         }
+
+        for (target, label) in [("lf", "LF"), ("crlf", "CRLF")] {
+            self.commands.push(Command {
+                id: format!("editor.eol.convert.{}", target),
+                label: format!("Editor: Convert Line Endings to {}", label),
+                shortcut: None,
+                category: Some("Editor".to_string()),
+            });
+        }
+        for (policy, label) in [
+            (forge_core::LineEndingPolicy::Preserve, "Preserve"),
+            (forge_core::LineEndingPolicy::Lf, "Force LF on Save"),
+            (forge_core::LineEndingPolicy::Crlf, "Force CRLF on Save"),
+        ] {
+            self.commands.push(Command {
+                id: format!("editor.eol.policy.{}", policy.id()),
+                label: format!("Editor: Line Ending Policy: {}", label),
+                shortcut: None,
+                category: Some("Editor".to_string()),
+            });
+        }
+
+        // Time-based undo, like Vim's :earlier / :later
+        for (secs, label) in [(10, "10s"), (60, "1m"), (600, "10m"), (3600, "1h")] {
+            self.commands.push(Command {
+                id: format!("edit.earlier.{}", secs),
+                label: format!("Edit: Earlier {}", label),
+                shortcut: None,
+                category: Some("Edit".to_string()),
+            });
+            self.commands.push(Command {
+                id: format!("edit.later.{}", secs),
+                label: format!("Edit: Later {}", label),
+                shortcut: None,
+                category: Some("Edit".to_string()),
+            });
+        }
+
+        // One entry per encoding so the fuzzy filter doubles as the picker
+        for enc in forge_core::Encoding::ALL {
+            self.commands.push(Command {
+                id: format!("file.reopen_with_encoding.{}", enc.id()),
+                label: format!("File: Reopen with Encoding: {}", enc.label()),
+                shortcut: None,
+                category: Some("File".to_string()),
+            });
+            self.commands.push(Command {
+                id: format!("file.save_with_encoding.{}", enc.id()),
+                label: format!("File: Save with Encoding: {}", enc.label()),
+                shortcut: None,
+                category: Some("File".to_string()),
+            });
+        }
+    }
+
+    /// One Change Language Mode entry per configured language, given as
+    /// `(id, display name)` pairs
+    pub fn set_languages(&mut self, languages: &[(String, String)]) {
+        self.commands.retain(|c| !c.id.starts_with("language.set."));
+        for (id, name) in languages {
+            self.commands.push(Command {
+                id: format!("language.set.{}", id),
+                label: format!("Change Language Mode: {}", name),
+                shortcut: None,
+                category: Some("Editor".to_string()),
+            });
+        }
+    }
+
+    /// Offer one entry per room open on the relay, replacing the last list
+    pub fn set_collab_rooms(&mut self, rooms: &[String]) {
+        self.commands.retain(|c| !c.id.starts_with("collab.join."));
+        for room in rooms {
+            self.commands.push(Command {
+                id: format!("collab.join.{}", room),
+                label: format!("Collaboration: Join Room: {}", room),
+                shortcut: None,
+                category: Some("Collaboration".to_string()),
+            });
+        }
     }
 
     pub fn open(&mut self, mode: PaletteMode) {
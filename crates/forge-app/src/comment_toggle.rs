@@ -1,28 +1,22 @@
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Language {
-    Rust,
-    JavaScript,
-    Python,
-    Html,
-    Css,
-    Go,
-    Other,
-}
+use forge_syntax::LanguageConfig;
 
 pub struct CommentToggler;
 
 impl CommentToggler {
-    fn get_comment_syntax(lang: Language) -> (&'static str, Option<&'static str>) {
-        match lang {
-            Language::Rust | Language::JavaScript | Language::Go | Language::Other => ("//", None),
-            Language::Python => ("#", None),
-            Language::Html => ("<!--", Some("-->")),
-            Language::Css => ("/*", Some("*/")),
+    /// The language's line comment token, or its block comment tokens when
+    /// it has no line comments
+    fn get_comment_syntax(config: &LanguageConfig) -> Option<(&str, Option<&str>)> {
+        match (&config.line_comment, &config.block_comment) {
+            (Some(line), _) => Some((line, None)),
+            (None, Some((open, close))) => Some((open, Some(close))),
+            (None, None) => None,
         }
     }
 
-    pub fn toggle_line(line: &str, lang: Language) -> String {
-        let (start_token, end_token) = Self::get_comment_syntax(lang);
+    pub fn toggle_line(line: &str, config: &LanguageConfig) -> String {
+        let Some((start_token, end_token)) = Self::get_comment_syntax(config) else {
+            return line.to_string();
+        };
         let trimmed = line.trim_start();
 
         // Check if line is commented
@@ -80,8 +74,10 @@ impl CommentToggler {
         }
     }
 
-    pub fn toggle_block(lines: &[&str], lang: Language) -> Vec<String> {
-        let (start_token, end_token) = Self::get_comment_syntax(lang);
+    pub fn toggle_block(lines: &[&str], config: &LanguageConfig) -> Vec<String> {
+        let Some((start_token, end_token)) = Self::get_comment_syntax(config) else {
+            return lines.iter().map(|line| line.to_string()).collect();
+        };
 
         let all_commented = lines
             .iter()
@@ -94,7 +90,7 @@ impl CommentToggler {
                     if line.trim().is_empty() {
                         line.to_string()
                     } else {
-                        Self::toggle_line(line, lang)
+                        Self::toggle_line(line, config)
                     }
                 } else {
                     let is_commented = line.trim_start().starts_with(start_token);
@@ -110,7 +106,7 @@ impl CommentToggler {
                             format!("{}{}{}{}", indent, start_token, " ", content)
                         }
                     } else {
-                        Self::toggle_line(line, lang)
+                        Self::toggle_line(line, config)
                     }
                 }
             })
@@ -121,39 +117,43 @@ impl CommentToggler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use forge_syntax::LanguageConfigs;
 
     #[test]
     fn test_toggle_line_rust() {
+        let rust = LanguageConfigs::global().get("rust");
         let line = "    let x = 1;";
-        let commented = CommentToggler::toggle_line(line, Language::Rust);
+        let commented = CommentToggler::toggle_line(line, &rust);
         assert_eq!(commented, "    // let x = 1;");
 
-        let uncommented = CommentToggler::toggle_line(&commented, Language::Rust);
+        let uncommented = CommentToggler::toggle_line(&commented, &rust);
         assert_eq!(uncommented, "    let x = 1;");
     }
 
     #[test]
     fn test_toggle_line_html() {
+        let html = LanguageConfigs::global().get("html");
         let line = "<div>";
-        let commented = CommentToggler::toggle_line(line, Language::Html);
+        let commented = CommentToggler::toggle_line(line, &html);
         assert!(commented.contains("<!-- <div> -->")); // exact spacing might vary
 
-        let uncommented = CommentToggler::toggle_line(&commented, Language::Html);
+        let uncommented = CommentToggler::toggle_line(&commented, &html);
         assert_eq!(uncommented, "<div>");
     }
 
     #[test]
     fn test_toggle_block() {
+        let rust = LanguageConfigs::global().get("rust");
         let lines = vec!["a", "b"];
         // Comment all
-        let commented = CommentToggler::toggle_block(&lines, Language::Rust);
+        let commented = CommentToggler::toggle_block(&lines, &rust);
         assert_eq!(commented[0], "// a");
         assert_eq!(commented[1], "// b");
 
         // Uncomment all
         let uncommented = CommentToggler::toggle_block(
             &commented.iter().map(|s| s.as_str()).collect::<Vec<_>>(),
-            Language::Rust,
+            &rust,
         );
         assert_eq!(uncommented[0], "a");
         assert_eq!(uncommented[1], "b");
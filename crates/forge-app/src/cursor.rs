@@ -148,7 +148,6 @@ impl CursorRenderer {
     }
 
     /// Generate selection rectangles
-    #[allow(dead_code)]
     pub fn selection_rects(
         &self,
         sel_start_line: usize,
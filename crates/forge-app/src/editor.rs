@@ -1,28 +1,60 @@
 //! Editor state — manages the text buffer, cursor, and viewport
 
-use forge_core::{Buffer, Change, ChangeSet, Position, Selection, Transaction};
-use forge_syntax::{HighlightSpan, Highlighter, Language, SyntaxParser};
+use crate::code_folding::FoldingManager;
+use forge_core::motion::{Motion, Scope, TextObject};
+use forge_core::syntax_selection::{self, Enclosing};
+use forge_core::{
+    Assoc, Buffer, Change, ChangeSet, ColumnUnit, DisplayOptions, DisplayPoint, InlayKind,
+    LargeFileLimits, LineCol, LineEdit, MappedFile, OpenMode, Position, Range, Selection,
+    Transaction,
+};
+use forge_syntax::{
+    HighlightSpan, Highlighter, Language, LanguageConfig, LanguageConfigs, TextMateGrammars,
+    TextMateHighlighter,
+};
+use std::borrow::Cow;
+use std::sync::Arc;
 use tracing::info;
 
 /// The editor state: buffer + cursor logic + viewport
 pub struct Editor {
     /// The text buffer (rope-backed)
     pub buffer: Buffer,
-    /// Vertical scroll offset in lines
+    /// Vertical scroll offset in display rows
     pub scroll_y: f64,
     /// Whether the cursor should be visible (for blink)
     #[allow(dead_code)]
     pub cursor_visible: bool,
     /// Window title (derived from file path)
     pub title: String,
-    /// Syntax parser for tree-sitter highlighting
-    pub syntax_parser: Option<SyntaxParser>,
     /// Detected language
     pub language: Language,
+    /// The configured language, for ones without a [`Language`] variant
+    /// such as Dockerfiles
+    language_id: Option<String>,
     /// Cached highlight spans (byte-offset based)
     pub highlight_spans: Vec<HighlightSpan>,
-    /// Ghost text (AI suggestion) to be rendered after cursor or inline
-    pub ghost_text: Option<String>,
+    /// Ghost text (AI suggestion), shown as an inlay at the cursor
+    ghost_text: Option<String>,
+    /// Cached mixed line ending check (refreshed on rehighlight)
+    pub mixed_line_endings: bool,
+    /// Opened in large-file mode: no syntax highlighting, minimap or word wrap
+    pub large_file: bool,
+    /// Read-only view of a file too big to load; the buffer stays empty
+    pub mapped: Option<Arc<MappedFile>>,
+    /// Selections before and after each Expand Selection, so Shrink can
+    /// retrace them
+    selection_history: Vec<(Selection, Selection)>,
+    /// Tokenizer for languages highlighted by a TextMate grammar
+    textmate: Option<TextMateHighlighter>,
+    /// No TextMate grammar was found for the language, so don't look again
+    /// on every edit
+    no_textmate_grammar: bool,
+    /// Keeps the trees of injected languages between highlights
+    highlighter: Highlighter,
+    /// One level of indentation from the settings, for languages that don't
+    /// settle it themselves
+    indent_unit: String,
 }
 
 impl Editor {
@@ -33,76 +65,331 @@ impl Editor {
             scroll_y: 0.0,
             cursor_visible: true,
             title: "Forge — [untitled]".to_string(),
-            syntax_parser: None,
             language: Language::Unknown,
+            language_id: None,
             highlight_spans: Vec::new(),
             ghost_text: None,
+            mixed_line_endings: false,
+            large_file: false,
+            mapped: None,
+            selection_history: Vec::new(),
+            textmate: None,
+            no_textmate_grammar: false,
+            highlighter: Highlighter::new(),
+            indent_unit: "    ".to_string(),
         }
     }
 
     /// Open a file in the editor
     pub fn open_file(path: &str) -> anyhow::Result<Self> {
+        Self::open_file_with(path, &LargeFileLimits::default())
+    }
+
+    /// Open a file, switching to large-file mode based on its size
+    pub fn open_file_with(path: &str, limits: &LargeFileLimits) -> anyhow::Result<Self> {
+        let mode = limits.mode_for_path(path)?;
+        if mode == OpenMode::Normal {
+            return Self::open_small_file(path);
+        }
+        let filename = std::path::Path::new(path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(path);
+        let mut editor = Self::new();
+        editor.title = format!("Forge — {}", filename);
+        editor.large_file = true;
+        match mode {
+            OpenMode::Chunked => editor.buffer = Buffer::open_chunked(path)?,
+            _ => {
+                editor.mapped = Some(Arc::new(MappedFile::open(path)?));
+                editor.buffer.set_read_only(true);
+            }
+        }
+        info!("Opened {} in large-file mode ({:?})", filename, mode);
+        Ok(editor)
+    }
+
+    fn open_small_file(path: &str) -> anyhow::Result<Self> {
         let buffer = Buffer::from_file(path)?;
         let filename = std::path::Path::new(path)
             .file_name()
             .and_then(|n| n.to_str())
             .unwrap_or(path);
         info!("Opened: {} ({} lines)", filename, buffer.len_lines());
+        let mixed_line_endings = buffer.has_mixed_line_endings();
+        if mixed_line_endings {
+            tracing::warn!("{} has mixed line endings", filename);
+        }
 
-        let language = Language::from_path(path);
-        let (syntax_parser, highlight_spans) = if language != Language::Unknown {
-            if let Ok(mut parser) = SyntaxParser::new(language) {
-                let text = buffer.text();
-                if let Ok(tree) = parser.parse(&text) {
-                    let spans = Highlighter::highlight(&tree, text.as_bytes(), language);
-                    info!("Syntax: {:?} — {} highlight spans", language, spans.len());
-                    (Some(parser), spans)
-                } else {
-                    (Some(parser), Vec::new())
-                }
-            } else {
-                (None, Vec::new())
+        let (language, language_id) = match Self::detect_language(path, &buffer) {
+            Some(config) if config.language() == Language::Unknown => {
+                (Language::Unknown, Some(config.id.clone()))
             }
-        } else {
-            (None, Vec::new())
+            Some(config) => (config.language(), None),
+            None => (Language::from_path(path), None),
         };
-
-        Ok(Self {
+        let mut editor = Self {
             buffer,
             scroll_y: 0.0,
             cursor_visible: true,
             title: format!("Forge — {}", filename),
-            syntax_parser,
             language,
-            highlight_spans,
+            language_id,
+            highlight_spans: Vec::new(),
             ghost_text: None,
-        })
+            mixed_line_endings,
+            large_file: false,
+            mapped: None,
+            selection_history: Vec::new(),
+            textmate: None,
+            no_textmate_grammar: false,
+            highlighter: Highlighter::new(),
+            indent_unit: "    ".to_string(),
+        };
+        editor.rehighlight();
+        if !editor.highlight_spans.is_empty() {
+            info!(
+                "Syntax: {:?} — {} highlight spans",
+                editor.language,
+                editor.highlight_spans.len()
+            );
+        }
+        Ok(editor)
+    }
+
+    /// The language of a file, by its name, modeline or first line
+    fn detect_language(path: &str, buffer: &Buffer) -> Option<Arc<LanguageConfig>> {
+        // Modelines are only looked for in the first and last five lines
+        let rope = buffer.rope();
+        let lines = rope.len_lines();
+        let mut excerpt = String::new();
+        for line in (0..lines.min(5)).chain(lines.saturating_sub(5).max(5)..lines) {
+            excerpt.extend(rope.line(line).chunks());
+        }
+        LanguageConfigs::global().detect(path, &excerpt)
     }
 
-    /// Re-parse and re-highlight the buffer after edits
+    /// How the editor's language is edited: comment tokens, brackets, words
+    pub fn language_config(&self) -> Arc<LanguageConfig> {
+        let configs = LanguageConfigs::global();
+        match &self.language_id {
+            Some(id) if self.language == Language::Unknown => configs.get(id),
+            _ => configs.for_language(self.language),
+        }
+    }
+
+    /// Switch to another configured language, reattaching the syntax tree
+    /// and highlighting from scratch
+    pub fn set_language(&mut self, id: &str) {
+        let config = LanguageConfigs::global().get(id);
+        self.language = config.language();
+        self.language_id = Some(config.id.clone());
+        self.textmate = None;
+        self.no_textmate_grammar = false;
+        self.highlighter = Highlighter::new();
+        self.highlight_spans.clear();
+        if self.large_file {
+            return;
+        }
+        match self.language.tree_sitter_language() {
+            Some(ts_lang) => self.buffer.set_syntax(ts_lang),
+            None => self.buffer.clear_syntax(),
+        }
+        self.rehighlight();
+        info!("Language set to {}", config.display_name());
+    }
+
+    /// Bring highlighting up to date after edits.
+    ///
+    /// The buffer reparses incrementally as it is edited; only spans in the
+    /// regions it reports as changed are recomputed; the rest are shifted.
     pub fn rehighlight(&mut self) {
-        if let Some(ref mut parser) = self.syntax_parser {
-            let text = self.buffer.text();
-            if let Ok(tree) = parser.parse(&text) {
-                self.highlight_spans =
-                    Highlighter::highlight(&tree, text.as_bytes(), self.language);
+        if self.large_file {
+            return;
+        }
+        self.mixed_line_endings = self.buffer.has_mixed_line_endings();
+        if self.buffer.syntax().is_none() {
+            match self.language.tree_sitter_language() {
+                Some(ts_lang) => self.buffer.set_syntax(ts_lang),
+                None => return self.rehighlight_textmate(),
             }
         }
+        let changes = self.buffer.take_syntax_changes();
+        if changes.is_empty() {
+            return;
+        }
+        let Some(tree) = self.buffer.syntax().and_then(|s| s.tree()) else {
+            return;
+        };
+        let rope = self.buffer.rope();
+        // Read the rope in place rather than copying it on every edit
+        let chunk = |offset: usize| {
+            if offset >= rope.len_bytes() {
+                return &[] as &[u8];
+            }
+            let (chunk, start, _, _) = rope.chunk_at_byte(offset);
+            &chunk.as_bytes()[offset - start..]
+        };
+
+        let mut spans: Vec<HighlightSpan> = std::mem::take(&mut self.highlight_spans)
+            .into_iter()
+            .filter_map(|span| {
+                let range = changes.map_range(span.start_byte..span.end_byte)?;
+                let fits = range.end <= rope.len_bytes();
+                (fits && !changes.touches(&range)).then_some(HighlightSpan {
+                    start_byte: range.start,
+                    end_byte: range.end,
+                    ..span
+                })
+            })
+            .collect();
+        for range in &changes.ranges {
+            spans.extend(self.highlighter.highlight_range(
+                tree,
+                chunk,
+                self.language,
+                range.clone(),
+            ));
+        }
+        spans.sort_by_key(|s| (s.start_byte, s.end_byte));
+        // Tokens touching two adjacent changed ranges are found twice
+        spans.dedup_by(|a, b| a.start_byte == b.start_byte && a.end_byte == b.end_byte);
+        self.highlight_spans = spans;
     }
 
-    /// Get current scroll top line index
+    /// Highlight with a TextMate grammar, retokenizing from the first
+    /// changed line until the tokenizer state settles
+    fn rehighlight_textmate(&mut self) {
+        self.rehighlight_textmate_with(TextMateGrammars::global());
+    }
+
+    fn rehighlight_textmate_with(&mut self, grammars: &TextMateGrammars) {
+        if self.no_textmate_grammar {
+            return;
+        }
+        let edit = match &mut self.textmate {
+            Some(_) => match self.buffer.take_edited_lines() {
+                Some(edit) => edit,
+                None => return,
+            },
+            None => {
+                let grammar = match &self.language_id {
+                    Some(id) => grammars.for_language(id),
+                    None => self
+                        .buffer
+                        .path()
+                        .and_then(|path| grammars.for_path(path))
+                        .or_else(|| grammars.for_language(self.language.name())),
+                };
+                let Some(grammar) = grammar else {
+                    self.no_textmate_grammar = true;
+                    return;
+                };
+                self.textmate = Some(TextMateHighlighter::new(grammar));
+                // Everything is tokenized now; edits from here on are
+                // tokenized by line
+                self.buffer.track_edited_lines();
+                self.buffer.take_edited_lines();
+                LineEdit {
+                    start: 0,
+                    old_end: 0,
+                    new_end: self.buffer.len_lines(),
+                }
+            }
+        };
+        let Some(textmate) = &mut self.textmate else {
+            return;
+        };
+        let rope = self.buffer.rope();
+        textmate.edit(edit.start..edit.old_end, edit.new_end, |line| {
+            Cow::from(rope.line(line))
+        });
+        self.highlight_spans = textmate.spans();
+    }
+
+    /// Get the display row at the top of the viewport
     pub fn scroll_top(&self) -> usize {
         self.scroll_y as usize
     }
 
-    /// Set scroll top
+    /// Scroll so buffer line `line` is at the top
     pub fn set_scroll_top(&mut self, line: usize) {
-        self.scroll_y = line as f64;
+        if self.mapped.is_some() {
+            self.scroll_y = line as f64;
+            return;
+        }
+        let line = line.min(self.buffer.len_lines().saturating_sub(1));
+        let start = Position::new(self.buffer.line_col_to_offset(line, 0));
+        self.scroll_y = self.buffer.to_display(start).row as f64;
+    }
+
+    /// Scroll so display row `row` is at the top
+    pub fn set_scroll_row(&mut self, row: usize) {
+        self.scroll_y = row as f64;
+    }
+
+    /// Get the number of display rows, after folding and wrapping
+    pub fn total_rows(&self) -> usize {
+        match &self.mapped {
+            Some(mapped) => mapped.len_lines(),
+            None => self.buffer.display_row_count(),
+        }
+    }
+
+    /// The buffer line starting on each of `rows`, `None` for rows that
+    /// continue a wrapped line
+    pub fn row_lines(&self, rows: std::ops::Range<usize>) -> Vec<Option<usize>> {
+        match &self.mapped {
+            Some(mapped) => (rows.start..rows.end.min(mapped.len_lines()))
+                .map(Some)
+                .collect(),
+            None => self
+                .buffer
+                .display_rows(rows)
+                .iter()
+                .map(|row| (!row.is_wrap).then_some(row.buffer_line))
+                .collect(),
+        }
+    }
+
+    /// Lay the buffer out with `options`. Large files are shown a line per
+    /// row as they are.
+    pub fn set_display_options(&mut self, options: DisplayOptions) {
+        if !self.large_file {
+            self.buffer.set_display_options(options);
+        }
+    }
+
+    /// Indent by a tab, or by `tab_size` spaces when `insert_spaces` is set
+    pub fn set_indent(&mut self, tab_size: usize, insert_spaces: bool) {
+        self.indent_unit = if insert_spaces {
+            " ".repeat(tab_size.max(1))
+        } else {
+            "\t".to_string()
+        };
     }
 
     /// Get total lines
     pub fn total_lines(&self) -> usize {
-        self.buffer.len_lines()
+        match &self.mapped {
+            Some(mapped) => mapped.len_lines(),
+            None => self.buffer.len_lines(),
+        }
+    }
+
+    /// Text of line `idx` for display, from the mapped file when there is one
+    pub fn display_line(&self, idx: usize) -> String {
+        match &self.mapped {
+            Some(mapped) => mapped.line(idx).unwrap_or_default(),
+            None => crate::guard::Guard::get_line(self.buffer.rope(), idx),
+        }
+    }
+
+    /// Install a finished background load. `None` while still loading.
+    pub fn poll_loading(&mut self) -> Option<anyhow::Result<()>> {
+        let result = self.buffer.poll_load()?;
+        self.scroll_y = 0.0;
+        Some(result)
     }
 
     /// Get the current cursor byte offset
@@ -115,6 +402,29 @@ impl Editor {
         self.buffer.offset_to_line_col(self.cursor_offset())
     }
 
+    /// Get current cursor line and column in grapheme clusters, which is
+    /// where it is drawn
+    pub fn cursor_display_line_col(&self) -> LineCol {
+        self.buffer.position_to_line_col(
+            Position::new(self.cursor_offset()),
+            ColumnUnit::Grapheme,
+        )
+    }
+
+    /// Get where the cursor is drawn, in display rows and columns
+    pub fn cursor_display_point(&self) -> DisplayPoint {
+        self.buffer.to_display(Position::new(self.cursor_offset()))
+    }
+
+    /// Get current cursor (line, col) with the column counted in `unit`,
+    /// e.g. the position encoding agreed with a language server
+    pub fn cursor_line_col_in(&self, unit: ColumnUnit) -> (usize, usize) {
+        let at = self
+            .buffer
+            .position_to_line_col(Position::new(self.cursor_offset()), unit);
+        (at.line, at.col)
+    }
+
     /// Get current cursor line (0-indexed)
     pub fn cursor_line(&self) -> usize {
         self.cursor_line_col().0
@@ -139,6 +449,25 @@ impl Editor {
         self.buffer.apply(tx);
     }
 
+    /// Insert text at the cursor as a single undo step (paste, indentation)
+    pub fn insert_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        let selection = self.buffer.selection().clone();
+        let mut changes = ChangeSet::new();
+        for range in selection.ranges() {
+            changes.add(Change::insert(range.head, text.to_string()));
+        }
+        // Every cursor ends up after its copy of the text
+        let selection =
+            selection.map(|range| Range::point(changes.map_position(range.head, Assoc::After)));
+        let transaction = Transaction::new(changes, Some(selection));
+        self.buffer.begin_undo_group();
+        self.buffer.apply(transaction);
+        self.buffer.end_undo_group();
+    }
+
     /// Delete the whole line
     pub fn delete_line(&mut self, line: usize) {
         let total = self.total_lines();
@@ -168,9 +497,121 @@ impl Editor {
         self.buffer.apply(tx);
     }
 
-    /// Insert a newline at cursor
+    /// Insert a newline at cursor, using the buffer's line ending style, and
+    /// indent the new line
     pub fn insert_newline(&mut self) {
-        self.insert_char('\n');
+        let offset = self.cursor_offset();
+        let newline = self.buffer.line_ending().as_str();
+
+        self.buffer.begin_undo_group();
+        let change = Change::insert(Position::new(offset), newline.to_string());
+        let tx = Transaction::new(
+            ChangeSet::with_change(change),
+            Some(Selection::point(Position::new(offset + newline.len()))),
+        );
+        self.buffer.apply(tx);
+        let line = self.cursor_line();
+        self.reindent_rows(line..line + 1, true);
+        let indent = line_indent(&self.buffer.rope().line(line).to_string()).len();
+        self.buffer.set_selection(Selection::point(Position::new(
+            self.buffer.line_col_to_offset(line, 0) + indent,
+        )));
+        self.buffer.end_undo_group();
+    }
+
+    /// Insert typed text at the cursor. A closing bracket typed at the start
+    /// of a line lines up with the line its block opened on.
+    pub fn type_text(&mut self, text: &str) {
+        self.buffer.begin_undo_group();
+        self.insert_text(text);
+        let line = self.cursor_line();
+        let line_start = self.buffer.line_col_to_offset(line, 0);
+        let typed = self.buffer.slice(line_start, self.cursor_offset());
+        let closes = self
+            .language_config()
+            .brackets
+            .iter()
+            .any(|(_, close)| typed.trim_start() == close);
+        if closes {
+            self.reindent_rows(line..line + 1, false);
+        }
+        self.buffer.end_undo_group();
+    }
+
+    /// Reindent every line the selections touch
+    pub fn reindent_selection(&mut self) {
+        let ranges: Vec<_> = self.buffer.selection().ranges().to_vec();
+        for range in ranges {
+            let (first, _) = self.buffer.offset_to_line_col(range.start().offset);
+            let (mut last, col) = self.buffer.offset_to_line_col(range.end().offset);
+            // A selection ending at the start of a line doesn't include it
+            if last > first && col == 0 {
+                last -= 1;
+            }
+            self.reindent_rows(first..last + 1, false);
+        }
+    }
+
+    /// Reindent the whole buffer
+    pub fn reindent_file(&mut self) {
+        self.reindent_rows(0..self.buffer.len_lines(), false);
+    }
+
+    /// Indent `rows` as the language's indent query suggests, or its indent
+    /// patterns when it has no query. Blank lines are left alone unless
+    /// `blank` is set.
+    fn reindent_rows(&mut self, rows: std::ops::Range<usize>, blank: bool) {
+        if self.buffer.syntax().is_none() {
+            self.rehighlight();
+        }
+        let config = self.language_config();
+        let rope = self.buffer.rope();
+        let tree = self.buffer.syntax().and_then(|s| s.tree());
+        let suggestions = forge_syntax::suggest_indents(
+            tree,
+            |line| Cow::from(rope.line(line)),
+            self.language,
+            &config,
+            rows.clone(),
+        );
+
+        // Lines are indented top-down, each building on the new indentation
+        // of the lines above it
+        let mut indents = std::collections::HashMap::new();
+        let mut changes = Vec::new();
+        for (row, suggestion) in rows.zip(suggestions) {
+            let text = self.buffer.rope().line(row).to_string();
+            let old = line_indent(&text);
+            if suggestion.basis == Some(row) || (!blank && text.trim().is_empty()) {
+                continue;
+            }
+            let basis_indent = match suggestion.basis {
+                Some(basis) => indents.get(&basis).cloned().unwrap_or_else(|| {
+                    line_indent(&self.buffer.rope().line(basis).to_string()).to_string()
+                }),
+                None => String::new(),
+            };
+            // A language's own unit wins, then the tabs already in use
+            let unit = match &config.indent_unit {
+                Some(unit) => unit.as_str(),
+                None if basis_indent.contains('\t') => "\t",
+                None => &self.indent_unit,
+            };
+            let new = suggestion.indent(&basis_indent, unit);
+            if new != old {
+                let start = self.buffer.line_col_to_offset(row, 0);
+                changes.push(Change::replace(
+                    Position::new(start),
+                    Position::new(start + old.len()),
+                    new.clone(),
+                ));
+            }
+            indents.insert(row, new);
+        }
+        if !changes.is_empty() {
+            self.buffer
+                .apply(Transaction::new(ChangeSet::from_changes(changes), None));
+        }
     }
 
     /// Delete the character before the cursor (backspace)
@@ -180,15 +621,7 @@ impl Editor {
             return;
         }
 
-        // Find the byte length of the previous character
-        let text = self.buffer.text();
-        let prev_char_len = text[..offset]
-            .chars()
-            .last()
-            .map(|c| c.len_utf8())
-            .unwrap_or(1);
-
-        let start = offset - prev_char_len;
+        let start = self.buffer.prev_grapheme_boundary(offset);
         let change = Change::delete(Position::new(start), Position::new(offset));
         let tx = Transaction::new(
             ChangeSet::with_change(change),
@@ -205,14 +638,7 @@ impl Editor {
             return;
         }
 
-        let text = self.buffer.text();
-        let next_char_len = text[offset..]
-            .chars()
-            .next()
-            .map(|c| c.len_utf8())
-            .unwrap_or(1);
-
-        let end = offset + next_char_len;
+        let end = self.buffer.next_grapheme_boundary(offset);
         let change = Change::delete(Position::new(offset), Position::new(end));
         let tx = Transaction::new(
             ChangeSet::with_change(change),
@@ -227,13 +653,7 @@ impl Editor {
         if offset == 0 {
             return;
         }
-        let text = self.buffer.text();
-        let prev_len = text[..offset]
-            .chars()
-            .last()
-            .map(|c| c.len_utf8())
-            .unwrap_or(1);
-        let new_offset = offset - prev_len;
+        let new_offset = self.buffer.prev_grapheme_boundary(offset);
         self.buffer
             .set_selection(Selection::point(Position::new(new_offset)));
     }
@@ -245,37 +665,29 @@ impl Editor {
         if offset >= len {
             return;
         }
-        let text = self.buffer.text();
-        let next_len = text[offset..]
-            .chars()
-            .next()
-            .map(|c| c.len_utf8())
-            .unwrap_or(1);
-        let new_offset = (offset + next_len).min(len);
+        let new_offset = self.buffer.next_grapheme_boundary(offset);
         self.buffer
             .set_selection(Selection::point(Position::new(new_offset)));
     }
 
-    /// Move cursor up one line
+    /// Move cursor up one display row, keeping its on-screen column
     pub fn move_up(&mut self) {
-        let (line, col) = self.cursor_line_col();
-        if line == 0 {
+        let DisplayPoint { row, col } = self.cursor_display_point();
+        if row == 0 {
             return;
         }
-        let new_offset = self.buffer.line_col_to_offset(line - 1, col);
-        self.buffer
-            .set_selection(Selection::point(Position::new(new_offset)));
+        let pos = self.buffer.from_display(DisplayPoint::new(row - 1, col));
+        self.buffer.set_selection(Selection::point(pos));
     }
 
-    /// Move cursor down one line
+    /// Move cursor down one display row, keeping its on-screen column
     pub fn move_down(&mut self) {
-        let (line, col) = self.cursor_line_col();
-        if line + 1 >= self.buffer.len_lines() {
+        let DisplayPoint { row, col } = self.cursor_display_point();
+        if row + 1 >= self.buffer.display_row_count() {
             return;
         }
-        let new_offset = self.buffer.line_col_to_offset(line + 1, col);
-        self.buffer
-            .set_selection(Selection::point(Position::new(new_offset)));
+        let pos = self.buffer.from_display(DisplayPoint::new(row + 1, col));
+        self.buffer.set_selection(Selection::point(pos));
     }
 
     /// Move cursor to beginning of line
@@ -305,16 +717,34 @@ impl Editor {
             .set_selection(Selection::point(Position::new(new_offset)));
     }
 
+    /// Move every cursor by a word, paragraph, ... motion, extending the
+    /// selections with Shift
+    pub fn move_by(&mut self, motion: Motion, extend: bool) {
+        self.buffer.move_cursors(motion, extend);
+    }
+
+    /// Select a text object around every cursor
+    pub fn select_text_object(&mut self, object: TextObject, scope: Scope) {
+        if matches!(
+            object,
+            TextObject::Function | TextObject::Class | TextObject::Argument
+        ) && self.buffer.syntax().is_none()
+        {
+            self.rehighlight();
+        }
+        self.buffer.select_text_object(object, scope);
+    }
+
     /// Scroll the viewport
     pub fn scroll(&mut self, delta: f64) {
         self.scroll_y = (self.scroll_y + delta).max(0.0);
-        let max_scroll = (self.buffer.len_lines() as f64 - 1.0).max(0.0);
+        let max_scroll = (self.total_rows() as f64 - 1.0).max(0.0);
         self.scroll_y = self.scroll_y.min(max_scroll);
     }
 
     /// Ensure cursor is visible in viewport
     pub fn ensure_cursor_visible(&mut self, visible_lines: usize) {
-        let (cursor_line, _) = self.cursor_line_col();
+        let cursor_line = self.cursor_display_point().row;
         let scroll_top = self.scroll_y as usize;
         let scroll_bottom = scroll_top + visible_lines.saturating_sub(1);
 
@@ -326,12 +756,41 @@ impl Editor {
     }
 
     /// Save the file
-    pub fn save(&mut self) -> anyhow::Result<()> {
-        self.buffer.save()?;
+    pub fn save(&mut self, options: &forge_core::SaveOptions) -> anyhow::Result<()> {
+        self.buffer.save_with(options)?;
         info!("Saved: {}", self.buffer.path().unwrap_or("[untitled]"));
         Ok(())
     }
 
+    /// Re-read the file from disk in a different encoding
+    pub fn reopen_with_encoding(&mut self, encoding: forge_core::Encoding) -> anyhow::Result<()> {
+        self.buffer.reload_with_encoding(encoding)?;
+        self.rehighlight();
+        info!(
+            "Reopened {} as {}",
+            self.buffer.path().unwrap_or("[untitled]"),
+            encoding
+        );
+        Ok(())
+    }
+
+    /// Point redo at the next (or previous) branch leaving the current state.
+    ///
+    /// Returns the selected branch and how many there are to choose from.
+    pub fn cycle_redo_branch(&mut self, forward: bool) -> Option<(usize, usize)> {
+        let history = self.buffer.history();
+        let branches = history.branches(history.current).to_vec();
+        let active = history.redo_branch(history.current)?;
+        let idx = branches.iter().position(|&b| b == active)?;
+        let next = if forward {
+            (idx + 1) % branches.len()
+        } else {
+            (idx + branches.len() - 1) % branches.len()
+        };
+        self.buffer.set_redo_branch(branches[next]);
+        Some((next, branches.len()))
+    }
+
     /// Update window title (adds * for dirty)
     pub fn window_title(&self) -> String {
         let base = &self.title;
@@ -350,15 +809,133 @@ impl Editor {
 
     /// Clone the editor view (buffer content is shared/cloned, but cursor/scroll independent)
     pub fn clone_view(&self) -> Self {
+        let mut buffer = self.buffer.clone();
+        buffer.set_inlays(InlayKind::Ghost, None);
+        if self.textmate.is_some() {
+            buffer.track_edited_lines();
+        }
         Self {
-            buffer: self.buffer.clone(),
+            buffer,
             scroll_y: self.scroll_y,
             cursor_visible: true,
             title: self.title.clone(),
-            syntax_parser: None, // Will be recreated on rehighlight if needed
             language: self.language,
+            language_id: self.language_id.clone(),
             highlight_spans: self.highlight_spans.clone(),
             ghost_text: None,
+            mixed_line_endings: self.mixed_line_endings,
+            large_file: self.large_file,
+            mapped: self.mapped.clone(),
+            selection_history: Vec::new(),
+            textmate: self.textmate.clone(),
+            no_textmate_grammar: self.no_textmate_grammar,
+            highlighter: Highlighter::new(),
+            indent_unit: self.indent_unit.clone(),
+        }
+    }
+
+    /// Grow every selection to the enclosing syntax node (Shift+Alt+Right)
+    pub fn expand_selection(&mut self) {
+        let current = self.buffer.selection().clone();
+        if self.selection_history.last().map(|(_, after)| after) != Some(&current) {
+            self.selection_history.clear();
+        }
+        self.select_by_syntax(syntax_selection::expand);
+        let expanded = self.buffer.selection().clone();
+        if expanded != current {
+            self.selection_history.push((current, expanded));
+        }
+    }
+
+    /// Undo the last expansion, or shrink every selection to its first child
+    /// node when there is nothing to retrace (Shift+Alt+Left)
+    pub fn shrink_selection(&mut self) {
+        match self.selection_history.pop() {
+            Some((before, after)) if after == *self.buffer.selection() => {
+                self.buffer.set_selection(before);
+            }
+            _ => {
+                self.selection_history.clear();
+                self.select_by_syntax(syntax_selection::shrink);
+            }
+        }
+    }
+
+    /// Move every selection to the next or previous sibling syntax node
+    pub fn select_sibling(&mut self, forward: bool) {
+        self.select_by_syntax(|tree, sel| syntax_selection::select_sibling(tree, sel, forward));
+    }
+
+    /// Select the function or class around every cursor
+    pub fn select_enclosing(&mut self, kind: Enclosing) {
+        self.select_by_syntax(|tree, sel| syntax_selection::select_enclosing(tree, sel, kind));
+    }
+
+    fn select_by_syntax(&mut self, f: impl Fn(&tree_sitter::Tree, &Selection) -> Selection) {
+        if self.buffer.syntax().is_none() {
+            self.rehighlight();
+        }
+        let Some(tree) = self.buffer.syntax().and_then(|s| s.tree()) else {
+            return;
+        };
+        let selection = f(tree, self.buffer.selection());
+        self.buffer.set_selection(selection);
+    }
+
+    /// Comment or uncomment every line the selections touch (Ctrl+/)
+    pub fn toggle_comment(&mut self) {
+        let mut lines = std::collections::BTreeSet::new();
+        for range in self.buffer.selection().ranges() {
+            let (first, _) = self.buffer.offset_to_line_col(range.start().offset);
+            let (mut last, col) = self.buffer.offset_to_line_col(range.end().offset);
+            // A selection ending at the start of a line doesn't include it
+            if last > first && col == 0 {
+                last -= 1;
+            }
+            lines.extend(first..=last);
+        }
+        let lines: Vec<usize> = lines.into_iter().collect();
+        let texts: Vec<String> = lines
+            .iter()
+            .map(|&line| {
+                let text = self.buffer.rope().line(line).to_string();
+                text.trim_end_matches(['\r', '\n']).to_string()
+            })
+            .collect();
+        let toggled = crate::comment_toggle::CommentToggler::toggle_block(
+            &texts.iter().map(String::as_str).collect::<Vec<_>>(),
+            &self.language_config(),
+        );
+        let changes: Vec<Change> = lines
+            .iter()
+            .zip(texts.iter().zip(toggled))
+            .filter(|(_, (old, new))| *old != new)
+            .map(|(&line, (old, new))| {
+                // Only replace what changed, so selections on the rest of
+                // the line stay put
+                let prefix = old
+                    .char_indices()
+                    .zip(new.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map_or(old.len().min(new.len()), |((i, _), _)| i);
+                let suffix = old[prefix..]
+                    .chars()
+                    .rev()
+                    .zip(new[prefix..].chars().rev())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a.len_utf8())
+                    .sum::<usize>();
+                let start = self.buffer.line_col_to_offset(line, 0) + prefix;
+                Change::replace(
+                    Position::new(start),
+                    Position::new(start + old.len() - prefix - suffix),
+                    new[prefix..new.len() - suffix].to_string(),
+                )
+            })
+            .collect();
+        if !changes.is_empty() {
+            self.buffer
+                .apply(Transaction::new(ChangeSet::from_changes(changes), None));
         }
     }
 
@@ -368,10 +945,21 @@ impl Editor {
         let primary = self.buffer.selection().primary();
 
         let search_text = if primary.is_empty() {
-            // No selection: select word under cursor
-            // Basic word selection logic
-            // For now, let's just return if empty, or expand selection to word
-            // Expanding to word is safer first step.
+            // No selection: select the word under the cursor first
+            let offset = primary.start().offset;
+            let (line, _) = self.buffer.offset_to_line_col(offset);
+            let line_start = self.buffer.line_col_to_offset(line, 0);
+            let line_text = self.buffer.rope().line(line).to_string();
+            if let Some(word) = self
+                .language_config()
+                .word_at(&line_text, offset - line_start)
+            {
+                self.buffer
+                    .set_selection(Selection::single(forge_core::Range::new(
+                        Position::new(line_start + word.start),
+                        Position::new(line_start + word.end),
+                    )));
+            }
             return;
         } else {
             self.buffer.slice(primary.start().offset, primary.end().offset)
@@ -390,10 +978,296 @@ impl Editor {
         }
     }
 
-    /// Add a cursor at a specific point (Alt+Click)
-    pub fn add_cursor_at_point(&mut self, line: usize, col: usize) {
-        let offset = self.buffer.line_col_to_offset(line, col);
-        let pos = forge_core::Position::new(offset);
+    /// Add a cursor at a point on screen (Alt+Click)
+    pub fn add_cursor_at_point(&mut self, point: DisplayPoint) {
+        let pos = self.buffer.from_display(point);
         self.buffer.add_selection_range(forge_core::Range::new(pos, pos));
     }
+
+    /// Fold the innermost indented block the cursor is in
+    pub fn fold_at_cursor(&mut self) {
+        let line = self.cursor_line();
+        let ranges = FoldingManager::new().compute_ranges(&self.buffer.text());
+        if let Some(range) = ranges
+            .iter()
+            .filter(|r| r.start_line <= line && line <= r.end_line)
+            .min_by_key(|r| r.end_line - r.start_line)
+        {
+            self.buffer.fold_lines(range.start_line, range.end_line);
+        }
+    }
+
+    /// Unfold the folds on the cursor's line
+    pub fn unfold_at_cursor(&mut self) {
+        let line = self.cursor_line();
+        let start = self.buffer.line_col_to_offset(line, 0);
+        let end = self.buffer.line_col_to_offset(line + 1, 0);
+        self.buffer.unfold(start..end);
+    }
+
+    pub fn ghost_text(&self) -> Option<&str> {
+        self.ghost_text.as_deref()
+    }
+
+    /// Show `text` ahead of the cursor without inserting it
+    pub fn set_ghost_text(&mut self, text: Option<String>) {
+        let at = self.cursor_offset();
+        self.buffer
+            .set_inlays(InlayKind::Ghost, text.clone().map(|text| (at, text)));
+        self.ghost_text = text;
+    }
+}
+
+/// The spaces and tabs a line starts with
+fn line_indent(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn incremental_rehighlight_matches_full_highlight() {
+        let mut editor = Editor::new();
+        editor.language = Language::Rust;
+        editor.buffer = Buffer::from_str("fn a() { 1 }\nfn b() { 2 }\n");
+        editor.rehighlight();
+
+        editor
+            .buffer
+            .set_selection(Selection::point(Position::new(22)));
+        editor.insert_text("let x = 3; ");
+        editor.backspace();
+        editor.rehighlight();
+
+        let text = editor.buffer.text();
+        let tree = editor.buffer.syntax().and_then(|s| s.tree()).unwrap();
+        let full = Highlighter::highlight(tree, text.as_bytes(), Language::Rust);
+        let ranges = |spans: &[HighlightSpan]| -> Vec<(usize, usize, forge_syntax::TokenType)> {
+            spans
+                .iter()
+                .map(|s| (s.start_byte, s.end_byte, s.token_type))
+                .collect()
+        };
+        assert_eq!(ranges(&editor.highlight_spans), ranges(&full));
+    }
+
+    #[test]
+    fn insert_text_pastes_at_every_cursor_as_one_step() {
+        let mut editor = Editor::new();
+        editor.buffer = Buffer::from_str("a\nb\n");
+        editor
+            .buffer
+            .set_selection(Selection::point(Position::new(1)));
+        editor
+            .buffer
+            .add_selection_range(Range::point(Position::new(3)));
+        editor.insert_text("xy");
+        assert_eq!(editor.buffer.text(), "axy\nbxy\n");
+        let heads: Vec<usize> = editor
+            .buffer
+            .selection()
+            .ranges()
+            .iter()
+            .map(|r| r.head.offset)
+            .collect();
+        assert_eq!(heads, vec![3, 7]);
+
+        editor.buffer.undo();
+        assert_eq!(editor.buffer.text(), "a\nb\n");
+    }
+
+    #[test]
+    fn textmate_grammars_highlight_languages_without_tree_sitter() {
+        let grammar = forge_syntax::textmate::Grammar::from_json(
+            r##"{"scopeName": "source.forgetest", "patterns": [
+                {"match": "#.*$", "name": "comment.line"},
+                {"match": "\\b(FROM|RUN)\\b", "name": "keyword.other"}
+            ]}"##,
+        )
+        .unwrap();
+        let grammars = TextMateGrammars::new();
+        let mut editor = Editor::new();
+        editor.buffer = Buffer::from_str("FROM rust\n# build\nRUN make\n");
+        editor.language_id = Some("forgetest".into());
+        editor.rehighlight_textmate_with(&grammars);
+        assert!(editor.no_textmate_grammar);
+
+        // The miss is remembered until the language changes
+        grammars.add(grammar, Some("forgetest"), Vec::new());
+        editor.rehighlight_textmate_with(&grammars);
+        assert!(editor.highlight_spans.is_empty());
+        editor.no_textmate_grammar = false;
+        editor.rehighlight_textmate_with(&grammars);
+        let tokens = |editor: &Editor| -> Vec<(usize, forge_syntax::TokenType)> {
+            editor
+                .highlight_spans
+                .iter()
+                .map(|s| (s.start_byte, s.token_type))
+                .collect()
+        };
+        assert_eq!(tokens(&editor).len(), 3);
+
+        editor
+            .buffer
+            .set_selection(Selection::point(Position::new(10)));
+        editor.insert_text("RUN ");
+        editor.rehighlight_textmate_with(&grammars);
+        assert_eq!(tokens(&editor).len(), 4);
+        assert_eq!(tokens(&editor)[1], (10, forge_syntax::TokenType::Keyword));
+        assert_eq!(tokens(&editor)[3], (22, forge_syntax::TokenType::Keyword));
+    }
+
+    #[test]
+    fn language_comes_from_content_and_can_be_changed() {
+        let path = std::env::temp_dir().join(format!("editor-{}-tool", std::process::id()));
+        std::fs::write(&path, "#!/usr/bin/env python3\ndef f():\n    pass\n").unwrap();
+        let mut editor = Editor::open_file(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(editor.language, Language::Python);
+        assert!(editor.buffer.syntax().is_some());
+        assert!(!editor.highlight_spans.is_empty());
+
+        editor.set_language("makefile");
+        assert_eq!(editor.language, Language::Unknown);
+        assert_eq!(editor.language_config().id, "makefile");
+        assert!(editor.buffer.syntax().is_none());
+
+        editor.set_language("rust");
+        assert_eq!(editor.language_config().line_comment.as_deref(), Some("//"));
+        assert!(editor.buffer.syntax().is_some());
+    }
+
+    #[test]
+    fn comments_and_words_follow_the_language() {
+        let mut editor = Editor::new();
+        editor.language = Language::Python;
+        editor.buffer = Buffer::from_str("def f():\n    return x\n");
+        editor.buffer.set_selection(Selection::single(forge_core::Range::new(
+            Position::new(2),
+            Position::new(13),
+        )));
+        editor.toggle_comment();
+        assert_eq!(editor.buffer.text(), "# def f():\n    # return x\n");
+        editor.toggle_comment();
+        assert_eq!(editor.buffer.text(), "def f():\n    return x\n");
+
+        editor.language = Language::Css;
+        editor.buffer = Buffer::from_str("a { --main-color: red }");
+        editor
+            .buffer
+            .set_selection(Selection::point(Position::new(8)));
+        editor.select_next_occurrence();
+        let word = editor.buffer.selection().primary();
+        assert_eq!((word.start().offset, word.end().offset), (4, 16));
+    }
+
+    #[test]
+    fn new_lines_and_closing_brackets_follow_the_syntax() {
+        let mut editor = Editor::new();
+        editor.language = Language::Rust;
+        editor.buffer = Buffer::from_str("fn main() {");
+        editor
+            .buffer
+            .set_selection(Selection::point(Position::new(11)));
+        editor.insert_newline();
+        editor.type_text("x();");
+        editor.insert_newline();
+        assert_eq!(editor.buffer.text(), "fn main() {\n    x();\n    ");
+        editor.type_text("}");
+        assert_eq!(editor.buffer.text(), "fn main() {\n    x();\n}");
+        editor.buffer.undo();
+        assert_eq!(editor.buffer.text(), "fn main() {\n    x();\n    ");
+
+        // The settings decide the unit unless the language does, as Go
+        // does with tabs
+        editor.set_indent(2, true);
+        editor.buffer = Buffer::from_str("fn main() {\nx();\n}\n");
+        editor.reindent_file();
+        assert_eq!(editor.buffer.text(), "fn main() {\n  x();\n}\n");
+
+        // Without an indent query, the language's patterns decide
+        editor.language = Language::Go;
+        editor.buffer = Buffer::from_str("func f() {\nx()\n      }\n");
+        editor.reindent_file();
+        assert_eq!(editor.buffer.text(), "func f() {\n\tx()\n}\n");
+    }
+
+    #[test]
+    fn shrink_retraces_expanded_selections() {
+        let mut editor = Editor::new();
+        editor.language = Language::Rust;
+        editor.buffer = Buffer::from_str("fn a() { foo(1, 2) }\n");
+        editor
+            .buffer
+            .set_selection(Selection::point(Position::new(16)));
+
+        let mut sizes = Vec::new();
+        for _ in 0..3 {
+            editor.expand_selection();
+            sizes.push(editor.buffer.selection().primary().len());
+        }
+        assert_eq!(sizes, vec![1, 6, 9]);
+        editor.shrink_selection();
+        editor.shrink_selection();
+        assert_eq!(editor.buffer.selection().primary().len(), 1);
+        editor.shrink_selection();
+        assert!(editor.buffer.selection().primary().is_empty());
+    }
+
+    #[test]
+    fn cursor_moves_and_deletes_by_grapheme() {
+        let mut editor = Editor::new();
+        editor.buffer = Buffer::from_str("e\u{301}\u{1F44D}\u{1F3FD}x\nabcd\n");
+        editor.move_right();
+        assert_eq!(editor.cursor_offset(), 3);
+        editor.move_right();
+        assert_eq!(editor.cursor_display_line_col(), LineCol::new(0, 2));
+
+        // Column 2 on screen is the same on the next line, bytes differ
+        editor.move_down();
+        assert_eq!(editor.cursor_offset(), "e\u{301}\u{1F44D}\u{1F3FD}x\nab".len());
+        editor.move_up();
+        editor.backspace();
+        assert_eq!(editor.buffer.text(), "e\u{301}x\nabcd\n");
+        editor.move_left();
+        editor.delete();
+        assert_eq!(editor.buffer.text(), "x\nabcd\n");
+    }
+
+    #[test]
+    fn cursor_moves_by_display_row() {
+        let mut editor = Editor::new();
+        editor.buffer = Buffer::from_str("fn a() {\n    one two three\n}\nend\n");
+        editor.set_display_options(DisplayOptions {
+            tab_size: 4,
+            wrap_width: Some(10),
+        });
+        editor
+            .buffer
+            .set_selection(Selection::point(Position::new(2)));
+
+        // Down through the rows of the wrapped line
+        editor.move_down();
+        editor.move_down();
+        assert_eq!(editor.cursor_display_point(), DisplayPoint::new(2, 2));
+        assert_eq!(editor.cursor_offset(), 19);
+        assert_eq!(editor.total_rows(), 6);
+
+        editor.fold_at_cursor();
+        assert_eq!(editor.total_rows(), 4);
+        assert_eq!(
+            editor.row_lines(0..4),
+            vec![Some(0), Some(2), Some(3), Some(4)]
+        );
+        // The cursor was folded away; down goes to the row after the fold
+        editor.move_down();
+        assert_eq!(editor.cursor_line(), 2);
+
+        editor.move_up();
+        assert_eq!(editor.cursor_line(), 0);
+        editor.unfold_at_cursor();
+        assert_eq!(editor.total_rows(), 6);
+    }
 }
@@ -1,12 +1,23 @@
 use crate::file_tree_ui::{DisplayNode, FileTreeUi};
+use forge_core::{Project, ProjectLimits};
 use std::path::{Path, PathBuf};
 
+/// Without a project index only the levels the explorer shows are read
+const SHALLOW: ProjectLimits = ProjectLimits {
+    max_files: 10_000,
+    max_depth: 2,
+};
+
 pub struct FileExplorer {
     pub root: Option<PathBuf>,
     pub nodes: Vec<DisplayNode>,
     pub paths: Vec<PathBuf>,
     pub selected: Option<usize>,
     pub ui: FileTreeUi,
+    /// Index generation the listing was built from
+    pub generation: u64,
+    /// Waiting for the project index's first scan to list it
+    pub indexing: bool,
 }
 
 impl FileExplorer {
@@ -17,46 +28,52 @@ impl FileExplorer {
             paths: Vec::new(),
             selected: None,
             ui: FileTreeUi::new(),
+            generation: 0,
+            indexing: false,
         }
     }
 
+    /// List `root` (honoring `.gitignore`) without indexing a project,
+    /// reading only the levels that are shown
     pub fn scan_directory(&mut self, root: &Path) -> anyhow::Result<()> {
-        self.root = Some(root.to_path_buf());
-        self.nodes.clear();
-        self.paths.clear();
-        self.scan_recursive(root, 0)?;
+        if !root.is_dir() {
+            anyhow::bail!("{} is not a directory", root.display());
+        }
+        let mut project = Project::with_limits(root, SHALLOW);
+        project.scan();
+        self.populate(&project);
         Ok(())
     }
 
-    fn scan_recursive(&mut self, dir: &Path, depth: usize) -> anyhow::Result<()> {
-        if depth > 3 {
-            return Ok(());
-        } // Limit depth
-        let mut entries: Vec<_> = std::fs::read_dir(dir)?.filter_map(|e| e.ok()).collect();
-        entries.sort_by(|a, b| {
-            let a_dir = a.file_type().map(|t| t.is_dir()).unwrap_or(false);
-            let b_dir = b.file_type().map(|t| t.is_dir()).unwrap_or(false);
-            b_dir.cmp(&a_dir).then(a.file_name().cmp(&b.file_name()))
-        });
-        for entry in entries {
-            let name = entry.file_name().to_string_lossy().to_string();
-            if name.starts_with('.') || name == "target" || name == "node_modules" {
-                continue;
-            }
-            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
-            let path = entry.path();
-            self.nodes.push(DisplayNode {
-                label: name,
-                depth,
-                is_dir,
-                expanded: depth == 0 && is_dir,
-            });
-            self.paths.push(path.clone());
-            if is_dir && depth == 0 {
-                let _ = self.scan_recursive(&path, depth + 1);
+    /// List the top level of an indexed project, with top-level directories
+    /// expanded one level
+    pub fn populate(&mut self, project: &Project) {
+        self.indexing = false;
+        self.root = Some(project.root.clone());
+        self.nodes.clear();
+        self.paths.clear();
+        for (path, is_dir) in project.list_dir(&project.root) {
+            self.push(path.clone(), 0, is_dir);
+            if is_dir {
+                for (child, child_is_dir) in project.list_dir(&path) {
+                    self.push(child, 1, child_is_dir);
+                }
             }
         }
-        Ok(())
+    }
+
+    fn push(&mut self, path: PathBuf, depth: usize, is_dir: bool) {
+        let label = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.nodes.push(DisplayNode {
+            label,
+            depth,
+            is_dir,
+            expanded: depth == 0 && is_dir,
+        });
+        self.paths.push(path);
     }
 
     pub fn get_path(&self, index: usize) -> Option<&Path> {
@@ -77,3 +94,32 @@ impl Default for FileExplorer {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_project_without_ignored_files() {
+        let root = std::env::temp_dir().join("forge_explorer_populate");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join(".gitignore"), "*.tmp\n").unwrap();
+        std::fs::write(root.join("README.md"), "").unwrap();
+        std::fs::write(root.join("scratch.tmp"), "").unwrap();
+        std::fs::write(root.join("src/main.rs"), "").unwrap();
+        std::fs::write(root.join("src/nested/deep.rs"), "").unwrap();
+
+        let mut explorer = FileExplorer::new();
+        explorer.scan_directory(&root).unwrap();
+        let listing: Vec<(&str, usize)> = explorer
+            .nodes
+            .iter()
+            .map(|n| (n.label.as_str(), n.depth))
+            .collect();
+        assert_eq!(
+            listing,
+            vec![("src", 0), ("nested", 1), ("main.rs", 1), ("README.md", 0)]
+        );
+    }
+}
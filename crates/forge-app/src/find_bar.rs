@@ -1,10 +1,32 @@
-use regex::RegexBuilder;
+use forge_core::{Buffer, MappedFile, MappedSearch, SearchOptions, Searcher};
+use ropey::Rope;
+use std::ops::Range;
+use std::path::PathBuf;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Match {
     pub line: usize,
     pub start_col: usize,
+    /// Where the match ends on its first line
     pub end_col: usize,
+    /// Byte range in the buffer
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Match {
+    fn from_range(rope: &Rope, range: Range<usize>) -> Self {
+        let line = rope.byte_to_line(range.start);
+        let line_start = rope.line_to_byte(line);
+        let line_end = line_start + rope.line(line).len_bytes();
+        Self {
+            line,
+            start_col: range.start - line_start,
+            end_col: range.end.min(line_end) - line_start,
+            start: range.start,
+            end: range.end,
+        }
+    }
 }
 
 pub struct FindBar {
@@ -15,6 +37,12 @@ pub struct FindBar {
     pub case_sensitive: bool,
     pub regex_mode: bool,
     pub whole_word: bool,
+    /// Byte offset the next search of a memory-mapped file starts from
+    mapped_from: usize,
+    /// Background search of the memory-mapped file at the path
+    mapped_search: Option<(PathBuf, MappedSearch)>,
+    /// Generation of the buffer search `matches` were loaded from
+    generation: Option<u64>,
 }
 
 impl Default for FindBar {
@@ -27,6 +55,9 @@ impl Default for FindBar {
             case_sensitive: false,
             regex_mode: false,
             whole_word: false,
+            mapped_from: 0,
+            mapped_search: None,
+            generation: None,
         }
     }
 }
@@ -45,6 +76,9 @@ impl FindBar {
         self.query.clear();
         self.matches.clear();
         self.current_match = None;
+        self.mapped_from = 0;
+        self.mapped_search = None;
+        self.generation = None;
     }
 
     pub fn set_case_sensitive(&mut self, value: bool) {
@@ -99,84 +133,156 @@ impl FindBar {
         self.matches.get(self.current_match.unwrap())
     }
 
-    pub fn search(&mut self, text: &str, query: &str) -> Vec<Match> {
+    fn options(&self) -> SearchOptions {
+        SearchOptions {
+            regex: self.regex_mode,
+            case_sensitive: self.case_sensitive,
+            whole_word: self.whole_word,
+        }
+    }
+
+    /// Search `buffer` for `query`. The buffer keeps the matches up to date
+    /// as it is edited; [`FindBar::sync`] picks them up.
+    pub fn search(&mut self, buffer: &mut Buffer, query: &str) -> Vec<Match> {
         self.query = query.to_string();
-        self.matches.clear();
         self.current_match = None;
+        self.mapped_from = 0;
+        self.mapped_search = None;
+
+        match Searcher::new(query, self.options()) {
+            Ok(searcher) if !query.is_empty() => buffer.set_search(searcher),
+            // An invalid regex finds nothing
+            _ => buffer.clear_search(),
+        }
+        self.load_matches(buffer);
 
-        if query.is_empty() {
-            return Vec::new();
+        // Reset current match if we found some
+        if !self.matches.is_empty() {
+            self.current_match = Some(0);
         }
 
-        if self.regex_mode {
-            let mut builder = RegexBuilder::new(query);
-            builder.case_insensitive(!self.case_sensitive);
-
-            if let Ok(re) = builder.build() {
-                for (line_idx, line) in text.lines().enumerate() {
-                    for m in re.find_iter(line) {
-                        self.matches.push(Match {
-                            line: line_idx,
-                            start_col: m.start(),
-                            end_col: m.end(),
-                        });
-                    }
+        self.matches.clone()
+    }
+
+    /// Catch up with edits to `buffer` and with switching to another one.
+    /// Drops the buffer's search once the bar is closed.
+    pub fn sync(&mut self, buffer: &mut Buffer) {
+        if !self.visible {
+            buffer.clear_search();
+            return;
+        }
+        let stale = match buffer.search() {
+            Some(search) => {
+                let searcher = search.searcher();
+                let same = searcher.query() == self.query && searcher.options() == self.options();
+                if same && self.generation != Some(search.generation()) {
+                    self.load_matches(buffer);
                 }
+                !same
             }
+            // A tab that hasn't been searched yet
+            None => self.generation.is_some(),
+        };
+        if stale {
+            let query = self.query.clone();
+            self.search(buffer, &query);
+        }
+    }
+
+    fn load_matches(&mut self, buffer: &Buffer) {
+        let Some(search) = buffer.search() else {
+            self.matches.clear();
+            self.generation = None;
+            return;
+        };
+        let rope = buffer.rope();
+        self.matches = search
+            .matches()
+            .iter()
+            .map(|range| Match::from_range(rope, range.clone()))
+            .collect();
+        self.generation = Some(search.generation());
+        self.current_match = match self.matches.len() {
+            0 => None,
+            len => self.current_match.map(|i| i.min(len - 1)),
+        };
+    }
+
+    /// Start looking for the next match of the query in a memory-mapped
+    /// file, replacing any search still running.
+    ///
+    /// Scanning a multi-gigabyte file for every match up front would stall
+    /// the UI, so the scan runs in the background, [`FindBar::poll_mapped`]
+    /// picks up the match and only that match is kept in `matches`. Wraps
+    /// around at the end of the file. Returns `false` if there is nothing to
+    /// search for.
+    pub fn find_next_mapped(&mut self, file: &MappedFile) -> bool {
+        self.mapped_search = None;
+        if self.query.is_empty() {
+            return false;
+        }
+        let pattern = if self.regex_mode {
+            self.query.clone()
         } else {
-            let query_lower = if self.case_sensitive {
-                query.to_string()
-            } else {
-                query.to_lowercase()
-            };
-
-            for (line_idx, line) in text.lines().enumerate() {
-                let line_to_search = if self.case_sensitive {
-                    line.to_string()
-                } else {
-                    line.to_lowercase()
-                };
-
-                let mut start = 0;
-                while let Some(idx) = line_to_search[start..].find(&query_lower) {
-                    let absolute_idx = start + idx;
-                    let end_idx = absolute_idx + query.len();
-
-                    let is_match = if self.whole_word {
-                        let pre_char = if absolute_idx > 0 {
-                            line_to_search.chars().nth(absolute_idx - 1)
-                        } else {
-                            None
-                        };
-                        let post_char = line_to_search.chars().nth(end_idx);
-
-                        let pre_ok = pre_char.map_or(true, |c| !c.is_alphanumeric());
-                        let post_ok = post_char.map_or(true, |c| !c.is_alphanumeric());
-
-                        pre_ok && post_ok
-                    } else {
-                        true
-                    };
-
-                    if is_match {
-                        self.matches.push(Match {
-                            line: line_idx,
-                            start_col: absolute_idx,
-                            end_col: end_idx,
-                        });
-                    }
-
-                    start = absolute_idx + 1;
-                }
+            regex::escape(&self.query)
+        };
+        let pattern = if self.whole_word {
+            format!(r"\b(?:{})\b", pattern)
+        } else {
+            pattern
+        };
+        let re = regex::bytes::RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build();
+        let Ok(re) = re else {
+            return false;
+        };
+        match file.search(re, self.mapped_from) {
+            Ok(search) => {
+                self.mapped_search = Some((file.path().to_path_buf(), search));
+                true
+            }
+            Err(e) => {
+                tracing::warn!("Failed to start searching {}: {}", file.path().display(), e);
+                false
             }
         }
+    }
 
-        // Reset current match if we found some
-        if !self.matches.is_empty() {
-            self.current_match = Some(0);
-        }
+    /// Whether a background search of a memory-mapped file is running
+    pub fn is_searching_mapped(&self) -> bool {
+        self.mapped_search.is_some()
+    }
 
-        self.matches.clone()
+    /// Collect a finished [`FindBar::find_next_mapped`] search of `file`.
+    ///
+    /// `None` while it is still running (or if none was started); otherwise
+    /// the match found, if any. A search of another file is cancelled.
+    pub fn poll_mapped(&mut self, file: &MappedFile) -> Option<Option<Match>> {
+        let (path, search) = self.mapped_search.as_ref()?;
+        if path != file.path() {
+            self.mapped_search = None;
+            return None;
+        }
+        let found = search.try_finish()?;
+        self.mapped_search = None;
+        let Some(found) = found else {
+            return Some(None);
+        };
+        // Empty matches would otherwise be found again at the same spot
+        self.mapped_from = found.end.max(found.start + 1);
+        let line = file.byte_to_line(found.start);
+        let line_start = file.line_to_byte(line).unwrap_or(0);
+        let m = Match {
+            line,
+            start_col: found.start - line_start,
+            end_col: found.end - line_start,
+            start: found.start,
+            end: found.end,
+        };
+        self.matches = vec![m.clone()];
+        self.current_match = Some(0);
+        Some(Some(m))
     }
 }
 
@@ -187,8 +293,8 @@ mod tests {
     #[test]
     fn test_search_basic() {
         let mut bar = FindBar::new();
-        let text = "hello world\nhello universe";
-        let matches = bar.search(text, "hello");
+        let mut buffer = Buffer::from_str("hello world\nhello universe");
+        let matches = bar.search(&mut buffer, "hello");
 
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].line, 0);
@@ -200,8 +306,8 @@ mod tests {
     #[test]
     fn test_next_prev_match() {
         let mut bar = FindBar::new();
-        let text = "a\na\na";
-        bar.search(text, "a");
+        let mut buffer = Buffer::from_str("a\na\na");
+        bar.search(&mut buffer, "a");
 
         assert_eq!(bar.match_count(), 3);
 
@@ -232,40 +338,95 @@ mod tests {
     #[test]
     fn test_case_sensitivity() {
         let mut bar = FindBar::new();
-        let text = "Hello hello";
+        let mut buffer = Buffer::from_str("Hello hello");
 
         bar.set_case_sensitive(true);
-        let matches = bar.search(text, "Hello");
+        let matches = bar.search(&mut buffer, "Hello");
         assert_eq!(matches.len(), 1);
 
         bar.set_case_sensitive(false);
-        let matches = bar.search(text, "Hello");
+        let matches = bar.search(&mut buffer, "Hello");
         assert_eq!(matches.len(), 2);
     }
 
     #[test]
     fn test_whole_word() {
         let mut bar = FindBar::new();
-        let text = "hello helloworld hello";
+        let mut buffer = Buffer::from_str("hello helloworld hello");
 
         bar.set_whole_word(true);
-        let matches = bar.search(text, "hello");
+        let matches = bar.search(&mut buffer, "hello");
         assert_eq!(matches.len(), 2); // First and last
 
         bar.set_whole_word(false);
-        let matches = bar.search(text, "hello");
+        let matches = bar.search(&mut buffer, "hello");
         assert_eq!(matches.len(), 3); // All occurrences
     }
 
     #[test]
     fn test_regex() {
         let mut bar = FindBar::new();
-        let text = "abc 123 def 456";
+        let mut buffer = Buffer::from_str("abc 123 def 456");
 
         bar.set_regex(true);
-        let matches = bar.search(text, "\\d+");
+        let matches = bar.search(&mut buffer, "\\d+");
         assert_eq!(matches.len(), 2);
         assert_eq!(matches[0].start_col, 4);
         assert_eq!(matches[1].start_col, 12);
     }
+
+    #[test]
+    fn test_find_next_mapped() {
+        let path = std::env::temp_dir().join("forge_find_bar_mapped.log");
+        std::fs::write(&path, "INFO start\nerror: disk\nINFO ok\nERROR: net\n").unwrap();
+        let file = MappedFile::open(&path).unwrap();
+
+        let mut bar = FindBar::new();
+        bar.search(&mut Buffer::new(), "error");
+        let mut next = || {
+            assert!(bar.find_next_mapped(&file));
+            loop {
+                if let Some(found) = bar.poll_mapped(&file) {
+                    break found.unwrap();
+                }
+                std::thread::yield_now();
+            }
+        };
+        let first = next();
+        assert_eq!((first.line, first.start_col, first.end_col), (1, 0, 5));
+        assert_eq!(next().line, 3);
+        // Wraps back to the first match
+        assert_eq!(next().line, 1);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_sync_follows_edits() {
+        let mut bar = FindBar::new();
+        let mut buffer = Buffer::from_str("one two\ntwo three\n");
+        bar.open();
+        bar.search(&mut buffer, "two");
+        bar.next_match();
+        assert_eq!(bar.current_match, Some(1));
+
+        buffer.apply(forge_core::Transaction::from_change(
+            forge_core::Change::delete(forge_core::Position::new(8), forge_core::Position::new(12)),
+        ));
+        bar.sync(&mut buffer);
+        assert_eq!(bar.match_count(), 1);
+        assert_eq!(bar.current_match, Some(0));
+        assert_eq!((bar.matches[0].start, bar.matches[0].end), (4, 7));
+
+        // Toggling an option searches again
+        bar.set_case_sensitive(true);
+        bar.search(&mut buffer, "ONE");
+        assert_eq!(bar.match_count(), 0);
+        bar.set_case_sensitive(false);
+        bar.sync(&mut buffer);
+        assert_eq!(bar.match_count(), 1);
+
+        bar.close();
+        bar.sync(&mut buffer);
+        assert!(buffer.search().is_none());
+    }
 }
@@ -7,6 +7,7 @@ pub enum Language {
     Rust,
     JavaScript,
     TypeScript,
+    Tsx,
     Python,
     Go,
     Html,
@@ -23,6 +24,7 @@ impl Formatter {
             Language::Rust => Self::format_rust(content),
             Language::JavaScript
             | Language::TypeScript
+            | Language::Tsx
             | Language::Html
             | Language::Css
             | Language::Json => Self::format_prettier(content, &lang),
@@ -55,7 +57,7 @@ impl Formatter {
     fn format_prettier(content: &str, lang: &Language) -> Result<String> {
         let parser = match lang {
             Language::JavaScript => "babel",
-            Language::TypeScript => "typescript",
+            Language::TypeScript | Language::Tsx => "typescript",
             Language::Html => "html",
             Language::Css => "css",
             Language::Json => "json",
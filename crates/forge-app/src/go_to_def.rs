@@ -1,5 +1,6 @@
+use forge_core::ColumnUnit;
 use forge_lsp::LspClient;
-use lsp_types::Location;
+use lsp_types::{Location, PositionEncodingKind};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::runtime::Runtime;
@@ -7,6 +8,21 @@ use url::Url;
 
 pub struct GoToDef;
 
+/// What the connected language server counts columns in; UTF-16 when none
+/// is connected, as that's the protocol default
+pub fn column_unit(client: &Option<Arc<LspClient>>) -> ColumnUnit {
+    let encoding = client
+        .as_ref()
+        .map_or(PositionEncodingKind::UTF16, |c| c.position_encoding());
+    if encoding == PositionEncodingKind::UTF8 {
+        ColumnUnit::Byte
+    } else if encoding == PositionEncodingKind::UTF32 {
+        ColumnUnit::Char
+    } else {
+        ColumnUnit::Utf16
+    }
+}
+
 impl GoToDef {
     pub fn execute(
         rt: &Arc<Runtime>,
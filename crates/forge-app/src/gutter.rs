@@ -3,8 +3,9 @@ use crate::ui::{colors, LayoutConstants, Zone};
 
 /// Editor gutter — line numbers + diagnostics indicators
 pub struct Gutter {
-    /// First visible line (0-indexed)
-    pub scroll_top: usize,
+    /// Buffer line starting on each visible row (0-indexed), `None` where a
+    /// wrapped line continues
+    pub rows: Vec<Option<usize>>,
     /// Total lines in the file
     pub total_lines: usize,
     /// Current cursor line (0-indexed)
@@ -27,7 +28,7 @@ pub enum DiagnosticSeverity {
 impl Gutter {
     pub fn new() -> Self {
         Self {
-            scroll_top: 0,
+            rows: vec![Some(0)],
             total_lines: 1,
             cursor_line: 0,
             diagnostics: Vec::new(),
@@ -45,11 +46,10 @@ impl Gutter {
         let mut rects = Vec::with_capacity(32);
         let visible = Self::visible_lines(zone);
 
-        for i in 0..visible {
-            let line = self.scroll_top + i;
-            if line >= self.total_lines {
-                break;
-            }
+        for (i, line) in self.rows.iter().enumerate().take(visible) {
+            let Some(line) = *line else {
+                continue;
+            };
             let y = zone.y + (i as f32 * LayoutConstants::LINE_HEIGHT);
 
             // Current line highlight
@@ -102,11 +102,10 @@ impl Gutter {
         let mut result = Vec::with_capacity(visible);
         let line_num_width = format!("{}", self.total_lines).len();
 
-        for i in 0..visible {
-            let line = self.scroll_top + i;
-            if line >= self.total_lines {
-                break;
-            }
+        for (i, line) in self.rows.iter().enumerate().take(visible) {
+            let Some(line) = *line else {
+                continue;
+            };
             let text = format!("{:>width$}", line + 1, width = line_num_width);
             let x = zone.x + 20.0; // After breakpoint area
             let y = zone.y + (i as f32 * LayoutConstants::LINE_HEIGHT) + 2.0;
@@ -135,13 +134,9 @@ impl Gutter {
             return None;
         }
         let line_index = (relative_y / LayoutConstants::LINE_HEIGHT) as usize;
-        let line = self.scroll_top + line_index;
-        if line < self.total_lines {
-            self.toggle_breakpoint(line);
-            Some(line)
-        } else {
-            None
-        }
+        let line = self.rows.get(line_index).copied().flatten()?;
+        self.toggle_breakpoint(line);
+        Some(line)
     }
 }
 
@@ -27,6 +27,7 @@ mod terminal_tabs;
 mod activity_bar;
 mod autocomplete;
 mod breadcrumb;
+mod collab;
 mod cursor;
 mod debug_ui;
 mod debug_views;
@@ -71,6 +72,8 @@ pub mod problems_panel;
 pub mod replace_bar;
 pub mod status_segments;
 pub mod title_bar;
+pub mod undo_tree_panel;
+pub mod recovery_dialog;
 pub mod word_wrap;
 
 // Session 3 - Terminal + Git + Search
@@ -106,8 +109,9 @@ fn main() -> Result<()> {
     while let Some(arg) = args.next() {
         match arg.as_str() {
             "--help" | "-h" => {
-                println!("Usage: forge [FILE] [--screenshot [PATH]] [--debug-zones]");
+                println!("Usage: forge [FILE|FOLDER] [--screenshot [PATH]] [--debug-zones]");
                 println!("  FILE                    Optional file path to open");
+                println!("  FOLDER                  Optional folder to open as the project");
                 println!("  --screenshot [PATH]     Render one frame and save as PNG");
                 println!("  --debug-zones           Draw colored borders around UI zones");
                 return Ok(());
@@ -1,4 +1,4 @@
-use forge_core::{Buffer, Position};
+use forge_core::{Buffer, ColumnUnit, LineCol, Position};
 
 #[derive(Clone, Debug)]
 pub struct ParamHint {
@@ -13,7 +13,7 @@ impl ParamHintProvider {
     pub fn provide(buffer: &Buffer, pos: Position) -> Option<ParamHint> {
         // Placeholder implementation
         // Check if we are inside a function call like `foo(`
-        let (line, col) = buffer.offset_to_line_col(pos.offset);
+        let LineCol { line, col } = buffer.position_to_line_col(pos, ColumnUnit::Char);
         if line >= buffer.len_lines() {
             return None;
         }
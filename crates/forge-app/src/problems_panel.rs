@@ -47,6 +47,15 @@ impl ProblemsPanel {
             .collect()
     }
 
+    /// File and zero-based line of every diagnostic, for opening them
+    /// together in a multi-buffer
+    pub fn locations(&self) -> Vec<(String, usize)> {
+        self.diagnostics
+            .iter()
+            .map(|d| (d.file.clone(), d.line.saturating_sub(1)))
+            .collect()
+    }
+
     pub fn count_by_severity(&self) -> (usize, usize, usize, usize) {
         let mut errors = 0;
         let mut warnings = 0;
@@ -127,4 +136,18 @@ mod tests {
         panel.clear();
         assert!(panel.diagnostics.is_empty());
     }
+
+    #[test]
+    fn test_locations_are_zero_based() {
+        let mut panel = ProblemsPanel::new();
+        panel.add(Diagnostic {
+            file: "main.rs".to_string(),
+            line: 3,
+            col: 1,
+            message: "Error".to_string(),
+            severity: Severity::Error,
+        });
+
+        assert_eq!(panel.locations(), vec![("main.rs".to_string(), 2)]);
+    }
 }
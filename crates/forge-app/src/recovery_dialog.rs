@@ -0,0 +1,156 @@
+use crate::diff_view::{DiffHunk, DiffLineKind};
+use forge_core::JournalInfo;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// "Recover unsaved work" dialog listing journals left by a crashed session.
+#[derive(Debug, Default)]
+pub struct RecoveryDialog {
+    pub visible: bool,
+    pub journals: Vec<JournalInfo>,
+    pub selected: usize,
+    /// Diff of the selected journal against the file on disk, once requested
+    pub diff: Option<Vec<DiffHunk>>,
+}
+
+impl RecoveryDialog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the dialog if there is anything to recover
+    pub fn open(&mut self, journals: Vec<JournalInfo>) {
+        self.visible = !journals.is_empty();
+        self.journals = journals;
+        self.selected = 0;
+        self.diff = None;
+    }
+
+    pub fn close(&mut self) {
+        self.visible = false;
+        self.diff = None;
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+        self.diff = None;
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.journals.len() {
+            self.selected += 1;
+        }
+        self.diff = None;
+    }
+
+    pub fn selected_journal(&self) -> Option<&JournalInfo> {
+        self.journals.get(self.selected)
+    }
+
+    /// Drop the selected journal from the list after it was recovered or
+    /// discarded; closes the dialog when none are left
+    pub fn remove_selected(&mut self) {
+        if self.selected < self.journals.len() {
+            self.journals.remove(self.selected);
+        }
+        self.selected = self.selected.min(self.journals.len().saturating_sub(1));
+        self.diff = None;
+        if self.journals.is_empty() {
+            self.visible = false;
+        }
+    }
+
+    /// Text shown in the overlay
+    pub fn render_text(&self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.render_text_at(now)
+    }
+
+    fn render_text_at(&self, now: u64) -> String {
+        let mut text = String::from(
+            "Recover Unsaved Work\n(Enter: recover, D: diff, Delete: discard, Esc: later)\n\n",
+        );
+        for (i, journal) in self.journals.iter().enumerate() {
+            let cursor = if i == self.selected { ">" } else { " " };
+            let stale = if journal.base_matches_disk() {
+                ""
+            } else {
+                "  (file changed since)"
+            };
+            text.push_str(&format!(
+                "{} {}  edited {}{}\n",
+                cursor,
+                journal.path,
+                Self::age(now.saturating_sub(journal.updated_at)),
+                stale
+            ));
+        }
+        if let Some(hunks) = &self.diff {
+            text.push('\n');
+            if hunks.is_empty() {
+                text.push_str("No differences from the file on disk\n");
+            }
+            for hunk in hunks {
+                text.push_str(&format!(
+                    "@@ -{},{} +{},{} @@\n",
+                    hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
+                ));
+                for line in &hunk.lines {
+                    let sign = match line.kind {
+                        DiffLineKind::Added => '+',
+                        DiffLineKind::Removed => '-',
+                        DiffLineKind::Context => ' ',
+                    };
+                    text.push_str(&format!("{}{}\n", sign, line.text));
+                }
+            }
+        }
+        text
+    }
+
+    fn age(secs: u64) -> String {
+        match secs {
+            0..=59 => format!("{}s ago", secs),
+            60..=3599 => format!("{}m ago", secs / 60),
+            3600..=86_399 => format!("{}h ago", secs / 3600),
+            _ => format!("{}d ago", secs / 86_400),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge_core::{Encoding, RecoveryManager};
+
+    #[test]
+    fn lists_journals_and_closes_when_all_handled() {
+        let dir = std::env::temp_dir().join("forge_test_recovery_dialog");
+        let _ = std::fs::remove_dir_all(&dir);
+        let recovery = RecoveryManager::with_dir(&dir);
+        recovery
+            .start("/nonexistent/forge/a.rs", 0, Encoding::Utf8)
+            .unwrap();
+        recovery
+            .start("/nonexistent/forge/b.rs", 0, Encoding::Utf8)
+            .unwrap();
+
+        let mut dialog = RecoveryDialog::new();
+        dialog.open(recovery.list().unwrap());
+        assert!(dialog.visible);
+        dialog.select_next();
+        let updated_at = dialog.selected_journal().unwrap().updated_at;
+        let text = dialog.render_text_at(updated_at + 120);
+        assert!(text.contains("> /nonexistent/forge/b.rs  edited 2m ago  (file changed since)"));
+
+        dialog.remove_selected();
+        assert_eq!(dialog.selected, 0);
+        assert!(dialog.visible);
+        dialog.remove_selected();
+        assert!(!dialog.visible);
+
+        dialog.open(Vec::new());
+        assert!(!dialog.visible);
+    }
+}
@@ -1 +1,43 @@
+use forge_lsp::LspClient;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use url::Url;
+
 pub struct References;
+
+impl References {
+    /// Ask the language server for every reference to the symbol at the
+    /// position; they come back as an `AppEvent::References`
+    pub fn execute(
+        rt: &Arc<Runtime>,
+        client: &Option<Arc<LspClient>>,
+        file_path: &str,
+        line: u32,
+        character: u32,
+        notifications: &mut crate::notifications::NotificationManager,
+        event_proxy: std::sync::mpsc::Sender<crate::application::AppEvent>,
+    ) {
+        let Some(client) = client else {
+            notifications.show(
+                "LSP client not connected",
+                crate::notifications::Level::Warning,
+            );
+            return;
+        };
+        let Some(uri) = std::fs::canonicalize(file_path)
+            .ok()
+            .and_then(|path| Url::from_file_path(path).ok())
+        else {
+            return;
+        };
+        let client = client.clone();
+        rt.spawn(async move {
+            match client.references(uri, line, character).await {
+                Ok(locations) => {
+                    let _ = event_proxy.send(crate::application::AppEvent::References(locations));
+                }
+                Err(e) => tracing::error!("References error: {}", e),
+            }
+        });
+    }
+}
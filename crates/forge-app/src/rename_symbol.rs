@@ -1,11 +1,11 @@
-use forge_core::{Buffer, Change, ChangeSet, Position, Transaction};
+use forge_core::{Buffer, Change, ChangeSet, ColumnUnit, LineCol, Position, Transaction};
 
 pub struct RenameProvider;
 
 impl RenameProvider {
     pub fn prepare_rename(buffer: &Buffer, pos: Position) -> Option<String> {
         // Find the word at the cursor position
-        let (line, col) = buffer.offset_to_line_col(pos.offset);
+        let LineCol { line, col } = buffer.position_to_line_col(pos, ColumnUnit::Char);
         if line >= buffer.len_lines() {
             return None;
         }
@@ -32,7 +32,7 @@ impl RenameProvider {
             end += 1;
         }
 
-        Some(line_content[start..end].to_string())
+        Some(chars[start..end].iter().collect())
     }
 
     pub fn apply_rename(buffer: &Buffer, pos: Position, new_name: &str) -> Option<Transaction> {
@@ -56,6 +56,6 @@ impl RenameProvider {
             return None;
         }
 
-        Some(Transaction::new(ChangeSet { changes }, None))
+        Some(Transaction::new(ChangeSet::from_changes(changes), None))
     }
 }
@@ -106,6 +106,8 @@ mod tests {
             line: 0,
             start_col: 0,
             end_col: 5,
+            start: 0,
+            end: 5,
         };
 
         let new_text = bar.replace_current(&mut text, "hello", "hi", &m);
@@ -46,8 +46,14 @@ pub struct StatusBar {
     pub language: String,
     /// Line ending style
     pub line_ending: String,
+    /// Whether the buffer mixes line ending styles
+    pub mixed_line_endings: bool,
     /// Git branch name
     pub git_branch: Option<String>,
+    /// Names of the others editing the active tab, when it is shared
+    pub collaborators: Vec<String>,
+    /// The collaborator whose cursor is followed
+    pub following: Option<String>,
     /// Frame time in ms
     pub frame_time_ms: f32,
     /// Confidence score (0-100)
@@ -73,7 +79,10 @@ impl StatusBar {
             encoding: String::from("UTF-8"),
             language: String::from("Plain Text"),
             line_ending: String::from("LF"),
+            mixed_line_endings: false,
             git_branch: None,
+            collaborators: Vec::new(),
+            following: None,
             frame_time_ms: 0.0,
             confidence_score: None,
             ai_status: String::from("Ready"),
@@ -108,6 +117,21 @@ impl StatusBar {
             });
         }
 
+        // Collaborators
+        if !self.collaborators.is_empty() {
+            items.push(StatusItem {
+                text: match &self.following {
+                    Some(name) => format!("👥 {} · following {}", self.collaborators.len(), name),
+                    None => format!("👥 {}", self.collaborators.len()),
+                },
+                tooltip: format!("Editing with {}", self.collaborators.join(", ")),
+                color: None,
+                alignment: StatusAlignment::Left,
+                priority: 95,
+                click_action: None,
+            });
+        }
+
         // Errors and warnings
         if self.error_count > 0 || self.warning_count > 0 {
             items.push(StatusItem {
@@ -171,9 +195,17 @@ impl StatusBar {
 
         // Line ending
         items.push(StatusItem {
-            text: self.line_ending.clone(),
-            tooltip: String::from("Select End of Line Sequence"),
-            color: None,
+            text: if self.mixed_line_endings {
+                format!("⚠ Mixed ({})", self.line_ending)
+            } else {
+                self.line_ending.clone()
+            },
+            tooltip: if self.mixed_line_endings {
+                String::from("Mixed line endings — click to convert")
+            } else {
+                String::from("Select End of Line Sequence")
+            },
+            color: self.mixed_line_endings.then_some(colors::WARNING),
             alignment: StatusAlignment::Right,
             priority: 60,
             click_action: Some(StatusAction::SelectLineEnding),
@@ -230,24 +262,14 @@ impl StatusBar {
         items
     }
 
-    /// Get text positions for rendering
-    /// Returns (text, x, y, color) tuples
-    #[allow(dead_code)]
-    pub fn text_positions(
-        &self,
-        zone: &Zone,
-        theme: &forge_theme::Theme,
-    ) -> Vec<(String, f32, f32, [f32; 4])> {
+    /// Lay out items horizontally.
+    /// Returns (item, x, width) tuples
+    fn layout_items(&self, zone: &Zone, theme: &forge_theme::Theme) -> Vec<(StatusItem, f32, f32)> {
         let items = self.build_items(theme);
         let mut result = Vec::with_capacity(items.len());
-        let text_y = zone.y + (zone.height - LayoutConstants::SMALL_FONT_SIZE) / 2.0;
         let char_width = LayoutConstants::CHAR_WIDTH;
         let padding = 12.0;
 
-        let default_fg = theme
-            .color("statusBar.foreground")
-            .unwrap_or(colors::TEXT_WHITE);
-
         // Left items
         let mut left_x = zone.x + padding;
         let mut left_items: Vec<&StatusItem> = items
@@ -257,9 +279,9 @@ impl StatusBar {
         left_items.sort_by(|a, b| b.priority.cmp(&a.priority));
 
         for item in &left_items {
-            let color = item.color.unwrap_or(default_fg);
-            result.push((item.text.clone(), left_x, text_y, color));
-            left_x += item.text.len() as f32 * char_width + padding;
+            let text_width = item.text.len() as f32 * char_width;
+            result.push(((*item).clone(), left_x, text_width));
+            left_x += text_width + padding;
         }
 
         // Right items (render from right edge leftward)
@@ -273,13 +295,44 @@ impl StatusBar {
         for item in &right_items {
             let text_width = item.text.len() as f32 * char_width;
             right_x -= text_width;
-            let color = item.color.unwrap_or(default_fg);
-            result.push((item.text.clone(), right_x, text_y, color));
+            result.push(((*item).clone(), right_x, text_width));
             right_x -= padding;
         }
 
         result
     }
+
+    /// Get text positions for rendering
+    /// Returns (text, x, y, color) tuples
+    #[allow(dead_code)]
+    pub fn text_positions(
+        &self,
+        zone: &Zone,
+        theme: &forge_theme::Theme,
+    ) -> Vec<(String, f32, f32, [f32; 4])> {
+        let text_y = zone.y + (zone.height - LayoutConstants::SMALL_FONT_SIZE) / 2.0;
+        let default_fg = theme
+            .color("statusBar.foreground")
+            .unwrap_or(colors::TEXT_WHITE);
+
+        self.layout_items(zone, theme)
+            .into_iter()
+            .map(|(item, x, _)| (item.text, x, text_y, item.color.unwrap_or(default_fg)))
+            .collect()
+    }
+
+    /// Find the click action of the item under the given x coordinate
+    pub fn action_at(
+        &self,
+        x: f32,
+        zone: &Zone,
+        theme: &forge_theme::Theme,
+    ) -> Option<StatusAction> {
+        self.layout_items(zone, theme)
+            .into_iter()
+            .find(|(_, item_x, width)| x >= *item_x && x < item_x + width)
+            .and_then(|(item, _, _)| item.click_action)
+    }
 }
 
 impl Default for StatusBar {
@@ -287,3 +340,28 @@ impl Default for StatusBar {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn click_on_line_ending_item() {
+        let mut bar = StatusBar::new();
+        bar.mixed_line_endings = true;
+        let theme = forge_theme::Theme::default_dark();
+        let zone = Zone::new(0.0, 0.0, 1200.0, 22.0);
+
+        let (item, x, _) = bar
+            .layout_items(&zone, &theme)
+            .into_iter()
+            .find(|(i, _, _)| i.text.contains("Mixed"))
+            .unwrap();
+        assert_eq!(item.color, Some(colors::WARNING));
+        assert!(matches!(
+            bar.action_at(x + 1.0, &zone, &theme),
+            Some(StatusAction::SelectLineEnding)
+        ));
+        assert!(bar.action_at(-5.0, &zone, &theme).is_none());
+    }
+}
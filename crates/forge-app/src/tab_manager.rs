@@ -1,6 +1,21 @@
 use crate::editor::Editor;
 use anyhow::Result;
-use std::path::PathBuf;
+use forge_core::{
+    ExternalChange, FileEvent, FileWatcher, Journal, JournalInfo, LargeFileLimits, MultiBuffer,
+    RecoveryManager, Session, Transaction, UndoRestore, UndoStore, UnmappableFile,
+};
+use std::collections::hash_map::{Entry, HashMap};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// Lines shown around each hit in a multi-buffer
+const EXCERPT_CONTEXT: usize = 2;
+/// Lines a multi-buffer excerpt grows by each way when expanded
+const EXCERPT_EXPAND: usize = 5;
+/// How often unsaved edits are written to the recovery journals, so typing
+/// doesn't sync the disk on every keystroke
+const JOURNAL_INTERVAL: Duration = Duration::from_millis(500);
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Pane {
@@ -13,6 +28,27 @@ pub struct TabManager {
     pub active: usize,
     pub active_secondary: Option<usize>,
     pub focused_pane: Pane,
+    /// Where undo history is persisted; `None` disables persistent undo
+    undo_store: Option<UndoStore>,
+    /// Messages for the user produced while opening files
+    notices: Vec<String>,
+    /// Sizes at which files open in large-file mode
+    large_file_limits: LargeFileLimits,
+    /// Notices when open files change on disk
+    watcher: Option<FileWatcher>,
+    /// Where unsaved edits are journaled; `None` disables crash recovery
+    recovery: Option<RecoveryManager>,
+    /// Open journals by file path, for buffers with unsaved changes
+    journals: HashMap<String, Journal>,
+    /// Files whose edits couldn't all be journaled; their next journal
+    /// starts from a snapshot of the text
+    unjournaled: HashSet<String>,
+    /// When the journals were last written
+    journaled_at: Instant,
+    /// Multi-buffers need rebuilding even though no buffer was edited
+    multi_buffers_stale: bool,
+    /// Files that changed on disk while their tabs were still loading them
+    deferred_syncs: Vec<PathBuf>,
 }
 
 pub struct Tab {
@@ -20,6 +56,10 @@ pub struct Tab {
     pub path: Option<PathBuf>,
     pub editor: Editor,
     pub is_modified: bool,
+    /// Set for tabs showing excerpts of other files; edits go to those files
+    pub multi_buffer: Option<MultiBuffer>,
+    /// Set while the tab is shared with collaborators
+    pub collab: Option<Session>,
 }
 
 impl TabManager {
@@ -29,9 +69,182 @@ impl TabManager {
             active: 0,
             active_secondary: None,
             focused_pane: Pane::Primary,
+            undo_store: None,
+            notices: Vec::new(),
+            large_file_limits: LargeFileLimits::default(),
+            watcher: FileWatcher::new(forge_core::file_watch::DEFAULT_POLL_INTERVAL)
+                .map_err(|e| tracing::warn!("File watching disabled: {}", e))
+                .ok(),
+            recovery: None,
+            journals: HashMap::new(),
+            unjournaled: HashSet::new(),
+            journaled_at: Instant::now(),
+            multi_buffers_stale: false,
+            deferred_syncs: Vec::new(),
+        }
+    }
+
+    pub fn set_large_file_limits(&mut self, limits: LargeFileLimits) {
+        self.large_file_limits = limits;
+    }
+
+    /// Enable persistent undo backed by `store`
+    pub fn set_undo_store(&mut self, store: UndoStore) {
+        self.undo_store = Some(store);
+    }
+
+    /// Enable crash recovery journals managed by `recovery`
+    pub fn set_recovery(&mut self, recovery: RecoveryManager) {
+        self.recovery = Some(recovery);
+    }
+
+    /// Journals of unsaved work left behind by a previous session
+    pub fn recoverable_journals(&self) -> Vec<JournalInfo> {
+        let Some(recovery) = &self.recovery else {
+            return Vec::new();
+        };
+        recovery.list().unwrap_or_else(|e| {
+            tracing::warn!("Failed to list recovery journals: {}", e);
+            Vec::new()
+        })
+    }
+
+    /// Open the file a journal belongs to and replay the unsaved edits into it
+    pub fn recover(&mut self, info: &JournalInfo) -> Result<()> {
+        let recovery = self
+            .recovery
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Crash recovery is disabled"))?;
+        let text = recovery.replay(info)?;
+        self.open_file(&info.path)?;
+        let Some(tab) = self.tabs.get_mut(self.active) else {
+            return Ok(());
+        };
+        let buffer_path = tab.editor.buffer.path().map(str::to_string);
+        let current = tab.editor.buffer.text();
+        let changes = forge_core::merge::diff_changes(&current, &text);
+        // Queue the recovered edits so they go into a fresh journal
+        tab.editor.buffer.enable_journal();
+        if !changes.is_empty() {
+            tab.editor
+                .buffer
+                .apply(forge_core::Transaction::new(changes, None));
+            tab.editor.rehighlight();
+            tab.is_modified = true;
+        }
+        self.flush_journals();
+
+        // The fresh journal replaces the old one when both are for the same
+        // path; otherwise the old one is deleted once the edits are safe
+        let journaled = buffer_path
+            .as_ref()
+            .is_some_and(|path| self.journals.contains_key(path));
+        let dirty = self.tabs[self.active].editor.buffer.is_dirty();
+        if (journaled && buffer_path.as_deref() != Some(info.path.as_str())) || !dirty {
+            self.discard_journal(info)?;
+        }
+        Ok(())
+    }
+
+    /// Unified diff of a journal's unsaved text against the file on disk
+    pub fn recovery_diff(&self, info: &JournalInfo) -> Result<Vec<crate::diff_view::DiffHunk>> {
+        let recovery = self
+            .recovery
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Crash recovery is disabled"))?;
+        let recovered = recovery.replay(info)?;
+        let on_disk = info.read_disk_text().unwrap_or_default();
+        crate::diff_view::DiffView::compute_diff(&on_disk, &recovered)
+    }
+
+    /// Delete a journal without recovering it
+    pub fn discard_journal(&self, info: &JournalInfo) -> Result<()> {
+        match &self.recovery {
+            Some(recovery) => recovery.discard(info),
+            None => Ok(()),
+        }
+    }
+
+    /// Journal pending edits, at most once every [`JOURNAL_INTERVAL`]; called
+    /// every frame
+    pub fn poll_journals(&mut self) {
+        if self.journaled_at.elapsed() >= JOURNAL_INTERVAL {
+            self.flush_journals();
+        }
+    }
+
+    /// Append edits made since the last call to each buffer's journal,
+    /// syncing each journal once.
+    ///
+    /// A journal is started when a buffer gets unsaved changes, restarted when
+    /// the file it is based on changes, compacted when it grows long, and
+    /// deleted once the buffer is saved or reverted. After a failure the
+    /// journal is dropped, since later edits wouldn't line up with it.
+    pub fn flush_journals(&mut self) {
+        self.journaled_at = Instant::now();
+        let Some(recovery) = &self.recovery else {
+            return;
+        };
+        for tab in &mut self.tabs {
+            let buffer = &mut tab.editor.buffer;
+            let (Some(path), Some(base_hash), false) =
+                (buffer.path(), buffer.disk_hash(), tab.editor.large_file)
+            else {
+                continue;
+            };
+            let path = path.to_string();
+            if !buffer.is_dirty() {
+                buffer.enable_journal();
+                remove_journal(&mut self.journals, &path);
+                self.unjournaled.remove(&path);
+                continue;
+            }
+            let changes = buffer.take_journal_changes();
+            if changes.is_empty() && !self.unjournaled.contains(&path) {
+                continue;
+            }
+            let journal = match self.journals.entry(path.clone()) {
+                Entry::Occupied(entry) if entry.get().base_hash() == base_hash => entry.into_mut(),
+                // New unsaved changes, or the file changed under the old journal
+                entry => match recovery.start(&path, base_hash, buffer.encoding()) {
+                    Ok(journal) => entry.insert_entry(journal).into_mut(),
+                    Err(e) => {
+                        tracing::warn!("Failed to start recovery journal for {}: {}", path, e);
+                        remove_journal(&mut self.journals, &path);
+                        self.unjournaled.insert(path);
+                        continue;
+                    }
+                },
+            };
+            let result = if self.unjournaled.contains(&path) {
+                journal.compact(&buffer.text())
+            } else {
+                journal.record_all(&changes).and_then(|()| {
+                    if journal.needs_compaction() {
+                        journal.compact(&buffer.text())
+                    } else {
+                        Ok(())
+                    }
+                })
+            };
+            match result {
+                Ok(()) => {
+                    self.unjournaled.remove(&path);
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to journal edits to {}: {}", path, e);
+                    remove_journal(&mut self.journals, &path);
+                    self.unjournaled.insert(path);
+                }
+            }
         }
     }
 
+    /// Drain notices queued since the last call
+    pub fn take_notices(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.notices)
+    }
+
     /// Open a scratch (untitled) tab so keyboard input works immediately.
     pub fn open_scratch(&mut self) {
         let mut editor = Editor::new();
@@ -46,6 +259,8 @@ impl TabManager {
             path: None,
             editor,
             is_modified: false,
+            multi_buffer: None,
+            collab: None,
         });
         self.active = 0;
     }
@@ -62,7 +277,34 @@ impl TabManager {
             self.active = idx;
             return Ok(());
         }
-        let editor = Editor::open_file(path)?;
+        let mut editor = match Editor::open_file_with(path, &self.large_file_limits) {
+            Ok(editor) => editor,
+            Err(e) => {
+                // Other failures are the caller's to report, but this one
+                // needs explaining
+                if let Some(unmappable) = e.downcast_ref::<UnmappableFile>() {
+                    self.notices.push(unmappable.to_string());
+                }
+                return Err(e);
+            }
+        };
+        if editor.mapped.is_some() {
+            self.notices.push(format!(
+                "{} is too large to edit and was opened read-only",
+                path
+            ));
+        } else if editor.large_file {
+            self.notices.push(format!(
+                "{} is large; syntax highlighting, minimap and word wrap are off",
+                path
+            ));
+        } else {
+            self.restore_undo(path, &mut editor);
+        }
+        // Mapped views read straight from the file and never go stale
+        if let (Some(watcher), None) = (&self.watcher, &editor.mapped) {
+            watcher.watch(path);
+        }
         let title = std::path::Path::new(path)
             .file_name()
             .map(|n| n.to_string_lossy().to_string())
@@ -72,14 +314,77 @@ impl TabManager {
             path: Some(PathBuf::from(path)),
             editor,
             is_modified: false,
+            multi_buffer: None,
+            collab: None,
         });
         self.active = self.tabs.len() - 1;
         Ok(())
     }
 
+    /// Load persisted undo history for a freshly opened file.
+    fn restore_undo(&mut self, path: &str, editor: &mut Editor) {
+        let Some(store) = &self.undo_store else {
+            return;
+        };
+        let Ok(bytes) = std::fs::read(path) else {
+            return;
+        };
+        match store.load(path, &bytes) {
+            Ok(UndoRestore::Restored(history)) => editor.buffer.restore_history(history),
+            Ok(UndoRestore::Stale) => self.notices.push(format!(
+                "{} changed outside Forge; its undo history was discarded",
+                path
+            )),
+            Ok(UndoRestore::Missing) => {}
+            Err(e) => tracing::warn!("Failed to load undo history for {}: {}", path, e),
+        }
+    }
+
+    /// Persist a tab's undo history if its buffer matches the file on disk.
+    fn persist_undo(&self, idx: usize) {
+        let (Some(store), Some(tab)) = (&self.undo_store, self.tabs.get(idx)) else {
+            return;
+        };
+        if tab.is_modified || tab.editor.large_file {
+            return;
+        }
+        let buffer = &tab.editor.buffer;
+        let Some(path) = buffer.path() else {
+            return;
+        };
+        let result = buffer
+            .encoded_bytes()
+            .and_then(|bytes| store.save(path, &bytes, buffer.history()));
+        if let Err(e) = result {
+            tracing::warn!("Failed to persist undo history for {}: {}", path, e);
+        }
+    }
+
+    /// Persist the undo history of every saved tab, e.g. before quitting
+    pub fn persist_all_undo(&self) {
+        for idx in 0..self.tabs.len() {
+            self.persist_undo(idx);
+        }
+    }
+
     pub fn close_tab(&mut self, idx: usize) {
         if idx < self.tabs.len() {
-            self.tabs.remove(idx);
+            self.persist_undo(idx);
+            let tab = self.tabs.remove(idx);
+            let still_open = |path: &PathBuf| self.tabs.iter().any(|t| t.path.as_ref() == Some(path));
+            if let (Some(watcher), Some(path)) = (&self.watcher, &tab.path) {
+                if !still_open(path) {
+                    watcher.unwatch(path);
+                }
+            }
+            // Closing without saving gives up the unsaved changes for good
+            if let Some(path) = tab.path.as_ref().filter(|p| !still_open(p)) {
+                if let Some(journal) = self.journals.remove(&*path.to_string_lossy()) {
+                    let _ = journal.remove();
+                }
+            }
+            // Excerpts of the file it showed go away
+            self.multi_buffers_stale = true;
             if self.active >= self.tabs.len() && !self.tabs.is_empty() {
                 self.active = self.tabs.len() - 1;
             }
@@ -124,13 +429,18 @@ impl TabManager {
             Pane::Secondary => self.active_secondary.unwrap_or(self.active),
         };
         if idx < self.tabs.len() {
+            // Each view passes its edits on to the other
+            self.tabs[idx].editor.buffer.track_mirror_changes();
             let tab = &self.tabs[idx];
-            let new_editor = tab.editor.clone_view();
+            let mut new_editor = tab.editor.clone_view();
+            new_editor.buffer.track_mirror_changes();
             self.tabs.push(Tab {
                 title: tab.title.clone(),
                 path: tab.path.clone(),
                 editor: new_editor,
                 is_modified: tab.is_modified,
+                multi_buffer: tab.multi_buffer.clone(),
+                collab: None,
             });
             self.active_secondary = Some(self.tabs.len() - 1);
             self.focused_pane = Pane::Secondary;
@@ -161,24 +471,421 @@ impl TabManager {
                 return;
             }
 
-            let source_buffer = self.tabs[active_idx].editor.buffer.clone();
+            // Without the edits, the first sync compares the texts instead
+            let changes = self.tabs[active_idx].editor.buffer.take_mirror_changes();
+            self.tabs[active_idx].editor.buffer.track_mirror_changes();
 
             for i in indices {
-                self.tabs[i].editor.buffer.sync_content_from(&source_buffer);
-                self.tabs[i].editor.rehighlight();
-                self.tabs[i].is_modified = self.tabs[active_idx].is_modified;
+                let (source, tab) = if i < active_idx {
+                    let (before, after) = self.tabs.split_at_mut(active_idx);
+                    (&after[0], &mut before[i])
+                } else {
+                    let (before, after) = self.tabs.split_at_mut(i);
+                    (&before[active_idx], &mut after[0])
+                };
+                tab.editor
+                    .buffer
+                    .sync_content_from(&source.editor.buffer, changes.as_deref());
+                tab.editor.rehighlight();
+                tab.is_modified = source.is_modified;
             }
         }
     }
 
-    pub fn mark_active_modified(&mut self) {
+    /// Save the focused tab. The tab stays modified if the write fails.
+    pub fn save_active(&mut self, options: &forge_core::SaveOptions) -> Result<()> {
+        self.save_active_with(|editor| editor.save(options))
+    }
+
+    /// Save the active tab in another encoding, which it only switches to
+    /// once the save succeeds
+    pub fn save_active_with_encoding(
+        &mut self,
+        encoding: forge_core::Encoding,
+        options: &forge_core::SaveOptions,
+    ) -> Result<()> {
+        self.save_active_with(|editor| editor.buffer.save_with_encoding(encoding, options))
+    }
+
+    fn save_active_with(&mut self, save: impl FnOnce(&mut Editor) -> Result<()>) -> Result<()> {
         let idx = match self.focused_pane {
             Pane::Primary => self.active,
             Pane::Secondary => self.active_secondary.unwrap_or(self.active),
         };
         if let Some(tab) = self.tabs.get_mut(idx) {
-            tab.is_modified = true;
+            if tab.editor.buffer.path().is_some() {
+                save(&mut tab.editor)?;
+                tab.is_modified = false;
+            }
+        }
+        self.persist_undo(idx);
+        Ok(())
+    }
+
+    pub fn mark_active_modified(&mut self) {
+        self.sync_multi_buffers();
+        let idx = match self.focused_pane {
+            Pane::Primary => self.active,
+            Pane::Secondary => self.active_secondary.unwrap_or(self.active),
+        };
+        match self.tabs.get_mut(idx) {
+            // The files it shows are marked instead
+            Some(tab) if tab.multi_buffer.is_some() => return,
+            Some(tab) if !tab.editor.buffer.is_read_only() => tab.is_modified = true,
+            _ => return,
         }
         self.sync_buffers();
     }
+
+    /// Finish background loads of large files; failures become notices
+    pub fn poll_loading(&mut self) {
+        for tab in &mut self.tabs {
+            match tab.editor.poll_loading() {
+                Some(Err(e)) => self.notices.push(format!(
+                    "Failed to load {}: {}; opened read-only",
+                    tab.title, e
+                )),
+                Some(Ok(())) if tab.editor.buffer.is_read_only() => self.notices.push(format!(
+                    "{} contains bytes that could not be decoded; opened read-only",
+                    tab.title
+                )),
+                _ => {}
+            }
+        }
+    }
+
+    /// Bring tabs up to date with files changed on disk by other programs.
+    ///
+    /// Clean tabs reload; tabs with unsaved changes get a three-way merge.
+    pub fn poll_external_changes(&mut self) {
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+        // The watcher reports a change once, so changes a loading tab
+        // couldn't take yet are retried here until it can
+        let mut paths = std::mem::take(&mut self.deferred_syncs);
+        for event in watcher.poll() {
+            let (FileEvent::Changed(path) | FileEvent::Removed(path)) = event;
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+        for path in &paths {
+            for tab in self.tabs.iter_mut().filter(|t| t.path.as_ref() == Some(path)) {
+                let name = path.display();
+                match tab.editor.buffer.sync_with_disk() {
+                    Ok(ExternalChange::Unchanged | ExternalChange::Reloaded) => {}
+                    Ok(ExternalChange::Loading) => {
+                        if !self.deferred_syncs.contains(path) {
+                            self.deferred_syncs.push(path.clone());
+                        }
+                        continue;
+                    }
+                    Ok(ExternalChange::Merged) => self
+                        .notices
+                        .push(format!("Merged changes made on disk into {}", name)),
+                    Ok(ExternalChange::Conflicts(n)) => self.notices.push(format!(
+                        "{} changed on disk: resolve {} conflict(s) before saving",
+                        name, n
+                    )),
+                    Ok(ExternalChange::Deleted) => self
+                        .notices
+                        .push(format!("{} was deleted on disk", name)),
+                    Err(e) => self
+                        .notices
+                        .push(format!("Could not reload {}: {}", name, e)),
+                }
+                tab.editor.rehighlight();
+                tab.is_modified = tab.editor.buffer.is_dirty();
+            }
+        }
+        self.sync_multi_buffers();
+    }
+
+    /// Open one tab showing each `(path, line)` hit with a few lines of
+    /// context. The files are opened in tabs of their own too, where edits
+    /// made in the excerpts show up unsaved.
+    pub fn open_multi_buffer(&mut self, title: &str, hits: &[(String, usize)]) -> Result<()> {
+        let active = self.active;
+        let mut multi = MultiBuffer::new();
+        for (path, line) in hits {
+            if self.tab_for(path).is_none() {
+                if let Err(e) = self.open_file(path) {
+                    self.notices.push(format!("Could not open {}: {}", path, e));
+                    continue;
+                }
+            }
+            if let Some(tab) = self.tab_for(path) {
+                multi.push(
+                    path,
+                    tab.editor.buffer.rope(),
+                    *line..*line + 1,
+                    EXCERPT_CONTEXT,
+                );
+            }
+        }
+        self.active = active;
+        if multi.is_empty() {
+            anyhow::bail!("Nothing to show");
+        }
+
+        let mut editor = Editor::new();
+        editor.buffer = forge_core::Buffer::from_str(&self.build_multi_buffer(&mut multi));
+        editor.buffer.track_changes();
+        for excerpt in multi.excerpts() {
+            if let Some(tab) = self.tab_for_mut(&excerpt.path) {
+                tab.editor.buffer.track_changes();
+            }
+        }
+        self.tabs.push(Tab {
+            title: title.to_string(),
+            path: None,
+            editor,
+            is_modified: false,
+            multi_buffer: Some(multi),
+            collab: None,
+        });
+        self.active = self.tabs.len() - 1;
+        Ok(())
+    }
+
+    /// Open a tab on a document someone else shared
+    pub fn open_shared(&mut self, title: &str, text: &str, mut session: Session) {
+        let mut editor = Editor::new();
+        editor.buffer = forge_core::Buffer::from_str(text);
+        session.attach(&mut editor.buffer);
+        self.tabs.push(Tab {
+            title: title.to_string(),
+            path: None,
+            editor,
+            is_modified: false,
+            multi_buffer: None,
+            collab: Some(session),
+        });
+        self.active = self.tabs.len() - 1;
+    }
+
+    /// Start sharing the tab showing `path`
+    pub fn share(&mut self, path: &Path, mut session: Session) -> Result<()> {
+        let Some(tab) = self
+            .tabs
+            .iter_mut()
+            .find(|t| t.multi_buffer.is_none() && t.path.as_deref() == Some(path))
+        else {
+            anyhow::bail!("{} is no longer open", path.display());
+        };
+        session.attach(&mut tab.editor.buffer);
+        tab.collab = Some(session);
+        Ok(())
+    }
+
+    /// The tab being shared, if any
+    pub fn shared_tab_mut(&mut self) -> Option<&mut Tab> {
+        self.tabs.iter_mut().find(|t| t.collab.is_some())
+    }
+
+    pub fn shared_tab(&self) -> Option<&Tab> {
+        self.tabs.iter().find(|t| t.collab.is_some())
+    }
+
+    /// Stop sharing; the tab keeps its text
+    pub fn unshare(&mut self) {
+        if let Some(tab) = self.shared_tab_mut() {
+            tab.collab = None;
+            tab.editor.buffer.unshare();
+        }
+    }
+
+    /// The session of the focused tab, if it is shared
+    pub fn active_session(&self) -> Option<&Session> {
+        self.tabs.get(self.focused_index())?.collab.as_ref()
+    }
+
+    pub fn active_session_mut(&mut self) -> Option<(&mut Session, &mut Editor)> {
+        let index = self.focused_index();
+        let tab = self.tabs.get_mut(index)?;
+        Some((tab.collab.as_mut()?, &mut tab.editor))
+    }
+
+    fn tab_for(&self, path: &str) -> Option<&Tab> {
+        self.tabs
+            .iter()
+            .find(|t| t.multi_buffer.is_none() && t.path.as_deref() == Some(Path::new(path)))
+    }
+
+    fn tab_for_mut(&mut self, path: &str) -> Option<&mut Tab> {
+        self.tabs
+            .iter_mut()
+            .find(|t| t.multi_buffer.is_none() && t.path.as_deref() == Some(Path::new(path)))
+    }
+
+    fn build_multi_buffer(&self, multi: &mut MultiBuffer) -> String {
+        multi.build(|path| self.tab_for(path).map(|t| t.editor.buffer.rope()))
+    }
+
+    /// Send edits made in multi-buffer tabs to the files they show, then
+    /// refresh every multi-buffer from those files. Does nothing unless one
+    /// of them was edited.
+    pub fn sync_multi_buffers(&mut self) {
+        let mut edited = std::mem::take(&mut self.multi_buffers_stale);
+        // Tabs opened (or split off) since the multi-buffer was built haven't
+        // queued their edits yet
+        let paths: Vec<String> = self
+            .tabs
+            .iter()
+            .filter_map(|t| t.multi_buffer.as_ref())
+            .flat_map(|multi| multi.excerpts().iter().map(|e| e.path.clone()))
+            .collect();
+        for tab in &mut self.tabs {
+            let shown = match &tab.path {
+                Some(path) => paths.iter().any(|p| Path::new(p) == path),
+                None => tab.multi_buffer.is_some(),
+            };
+            if shown && !tab.editor.buffer.tracks_changes() {
+                tab.editor.buffer.track_changes();
+                edited = true;
+            }
+        }
+        if !edited && !self.tabs.iter().any(|t| t.editor.buffer.has_changes()) {
+            return;
+        }
+
+        for idx in 0..self.tabs.len() {
+            let Some(mut multi) = self.tabs[idx].multi_buffer.take() else {
+                continue;
+            };
+            for changes in self.tabs[idx].editor.buffer.take_changes() {
+                let per_file = match multi.split_edits(&changes) {
+                    Ok(per_file) => per_file,
+                    // Rebuilding below puts the header back
+                    Err(e) => {
+                        self.notices.push(e.to_string());
+                        continue;
+                    }
+                };
+                for (path, changes) in per_file {
+                    let Some(tab) = self.tab_for_mut(&path) else {
+                        continue;
+                    };
+                    if tab.editor.buffer.is_read_only() {
+                        continue;
+                    }
+                    tab.editor
+                        .buffer
+                        .apply(Transaction::new(changes.clone(), None));
+                    tab.editor.rehighlight();
+                    tab.is_modified = true;
+                    multi.map_through(&path, &changes, tab.editor.buffer.rope());
+                }
+                // Later edits were made against the text this one left
+                self.build_multi_buffer(&mut multi);
+            }
+
+            let text = self.build_multi_buffer(&mut multi);
+            let buffer = &mut self.tabs[idx].editor.buffer;
+            let current = buffer.text();
+            if current != text {
+                let changes = forge_core::merge::diff_changes(&current, &text);
+                buffer.apply(Transaction::new(changes, None));
+                // Already in the files
+                buffer.take_changes();
+                // Older undo steps no longer line up with the files, whose own
+                // tabs still have them
+                buffer.restore_history(forge_core::History::new());
+            }
+            self.tabs[idx].multi_buffer = Some(multi);
+        }
+        // Every multi-buffer has caught up with the files
+        for tab in &mut self.tabs {
+            tab.editor.buffer.take_changes();
+        }
+    }
+
+    /// In a multi-buffer tab, show more lines around the excerpt at `offset`
+    pub fn expand_excerpt_at(&mut self, offset: usize) -> bool {
+        let idx = self.focused_index();
+        let Some(mut multi) = self.tabs.get_mut(idx).and_then(|t| t.multi_buffer.take()) else {
+            return false;
+        };
+        let expanded = match multi.excerpt_at(offset) {
+            Some(excerpt) => {
+                let path = multi.excerpts()[excerpt].path.clone();
+                if let Some(tab) = self.tab_for(&path) {
+                    let rope = tab.editor.buffer.rope();
+                    multi.expand(excerpt, rope, EXCERPT_EXPAND, EXCERPT_EXPAND);
+                }
+                true
+            }
+            None => false,
+        };
+        self.tabs[idx].multi_buffer = Some(multi);
+        self.multi_buffers_stale = true;
+        self.sync_multi_buffers();
+        expanded
+    }
+
+    /// Whether `offset` is on an excerpt header of the focused multi-buffer
+    pub fn is_excerpt_header(&self, offset: usize) -> bool {
+        self.tabs
+            .get(self.focused_index())
+            .and_then(|t| t.multi_buffer.as_ref())
+            .is_some_and(|multi| multi.is_header(offset))
+    }
+
+    /// From a multi-buffer tab, switch to the file under the cursor with the
+    /// cursor at the same spot
+    pub fn open_excerpt_at_cursor(&mut self) -> bool {
+        let Some(tab) = self.tabs.get(self.focused_index()) else {
+            return false;
+        };
+        let Some(multi) = &tab.multi_buffer else {
+            return false;
+        };
+        let offset = tab.editor.buffer.selection().primary().head.offset;
+        let Some((excerpt, at)) = multi.locate(offset).or_else(|| {
+            multi
+                .excerpt_at(offset)
+                .map(|e| (e, multi.excerpts()[e].range().start))
+        }) else {
+            return false;
+        };
+        let path = multi.excerpts()[excerpt].path.clone();
+        let Some(target) = self
+            .tabs
+            .iter()
+            .position(|t| t.multi_buffer.is_none() && t.path.as_deref() == Some(Path::new(&path)))
+        else {
+            return false;
+        };
+        self.active = target;
+        self.focused_pane = Pane::Primary;
+        let editor = &mut self.tabs[target].editor;
+        editor
+            .buffer
+            .set_selection(forge_core::Selection::point(forge_core::Position::new(at)));
+        let line = editor.buffer.offset_to_line_col(at).0;
+        editor.set_scroll_top(line.saturating_sub(5));
+        true
+    }
+
+    fn focused_index(&self) -> usize {
+        match self.focused_pane {
+            Pane::Primary => self.active,
+            Pane::Secondary => self.active_secondary.unwrap_or(self.active),
+        }
+    }
+
+    /// Whether any tab is still loading in the background
+    pub fn is_loading(&self) -> bool {
+        self.tabs.iter().any(|t| t.editor.buffer.is_loading)
+    }
+}
+
+/// Close and delete the journal for `path`, if one is open
+fn remove_journal(journals: &mut HashMap<String, Journal>, path: &str) {
+    if let Some(journal) = journals.remove(path) {
+        if let Err(e) = journal.remove() {
+            tracing::warn!("Failed to remove recovery journal for {}: {}", path, e);
+        }
+    }
 }
@@ -1,5 +1,7 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use forge_core::project::{Project, ProjectKind};
+use std::path::PathBuf;
 use std::process::Command;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -8,6 +10,9 @@ pub struct Task {
     pub command: String,
     pub group: Option<String>,
     pub problem_matcher: Option<String>,
+    /// Directory to run in; the current directory if unset
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -28,6 +33,40 @@ impl TaskRunner {
         self.tasks = config.tasks;
     }
 
+    /// Build and test tasks for each sub-project, run from its own root
+    pub fn member_tasks(project: &Project) -> Vec<Task> {
+        let node = if project.root.join("pnpm-lock.yaml").exists() {
+            "pnpm"
+        } else {
+            "npm"
+        };
+        let mut tasks = Vec::new();
+        for member in &project.members {
+            let commands = match member.kind {
+                ProjectKind::Rust => vec![
+                    ("build", "cargo build".to_string()),
+                    ("test", "cargo test".to_string()),
+                ],
+                ProjectKind::Node => vec![
+                    ("build", format!("{} run build", node)),
+                    ("test", format!("{} test", node)),
+                ],
+                ProjectKind::Python => vec![("test", "python -m pytest".to_string())],
+                ProjectKind::Go | ProjectKind::Generic => Vec::new(),
+            };
+            for (group, command) in commands {
+                tasks.push(Task {
+                    label: format!("{}: {}", member.name, group),
+                    command,
+                    group: Some(group.to_string()),
+                    problem_matcher: None,
+                    cwd: Some(member.root.clone()),
+                });
+            }
+        }
+        tasks
+    }
+
     pub fn run_task(&self, label: &str) -> Result<()> {
         if let Some(task) = self.tasks.iter().find(|t| t.label == label) {
             // Placeholder: In a real app, this would spawn a terminal process
@@ -38,9 +77,36 @@ impl TaskRunner {
             // Splitting command string is naive but sufficient for placeholder
             let parts: Vec<&str> = task.command.split_whitespace().collect();
             if let Some((cmd, args)) = parts.split_first() {
-                Command::new(cmd).args(args).spawn()?;
+                let mut command = Command::new(cmd);
+                command.args(args);
+                if let Some(cwd) = &task.cwd {
+                    command.current_dir(cwd);
+                }
+                command.spawn()?;
             }
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn member_tasks_run_in_member_roots() {
+        let root = std::env::temp_dir().join("forge_task_members");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("crates/core")).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\nmembers = [\"crates/*\"]\n").unwrap();
+        std::fs::write(root.join("crates/core/Cargo.toml"), "[package]\nname = \"core\"\n").unwrap();
+        let mut project = Project::new(&root);
+        project.scan();
+
+        let tasks = TaskRunner::member_tasks(&project);
+        let labels: Vec<&str> = tasks.iter().map(|t| t.label.as_str()).collect();
+        assert_eq!(labels, vec!["core: build", "core: test"]);
+        assert_eq!(tasks[1].command, "cargo test");
+        assert_eq!(tasks[1].cwd.as_deref(), Some(root.join("crates/core").as_path()));
+    }
+}
@@ -0,0 +1,176 @@
+use forge_core::History;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// One line of the undo tree panel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoTreeRow {
+    /// History node index
+    pub node: usize,
+    /// Nesting level; siblings after the first branch are indented
+    pub depth: usize,
+    /// Whether this is the buffer's current state
+    pub is_current: bool,
+    /// Whether redo from the parent follows this node
+    pub is_redo_branch: bool,
+    pub label: String,
+}
+
+/// Overlay listing every state in the active buffer's undo tree.
+#[derive(Debug, Default)]
+pub struct UndoTreePanel {
+    pub visible: bool,
+    pub rows: Vec<UndoTreeRow>,
+    pub selected: usize,
+}
+
+impl UndoTreePanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the panel for `history`, with the current state selected.
+    pub fn open(&mut self, history: &History) {
+        self.refresh(history);
+        self.visible = true;
+    }
+
+    pub fn close(&mut self) {
+        self.visible = false;
+    }
+
+    /// Rebuild the rows after the history or current node changed
+    pub fn refresh(&mut self, history: &History) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        self.refresh_at(history, now);
+    }
+
+    fn refresh_at(&mut self, history: &History, now: u64) {
+        self.rows.clear();
+        // Depth-first, newest branch first so the default redo path reads top-down
+        let mut stack = vec![(0, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            let parent = history.nodes[node].parent;
+            self.rows.push(UndoTreeRow {
+                node,
+                depth,
+                is_current: node == history.current,
+                is_redo_branch: parent.is_some_and(|p| history.redo_branch(p) == Some(node)),
+                label: Self::describe(history, node, now),
+            });
+            let children = history.branches(node);
+            for (i, &child) in children.iter().enumerate() {
+                let extra = usize::from(i + 1 != children.len());
+                stack.push((child, depth + extra));
+            }
+        }
+        self.selected = self
+            .rows
+            .iter()
+            .position(|r| r.is_current)
+            .unwrap_or(0);
+    }
+
+    fn describe(history: &History, node: usize, now: u64) -> String {
+        let entry = &history.nodes[node];
+        if entry.parent.is_none() {
+            return "Original".to_string();
+        }
+        let (mut inserted, mut deleted) = (0, 0);
+        for change in &entry.transaction.changes.changes {
+            inserted += change.text.as_ref().map_or(0, |t| t.chars().count());
+            deleted += change.end.offset - change.start.offset;
+        }
+        format!(
+            "#{} +{} -{}  {}",
+            node,
+            inserted,
+            deleted,
+            Self::age(now.saturating_sub(entry.timestamp))
+        )
+    }
+
+    fn age(secs: u64) -> String {
+        match secs {
+            0..=59 => format!("{}s ago", secs),
+            60..=3599 => format!("{}m ago", secs / 60),
+            3600..=86_399 => format!("{}h ago", secs / 3600),
+            _ => format!("{}d ago", secs / 86_400),
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected + 1 < self.rows.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// History node of the highlighted row
+    pub fn selected_node(&self) -> Option<usize> {
+        self.rows.get(self.selected).map(|r| r.node)
+    }
+
+    /// Text shown in the overlay, one row per line
+    pub fn render_text(&self) -> String {
+        let mut text = String::from("Undo Tree  (Enter: restore, Esc: close)\n\n");
+        for (i, row) in self.rows.iter().enumerate() {
+            let cursor = if i == self.selected { ">" } else { " " };
+            let marker = if row.is_current {
+                "●"
+            } else if row.is_redo_branch {
+                "│"
+            } else {
+                "○"
+            };
+            text.push_str(&format!(
+                "{} {}{} {}\n",
+                cursor,
+                "  ".repeat(row.depth),
+                marker,
+                row.label
+            ));
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge_core::{Change, ChangeSet, Position, Transaction};
+
+    fn push(history: &mut History, text: &str) -> usize {
+        let tx = Transaction::new(
+            ChangeSet::with_change(Change::insert(Position::new(0), text.to_string())),
+            None,
+        );
+        history.push(tx, Transaction::new(ChangeSet::new(), None))
+    }
+
+    #[test]
+    fn lists_branches_and_selects_current() {
+        let mut history = History::new();
+        let a = push(&mut history, "a");
+        history.undo();
+        let b = push(&mut history, "bb");
+
+        let mut panel = UndoTreePanel::new();
+        panel.refresh_at(&history, history.nodes[b].timestamp + 90);
+
+        let nodes: Vec<usize> = panel.rows.iter().map(|r| r.node).collect();
+        assert_eq!(nodes, vec![0, b, a]);
+        assert_eq!(panel.rows[2].depth, 1);
+        assert_eq!(panel.selected_node(), Some(b));
+        assert_eq!(panel.rows[1].label, format!("#{} +2 -0  1m ago", b));
+
+        panel.select_next();
+        assert_eq!(panel.selected_node(), Some(a));
+        panel.select_next();
+        assert_eq!(panel.selected_node(), Some(a));
+    }
+}
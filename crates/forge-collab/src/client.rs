@@ -0,0 +1,177 @@
+//! An editor's connection to the relay
+
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use forge_core::collab::{ClientMessage, ServerMessage};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::TcpStream;
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Messages to and from the relay, readable without blocking from a UI thread
+pub struct Connection {
+    outgoing: UnboundedSender<ClientMessage>,
+    incoming: Receiver<ServerMessage>,
+}
+
+impl Connection {
+    /// Connect to the relay at `addr`. `wake` is called after each message
+    /// arrives, so the caller knows to poll [`Connection::try_recv`]. Losing
+    /// the connection arrives as a [`ServerMessage::Error`].
+    pub fn connect(rt: &Runtime, addr: &str, wake: impl Fn() + Send + 'static) -> Result<Self> {
+        let stream = rt
+            .block_on(async {
+                tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect(addr)).await
+            })
+            .with_context(|| format!("Timed out connecting to {}", addr))?
+            .with_context(|| format!("Can't reach the relay at {}", addr))?;
+        let (read, mut write) = stream.into_split();
+
+        let (outgoing, mut queued) = unbounded_channel::<ClientMessage>();
+        rt.spawn(async move {
+            while let Some(message) = queued.recv().await {
+                let mut line = serde_json::to_string(&message)?;
+                line.push('\n');
+                write.write_all(line.as_bytes()).await?;
+            }
+            anyhow::Ok(())
+        });
+
+        let (received, incoming) = mpsc::channel();
+        rt.spawn(async move {
+            let mut lines = BufReader::new(read).lines();
+            let reason = loop {
+                match lines.next_line().await {
+                    Ok(Some(line)) => match serde_json::from_str::<ServerMessage>(&line) {
+                        Ok(message) => {
+                            if received.send(message).is_err() {
+                                return;
+                            }
+                            wake();
+                        }
+                        Err(e) => tracing::warn!("Unreadable message from the relay: {}", e),
+                    },
+                    Ok(None) => break "the relay closed the connection".to_string(),
+                    Err(e) => break e.to_string(),
+                }
+            };
+            let _ = received.send(ServerMessage::Error {
+                message: format!("Disconnected: {}", reason),
+            });
+            wake();
+        });
+
+        Ok(Self { outgoing, incoming })
+    }
+
+    pub fn send(&self, message: ClientMessage) {
+        let _ = self.outgoing.send(message);
+    }
+
+    pub fn try_recv(&self) -> Option<ServerMessage> {
+        self.incoming.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use forge_core::{Buffer, Change, Position, Session, Transaction};
+    use std::time::Instant;
+
+    fn recv(connection: &Connection) -> ServerMessage {
+        let start = Instant::now();
+        loop {
+            if let Some(message) = connection.try_recv() {
+                return message;
+            }
+            assert!(start.elapsed() < Duration::from_secs(5), "no reply");
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    fn join(connection: &Connection, text: &str) -> (Session, Buffer) {
+        let ServerMessage::Welcome {
+            peer,
+            revision,
+            text: room_text,
+            peers,
+        } = recv(connection)
+        else {
+            panic!("expected a welcome");
+        };
+        let mut buffer = Buffer::from_str(text);
+        let mut session = Session::new(peer, revision, &room_text, peers);
+        session.attach(&mut buffer);
+        (session, buffer)
+    }
+
+    #[test]
+    fn peers_edit_through_a_local_relay() {
+        let rt = Runtime::new().unwrap();
+        let listener = rt
+            .block_on(tokio::net::TcpListener::bind("127.0.0.1:0"))
+            .unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        rt.spawn(crate::serve(listener));
+
+        let host = Connection::connect(&rt, &addr, || {}).unwrap();
+        host.send(ClientMessage::Join {
+            room: "notes.txt".into(),
+            name: "host".into(),
+            text: Some("shared".into()),
+        });
+        let (mut host_session, mut host_buffer) = join(&host, "shared");
+
+        let guest = Connection::connect(&rt, &addr, || {}).unwrap();
+        guest.send(ClientMessage::ListRooms);
+        assert_eq!(
+            recv(&guest),
+            ServerMessage::Rooms {
+                rooms: vec!["notes.txt".into()]
+            }
+        );
+        guest.send(ClientMessage::Join {
+            room: "notes.txt".into(),
+            name: "guest".into(),
+            text: None,
+        });
+        let (mut guest_session, mut guest_buffer) = join(&guest, "shared");
+        assert_eq!(guest_session.peers()[0].name, "host");
+
+        host_buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "we ".into(),
+        )));
+        guest_buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(6),
+            " text".into(),
+        )));
+
+        let start = Instant::now();
+        let mut peers = [
+            (&host, &mut host_session, &mut host_buffer),
+            (&guest, &mut guest_session, &mut guest_buffer),
+        ];
+        while peers.iter().any(|(_, _, b)| b.text() != "we shared text") {
+            assert!(
+                start.elapsed() < Duration::from_secs(5),
+                "peers never converged"
+            );
+            for (connection, session, buffer) in &mut peers {
+                session.sync(buffer);
+                while let Some(message) = connection.try_recv() {
+                    session.receive(message, buffer);
+                }
+                for message in session.take_outgoing() {
+                    connection.send(message);
+                }
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
@@ -0,0 +1,14 @@
+//! forge-collab: the relay that shared editing sessions meet on, and the
+//! connection editors use to reach it.
+//!
+//! Peers and the relay exchange [`forge_core::collab`] messages as JSON, one
+//! per line, over TCP.
+
+pub mod client;
+pub mod relay;
+
+pub use client::Connection;
+pub use relay::serve;
+
+/// Where the relay listens unless told otherwise
+pub const DEFAULT_ADDR: &str = "127.0.0.1:7420";
@@ -0,0 +1,38 @@
+//! forge-relay — the server shared editing sessions meet on
+//!
+//! Run it somewhere every collaborator can reach; for pairing on one machine
+//! the default localhost address is enough.
+
+use anyhow::{anyhow, Result};
+use tokio::net::TcpListener;
+use tracing::info;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut addr = forge_collab::DEFAULT_ADDR.to_string();
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--help" | "-h" => {
+                println!("Usage: forge-relay [--listen ADDR]");
+                println!(
+                    "  --listen ADDR   Address to accept editors on (default {})",
+                    forge_collab::DEFAULT_ADDR
+                );
+                return Ok(());
+            }
+            "--listen" => {
+                addr = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--listen needs an address"))?;
+            }
+            _ => return Err(anyhow!("Unknown argument: {}", arg)),
+        }
+    }
+
+    let listener = TcpListener::bind(&addr).await?;
+    info!("Relay listening on {}", listener.local_addr()?);
+    forge_collab::serve(listener).await
+}
@@ -0,0 +1,178 @@
+//! The relay server: one [`Room`] per shared document, sequencing its
+//! peers' edits and passing on their selections.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use forge_core::collab::{ClientMessage, PeerId, PeerInfo, ServerMessage};
+use forge_core::Room;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+type Outbox = UnboundedSender<ServerMessage>;
+
+struct Channel {
+    room: Room,
+    members: HashMap<PeerId, Outbox>,
+}
+
+impl Channel {
+    fn broadcast(&self, message: &ServerMessage, except: Option<PeerId>) {
+        for (id, outbox) in &self.members {
+            if Some(*id) != except {
+                let _ = outbox.send(message.clone());
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Relay {
+    rooms: HashMap<String, Channel>,
+    next_peer: PeerId,
+}
+
+impl Relay {
+    fn handle(
+        &mut self,
+        peer: PeerId,
+        joined: &mut Option<String>,
+        message: ClientMessage,
+        outbox: &Outbox,
+    ) {
+        let reply = |message: String| {
+            let _ = outbox.send(ServerMessage::Error { message });
+        };
+        match message {
+            ClientMessage::ListRooms => {
+                let mut rooms: Vec<String> = self.rooms.keys().cloned().collect();
+                rooms.sort();
+                let _ = outbox.send(ServerMessage::Rooms { rooms });
+            }
+            ClientMessage::Join { room, name, text } => {
+                if joined.is_some() {
+                    return reply("Already in a room".into());
+                }
+                let channel = match (self.rooms.contains_key(&room), text) {
+                    (true, Some(_)) => return reply(format!("{} is already shared", room)),
+                    (false, None) => return reply(format!("No room named {}", room)),
+                    (true, None) => self.rooms.get_mut(&room).unwrap(),
+                    (false, Some(text)) => self.rooms.entry(room.clone()).or_insert(Channel {
+                        room: Room::new(&text),
+                        members: HashMap::new(),
+                    }),
+                };
+                let welcome = channel.room.join(peer, &name);
+                channel.broadcast(
+                    &ServerMessage::Joined {
+                        peer: PeerInfo {
+                            id: peer,
+                            name,
+                            selection: None,
+                        },
+                    },
+                    None,
+                );
+                channel.members.insert(peer, outbox.clone());
+                let _ = outbox.send(welcome);
+                tracing::info!("Peer {} joined {}", peer, room);
+                *joined = Some(room);
+            }
+            ClientMessage::Edit { revision, changes } => {
+                let Some(channel) = joined.as_ref().and_then(|r| self.rooms.get_mut(r)) else {
+                    return reply("Not in a room".into());
+                };
+                match channel.room.edit(peer, revision, changes) {
+                    Ok(changes) => channel.broadcast(&ServerMessage::Edit { peer, changes }, None),
+                    Err(e) => reply(e.to_string()),
+                }
+            }
+            ClientMessage::Select { selection } => {
+                let Some(channel) = joined.as_ref().and_then(|r| self.rooms.get_mut(r)) else {
+                    return reply("Not in a room".into());
+                };
+                let selection = channel.room.select(peer, selection);
+                channel.broadcast(&ServerMessage::Select { peer, selection }, Some(peer));
+            }
+            ClientMessage::Ack { revision } => {
+                if let Some(channel) = joined.as_ref().and_then(|r| self.rooms.get_mut(r)) {
+                    channel.room.ack(peer, revision);
+                }
+            }
+        }
+    }
+
+    fn leave(&mut self, peer: PeerId, room: &str) {
+        let Some(channel) = self.rooms.get_mut(room) else {
+            return;
+        };
+        channel.room.leave(peer);
+        channel.members.remove(&peer);
+        channel.broadcast(&ServerMessage::Left { peer }, None);
+        if channel.members.is_empty() {
+            self.rooms.remove(room);
+            tracing::info!("Closed {}", room);
+        }
+    }
+}
+
+/// Accept peers on `listener` until it fails
+pub async fn serve(listener: TcpListener) -> Result<()> {
+    let relay = Arc::new(Mutex::new(Relay::default()));
+    loop {
+        let (socket, addr) = listener.accept().await?;
+        let relay = relay.clone();
+        tokio::spawn(async move {
+            if let Err(e) = handle_peer(socket, &relay).await {
+                tracing::warn!("Peer at {} dropped: {}", addr, e);
+            }
+        });
+    }
+}
+
+async fn handle_peer(socket: TcpStream, relay: &Mutex<Relay>) -> Result<()> {
+    let (read, mut write) = socket.into_split();
+    let (outbox, mut queued) = unbounded_channel::<ServerMessage>();
+    let writer = tokio::spawn(async move {
+        while let Some(message) = queued.recv().await {
+            let mut line = serde_json::to_string(&message)?;
+            line.push('\n');
+            write.write_all(line.as_bytes()).await?;
+        }
+        anyhow::Ok(())
+    });
+
+    let peer = {
+        let mut relay = relay.lock().unwrap();
+        relay.next_peer += 1;
+        relay.next_peer
+    };
+    let mut joined = None;
+    let mut lines = BufReader::new(read).lines();
+    let result = async {
+        while let Some(line) = lines.next_line().await? {
+            match serde_json::from_str::<ClientMessage>(&line) {
+                Ok(message) => relay
+                    .lock()
+                    .unwrap()
+                    .handle(peer, &mut joined, message, &outbox),
+                Err(e) => {
+                    let _ = outbox.send(ServerMessage::Error {
+                        message: format!("Bad message: {}", e),
+                    });
+                }
+            }
+        }
+        anyhow::Ok(())
+    }
+    .await;
+
+    if let Some(room) = joined {
+        relay.lock().unwrap().leave(peer, &room);
+    }
+    drop(outbox);
+    writer.await??;
+    result
+}
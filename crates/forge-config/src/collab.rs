@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CollabConfig {
+    /// Address of the relay shared sessions go through
+    pub relay: String,
+    /// Name shown to collaborators; defaults to the login name
+    pub name: Option<String>,
+}
+
+impl Default for CollabConfig {
+    fn default() -> Self {
+        Self {
+            relay: "127.0.0.1:7420".into(),
+            name: None,
+        }
+    }
+}
+
+impl CollabConfig {
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .or_else(|| std::env::var("USER").ok())
+            .or_else(|| std::env::var("USERNAME").ok())
+            .unwrap_or_else(|| "guest".into())
+    }
+}
@@ -12,6 +12,21 @@ pub struct EditorConfig {
     pub cursor_blink: bool,
     pub bracket_matching: bool,
     pub indent_guides: bool,
+    /// Keep a `.bak` copy of the previous contents when saving
+    pub backup_on_save: bool,
+    /// Keep undo history across restarts
+    pub persistent_undo: bool,
+    /// Largest undo history persisted per file, in KiB
+    pub undo_history_max_kb: u64,
+    /// Persisted undo history older than this is discarded
+    pub undo_history_max_age_days: u64,
+    /// Journal unsaved edits so they can be recovered after a crash
+    pub crash_recovery: bool,
+    /// Files at least this big (in MiB) load in the background with syntax
+    /// highlighting, minimap and word wrap turned off
+    pub large_file_threshold_mb: u64,
+    /// Files at least this big (in MiB) open read-only via a memory map
+    pub large_file_mmap_threshold_mb: u64,
 }
 
 impl Default for EditorConfig {
@@ -26,6 +41,13 @@ impl Default for EditorConfig {
             cursor_blink: true,
             bracket_matching: true,
             indent_guides: true,
+            backup_on_save: false,
+            persistent_undo: true,
+            undo_history_max_kb: 10 * 1024,
+            undo_history_max_age_days: 30,
+            crash_recovery: true,
+            large_file_threshold_mb: 64,
+            large_file_mmap_threshold_mb: 1024,
         }
     }
 }
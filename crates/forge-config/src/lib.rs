@@ -1,6 +1,8 @@
 //! Forge configuration — TOML-based with sensible defaults.
+mod collab;
 mod editor;
 mod terminal;
+pub use collab::CollabConfig;
 pub use editor::EditorConfig;
 pub use terminal::TerminalConfig;
 
@@ -13,6 +15,7 @@ use std::path::PathBuf;
 pub struct ForgeConfig {
     pub editor: EditorConfig,
     pub terminal: TerminalConfig,
+    pub collab: CollabConfig,
     pub theme: String,
     pub font_family: String,
     pub font_size: f32,
@@ -23,6 +26,7 @@ impl Default for ForgeConfig {
         Self {
             editor: EditorConfig::default(),
             terminal: TerminalConfig::default(),
+            collab: CollabConfig::default(),
             theme: "Forge Dark".into(),
             font_family: "Cascadia Code".into(),
             font_size: 14.0,
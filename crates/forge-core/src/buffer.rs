@@ -1,7 +1,21 @@
-use crate::{Encoding, History, LineEnding, Selection, Syntax, Transaction};
+use crate::coords::{self, ColumnUnit, LineCol, LineEdit};
+use crate::display_map::{DisplayMap, DisplayOptions, DisplayPoint, DisplayRow, InlayKind};
+use crate::file_io::{FileIO, SaveOptions};
+use crate::large_file::ChunkedLoad;
+use crate::line_ending::{LineEndingCounts, LineEndingPolicy};
+use crate::merge::{self, ConflictSide, ExternalChange};
+use crate::motion::{self, Motion, Scope, TextObject};
+use crate::search::{IncrementalSearch, Searcher};
+use crate::syntax::SyntaxChanges;
+use crate::undo_group::{Edit, UndoCoalescer};
+use crate::undo_store::content_hash;
+use crate::{
+    Change, ChangeSet, Encoding, History, LineEnding, Position, Selection, Syntax, Transaction,
+};
 use anyhow::Result;
 use ropey::Rope;
 use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tree_sitter::Language;
 
 /// The main text buffer with rope data structure and transaction-based editing
@@ -17,12 +31,49 @@ pub struct Buffer {
     dirty: bool,
     /// Original line ending style
     line_ending: LineEnding,
+    /// How line endings are normalized on save
+    line_ending_policy: LineEndingPolicy,
+    /// Line endings of each style, kept up to date as the buffer is edited
+    line_ending_counts: LineEndingCounts,
     /// Original file encoding
     encoding: Encoding,
+    /// Whether the file had a byte order mark (preserved on save)
+    has_bom: bool,
     /// File path (if loaded from disk)
     path: Option<String>,
     /// Syntax highlighting state
     syntax: Option<Syntax>,
+    /// Matches of the find bar's query, updated with every edit
+    search: Option<IncrementalSearch>,
+    /// Folds, soft wrap and inlays, updated with every edit
+    display: Option<DisplayMap>,
+    /// Which edits get merged into the current undo step
+    undo: UndoCoalescer,
+    /// Background load filling the rope, for large files
+    loader: Option<ChunkedLoad>,
+    /// Edits are ignored and saving is refused
+    read_only: bool,
+    /// Text as last read from or written to disk; the base for merges
+    disk_base: Option<Rope>,
+    /// Hash of the file's bytes at that point
+    disk_hash: Option<u64>,
+    /// A merge with external changes left conflicts in the text
+    external_conflict: bool,
+    /// Edits applied since the last [`Buffer::take_journal_changes`];
+    /// `None` unless crash recovery journaling is enabled
+    unjournaled: Option<Vec<ChangeSet>>,
+    /// Edits made here since the last [`Buffer::take_shared_changes`];
+    /// `None` unless the buffer is shared with collaborators
+    unshared: Option<Vec<ChangeSet>>,
+    /// Edits made here since the last [`Buffer::take_changes`]; `None`
+    /// unless [`Buffer::track_changes`] was called
+    untaken: Option<Vec<ChangeSet>>,
+    /// Edits made here since the last [`Buffer::take_mirror_changes`];
+    /// `None` unless [`Buffer::track_mirror_changes`] was called
+    unmirrored: Option<Vec<ChangeSet>>,
+    /// Lines replaced since the last [`Buffer::take_edited_lines`]; `None`
+    /// unless [`Buffer::track_edited_lines`] was called
+    edited_lines: Option<Vec<LineEdit>>,
     /// Is the buffer fully loaded? (Async loading support)
     pub is_loading: bool,
 }
@@ -36,9 +87,25 @@ impl Clone for Buffer {
             selection: self.selection.clone(),
             dirty: self.dirty,
             line_ending: self.line_ending,
+            line_ending_policy: self.line_ending_policy,
+            line_ending_counts: self.line_ending_counts,
             encoding: self.encoding,
+            has_bom: self.has_bom,
             path: self.path.clone(),
             syntax: None, // We don't clone syntax state for now
+            search: self.search.clone(),
+            display: self.display.clone(),
+            undo: UndoCoalescer::default(),
+            loader: None,
+            read_only: self.read_only,
+            disk_base: self.disk_base.clone(),
+            disk_hash: self.disk_hash,
+            external_conflict: self.external_conflict,
+            unjournaled: None,
+            unshared: None,
+            untaken: None,
+            unmirrored: None,
+            edited_lines: None,
             is_loading: self.is_loading,
         }
     }
@@ -109,9 +176,25 @@ impl Buffer {
             selection: Selection::default(),
             dirty: false,
             line_ending: LineEnding::detect_system(),
+            line_ending_policy: LineEndingPolicy::default(),
+            line_ending_counts: LineEndingCounts::default(),
             encoding: Encoding::Utf8,
+            has_bom: false,
             path: None,
             syntax: None,
+            search: None,
+            display: None,
+            undo: UndoCoalescer::default(),
+            loader: None,
+            read_only: false,
+            disk_base: None,
+            disk_hash: None,
+            external_conflict: false,
+            unjournaled: None,
+            unshared: None,
+            untaken: None,
+            unmirrored: None,
+            edited_lines: None,
             is_loading: false,
         }
     }
@@ -125,38 +208,124 @@ impl Buffer {
             selection: Selection::default(),
             dirty: false,
             line_ending: LineEnding::detect_from_str(s),
+            line_ending_policy: LineEndingPolicy::default(),
+            line_ending_counts: LineEndingCounts::scan(&Rope::from_str(s)),
             encoding: Encoding::Utf8,
+            has_bom: false,
             path: None,
             syntax: None,
+            search: None,
+            display: None,
+            undo: UndoCoalescer::default(),
+            loader: None,
+            read_only: false,
+            disk_base: None,
+            disk_hash: None,
+            external_conflict: false,
+            unjournaled: None,
+            unshared: None,
+            untaken: None,
+            unmirrored: None,
+            edited_lines: None,
             is_loading: false,
         }
     }
 
-    /// Load a buffer from a file
+    /// Load a buffer from a file, detecting its encoding. Syntax is attached
+    /// with [`Buffer::set_syntax`] once the file's language is known.
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
-        let content = std::fs::read_to_string(path.as_ref())?;
-        let line_ending = LineEnding::detect_from_str(&content);
+        let bytes = std::fs::read(path.as_ref())?;
+        let decoded = Encoding::decode_detect(&bytes)?;
+        let line_ending = LineEnding::detect_from_str(&decoded.text);
 
-        let mut buffer = Self {
-            rope: Rope::from_str(&content),
+        let rope = Rope::from_str(&decoded.text);
+        Ok(Self {
+            rope: rope.clone(),
             history: History::new(),
             selection: Selection::default(),
             dirty: false,
             line_ending,
-            encoding: Encoding::Utf8,
+            line_ending_policy: LineEndingPolicy::default(),
+            line_ending_counts: LineEndingCounts::scan(&rope),
+            encoding: decoded.encoding,
+            has_bom: decoded.has_bom,
             path: Some(path.as_ref().to_string_lossy().to_string()),
             syntax: None,
+            search: None,
+            display: None,
+            undo: UndoCoalescer::default(),
+            loader: None,
+            read_only: false,
+            disk_base: Some(rope),
+            disk_hash: Some(content_hash(&bytes)),
+            external_conflict: false,
+            unjournaled: None,
+            unshared: None,
+            untaken: None,
+            unmirrored: None,
+            edited_lines: None,
             is_loading: false,
-        };
+        })
+    }
 
-        // Auto-detect Rust
-        if let Some(ext) = path.as_ref().extension() {
-            if ext == "rs" {
-                buffer.set_syntax(tree_sitter_rust::LANGUAGE.into());
-            }
+    /// Start loading a large file in the background.
+    ///
+    /// The buffer is empty and read-only until [`Buffer::poll_load`] reports
+    /// the load finished.
+    pub fn open_chunked<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut buffer = Self::new();
+        buffer.loader = Some(ChunkedLoad::start(path.as_ref())?);
+        buffer.path = Some(path.as_ref().to_string_lossy().to_string());
+        buffer.is_loading = true;
+        Ok(buffer)
+    }
+
+    /// Install the text of a finished background load.
+    ///
+    /// Returns `None` while the load is still running (or if there is none).
+    /// If part of the file couldn't be decoded the buffer stays read-only, so
+    /// saving can't overwrite the file with replacement characters. If the
+    /// load failed the buffer is left empty and read-only.
+    pub fn poll_load(&mut self) -> Option<Result<()>> {
+        let result = self.loader.as_ref()?.try_finish()?;
+        self.loader = None;
+        self.is_loading = false;
+        if result.is_err() {
+            self.read_only = true;
         }
+        Some(result.map(|loaded| {
+            self.disk_base = Some(loaded.rope.clone());
+            self.disk_hash = Some(loaded.hash);
+            let old_lines = self.rope.len_lines();
+            self.rope = loaded.rope;
+            self.replaced_all_lines(old_lines);
+            self.line_ending_counts = LineEndingCounts::scan(&self.rope);
+            self.encoding = loaded.encoding;
+            self.has_bom = loaded.has_bom;
+            self.line_ending = loaded.line_ending;
+            self.read_only |= loaded.lossy;
+            self.selection = Selection::default();
+            if let Some(search) = &mut self.search {
+                search.rescan(&self.rope);
+            }
+            if let Some(display) = &mut self.display {
+                display.reset(&self.rope);
+            }
+        }))
+    }
 
-        Ok(buffer)
+    /// Fraction of a background load completed, if one is running
+    pub fn load_progress(&self) -> Option<f32> {
+        self.loader.as_ref().map(ChunkedLoad::progress)
+    }
+
+    /// Make the buffer read-only (or editable again)
+    pub fn set_read_only(&mut self, read_only: bool) {
+        self.read_only = read_only;
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only || self.is_loading
     }
 
     /// Set the syntax language for the buffer
@@ -166,22 +335,303 @@ impl Buffer {
         self.syntax = Some(syntax);
     }
 
+    /// Stop tracking syntax, for a language without a grammar
+    pub fn clear_syntax(&mut self) {
+        self.syntax = None;
+    }
+
     /// Get the syntax state
     pub fn syntax(&self) -> Option<&Syntax> {
         self.syntax.as_ref()
     }
 
+    /// Syntax edits and changed regions since the last call, so highlighting
+    /// and other tree-derived state can be updated incrementally
+    pub fn take_syntax_changes(&mut self) -> SyntaxChanges {
+        self.syntax
+            .as_mut()
+            .map(Syntax::take_changes)
+            .unwrap_or_default()
+    }
+
+    /// Search the buffer for `searcher`'s query. The matches are kept up to
+    /// date as the buffer is edited, re-scanning only around each edit.
+    pub fn set_search(&mut self, searcher: Searcher) {
+        self.search = Some(IncrementalSearch::new(searcher, &self.rope));
+    }
+
+    /// The current search and its matches
+    pub fn search(&self) -> Option<&IncrementalSearch> {
+        self.search.as_ref()
+    }
+
+    pub fn clear_search(&mut self) {
+        self.search = None;
+    }
+
+    /// Lay the buffer out for display with `options`. Folds and inlays
+    /// are kept when the layout already exists.
+    pub fn set_display_options(&mut self, options: DisplayOptions) {
+        match &mut self.display {
+            Some(display) => display.set_options(&self.rope, options),
+            None => self.display = Some(DisplayMap::new(&self.rope, options)),
+        }
+    }
+
+    pub fn display_map(&self) -> Option<&DisplayMap> {
+        self.display.as_ref()
+    }
+
+    /// Hide `range` behind a fold placeholder, if the buffer has a layout
+    pub fn fold(&mut self, range: std::ops::Range<usize>) {
+        if let Some(display) = &mut self.display {
+            display.fold(&self.rope, range);
+        }
+    }
+
+    /// Fold the lines after `start_line` up to and including `end_line`
+    pub fn fold_lines(&mut self, start_line: usize, end_line: usize) {
+        if let Some(display) = &mut self.display {
+            display.fold_lines(&self.rope, start_line, end_line);
+        }
+    }
+
+    /// Remove the folds touching `range`
+    pub fn unfold(&mut self, range: std::ops::Range<usize>) {
+        if let Some(display) = &mut self.display {
+            display.unfold(&self.rope, range);
+        }
+    }
+
+    /// Replace the inlays of `kind`, given as offsets and text
+    pub fn set_inlays(
+        &mut self,
+        kind: InlayKind,
+        inlays: impl IntoIterator<Item = (usize, String)>,
+    ) {
+        if let Some(display) = &mut self.display {
+            display.set_inlays(&self.rope, kind, inlays);
+        }
+    }
+
+    /// Where `pos` is on screen. Without a layout, rows are lines and
+    /// columns are grapheme clusters.
+    pub fn to_display(&self, pos: Position) -> DisplayPoint {
+        match &self.display {
+            Some(display) => display.to_display(&self.rope, pos.offset),
+            None => {
+                let at = self.position_to_line_col(pos, ColumnUnit::Grapheme);
+                DisplayPoint::new(at.line, at.col)
+            }
+        }
+    }
+
+    /// The position shown at `point`, the inverse of [`Buffer::to_display`]
+    pub fn from_display(&self, point: DisplayPoint) -> Position {
+        match &self.display {
+            Some(display) => Position::new(display.to_buffer(&self.rope, point)),
+            None => {
+                self.line_col_to_position(LineCol::new(point.row, point.col), ColumnUnit::Grapheme)
+            }
+        }
+    }
+
+    pub fn display_row_count(&self) -> usize {
+        self.display
+            .as_ref()
+            .map_or(self.rope.len_lines(), DisplayMap::row_count)
+    }
+
+    /// The text of `rows` as drawn; without a layout, the lines themselves
+    pub fn display_rows(&self, rows: std::ops::Range<usize>) -> Vec<DisplayRow> {
+        match &self.display {
+            Some(display) => display.rows(&self.rope, rows),
+            None => {
+                let end = rows.end.min(self.rope.len_lines());
+                (rows.start.min(end)..end)
+                    .map(|line| DisplayRow::plain(&self.rope, line))
+                    .collect()
+            }
+        }
+    }
+
+    /// Start queueing every edit for the crash recovery journal
+    pub fn enable_journal(&mut self) {
+        self.unjournaled.get_or_insert_with(Vec::new);
+    }
+
+    /// Edits applied since the last call, in order, for appending to the
+    /// crash recovery journal. Empty unless [`Buffer::enable_journal`] was called.
+    pub fn take_journal_changes(&mut self) -> Vec<ChangeSet> {
+        self.unjournaled
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    fn clear_journal_changes(&mut self) {
+        if let Some(unjournaled) = &mut self.unjournaled {
+            unjournaled.clear();
+        }
+    }
+
+    /// Start queueing the edits made in this buffer for views built from it,
+    /// such as multi-buffers
+    pub fn track_changes(&mut self) {
+        self.untaken.get_or_insert_with(Vec::new);
+    }
+
+    pub fn tracks_changes(&self) -> bool {
+        self.untaken.is_some()
+    }
+
+    /// Whether edits are waiting in the [`Buffer::track_changes`] queue
+    pub fn has_changes(&self) -> bool {
+        self.untaken
+            .as_ref()
+            .is_some_and(|changes| !changes.is_empty())
+    }
+
+    /// Edits made since the last call, in order. Empty unless
+    /// [`Buffer::track_changes`] was called.
+    pub fn take_changes(&mut self) -> Vec<ChangeSet> {
+        self.untaken
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Start queueing the edits made in this buffer for other views of the
+    /// same file
+    pub fn track_mirror_changes(&mut self) {
+        self.unmirrored.get_or_insert_with(Vec::new);
+    }
+
+    /// Edits made since the last call, in order, for
+    /// [`Buffer::sync_content_from`]. `None` unless
+    /// [`Buffer::track_mirror_changes`] was called.
+    pub fn take_mirror_changes(&mut self) -> Option<Vec<ChangeSet>> {
+        self.unmirrored.as_mut().map(std::mem::take)
+    }
+
+    /// Start recording which lines edits replace, for views kept per line
+    /// such as TextMate highlighting
+    pub fn track_edited_lines(&mut self) {
+        self.edited_lines.get_or_insert_with(Vec::new);
+    }
+
+    /// The lines replaced since the last call, as one edit. `None` if
+    /// nothing changed or [`Buffer::track_edited_lines`] wasn't called.
+    pub fn take_edited_lines(&mut self) -> Option<LineEdit> {
+        let edits = std::mem::take(self.edited_lines.as_mut()?);
+        edits.into_iter().reduce(LineEdit::then)
+    }
+
+    /// Record that the whole text was replaced, `old_lines` long before
+    fn replaced_all_lines(&mut self, old_lines: usize) {
+        if let Some(edited) = &mut self.edited_lines {
+            edited.push(LineEdit {
+                start: 0,
+                old_end: old_lines,
+                new_end: self.rope.len_lines(),
+            });
+        }
+    }
+
+    /// Start queueing the edits made in this buffer for collaborators
+    pub fn share(&mut self) {
+        self.unshared.get_or_insert_with(Vec::new);
+    }
+
+    pub fn unshare(&mut self) {
+        self.unshared = None;
+    }
+
+    pub fn is_shared(&self) -> bool {
+        self.unshared.is_some()
+    }
+
+    /// Edits made since the last call, in order, for sending to
+    /// collaborators. Empty unless [`Buffer::share`] was called.
+    pub fn take_shared_changes(&mut self) -> Vec<ChangeSet> {
+        self.unshared
+            .as_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    }
+
+    /// Apply an edit from a collaboration session: a collaborator's edit or
+    /// an undo step of the session. It isn't queued for sharing again, and
+    /// the buffer's own undo history is rebased over it, so local undo
+    /// leaves it in place.
+    pub fn apply_shared(&mut self, changes: ChangeSet) {
+        let unshared = self.unshared.take();
+        self.apply_transaction_internal(&Transaction::new(changes.clone(), None));
+        self.unshared = unshared;
+        self.undo.break_step();
+        self.history.rebase(&changes, &self.rope);
+        self.dirty = true;
+    }
+
+    /// Hash of the file's bytes as last loaded or saved; edits in the
+    /// journal apply on top of that
+    pub fn disk_hash(&self) -> Option<u64> {
+        self.disk_hash
+    }
+
     /// Apply transaction and update syntax (internal helper)
     fn apply_transaction_internal(&mut self, transaction: &Transaction) {
-        // Iterate changes
-        for change in &transaction.changes.changes {
+        // Back to front, so each change's offsets are still valid when applied
+        for change in transaction.changes.application_order() {
             // Update syntax BEFORE applying to rope
             if let Some(syntax) = &mut self.syntax {
                 syntax.update(&self.rope, change);
             }
 
-            // Apply to rope
+            // Apply to rope, counting the line endings it touches before
+            // and after
+            let start = change.start.offset;
+            self.line_ending_counts
+                .remove(LineEndingCounts::scan_around(
+                    &self.rope,
+                    start,
+                    change.end.offset,
+                ));
+            let lines = self.edited_lines.is_some().then(|| {
+                let line = self.rope.byte_to_line(start);
+                (line, self.rope.byte_to_line(change.end.offset) + 1)
+            });
             change.apply(&mut self.rope);
+            self.line_ending_counts.add(LineEndingCounts::scan_around(
+                &self.rope,
+                start,
+                start + change.inserted_len(),
+            ));
+            if let (Some(edited), Some((line, old_end))) = (&mut self.edited_lines, lines) {
+                edited.push(LineEdit {
+                    start: line,
+                    old_end,
+                    new_end: self.rope.byte_to_line(start + change.inserted_len()) + 1,
+                });
+            }
+        }
+        if let Some(unjournaled) = &mut self.unjournaled {
+            unjournaled.push(transaction.changes.clone());
+        }
+        if let Some(unshared) = &mut self.unshared {
+            unshared.push(transaction.changes.clone());
+        }
+        if let Some(untaken) = &mut self.untaken {
+            untaken.push(transaction.changes.clone());
+        }
+        if let Some(unmirrored) = &mut self.unmirrored {
+            unmirrored.push(transaction.changes.clone());
+        }
+        if let Some(search) = &mut self.search {
+            search.update(&self.rope, &transaction.changes);
+        }
+        if let Some(display) = &mut self.display {
+            display.update(&self.rope, &transaction.changes);
         }
 
         // Reparse syntax AFTER all changes to ensure consistency
@@ -189,46 +639,113 @@ impl Buffer {
             syntax.reparse(&self.rope);
         }
 
-        if let Some(new_selection) = &transaction.selection {
-            self.selection = new_selection.clone();
-        }
+        // Without an explicit selection, cursors follow the text they were on
+        self.selection = match &transaction.selection {
+            Some(new_selection) => new_selection.clone(),
+            None => self.selection.map_through(&transaction.changes),
+        };
     }
 
-    /// Apply a transaction to the buffer
+    /// Apply a transaction to the buffer.
+    ///
+    /// Consecutive typing or deleting is merged into one undo step, as is
+    /// everything inside an undo group.
     pub fn apply(&mut self, transaction: Transaction) {
+        if self.is_read_only() {
+            return;
+        }
+        // Capture the inverse while the pre-edit text is still available
+        let inversion = transaction.invert(&self.rope);
+        let edit = Edit::classify(&transaction, &self.rope);
         self.apply_transaction_internal(&transaction);
 
-        // Add the ORIGINAL transaction to history (we'll invert on undo)
-        self.history.push(transaction);
+        if self.undo.should_merge(self.history.current, edit.as_ref()) {
+            self.history.merge_into_current(transaction, inversion);
+        } else {
+            self.history.push(transaction, inversion);
+        }
+        self.undo.record(self.history.current, edit.as_ref());
         self.dirty = true;
     }
 
+    /// Start a group of edits that undo as one step (formatting, snippets,
+    /// AI edits). Groups nest; only the outermost [`Buffer::end_undo_group`]
+    /// closes the step.
+    pub fn begin_undo_group(&mut self) {
+        self.undo.begin_group();
+    }
+
+    /// Close a group opened with [`Buffer::begin_undo_group`]
+    pub fn end_undo_group(&mut self) {
+        self.undo.end_group();
+    }
+
+    /// Set how long a pause in typing must be to start a new undo step
+    pub fn set_undo_coalesce_timeout(&mut self, timeout: Duration) {
+        self.undo.timeout = timeout;
+    }
+
     /// Undo the last transaction
     pub fn undo(&mut self) {
-        if let Some(tx_to_undo) = self.history.get_current() {
-            // Clone the transaction so we can use it after modifying history
-            let tx_clone = tx_to_undo.clone();
-
+        self.undo.break_step();
+        if let Some(inverse) = self.history.current_inversion().cloned() {
             // Move back in history first
             if self.history.undo() {
-                // Now invert and apply the transaction
-                let inverse = tx_clone.invert(&self.rope);
                 self.apply_transaction_internal(&inverse);
+                self.dirty = true;
             }
         }
     }
 
     /// Redo the last undone transaction
     pub fn redo(&mut self) {
+        self.undo.break_step();
         if self.history.can_redo() {
             // Move forward in history
             if let Some(redo_tx) = self.history.redo().cloned() {
                 // Apply the forward transaction
                 self.apply_transaction_internal(&redo_tx);
+                self.dirty = true;
             }
         }
     }
 
+    /// Jump to any node in the undo tree, undoing and redoing as needed.
+    ///
+    /// Returns true if the document changed.
+    pub fn goto_history_state(&mut self, node: usize) -> bool {
+        self.undo.break_step();
+        let steps = self.history.goto(node);
+        for step in &steps {
+            self.apply_transaction_internal(step);
+        }
+        if !steps.is_empty() {
+            self.dirty = true;
+        }
+        !steps.is_empty()
+    }
+
+    /// Go back to the state the buffer was in `duration` ago (`:earlier 10m`)
+    pub fn earlier(&mut self, duration: Duration) -> bool {
+        self.goto_history_state(self.history.earlier(duration))
+    }
+
+    /// Go forward in time by `duration` (`:later 10m`)
+    pub fn later(&mut self, duration: Duration) -> bool {
+        self.goto_history_state(self.history.later(duration))
+    }
+
+    /// Restore the text as it was at `time`
+    pub fn restore_to_time(&mut self, time: SystemTime) -> bool {
+        let secs = time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+        self.goto_history_state(self.history.state_at(secs))
+    }
+
+    /// Choose which branch redo follows; `child` must be a history node
+    pub fn set_redo_branch(&mut self, child: usize) -> bool {
+        self.history.set_redo_branch(child)
+    }
+
     /// Get the text content as a string
     pub fn text(&self) -> String {
         self.rope.to_string()
@@ -239,9 +756,9 @@ impl Buffer {
         &self.rope
     }
 
-    /// Get a slice of the rope
+    /// Get the text between two byte offsets
     pub fn slice(&self, start: usize, end: usize) -> String {
-        self.rope.slice(start..end).to_string()
+        self.rope.byte_slice(start..end).to_string()
     }
 
     /// Get the number of bytes in the buffer
@@ -254,18 +771,59 @@ impl Buffer {
         self.rope.len_lines()
     }
 
+    /// Get the undo history tree
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    /// Replace the undo history, e.g. with one restored from disk.
+    ///
+    /// The history's current node must describe the buffer's current text.
+    pub fn restore_history(&mut self, history: History) {
+        self.undo.break_step();
+        self.history = history;
+    }
+
     /// Get the buffer's selection
     pub fn selection(&self) -> &Selection {
         &self.selection
     }
 
-    /// Set the buffer's selection
+    /// Set the buffer's selection. Moving the cursor ends the current undo step.
     pub fn set_selection(&mut self, selection: Selection) {
+        if selection != self.selection {
+            self.undo.break_step();
+        }
         self.selection = selection;
     }
 
+    /// Move every cursor by `motion`. With `extend` the anchors stay put, so
+    /// the selections grow or shrink instead.
+    pub fn move_cursors(&mut self, motion: Motion, extend: bool) {
+        let selection = self.selection.map_merged(|range| {
+            let head = motion::apply(&self.rope, range.head, motion);
+            if extend {
+                crate::Range::new(range.anchor, head)
+            } else {
+                crate::Range::point(head)
+            }
+        });
+        self.set_selection(selection);
+    }
+
+    /// Select `object` around every cursor. Cursors not inside such an
+    /// object keep their selection.
+    pub fn select_text_object(&mut self, object: TextObject, scope: Scope) {
+        let tree = self.syntax.as_ref().and_then(Syntax::tree);
+        let selection = self.selection.map_merged(|range| {
+            motion::text_object(&self.rope, tree, range, object, scope).unwrap_or(*range)
+        });
+        self.set_selection(selection);
+    }
+
     /// Add a range to the current selection (Multi-cursor)
     pub fn add_selection_range(&mut self, range: crate::Range) {
+        self.undo.break_step();
         self.selection.push(range);
     }
 
@@ -284,11 +842,175 @@ impl Buffer {
         self.path.as_deref()
     }
 
+    /// Get the line ending style used for new lines
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Get the save-time line ending policy
+    pub fn line_ending_policy(&self) -> LineEndingPolicy {
+        self.line_ending_policy
+    }
+
+    /// Set the save-time line ending policy
+    pub fn set_line_ending_policy(&mut self, policy: LineEndingPolicy) {
+        self.line_ending_policy = policy;
+        if let Some(target) = policy.target() {
+            self.line_ending = target;
+        }
+    }
+
+    /// Count each line ending style in the buffer
+    pub fn line_ending_counts(&self) -> LineEndingCounts {
+        self.line_ending_counts
+    }
+
+    /// Check whether the buffer mixes line ending styles
+    pub fn has_mixed_line_endings(&self) -> bool {
+        self.line_ending_counts().is_mixed()
+    }
+
+    /// Rewrite every line ending to `target` as a single undoable transaction.
+    ///
+    /// Returns `true` if the text changed.
+    pub fn convert_line_endings(&mut self, target: LineEnding) -> bool {
+        self.line_ending = target;
+        let changes = target.conversion_changes(&self.rope);
+        if changes.is_empty() {
+            return false;
+        }
+
+        self.apply(Transaction::new(changes, None));
+        true
+    }
+
+    /// Get the encoding the buffer is saved in
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Check if the file is saved with a byte order mark
+    pub fn has_bom(&self) -> bool {
+        self.has_bom
+    }
+
+    /// Status bar label for the encoding, e.g. "UTF-8 with BOM"
+    pub fn encoding_label(&self) -> String {
+        if self.has_bom && self.encoding == Encoding::Utf8 {
+            format!("{} with BOM", self.encoding.label())
+        } else {
+            self.encoding.label().to_string()
+        }
+    }
+
+    /// Change the encoding used for subsequent saves ("Save with Encoding").
+    ///
+    /// Switching to UTF-16 always writes a BOM; staying on the same encoding
+    /// keeps whatever BOM state the file had.
+    pub fn set_encoding(&mut self, encoding: Encoding) {
+        if encoding != self.encoding {
+            self.has_bom = matches!(encoding, Encoding::Utf16Le | Encoding::Utf16Be);
+            self.encoding = encoding;
+            self.dirty = true;
+        }
+    }
+
+    /// Re-read the file from disk, decoding it as `encoding` ("Reopen with Encoding").
+    ///
+    /// Refuses while there are unsaved changes, and discards undo history,
+    /// since offsets no longer line up.
+    pub fn reload_with_encoding(&mut self, encoding: Encoding) -> Result<()> {
+        let path = self
+            .path
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No file path set"))?;
+        if self.dirty {
+            anyhow::bail!("Save or revert the unsaved changes first");
+        }
+        let bytes = std::fs::read(&path)?;
+        let decoded = encoding.decode_file(&bytes)?;
+        let text = decoded.text;
+
+        self.has_bom = decoded.has_bom;
+        self.encoding = encoding;
+        self.line_ending = LineEnding::detect_from_str(&text);
+        if self.unshared.is_some() || self.untaken.is_some() || self.unmirrored.is_some() {
+            let changes = merge::diff_changes(&self.rope.to_string(), &text);
+            for queue in [&mut self.unshared, &mut self.untaken, &mut self.unmirrored]
+                .into_iter()
+                .flatten()
+            {
+                queue.push(changes.clone());
+            }
+        }
+        let old_lines = self.rope.len_lines();
+        self.rope = Rope::from_str(&text);
+        self.replaced_all_lines(old_lines);
+        self.line_ending_counts = LineEndingCounts::scan(&self.rope);
+        self.disk_base = Some(self.rope.clone());
+        self.disk_hash = Some(content_hash(&bytes));
+        self.external_conflict = false;
+        self.history = History::new();
+        self.clear_journal_changes();
+        self.undo.break_step();
+        self.selection = Selection::default();
+        self.dirty = false;
+        if let Some(syntax) = &mut self.syntax {
+            syntax.parse(&self.rope);
+        }
+        if let Some(search) = &mut self.search {
+            search.rescan(&self.rope);
+        }
+        if let Some(display) = &mut self.display {
+            display.reset(&self.rope);
+        }
+        Ok(())
+    }
+
+    /// Encode the buffer contents in its file encoding
+    pub fn encoded_bytes(&self) -> Result<Vec<u8>> {
+        self.encoding.encode(&self.text(), self.has_bom)
+    }
+
+    /// Convert the buffer to the line ending the policy forces, if any, as
+    /// its own undo step, then encode it for saving
+    fn contents_to_save(&mut self) -> Result<Vec<u8>> {
+        if let Some(target) = self.line_ending_policy.target() {
+            self.undo.break_step();
+            if self.convert_line_endings(target) {
+                self.undo.break_step();
+            }
+        }
+        self.encoded_bytes()
+    }
+
     /// Save the buffer to its file path
     pub fn save(&mut self) -> Result<()> {
-        if let Some(ref path) = self.path {
-            std::fs::write(path, self.text())?;
-            self.mark_clean();
+        self.save_with(&SaveOptions::default())
+    }
+
+    /// Save in another encoding ("Save with Encoding"). If the text can't be
+    /// encoded or written, the buffer keeps its old encoding.
+    pub fn save_with_encoding(&mut self, encoding: Encoding, options: &SaveOptions) -> Result<()> {
+        let previous = (self.encoding, self.has_bom, self.dirty);
+        self.set_encoding(encoding);
+        let result = self.save_with(options);
+        if result.is_err() {
+            (self.encoding, self.has_bom, self.dirty) = previous;
+        }
+        result
+    }
+
+    /// Save the buffer to its file path with explicit save options.
+    ///
+    /// The write is atomic; if it fails the buffer stays dirty.
+    pub fn save_with(&mut self, options: &SaveOptions) -> Result<()> {
+        self.ensure_writable()?;
+        if let Some(path) = self.path.clone() {
+            self.ensure_disk_unchanged(&path)?;
+            let bytes = self.contents_to_save()?;
+            FileIO::save_atomic_with(Path::new(&path), &bytes, options)?;
+            self.mark_saved(&bytes);
             Ok(())
         } else {
             Err(anyhow::anyhow!("No file path set"))
@@ -297,13 +1019,144 @@ impl Buffer {
 
     /// Save the buffer to a specific path
     pub fn save_as<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
-        std::fs::write(path.as_ref(), self.text())?;
+        self.ensure_writable()?;
+        if self.has_unresolved_conflicts() {
+            anyhow::bail!("Resolve the conflicts with the file on disk before saving");
+        }
+        let bytes = self.contents_to_save()?;
+        FileIO::save_atomic(path.as_ref(), &bytes)?;
         self.path = Some(path.as_ref().to_string_lossy().to_string());
+        self.mark_saved(&bytes);
+        Ok(())
+    }
+
+    /// Refuse to overwrite external changes the buffer hasn't caught up with,
+    /// or a file the buffer never finished loading
+    fn ensure_disk_unchanged(&self, path: &str) -> Result<()> {
+        if self.has_unresolved_conflicts() {
+            anyhow::bail!("Resolve the conflicts with the file on disk before saving");
+        }
+        if self.disk_hash.is_none() {
+            anyhow::bail!("{} was never loaded; use Save As to write it", path);
+        }
+        if let (Some(expected), Ok(bytes)) = (self.disk_hash, std::fs::read(path)) {
+            if content_hash(&bytes) != expected {
+                anyhow::bail!("{} changed on disk; reload or merge before saving", path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Record what was written
+    fn mark_saved(&mut self, bytes: &[u8]) {
+        self.disk_base = Some(self.rope.clone());
+        self.disk_hash = Some(content_hash(bytes));
+        self.external_conflict = false;
+        self.clear_journal_changes();
         self.mark_clean();
+    }
+
+    /// Catch up with the file on disk after it changed externally.
+    ///
+    /// A buffer without unsaved changes is reloaded. Otherwise the unsaved
+    /// changes and the disk changes are three-way merged against the text
+    /// last loaded, with conflicts written inline between markers; saving is
+    /// refused until they are resolved. Either way the update is applied as
+    /// one undoable edit, so cursors stay on the text they were on.
+    pub fn sync_with_disk(&mut self) -> Result<ExternalChange> {
+        let path = self
+            .path
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("No file path set"))?;
+        if self.is_loading {
+            return Ok(ExternalChange::Loading);
+        }
+        if self.read_only {
+            return Ok(ExternalChange::Unchanged);
+        }
+        let bytes = match std::fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(ExternalChange::Deleted);
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let hash = content_hash(&bytes);
+        if self.disk_hash == Some(hash) {
+            return Ok(ExternalChange::Unchanged);
+        }
+        // Saves go back in whatever encoding the file is in now
+        let decoded = match self.encoding.decode_file(&bytes) {
+            Ok(decoded) => decoded,
+            Err(_) => Encoding::decode_detect(&bytes)?,
+        };
+        self.encoding = decoded.encoding;
+        self.has_bom = decoded.has_bom;
+        let disk_text = decoded.text;
+
+        let current = self.text();
+        let (target, outcome) = if self.dirty {
+            let base = self
+                .disk_base
+                .as_ref()
+                .map_or_else(String::new, Rope::to_string);
+            let merged = merge::merge3(&base, &current, &disk_text);
+            let outcome = match merged.conflicts {
+                0 => ExternalChange::Merged,
+                n => ExternalChange::Conflicts(n),
+            };
+            (merged.text, outcome)
+        } else {
+            (disk_text.clone(), ExternalChange::Reloaded)
+        };
+
+        let changes = merge::diff_changes(&current, &target);
+        if !changes.is_empty() {
+            self.undo.break_step();
+            self.apply(Transaction::new(changes, None));
+            self.undo.break_step();
+        }
+        self.dirty = target != disk_text;
+        self.disk_base = Some(Rope::from_str(&disk_text));
+        self.disk_hash = Some(hash);
+        // The journal now has to start from the new disk contents
+        self.clear_journal_changes();
+        if let (Some(unjournaled), true) = (&mut self.unjournaled, self.dirty) {
+            unjournaled.push(merge::diff_changes(&disk_text, &target));
+        }
+        self.external_conflict = matches!(outcome, ExternalChange::Conflicts(_));
+        Ok(outcome)
+    }
+
+    /// Whether a merge with external changes left conflict markers behind
+    pub fn has_unresolved_conflicts(&self) -> bool {
+        self.external_conflict && merge::has_conflicts(&self.text())
+    }
+
+    /// Resolve the conflict around byte `offset` by keeping one side.
+    ///
+    /// Returns false if `offset` is not inside a conflict.
+    pub fn resolve_conflict_at(&mut self, offset: usize, side: ConflictSide) -> bool {
+        match merge::resolve_conflict(&self.text(), offset, side) {
+            Some(change) => {
+                self.apply(Transaction::from_change(change));
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn ensure_writable(&self) -> Result<()> {
+        if self.is_loading {
+            anyhow::bail!("File is still loading");
+        }
+        if self.read_only {
+            anyhow::bail!("Buffer is read-only");
+        }
         Ok(())
     }
 
-    /// Convert byte offset to line and column
+    /// Convert byte offset to line and byte column
     pub fn offset_to_line_col(&self, offset: usize) -> (usize, usize) {
         let line = self.rope.byte_to_line(offset);
         let line_start = self.rope.line_to_byte(line);
@@ -311,24 +1164,90 @@ impl Buffer {
         (line, col)
     }
 
-    /// Convert line and column to byte offset
+    /// Convert line and byte column to byte offset
     pub fn line_col_to_offset(&self, line: usize, col: usize) -> usize {
         let line_start = self.rope.line_to_byte(line);
         line_start + col
     }
 
-    /// Sync content from another buffer (preserves selection/syntax state)
-    pub fn sync_content_from(&mut self, other: &Buffer) {
-        self.rope = other.rope.clone();
-        self.history = other.history.clone();
+    /// Line and column of `pos`, with the column counted in `unit`
+    pub fn position_to_line_col(&self, pos: Position, unit: ColumnUnit) -> LineCol {
+        coords::to_line_col(&self.rope, pos.offset, unit)
+    }
+
+    /// Position of a line and column counted in `unit`, clamped to the text
+    pub fn line_col_to_position(&self, at: LineCol, unit: ColumnUnit) -> Position {
+        Position::new(coords::to_offset(&self.rope, at, unit))
+    }
+
+    /// Convert a column on `line` from one unit to another
+    pub fn convert_column(
+        &self,
+        line: usize,
+        col: usize,
+        from: ColumnUnit,
+        to: ColumnUnit,
+    ) -> usize {
+        let pos = self.line_col_to_position(LineCol::new(line, col), from);
+        self.position_to_line_col(pos, to).col
+    }
+
+    /// Byte offset of the grapheme cluster boundary after `offset`
+    pub fn next_grapheme_boundary(&self, offset: usize) -> usize {
+        coords::next_grapheme_boundary(&self.rope, offset)
+    }
+
+    /// Byte offset of the grapheme cluster boundary before `offset`
+    pub fn prev_grapheme_boundary(&self, offset: usize) -> usize {
+        coords::prev_grapheme_boundary(&self.rope, offset)
+    }
+
+    /// Sync content from another view of the same file. `changes` are the
+    /// edits made there since the last sync, from
+    /// [`Buffer::take_mirror_changes`]; they are applied like any other
+    /// edit, so syntax, search matches, folds and inlays follow them. Without
+    /// them, or if they don't lead to the other buffer's text, the difference
+    /// between the two texts is applied instead.
+    pub fn sync_content_from(&mut self, other: &Buffer, changes: Option<&[ChangeSet]>) {
+        // The edits are journaled through the buffer they were made in, and
+        // aren't passed back to it
+        let unjournaled = self.unjournaled.take();
+        let unmirrored = self.unmirrored.take();
+        for changes in self.changes_to(other, changes) {
+            self.apply_transaction_internal(&Transaction::new(changes, None));
+        }
+        self.unjournaled = unjournaled;
+        self.unmirrored = unmirrored;
+
+        self.history.catch_up(&other.history);
+        self.undo.break_step();
         self.dirty = other.dirty;
         self.line_ending = other.line_ending;
+        self.line_ending_policy = other.line_ending_policy;
         self.encoding = other.encoding;
+        self.has_bom = other.has_bom;
         // Path should match, but we copy it anyway
         self.path = other.path.clone();
-        // We don't sync syntax state as it depends on local parser state in Editor
-        // But invalidating it might be good?
-        // self.syntax = None;
+    }
+
+    /// `changes` if they take this buffer's text to `other`'s, otherwise the
+    /// difference between the two texts
+    fn changes_to(&self, other: &Buffer, changes: Option<&[ChangeSet]>) -> Vec<ChangeSet> {
+        let mut len = self.rope.len_bytes() as isize;
+        let lines_up = changes.is_some_and(|changes| {
+            changes.iter().all(|changes| {
+                let fits = changes.changes.iter().all(|c| c.end.offset as isize <= len);
+                len += changes.changes.iter().map(Change::len_delta).sum::<isize>();
+                fits
+            }) && len == other.rope.len_bytes() as isize
+        });
+        match changes {
+            Some(changes) if lines_up => changes.to_vec(),
+            _ => vec![merge::diff_changes(
+                &self.rope.to_string(),
+                &other.rope.to_string(),
+            )],
+        }
     }
 }
 
@@ -338,31 +1257,10 @@ impl Default for Buffer {
     }
 }
 
-impl LineEnding {
-    /// Detect the system's default line ending
-    fn detect_system() -> Self {
-        #[cfg(windows)]
-        return LineEnding::CRLF;
-        #[cfg(not(windows))]
-        return LineEnding::LF;
-    }
-
-    /// Detect line ending from a string
-    fn detect_from_str(s: &str) -> Self {
-        if s.contains("\r\n") {
-            LineEnding::CRLF
-        } else if s.contains('\r') {
-            LineEnding::CR
-        } else {
-            LineEnding::LF
-        }
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{Change, ChangeSet, Position};
+    use crate::{Change, ChangeSet, Position, Range};
 
     #[test]
     fn test_buffer_creation() {
@@ -419,6 +1317,159 @@ mod tests {
         assert_eq!((line, col), (1, 2));
     }
 
+    #[test]
+    fn test_encoding_round_trip() {
+        let dir = std::env::temp_dir().join("forge_buffer_encoding_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("utf16.txt");
+        let original = Encoding::Utf16Le.encode("héllo\n", true).unwrap();
+        std::fs::write(&path, &original).unwrap();
+
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        assert_eq!(buffer.text(), "héllo\n");
+        assert_eq!(buffer.encoding(), Encoding::Utf16Le);
+        assert!(buffer.has_bom());
+
+        buffer.save().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), original);
+
+        // Text Latin-1 can't represent keeps the old encoding, and unsaved
+        // changes aren't thrown away by reopening
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(7),
+            "€".into(),
+        )));
+        buffer
+            .save_with_encoding(Encoding::Latin1, &SaveOptions::default())
+            .unwrap_err();
+        assert_eq!(buffer.encoding(), Encoding::Utf16Le);
+        assert!(buffer.has_bom() && buffer.is_dirty());
+        buffer.reload_with_encoding(Encoding::Latin1).unwrap_err();
+        assert_eq!(buffer.text(), "héllo\n€");
+        buffer.undo();
+        buffer.save().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), original);
+
+        buffer.set_encoding(Encoding::Latin1);
+        buffer.save().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"h\xE9llo\n");
+
+        buffer.reload_with_encoding(Encoding::Utf8).unwrap_err();
+        buffer.reload_with_encoding(Encoding::Latin1).unwrap();
+        assert_eq!(buffer.text(), "héllo\n");
+        assert!(!buffer.is_dirty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_convert_line_endings_undoable() {
+        let mut buffer = Buffer::from_str("a\r\nb\nc\r\n");
+        assert!(buffer.has_mixed_line_endings());
+        buffer.set_selection(Selection::point(Position::new(6)));
+
+        assert!(buffer.convert_line_endings(LineEnding::LF));
+        assert_eq!(buffer.text(), "a\nb\nc\n");
+        assert_eq!(buffer.line_ending(), LineEnding::LF);
+        assert_eq!(buffer.selection().primary().head, Position::new(5));
+        assert!(!buffer.has_mixed_line_endings());
+
+        // The whole conversion is a single undo step
+        buffer.undo();
+        assert_eq!(buffer.text(), "a\r\nb\nc\r\n");
+        buffer.redo();
+        assert_eq!(buffer.text(), "a\nb\nc\n");
+        assert!(!buffer.convert_line_endings(LineEnding::LF));
+    }
+
+    #[test]
+    fn test_line_ending_counts_follow_edits() {
+        let mut buffer = Buffer::from_str("a\r\nb\nc\rd");
+        let edits = [
+            // Splits a CRLF into CR + LF
+            (2, 2, "x"),
+            // Joins a CR with a following LF
+            (2, 3, ""),
+            (5, 6, "\r"),
+            (0, 0, "\n\r"),
+            (8, 9, "\r\n\n"),
+        ];
+        for (start, end, text) in edits {
+            buffer.apply(Transaction::from_change(Change::replace(
+                Position::new(start),
+                Position::new(end),
+                text.to_string(),
+            )));
+            assert_eq!(
+                buffer.line_ending_counts(),
+                LineEndingCounts::scan(buffer.rope()),
+                "{:?}",
+                buffer.text()
+            );
+        }
+        buffer.undo();
+        assert_eq!(
+            buffer.line_ending_counts(),
+            LineEndingCounts::scan(buffer.rope())
+        );
+    }
+
+    #[test]
+    fn test_undo_deletion() {
+        let mut buffer = Buffer::from_str("hello world");
+        let change = Change::delete(Position::new(5), Position::new(11));
+        buffer.apply(Transaction::from_change(change));
+        assert_eq!(buffer.text(), "hello");
+
+        buffer.undo();
+        assert_eq!(buffer.text(), "hello world");
+    }
+
+    #[test]
+    fn test_save_applies_line_ending_policy() {
+        let dir = std::env::temp_dir().join("forge_buffer_eol_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("eol.txt");
+
+        let mut buffer = Buffer::from_str("one\ntwo\r\n");
+        buffer.set_line_ending_policy(LineEndingPolicy::Crlf);
+        buffer.save_as(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one\r\ntwo\r\n");
+        // The buffer is converted too, so it matches the disk
+        assert_eq!(buffer.text(), "one\r\ntwo\r\n");
+        assert!(!buffer.is_dirty());
+
+        // The conversion is one undo step of its own
+        buffer.undo();
+        assert_eq!(buffer.text(), "one\ntwo\r\n");
+        assert!(buffer.is_dirty());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_failed_save_keeps_buffer_dirty() {
+        let dir = std::env::temp_dir().join("forge_buffer_failed_save_test");
+        std::fs::create_dir_all(&dir).unwrap();
+        // A directory can't be replaced by a file, so the rename fails
+        let target = dir.join("occupied");
+        std::fs::create_dir_all(&target).unwrap();
+
+        let mut buffer = Buffer::from_str("data");
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(4),
+            "!".to_string(),
+        )));
+        buffer.path = Some(target.to_string_lossy().to_string());
+        buffer.disk_hash = Some(0);
+
+        assert!(buffer.save().is_err());
+        assert!(buffer.is_dirty());
+        assert!(target.is_dir());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn test_syntax_integration() {
         // 1. Create buffer
@@ -474,4 +1525,446 @@ mod tests {
             assert_eq!(first_child.kind(), "fn");
         }
     }
+
+    #[test]
+    fn chunked_open_is_read_only_until_loaded() {
+        let path = std::env::temp_dir().join("forge_buffer_chunked.log");
+        std::fs::write(&path, "first\nsecond\n").unwrap();
+
+        let mut buffer = Buffer::open_chunked(&path).unwrap();
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "x".into(),
+        )));
+        assert!(buffer.save().is_err());
+        assert_eq!(buffer.sync_with_disk().unwrap(), ExternalChange::Loading);
+
+        let result = loop {
+            if let Some(result) = buffer.poll_load() {
+                break result;
+            }
+            std::thread::yield_now();
+        };
+        result.unwrap();
+        assert!(!buffer.is_loading);
+        assert_eq!(buffer.text(), "first\nsecond\n");
+        assert!(!buffer.is_dirty());
+
+        // Changes made on disk since the load aren't overwritten
+        std::fs::write(&path, "first\nchanged\n").unwrap();
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "x".into(),
+        )));
+        assert!(buffer.save().is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first\nchanged\n");
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn failed_chunked_load_is_read_only() {
+        // A directory opens but can't be read, so the load fails
+        let dir = std::env::temp_dir().join("forge_buffer_failed_load");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut buffer = Buffer::open_chunked(&dir).unwrap();
+        let result = loop {
+            if let Some(result) = buffer.poll_load() {
+                break result;
+            }
+            std::thread::yield_now();
+        };
+        assert!(result.is_err());
+        assert!(buffer.is_read_only());
+
+        // Even if made editable, a save can't replace the file it never read
+        buffer.set_read_only(false);
+        assert!(buffer.save().is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn clean_buffer_reloads_and_keeps_cursor() {
+        let path = std::env::temp_dir().join("forge_buffer_reload.txt");
+        std::fs::write(&path, "alpha\nbeta\ngamma\n").unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.set_selection(Selection::point(Position::new(12))); // in "gamma"
+
+        std::fs::write(&path, "header\nalpha\nbeta\ngamma\n").unwrap();
+        assert_eq!(buffer.sync_with_disk().unwrap(), ExternalChange::Reloaded);
+        assert_eq!(buffer.text(), "header\nalpha\nbeta\ngamma\n");
+        assert_eq!(buffer.selection().primary().head.offset, 19);
+        assert!(!buffer.is_dirty());
+        assert_eq!(buffer.sync_with_disk().unwrap(), ExternalChange::Unchanged);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn reload_keeps_the_encoding_found_on_disk() {
+        let path = std::env::temp_dir().join("forge_buffer_reload_encoding.txt");
+        std::fs::write(&path, "caf\u{e9}\n").unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        assert_eq!(buffer.encoding(), Encoding::Utf8);
+
+        // Rewritten as Latin-1, which isn't valid UTF-8
+        std::fs::write(&path, b"caf\xe9!\n").unwrap();
+        assert_eq!(buffer.sync_with_disk().unwrap(), ExternalChange::Reloaded);
+        assert_eq!(buffer.text(), "caf\u{e9}!\n");
+        assert_eq!(buffer.encoding(), Encoding::Latin1);
+
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "> ".to_string(),
+        )));
+        buffer.save().unwrap();
+        assert_eq!(std::fs::read(&path).unwrap(), b"> caf\xe9!\n");
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn dirty_buffer_merges_external_changes() {
+        let path = std::env::temp_dir().join("forge_buffer_merge.txt");
+        std::fs::write(&path, "a\nb\nc\n").unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.apply(Transaction::from_change(Change::replace(
+            Position::new(0),
+            Position::new(1),
+            "A".into(),
+        )));
+
+        // Saving over an unseen external change is refused
+        std::fs::write(&path, "a\nb\nC\n").unwrap();
+        assert!(buffer.save().is_err());
+
+        assert_eq!(buffer.sync_with_disk().unwrap(), ExternalChange::Merged);
+        assert_eq!(buffer.text(), "A\nb\nC\n");
+        assert!(buffer.is_dirty());
+        buffer.save().unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "A\nb\nC\n");
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn conflicts_block_saving_until_resolved() {
+        let path = std::env::temp_dir().join("forge_buffer_conflict.txt");
+        std::fs::write(&path, "a\nb\n").unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.apply(Transaction::from_change(Change::replace(
+            Position::new(2),
+            Position::new(3),
+            "mine".into(),
+        )));
+        std::fs::write(&path, "a\ntheirs\n").unwrap();
+
+        assert_eq!(
+            buffer.sync_with_disk().unwrap(),
+            ExternalChange::Conflicts(1)
+        );
+        assert!(buffer.has_unresolved_conflicts());
+        assert!(buffer.save().is_err());
+
+        let offset = buffer.text().find("mine").unwrap();
+        assert!(buffer.resolve_conflict_at(offset, ConflictSide::Buffer));
+        assert_eq!(buffer.text(), "a\nmine\n");
+        buffer.save().unwrap();
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn journal_changes_rebuild_text_from_disk() {
+        let path = std::env::temp_dir().join("forge_buffer_journal.txt");
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        let mut buffer = Buffer::from_file(&path).unwrap();
+        buffer.enable_journal();
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "zero\n".into(),
+        )));
+        buffer.undo();
+        buffer.redo();
+
+        let replay = |buffer: &mut Buffer| {
+            let mut rope = Rope::from_str(&std::fs::read_to_string(&path).unwrap());
+            for changes in buffer.take_journal_changes() {
+                changes.apply(&mut rope);
+            }
+            rope.to_string()
+        };
+        assert_eq!(replay(&mut buffer), "zero\none\ntwo\nthree\n");
+
+        // After merging external changes the journal restarts from the new file
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "-1\n".into(),
+        )));
+        std::fs::write(&path, "one\ntwo\nthree\nfour\n").unwrap();
+        assert_eq!(buffer.sync_with_disk().unwrap(), ExternalChange::Merged);
+        assert_ne!(buffer.disk_hash(), None);
+        assert_eq!(replay(&mut buffer), buffer.text());
+
+        buffer.save().unwrap();
+        assert!(buffer.take_journal_changes().is_empty());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn goto_history_state_switches_branches() {
+        let mut buffer = Buffer::from_str("x");
+        let insert = |text: &str| {
+            Transaction::new(
+                ChangeSet::with_change(Change::insert(Position::new(1), text.to_string())),
+                None,
+            )
+        };
+        buffer.apply(insert("a"));
+        let first = buffer.history().current;
+        buffer.undo();
+        buffer.apply(insert("b"));
+        assert_eq!(buffer.text(), "xb");
+
+        buffer.mark_clean();
+        assert!(buffer.goto_history_state(first));
+        assert_eq!(buffer.text(), "xa");
+        assert!(buffer.is_dirty());
+
+        // Undo/redo now stay on the branch we jumped to
+        buffer.undo();
+        buffer.redo();
+        assert_eq!(buffer.text(), "xa");
+
+        buffer.mark_clean();
+        assert!(buffer.restore_to_time(UNIX_EPOCH));
+        assert_eq!(buffer.text(), "x");
+        assert!(buffer.is_dirty());
+
+        buffer.mark_clean();
+        buffer.redo();
+        assert!(buffer.is_dirty());
+    }
+
+    #[test]
+    fn multi_cursor_edit_keeps_cursors_on_their_text() {
+        let mut buffer = Buffer::from_str("one two three");
+        let mut ranges = smallvec::SmallVec::new();
+        for offset in [3, 7, 13] {
+            ranges.push(Range::point(Position::new(offset)));
+        }
+        buffer.set_selection(Selection::new(ranges, 0));
+
+        let mut changes = ChangeSet::new();
+        for offset in [3, 7, 13] {
+            changes.add(Change::insert(Position::new(offset), ";".to_string()));
+        }
+        buffer.apply(Transaction::new(changes, None));
+        assert_eq!(buffer.text(), "one; two; three;");
+        let heads: Vec<usize> = buffer
+            .selection()
+            .ranges()
+            .iter()
+            .map(|r| r.head.offset)
+            .collect();
+        assert_eq!(heads, vec![4, 9, 16]);
+
+        buffer.undo();
+        assert_eq!(buffer.text(), "one two three");
+        assert_eq!(buffer.selection().ranges()[2].head.offset, 13);
+    }
+
+    #[test]
+    fn motions_and_text_objects_apply_to_every_cursor() {
+        let mut buffer = Buffer::from_str("one two\nthree (four)\n");
+        let mut selection = Selection::point(Position::new(0));
+        selection.push(Range::point(Position::new(8)));
+        buffer.set_selection(selection);
+
+        buffer.move_cursors(Motion::NextEnd(motion::Unit::Word), true);
+        let ends: Vec<_> = buffer.selection().ranges().iter().map(Range::len).collect();
+        assert_eq!(ends, vec![3, 5]);
+
+        // Both cursors' paragraphs are the same one, so they merge
+        buffer.select_text_object(TextObject::Paragraph, Scope::Inside);
+        assert_eq!(buffer.selection().len(), 1);
+        assert_eq!(buffer.selection().primary().len(), buffer.len_bytes());
+    }
+
+    #[test]
+    fn tracked_changes_queue_until_taken() {
+        let mut buffer = Buffer::from_str("one\n");
+        let insert =
+            |at: usize| Transaction::from_change(Change::insert(Position::new(at), "x".into()));
+        buffer.apply(insert(0));
+        assert!(!buffer.has_changes());
+
+        buffer.track_changes();
+        buffer.apply(insert(0));
+        buffer.apply(insert(2));
+        assert!(buffer.has_changes());
+        let changes = buffer.take_changes();
+        assert_eq!(changes.len(), 2);
+        assert!(!buffer.has_changes());
+
+        // Copying another buffer's text counts as an edit too
+        let other = Buffer::from_str("two\n");
+        buffer.sync_content_from(&other, None);
+        let mut rope = Rope::from_str("xxxone\n");
+        for changes in buffer.take_changes() {
+            changes.apply(&mut rope);
+        }
+        assert_eq!(rope.to_string(), "two\n");
+    }
+
+    #[test]
+    fn syncing_another_view_moves_its_folds() {
+        let mut source = Buffer::from_str("fn a() {\n    1\n}\nfn b() {}\n");
+        source.track_mirror_changes();
+        let mut mirror = source.clone();
+        mirror.set_display_options(DisplayOptions::default());
+        mirror.fold_lines(0, 2);
+        let folded = mirror.display_map().unwrap().folds().to_vec();
+        assert_eq!(folded.len(), 1);
+
+        source.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "// a\n".into(),
+        )));
+        let changes = source.take_mirror_changes();
+        mirror.sync_content_from(&source, changes.as_deref());
+        assert_eq!(mirror.text(), source.text());
+        let moved: Vec<_> = folded.iter().map(|f| f.start + 5..f.end + 5).collect();
+        assert_eq!(mirror.display_map().unwrap().folds(), moved);
+
+        // The history came along, so the edit can be undone from either view
+        mirror.undo();
+        assert_eq!(mirror.text(), "fn a() {\n    1\n}\nfn b() {}\n");
+    }
+
+    #[test]
+    fn edited_lines_merge_until_taken() {
+        let mut buffer = Buffer::from_str("a\nb\nc\nd\ne\n");
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "x".into(),
+        )));
+        assert_eq!(buffer.take_edited_lines(), None);
+
+        buffer.track_edited_lines();
+        // Split line 3 in two, then edit line 1
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(7),
+            "\n".into(),
+        )));
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(3),
+            "y".into(),
+        )));
+        assert_eq!(
+            buffer.take_edited_lines(),
+            Some(LineEdit {
+                start: 1,
+                old_end: 4,
+                new_end: 5,
+            })
+        );
+        assert_eq!(buffer.take_edited_lines(), None);
+    }
+
+    #[test]
+    fn merged_selections_keep_their_direction() {
+        let mut buffer = Buffer::from_str("one two three");
+        let mut selection = Selection::single(Range::new(Position::new(7), Position::new(4)));
+        selection.push(Range::new(Position::new(13), Position::new(8)));
+        buffer.set_selection(selection);
+
+        // The second selection grows back over the first one
+        buffer.move_cursors(Motion::PrevStart(motion::Unit::Word), true);
+        assert_eq!(buffer.selection().len(), 1);
+        let merged = buffer.selection().primary();
+        assert_eq!((merged.anchor.offset, merged.head.offset), (13, 0));
+    }
+
+    #[test]
+    fn shared_edits_keep_local_history() {
+        let mut buffer = Buffer::from_str("abc");
+        buffer.share();
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(3),
+            "def".to_string(),
+        )));
+        buffer.apply(Transaction::from_change(Change::delete(
+            Position::new(0),
+            Position::new(1),
+        )));
+        buffer.undo();
+        assert_eq!(buffer.text(), "abcdef");
+
+        // A collaborator edits inside the text the first step inserted
+        buffer.apply_shared(ChangeSet::with_change(Change::replace(
+            Position::new(4),
+            Position::new(5),
+            "X".to_string(),
+        )));
+        assert_eq!(buffer.text(), "abcdXf");
+
+        // Redo follows the branch that was undone
+        buffer.redo();
+        assert_eq!(buffer.text(), "bcdXf");
+        buffer.undo();
+        buffer.undo();
+        assert_eq!(buffer.text(), "abcX");
+        buffer.redo();
+        assert_eq!(buffer.text(), "abcdXf");
+    }
+
+    #[test]
+    fn search_matches_follow_edits_and_undo() {
+        let mut buffer = Buffer::from_str(
+            "let x = 1;
+let y = x;
+",
+        );
+        let searcher = Searcher::new("x", crate::SearchOptions::default()).unwrap();
+        buffer.set_search(searcher);
+        assert_eq!(buffer.search().unwrap().matches(), &[4..5, 19..20]);
+
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "// x\n".to_string(),
+        )));
+        assert_eq!(buffer.search().unwrap().matches(), &[3..4, 9..10, 24..25]);
+        buffer.undo();
+        assert_eq!(buffer.search().unwrap().matches(), &[4..5, 19..20]);
+    }
+
+    #[test]
+    fn display_map_follows_edits_and_undo() {
+        let mut buffer = Buffer::from_str("fn a() {\n\tb();\n}\nc\n");
+        assert_eq!(
+            buffer.to_display(Position::new(10)),
+            DisplayPoint::new(1, 1)
+        );
+
+        buffer.set_display_options(DisplayOptions::default());
+        assert_eq!(
+            buffer.to_display(Position::new(10)),
+            DisplayPoint::new(1, 4)
+        );
+        buffer.fold_lines(0, 2);
+        assert_eq!(buffer.display_row_count(), 3);
+        assert_eq!(buffer.display_rows(1..2)[0].text, "c");
+
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "// a\n".to_string(),
+        )));
+        assert_eq!(
+            buffer.to_display(Position::new(22)),
+            DisplayPoint::new(2, 0)
+        );
+        assert_eq!(
+            buffer.from_display(DisplayPoint::new(1, 9)),
+            Position::new(21)
+        );
+        buffer.undo();
+        assert_eq!(buffer.display_rows(0..1)[0].text, "fn a() {⋯");
+    }
 }
@@ -0,0 +1,708 @@
+//! Shared editing sessions.
+//!
+//! Every collaborator edits their own copy of a document, and a relay puts
+//! the edits in one order. An edit is sent with the revision it was made
+//! against; the [`Room`] on the relay rebases it over the edits sequenced
+//! since with [`ChangeSet::transform`] and broadcasts the result, which each
+//! [`Session`] in turn rebases over its own edits still on the way. A session
+//! has at most one edit in flight, so the relay never has to rebase over an
+//! edit the sender hasn't seen.
+//!
+//! Undo is per peer: a session keeps its own undo steps and rebases them over
+//! everyone else's edits, so undoing never reverts a collaborator's text.
+//!
+//! The room only keeps the edits some peer may still send an edit against.
+//! A peer's sequenced edit tells the room how far it has caught up; a peer
+//! that isn't editing acknowledges every [`ACK_INTERVAL`] revisions instead.
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use ropey::Rope;
+use serde::{Deserialize, Serialize};
+
+use crate::undo_group::DEFAULT_COALESCE_TIMEOUT;
+use crate::{merge, Assoc, Buffer, ChangeSet, Position, Range, Selection, Transaction};
+
+pub type PeerId = u64;
+
+/// Revisions a session receives before acknowledging them without an edit
+pub const ACK_INTERVAL: u64 = 64;
+
+/// A collaborator in a room
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerInfo {
+    pub id: PeerId,
+    pub name: String,
+    /// Last selection the peer shared
+    pub selection: Option<Selection>,
+}
+
+/// Messages from a peer to the relay
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Ask for the names of the open rooms
+    ListRooms,
+    /// Open a room holding `text`, or with no text join an open one
+    Join {
+        room: String,
+        name: String,
+        text: Option<String>,
+    },
+    /// An edit made against `revision`
+    Edit { revision: u64, changes: ChangeSet },
+    /// The sender's selection, in the text as of the last revision it saw
+    Select { selection: Selection },
+    /// The sender has seen every revision up to `revision`
+    Ack { revision: u64 },
+}
+
+/// Messages from the relay to a peer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    Rooms {
+        rooms: Vec<String>,
+    },
+    /// The room as the peer finds it on joining
+    Welcome {
+        peer: PeerId,
+        revision: u64,
+        text: String,
+        peers: Vec<PeerInfo>,
+    },
+    Joined {
+        peer: PeerInfo,
+    },
+    Left {
+        peer: PeerId,
+    },
+    /// The next revision: an edit by `peer`, rebased onto the revisions
+    /// before it. The sender gets its own edit back as the acknowledgement.
+    Edit {
+        peer: PeerId,
+        changes: ChangeSet,
+    },
+    /// A peer's selection, in the text as of the latest revision
+    Select {
+        peer: PeerId,
+        selection: Selection,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// The relay's copy of a shared document
+#[derive(Debug, Clone)]
+pub struct Room {
+    text: Rope,
+    /// The edits since revision `base`, in order; edit `n` turned revision
+    /// `base + n` into `base + n + 1`
+    edits: Vec<ChangeSet>,
+    base: u64,
+    peers: Vec<PeerInfo>,
+    /// The latest revision each peer is known to have seen
+    seen: Vec<(PeerId, u64)>,
+}
+
+impl Room {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: Rope::from_str(text),
+            edits: Vec::new(),
+            base: 0,
+            peers: Vec::new(),
+            seen: Vec::new(),
+        }
+    }
+
+    pub fn text(&self) -> &Rope {
+        &self.text
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.base + self.edits.len() as u64
+    }
+
+    /// How many edits the room still keeps for rebasing
+    pub fn kept_edits(&self) -> usize {
+        self.edits.len()
+    }
+
+    pub fn peers(&self) -> &[PeerInfo] {
+        &self.peers
+    }
+
+    /// Add a peer, returning the welcome to send it
+    pub fn join(&mut self, id: PeerId, name: &str) -> ServerMessage {
+        let welcome = ServerMessage::Welcome {
+            peer: id,
+            revision: self.revision(),
+            text: self.text.to_string(),
+            peers: self.peers.clone(),
+        };
+        self.peers.push(PeerInfo {
+            id,
+            name: name.to_string(),
+            selection: None,
+        });
+        self.seen.push((id, self.revision()));
+        welcome
+    }
+
+    pub fn leave(&mut self, id: PeerId) {
+        self.peers.retain(|p| p.id != id);
+        self.seen.retain(|&(peer, _)| peer != id);
+        self.trim();
+    }
+
+    /// Note that `peer` has seen every revision up to `revision`
+    pub fn ack(&mut self, peer: PeerId, revision: u64) {
+        let revision = revision.min(self.revision());
+        if let Some((_, seen)) = self.seen.iter_mut().find(|(id, _)| *id == peer) {
+            *seen = (*seen).max(revision);
+        }
+        self.trim();
+    }
+
+    /// Drop the edits every peer has seen; no edit can be made against an
+    /// older revision any more
+    fn trim(&mut self) {
+        let oldest = self
+            .seen
+            .iter()
+            .map(|&(_, seen)| seen)
+            .min()
+            .unwrap_or_else(|| self.revision());
+        if oldest > self.base {
+            self.edits.drain(..(oldest - self.base) as usize);
+            self.base = oldest;
+        }
+    }
+
+    /// Sequence an edit made against `revision`, returning it rebased onto
+    /// the latest revision
+    pub fn edit(&mut self, peer: PeerId, revision: u64, changes: ChangeSet) -> Result<ChangeSet> {
+        if revision > self.revision() {
+            bail!(
+                "Edit against revision {} but the room is at {}",
+                revision,
+                self.revision()
+            );
+        }
+        if !self.peers.iter().any(|p| p.id == peer) {
+            bail!("Unknown peer {}", peer);
+        }
+        if revision < self.base {
+            bail!(
+                "Edit against revision {} but the room only goes back to {}",
+                revision,
+                self.base
+            );
+        }
+        let changes = self.edits[(revision - self.base) as usize..]
+            .iter()
+            .fold(changes, |changes, earlier| {
+                changes.transform(earlier, Assoc::After)
+            });
+        check_changes(&changes, &self.text)?;
+        changes.apply(&mut self.text);
+        for other in &mut self.peers {
+            other.selection = other.selection.as_ref().map(|s| s.map_through(&changes));
+        }
+        self.edits.push(changes.clone());
+        // The sender's next edit is against this revision or a later one
+        let revision = self.revision();
+        self.ack(peer, revision);
+        Ok(changes)
+    }
+
+    /// Record a peer's selection, returning it clamped to the text
+    pub fn select(&mut self, peer: PeerId, selection: Selection) -> Selection {
+        let len = self.text.len_bytes();
+        let selection = selection.map(|r| {
+            Range::new(
+                Position::new(r.anchor.offset.min(len)),
+                Position::new(r.head.offset.min(len)),
+            )
+        });
+        if let Some(info) = self.peers.iter_mut().find(|p| p.id == peer) {
+            info.selection = Some(selection.clone());
+        }
+        selection
+    }
+}
+
+/// Refuse edits that would split a character or reach past the text
+pub(crate) fn check_changes(changes: &ChangeSet, rope: &Rope) -> Result<()> {
+    let on_boundary = |offset: usize| {
+        offset <= rope.len_bytes() && rope.char_to_byte(rope.byte_to_char(offset)) == offset
+    };
+    let mut pos = 0;
+    for change in changes.sorted() {
+        let (start, end) = (change.start.offset, change.end.offset);
+        if start < pos || end < start || !on_boundary(start) || !on_boundary(end) {
+            bail!("Edit doesn't fit the text at {}..{}", start, end);
+        }
+        pos = end;
+    }
+    Ok(())
+}
+
+/// One peer's side of a shared document
+#[derive(Debug, Clone)]
+pub struct Session {
+    id: PeerId,
+    /// Last revision received from the relay
+    revision: u64,
+    /// Last revision the relay knows this peer has seen
+    acked: u64,
+    /// The document with this peer's own edits applied
+    text: Rope,
+    /// Edit sent and not yet sequenced, against `revision`
+    inflight: Option<ChangeSet>,
+    /// Edits made since, to send once `inflight` comes back
+    pending: Option<ChangeSet>,
+    /// Inverses of this peer's edits; the last applies to the current text
+    undo: Vec<ChangeSet>,
+    redo: Vec<ChangeSet>,
+    last_edit: Option<Instant>,
+    coalesce_timeout: Duration,
+    /// Local selection, and whether the relay has it yet
+    selection: Option<Selection>,
+    selection_sent: bool,
+    peers: Vec<PeerInfo>,
+    following: Option<PeerId>,
+    outgoing: Vec<ClientMessage>,
+}
+
+impl Session {
+    /// Start a session from the relay's welcome
+    pub fn new(id: PeerId, revision: u64, text: &str, peers: Vec<PeerInfo>) -> Self {
+        Self {
+            id,
+            revision,
+            acked: revision,
+            text: Rope::from_str(text),
+            inflight: None,
+            pending: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_edit: None,
+            coalesce_timeout: DEFAULT_COALESCE_TIMEOUT,
+            selection: None,
+            selection_sent: false,
+            peers,
+            following: None,
+            outgoing: Vec::new(),
+        }
+    }
+
+    pub fn id(&self) -> PeerId {
+        self.id
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// The other peers in the room
+    pub fn peers(&self) -> &[PeerInfo] {
+        &self.peers
+    }
+
+    pub fn peer(&self, id: PeerId) -> Option<&PeerInfo> {
+        self.peers.iter().find(|p| p.id == id)
+    }
+
+    /// Follow a peer's cursor, or stop following with `None`
+    pub fn follow(&mut self, peer: Option<PeerId>) {
+        self.following = peer.filter(|&id| self.peer(id).is_some());
+    }
+
+    pub fn following(&self) -> Option<&PeerInfo> {
+        self.peer(self.following?)
+    }
+
+    /// Whether every local edit has been sequenced by the relay
+    pub fn is_synced(&self) -> bool {
+        self.inflight.is_none() && self.pending.is_none()
+    }
+
+    /// Set how long a pause in typing must be to start a new undo step
+    pub fn set_undo_coalesce_timeout(&mut self, timeout: Duration) {
+        self.coalesce_timeout = timeout;
+    }
+
+    /// Share `buffer` in this session. Where its text differs from the
+    /// session's, the difference is sent as an edit.
+    pub fn attach(&mut self, buffer: &mut Buffer) {
+        buffer.share();
+        let text = buffer.text();
+        let current = self.text.to_string();
+        if text != current {
+            self.absorb(&merge::diff_changes(&current, &text));
+        }
+        self.selection_sent = false;
+        self.sync(buffer);
+    }
+
+    /// Pick up the edits and selection changes made in `buffer` since the
+    /// last call, and queue what the relay should hear about
+    pub fn sync(&mut self, buffer: &mut Buffer) {
+        for changes in buffer.take_shared_changes() {
+            let inverse = self.absorb(&changes);
+            let coalesce = self
+                .last_edit
+                .is_some_and(|at| at.elapsed() <= self.coalesce_timeout);
+            match self.undo.last_mut() {
+                // The new inverse applies first, then the older one
+                Some(step) if coalesce => *step = inverse.compose(step),
+                _ => self.undo.push(inverse),
+            }
+            self.redo.clear();
+            self.last_edit = Some(Instant::now());
+        }
+        if self.selection.as_ref() != Some(buffer.selection()) {
+            self.selection = Some(buffer.selection().clone());
+            self.selection_sent = false;
+        }
+        self.flush();
+    }
+
+    /// Undo this peer's last edit, leaving others' edits alone
+    pub fn undo(&mut self, buffer: &mut Buffer) -> bool {
+        self.sync(buffer);
+        let Some(step) = self.undo.pop() else {
+            return false;
+        };
+        let inverse = self.apply_step(step, buffer);
+        self.redo.push(inverse);
+        true
+    }
+
+    pub fn redo(&mut self, buffer: &mut Buffer) -> bool {
+        self.sync(buffer);
+        let Some(step) = self.redo.pop() else {
+            return false;
+        };
+        let inverse = self.apply_step(step, buffer);
+        self.undo.push(inverse);
+        true
+    }
+
+    fn apply_step(&mut self, step: ChangeSet, buffer: &mut Buffer) -> ChangeSet {
+        let inverse = self.absorb(&step);
+        buffer.apply_shared(step);
+        self.last_edit = None;
+        self.flush();
+        inverse
+    }
+
+    /// Take in a message from the relay, applying others' edits to `buffer`
+    pub fn receive(&mut self, message: ServerMessage, buffer: &mut Buffer) {
+        // Edits made since the last sync must be rebased too
+        self.sync(buffer);
+        match message {
+            ServerMessage::Edit { peer, .. } if peer == self.id => {
+                self.inflight = None;
+                self.revision += 1;
+                // Sequencing the edit told the relay how far this peer got
+                self.acked = self.revision;
+            }
+            ServerMessage::Edit { changes, .. } => {
+                self.revision += 1;
+                let changes = self.rebase_remote(changes);
+                changes.apply(&mut self.text);
+                self.map_peers(&changes);
+                buffer.apply_shared(changes);
+                // Keep the already shared selection from counting as changed
+                if self.selection_sent {
+                    self.selection = Some(buffer.selection().clone());
+                }
+            }
+            ServerMessage::Select { peer, selection } => {
+                let selection = [&self.inflight, &self.pending]
+                    .into_iter()
+                    .flatten()
+                    .fold(selection, |s, local| s.map_through(local));
+                if let Some(info) = self.peers.iter_mut().find(|p| p.id == peer) {
+                    info.selection = Some(selection);
+                }
+            }
+            ServerMessage::Joined { peer } => {
+                self.peers.retain(|p| p.id != peer.id);
+                self.peers.push(peer);
+                // Let the newcomer see where we are
+                self.selection_sent = false;
+            }
+            ServerMessage::Left { peer } => {
+                self.peers.retain(|p| p.id != peer);
+                if self.following == Some(peer) {
+                    self.following = None;
+                }
+            }
+            ServerMessage::Rooms { .. }
+            | ServerMessage::Welcome { .. }
+            | ServerMessage::Error { .. } => {}
+        }
+        self.flush();
+    }
+
+    /// Messages for the relay queued since the last call
+    pub fn take_outgoing(&mut self) -> Vec<ClientMessage> {
+        std::mem::take(&mut self.outgoing)
+    }
+
+    /// Rebase an edit from the relay over this peer's unsequenced edits,
+    /// and those and the undo steps over it
+    fn rebase_remote(&mut self, mut changes: ChangeSet) -> ChangeSet {
+        for local in [&mut self.inflight, &mut self.pending]
+            .into_iter()
+            .flatten()
+        {
+            // The relay puts the remote edit first, so its text goes first
+            let rebased = changes.transform(local, Assoc::Before);
+            *local = local.transform(&changes, Assoc::After);
+            changes = rebased;
+        }
+        for stack in [&mut self.undo, &mut self.redo] {
+            // Each step applies to the text after undoing the ones above it
+            let mut remote = changes.clone();
+            for step in stack.iter_mut().rev() {
+                let rebased = step.transform(&remote, Assoc::Before);
+                remote = remote.transform(step, Assoc::After);
+                *step = rebased;
+            }
+        }
+        changes
+    }
+
+    /// Apply a local edit to the session's text and queue it, returning its
+    /// inverse
+    fn absorb(&mut self, changes: &ChangeSet) -> ChangeSet {
+        let inverse = Transaction::new(changes.clone(), None)
+            .invert(&self.text)
+            .changes;
+        changes.apply(&mut self.text);
+        self.pending = Some(match self.pending.take() {
+            Some(pending) => pending.compose(changes),
+            None => changes.clone(),
+        });
+        self.map_peers(changes);
+        inverse
+    }
+
+    fn map_peers(&mut self, changes: &ChangeSet) {
+        for peer in &mut self.peers {
+            peer.selection = peer.selection.as_ref().map(|s| s.map_through(changes));
+        }
+    }
+
+    /// Send the pending edit if nothing is in flight. The selection is only
+    /// sent once every edit is sequenced, so its offsets mean the same to
+    /// the relay as here.
+    fn flush(&mut self) {
+        if self.inflight.is_none() {
+            if let Some(changes) = self.pending.take().filter(|c| !c.is_empty()) {
+                self.outgoing.push(ClientMessage::Edit {
+                    revision: self.revision,
+                    changes: changes.clone(),
+                });
+                self.inflight = Some(changes);
+            }
+        }
+        // An edit in flight acknowledges the revisions before it
+        if self.inflight.is_none() && self.revision >= self.acked + ACK_INTERVAL {
+            self.outgoing.push(ClientMessage::Ack {
+                revision: self.revision,
+            });
+            self.acked = self.revision;
+        }
+        if self.is_synced() && !self.selection_sent {
+            if let Some(selection) = &self.selection {
+                self.outgoing.push(ClientMessage::Select {
+                    selection: selection.clone(),
+                });
+                self.selection_sent = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Change;
+
+    /// A relay and its peers exchanging messages in memory
+    struct Relay {
+        room: Room,
+        peers: Vec<(Session, Buffer)>,
+    }
+
+    impl Relay {
+        fn new(text: &str, peers: usize) -> Self {
+            let mut room = Room::new(text);
+            let peers = (0..peers as PeerId)
+                .map(|id| {
+                    let ServerMessage::Welcome {
+                        revision,
+                        text,
+                        peers,
+                        ..
+                    } = room.join(id, &format!("peer {}", id))
+                    else {
+                        unreachable!()
+                    };
+                    let mut session = Session::new(id, revision, &text, peers);
+                    session.set_undo_coalesce_timeout(Duration::ZERO);
+                    let mut buffer = Buffer::from_str(&text);
+                    session.attach(&mut buffer);
+                    (session, buffer)
+                })
+                .collect();
+            let mut relay = Self { room, peers };
+            for id in 0..relay.peers.len() {
+                for other in 0..id {
+                    let peer = relay.room.peers()[id].clone();
+                    let (session, buffer) = &mut relay.peers[other];
+                    session.receive(ServerMessage::Joined { peer }, buffer);
+                }
+            }
+            relay
+        }
+
+        fn edit(&mut self, peer: usize, at: usize, end: usize, text: &str) {
+            let (session, buffer) = &mut self.peers[peer];
+            let change = Change::replace(Position::new(at), Position::new(end), text.into());
+            buffer.apply(Transaction::from_change(change));
+            session.sync(buffer);
+        }
+
+        /// Deliver the messages `peer` has queued
+        fn deliver(&mut self, peer: usize) {
+            let id = self.peers[peer].0.id();
+            for message in self.peers[peer].0.take_outgoing() {
+                let broadcast = match message {
+                    ClientMessage::Edit { revision, changes } => ServerMessage::Edit {
+                        peer: id,
+                        changes: self.room.edit(id, revision, changes).unwrap(),
+                    },
+                    ClientMessage::Select { selection } => ServerMessage::Select {
+                        peer: id,
+                        selection: self.room.select(id, selection),
+                    },
+                    ClientMessage::Ack { revision } => {
+                        self.room.ack(id, revision);
+                        continue;
+                    }
+                    _ => continue,
+                };
+                for (session, buffer) in &mut self.peers {
+                    if !matches!(broadcast, ServerMessage::Select { .. }) || session.id() != id {
+                        session.receive(broadcast.clone(), buffer);
+                    }
+                }
+            }
+        }
+
+        fn settle(&mut self) {
+            while self.peers.iter().any(|(s, _)| !s.outgoing.is_empty()) {
+                for peer in 0..self.peers.len() {
+                    self.deliver(peer);
+                }
+            }
+        }
+
+        fn texts(&self) -> Vec<String> {
+            self.peers.iter().map(|(_, b)| b.text()).collect()
+        }
+    }
+
+    #[test]
+    fn concurrent_edits_converge() {
+        let mut relay = Relay::new("hello world", 3);
+        relay.edit(0, 0, 5, "howdy");
+        relay.edit(1, 11, 11, "!");
+        relay.deliver(1);
+        // Peer 0's edit is still in flight; peer 2 has seen only peer 1's
+        relay.edit(1, 6, 11, "there");
+        relay.edit(2, 5, 5, ",");
+        relay.settle();
+
+        let expected = relay.room.text().to_string();
+        assert_eq!(expected, "howdy, there!");
+        assert_eq!(relay.texts(), vec![expected; 3]);
+        assert!(relay.peers.iter().all(|(s, _)| s.is_synced()));
+    }
+
+    #[test]
+    fn undo_only_reverts_own_edits() {
+        let mut relay = Relay::new("one two", 2);
+        relay.edit(0, 3, 3, " and a half");
+        relay.settle();
+        // Typed concurrently with the undo below
+        relay.edit(1, 0, 0, "> ");
+        relay.edit(1, 9, 9, "-and-");
+
+        let (session, buffer) = &mut relay.peers[0];
+        assert!(session.undo(buffer));
+        assert_eq!(buffer.text(), "one two");
+        relay.settle();
+        assert_eq!(relay.texts(), vec!["> one-and- two"; 2]);
+
+        // Peer 1's edits aren't peer 0's to undo
+        let (session, buffer) = &mut relay.peers[0];
+        assert!(!session.undo(buffer));
+        assert!(session.redo(buffer));
+        relay.settle();
+        assert_eq!(relay.texts(), vec!["> one and a half-and- two"; 2]);
+    }
+
+    #[test]
+    fn room_forgets_edits_every_peer_has_seen() {
+        let mut relay = Relay::new("", 2);
+        for i in 0..ACK_INTERVAL as usize + 10 {
+            relay.edit(0, i, i, "x");
+            relay.settle();
+        }
+        // Peer 1 only reads, so it has acknowledged once so far
+        assert_eq!(relay.room.kept_edits(), 10);
+
+        // Its edit catches it up; only the edit peer 0 hasn't acknowledged
+        // yet is kept
+        relay.edit(1, 0, 0, "y");
+        relay.settle();
+        assert_eq!(relay.room.kept_edits(), 1);
+        relay.edit(0, 0, 0, "z");
+        relay.settle();
+        let expected = relay.room.text().to_string();
+        assert_eq!(relay.texts(), vec![expected; 2]);
+    }
+
+    #[test]
+    fn selections_follow_edits() {
+        let mut relay = Relay::new("abc def", 2);
+        let (_, buffer) = &mut relay.peers[1];
+        buffer.set_selection(Selection::single(Range::new(
+            Position::new(4),
+            Position::new(7),
+        )));
+        let (session, buffer) = &mut relay.peers[1];
+        session.sync(buffer);
+        relay.settle();
+        relay.edit(0, 0, 0, "xyz ");
+        relay.settle();
+
+        let (session, _) = &relay.peers[0];
+        let seen = session.peer(1).and_then(|p| p.selection.clone()).unwrap();
+        assert_eq!(
+            seen.primary(),
+            &Range::new(Position::new(8), Position::new(11))
+        );
+        assert_eq!(relay.peers[1].1.selection(), &seen);
+    }
+}
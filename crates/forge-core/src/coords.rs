@@ -0,0 +1,267 @@
+//! Line/column coordinates in the units different consumers count in.
+//!
+//! Buffer positions are byte offsets. The cursor moves and is drawn by
+//! grapheme cluster, LSP servers count UTF-16 code units unless another
+//! encoding was negotiated, and other tools count chars. Converting through
+//! here keeps those from being mixed up on lines with non-ASCII text.
+
+use ropey::Rope;
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
+
+/// What a column is counted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum ColumnUnit {
+    /// UTF-8 bytes, like [`Position`](crate::Position)
+    #[default]
+    Byte,
+    /// Unicode scalar values, i.e. UTF-32 code units
+    Char,
+    /// UTF-16 code units, the LSP default
+    Utf16,
+    /// User-perceived characters: an emoji sequence or a letter with
+    /// combining marks is a single column
+    Grapheme,
+}
+
+/// A zero-based line and column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl LineCol {
+    pub fn new(line: usize, col: usize) -> Self {
+        Self { line, col }
+    }
+}
+
+/// Lines `start..old_end` of a text replaced by `start..new_end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineEdit {
+    pub start: usize,
+    pub old_end: usize,
+    pub new_end: usize,
+}
+
+impl LineEdit {
+    /// One edit covering this one and `next`, which was made after it
+    pub fn then(self, next: LineEdit) -> LineEdit {
+        // The end of both, in the lines between the two edits
+        let end = self.new_end.max(next.old_end);
+        LineEdit {
+            start: self.start.min(next.start),
+            old_end: self.old_end + (end - self.new_end),
+            new_end: end - next.old_end + next.new_end,
+        }
+    }
+}
+
+/// Line and column of byte `offset`, with the column counted in `unit`.
+///
+/// Offsets inside a char are rounded down to its start; inside a grapheme
+/// cluster they count as past it.
+pub fn to_line_col(rope: &Rope, offset: usize, unit: ColumnUnit) -> LineCol {
+    let offset = rope.char_to_byte(rope.byte_to_char(offset.min(rope.len_bytes())));
+    let line = rope.byte_to_line(offset);
+    let start = rope.line_to_byte(line);
+    let before = rope.byte_slice(start..offset);
+    let col = match unit {
+        ColumnUnit::Byte => offset - start,
+        ColumnUnit::Char => before.len_chars(),
+        ColumnUnit::Utf16 => before.len_utf16_cu(),
+        ColumnUnit::Grapheme => {
+            let mut count = 0;
+            let mut pos = start;
+            while pos < offset {
+                pos = next_grapheme_boundary(rope, pos);
+                count += 1;
+            }
+            count
+        }
+    };
+    LineCol::new(line, col)
+}
+
+/// Byte offset of `at`, with the column counted in `unit`.
+///
+/// Lines past the end clamp to the last line and columns past the end of a
+/// line to before its line break; a column inside a char (half of a UTF-16
+/// surrogate pair, or a byte in the middle of a char) rounds down.
+pub fn to_offset(rope: &Rope, at: LineCol, unit: ColumnUnit) -> usize {
+    let line = at.line.min(rope.len_lines() - 1);
+    let start = rope.line_to_byte(line);
+    let end = line_content_end(rope, line);
+    let content = rope.byte_slice(start..end);
+    match unit {
+        ColumnUnit::Byte => {
+            let offset = (start + at.col).min(end);
+            rope.char_to_byte(rope.byte_to_char(offset))
+        }
+        ColumnUnit::Char => start + content.char_to_byte(at.col.min(content.len_chars())),
+        ColumnUnit::Utf16 => {
+            let char_idx = content.utf16_cu_to_char(at.col.min(content.len_utf16_cu()));
+            start + content.char_to_byte(char_idx)
+        }
+        ColumnUnit::Grapheme => {
+            let mut pos = start;
+            for _ in 0..at.col {
+                if pos >= end {
+                    break;
+                }
+                pos = next_grapheme_boundary(rope, pos);
+            }
+            pos.min(end)
+        }
+    }
+}
+
+/// Byte offset of the end of a line's text, before its line break
+pub(crate) fn line_content_end(rope: &Rope, line: usize) -> usize {
+    let slice = rope.line(line);
+    let trailing = slice
+        .chars_at(slice.len_chars())
+        .reversed()
+        .take_while(|&c| c == '\n' || c == '\r')
+        .count();
+    rope.line_to_byte(line) + slice.len_bytes() - trailing
+}
+
+/// The first grapheme cluster boundary after byte `offset`
+pub fn next_grapheme_boundary(rope: &Rope, offset: usize) -> usize {
+    let len = rope.len_bytes();
+    if offset >= len {
+        return len;
+    }
+    let offset = rope.char_to_byte(rope.byte_to_char(offset));
+    let (mut chunk, mut chunk_start, _, _) = rope.chunk_at_byte(offset);
+    let mut cursor = GraphemeCursor::new(offset, len, true);
+    loop {
+        match cursor.next_boundary(chunk, chunk_start) {
+            Ok(next) => return next.unwrap_or(len),
+            Err(GraphemeIncomplete::NextChunk) => {
+                chunk_start += chunk.len();
+                chunk = rope.chunk_at_byte(chunk_start).0;
+            }
+            Err(GraphemeIncomplete::PreContext(at)) => {
+                let (context, context_start, _, _) = rope.chunk_at_byte(at - 1);
+                cursor.provide_context(context, context_start);
+            }
+            // Not produced when moving forward; step one char to be safe
+            Err(_) => return rope.char_to_byte(rope.byte_to_char(offset) + 1),
+        }
+    }
+}
+
+/// The last grapheme cluster boundary before byte `offset`
+pub fn prev_grapheme_boundary(rope: &Rope, offset: usize) -> usize {
+    if offset == 0 {
+        return 0;
+    }
+    let offset = rope.char_to_byte(rope.byte_to_char(offset.min(rope.len_bytes())));
+    let (mut chunk, mut chunk_start, _, _) = rope.chunk_at_byte(offset);
+    let mut cursor = GraphemeCursor::new(offset, rope.len_bytes(), true);
+    loop {
+        match cursor.prev_boundary(chunk, chunk_start) {
+            Ok(prev) => return prev.unwrap_or(0),
+            Err(GraphemeIncomplete::PrevChunk) => {
+                let (prev, prev_start, _, _) = rope.chunk_at_byte(chunk_start - 1);
+                chunk = prev;
+                chunk_start = prev_start;
+            }
+            Err(GraphemeIncomplete::PreContext(at)) => {
+                let (context, context_start, _, _) = rope.chunk_at_byte(at - 1);
+                cursor.provide_context(context, context_start);
+            }
+            Err(_) => return rope.char_to_byte(rope.byte_to_char(offset) - 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // "e" + combining acute, a family emoji joined with ZWJs, then a CJK char
+    const LINE: &str =
+        "ab e\u{301} \u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467} \u{4E2D}x\r\nnext\n";
+
+    #[test]
+    fn columns_agree_across_units() {
+        let rope = Rope::from_str(LINE);
+        let x = LINE.find('x').unwrap();
+        let cols = |unit| to_line_col(&rope, x, unit).col;
+        assert_eq!(cols(ColumnUnit::Byte), x);
+        assert_eq!(cols(ColumnUnit::Char), 13);
+        assert_eq!(cols(ColumnUnit::Utf16), 16);
+        assert_eq!(cols(ColumnUnit::Grapheme), 8);
+        for unit in [
+            ColumnUnit::Byte,
+            ColumnUnit::Char,
+            ColumnUnit::Utf16,
+            ColumnUnit::Grapheme,
+        ] {
+            assert_eq!(to_offset(&rope, LineCol::new(0, cols(unit)), unit), x);
+        }
+
+        // Past the end of a line stops before its CRLF; past the last line
+        // clamps to it
+        let line_end = LINE.find('\r').unwrap();
+        assert_eq!(
+            to_offset(&rope, LineCol::new(0, 99), ColumnUnit::Grapheme),
+            line_end
+        );
+        assert_eq!(
+            to_offset(&rope, LineCol::new(0, 999), ColumnUnit::Byte),
+            line_end
+        );
+        assert_eq!(
+            to_offset(&rope, LineCol::new(9, 2), ColumnUnit::Char),
+            LINE.len()
+        );
+        // Half a surrogate pair rounds down to the emoji's start
+        let emoji = LINE.find('\u{1F468}').unwrap();
+        assert_eq!(
+            to_offset(&rope, LineCol::new(0, 7), ColumnUnit::Utf16),
+            emoji
+        );
+    }
+
+    #[test]
+    fn steps_over_whole_grapheme_clusters() {
+        let rope = Rope::from_str(LINE);
+        let mut stops = vec![0];
+        while *stops.last().unwrap() < LINE.len() {
+            stops.push(next_grapheme_boundary(&rope, *stops.last().unwrap()));
+        }
+        let clusters: Vec<&str> = stops.windows(2).map(|w| &LINE[w[0]..w[1]]).collect();
+        assert_eq!(
+            clusters,
+            vec![
+                "a",
+                "b",
+                " ",
+                "e\u{301}",
+                " ",
+                "\u{1F468}\u{200D}\u{1F469}\u{200D}\u{1F467}",
+                " ",
+                "\u{4E2D}",
+                "x",
+                "\r\n",
+                "n",
+                "e",
+                "x",
+                "t",
+                "\n"
+            ]
+        );
+
+        let mut back = vec![LINE.len()];
+        while *back.last().unwrap() > 0 {
+            back.push(prev_grapheme_boundary(&rope, *back.last().unwrap()));
+        }
+        back.reverse();
+        assert_eq!(back, stops);
+    }
+}
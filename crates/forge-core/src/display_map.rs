@@ -0,0 +1,862 @@
+//! Where buffer text shows up on screen.
+//!
+//! Folds hide ranges of text behind a placeholder, inlays (inlay hints,
+//! ghost completions) show text that isn't in the buffer, tabs expand to the
+//! next tab stop and long lines soft wrap. A [`DisplayMap`] composes all four
+//! into one grid of display rows and columns, so rendering, hit-testing and
+//! cursor movement agree on where everything is.
+//!
+//! Columns count grapheme clusters, like [`ColumnUnit::Grapheme`](crate::ColumnUnit),
+//! with a tab as wide as the distance to the next tab stop.
+
+use std::ops::Range;
+
+use ropey::Rope;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::coords::{line_content_end, next_grapheme_boundary};
+use crate::{Assoc, ChangeSet, Position};
+
+/// Shown in place of folded text
+pub const FOLD_PLACEHOLDER: &str = "⋯";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// Columns from one tab stop to the next
+    pub tab_size: usize,
+    /// Wrap lines wider than this many columns; `None` to never wrap
+    pub wrap_width: Option<usize>,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            tab_size: 4,
+            wrap_width: None,
+        }
+    }
+}
+
+/// A zero-based row and column on screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct DisplayPoint {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl DisplayPoint {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum InlayKind {
+    /// Type and parameter hints from a language server
+    Hint,
+    /// A completion offered ahead of the cursor
+    Ghost,
+}
+
+/// Text shown at a buffer offset without being part of the buffer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Inlay {
+    pub offset: usize,
+    pub text: String,
+    pub kind: InlayKind,
+}
+
+/// Where the text of a [`DisplayChunk`] comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChunkSource {
+    /// This range of the buffer, as it is
+    Text(Range<usize>),
+    /// The tab at this offset, expanded to spaces
+    Tab(usize),
+    /// The inlay at this index of [`DisplayMap::inlays`]
+    Inlay(usize),
+    /// The placeholder of the fold at this index of [`DisplayMap::folds`]
+    Fold(usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayChunk {
+    /// Byte range in [`DisplayRow::text`]
+    pub range: Range<usize>,
+    pub source: ChunkSource,
+}
+
+/// The text of one row on screen, and where each part of it comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayRow {
+    pub text: String,
+    pub chunks: Vec<DisplayChunk>,
+    /// Columns the row takes up
+    pub width: usize,
+    /// The buffer line the row starts in
+    pub buffer_line: usize,
+    /// The row continues a line wrapped on the row above
+    pub is_wrap: bool,
+}
+
+impl DisplayRow {
+    fn new(buffer_line: usize, is_wrap: bool) -> Self {
+        Self {
+            text: String::new(),
+            chunks: Vec::new(),
+            width: 0,
+            buffer_line,
+            is_wrap,
+        }
+    }
+
+    /// Buffer line `line` as it is, for a buffer without a layout
+    pub(crate) fn plain(rope: &Rope, line: usize) -> Self {
+        let start = rope.line_to_byte(line);
+        let end = line_content_end(rope, line);
+        let mut row = Self::new(line, false);
+        if start < end {
+            let text = rope.byte_slice(start..end).to_string();
+            row.push(
+                &text,
+                ChunkSource::Text(start..end),
+                text.graphemes(true).count(),
+            );
+        }
+        row
+    }
+
+    /// Append `text`, extending the last chunk when `source` continues it
+    fn push(&mut self, text: &str, source: ChunkSource, width: usize) {
+        self.width += width;
+        let start = self.text.len();
+        self.text.push_str(text);
+        let end = self.text.len();
+        if let Some(last) = self.chunks.last_mut() {
+            let merged = match (&mut last.source, &source) {
+                (ChunkSource::Text(prev), ChunkSource::Text(next)) if prev.end == next.start => {
+                    prev.end = next.end;
+                    true
+                }
+                (ChunkSource::Inlay(prev), ChunkSource::Inlay(next)) => prev == next,
+                _ => false,
+            };
+            if merged {
+                last.range.end = end;
+                return;
+            }
+        }
+        self.chunks.push(DisplayChunk {
+            range: start..end,
+            source,
+        });
+    }
+}
+
+/// What fills one or more columns of a display line
+#[derive(Debug, Clone, Copy)]
+enum CellKind {
+    /// A grapheme cluster of the buffer
+    Text(usize, usize),
+    Tab(usize),
+    /// A grapheme cluster of an inlay's text
+    Inlay(usize, usize, usize),
+    Fold(usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Cell {
+    kind: CellKind,
+    width: usize,
+    /// A line may wrap after it
+    space: bool,
+}
+
+/// One buffer line, or several joined by folds
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct LineLayout {
+    /// Offset of its first byte
+    start: usize,
+    /// Column each row after the first starts at
+    wraps: Vec<usize>,
+}
+
+/// Maps buffer offsets to display rows and columns and back, kept up to
+/// date as the buffer is edited.
+///
+/// The layout of each line is cached as where it starts and where it wraps;
+/// an edit, fold or inlay only lays out again the lines it touched.
+#[derive(Debug, Clone)]
+pub struct DisplayMap {
+    options: DisplayOptions,
+    /// Hidden ranges, sorted and not overlapping
+    folds: Vec<Range<usize>>,
+    /// Sorted by offset
+    inlays: Vec<Inlay>,
+    lines: Vec<LineLayout>,
+    /// First row of each line, then the number of rows
+    first_rows: Vec<usize>,
+}
+
+impl DisplayMap {
+    pub fn new(rope: &Rope, options: DisplayOptions) -> Self {
+        let mut map = Self {
+            options,
+            folds: Vec::new(),
+            inlays: Vec::new(),
+            lines: Vec::new(),
+            first_rows: Vec::new(),
+        };
+        map.reset(rope);
+        map
+    }
+
+    pub fn options(&self) -> DisplayOptions {
+        self.options
+    }
+
+    /// Change the tab size or wrap width, laying out every line again
+    pub fn set_options(&mut self, rope: &Rope, options: DisplayOptions) {
+        if options != self.options {
+            self.options = options;
+            self.lines = self.layout(rope, 0, usize::MAX).0;
+            self.index();
+        }
+    }
+
+    /// Start over on a rope that replaced the old one wholesale, dropping
+    /// folds and inlays
+    pub fn reset(&mut self, rope: &Rope) {
+        self.folds.clear();
+        self.inlays.clear();
+        self.lines = self.layout(rope, 0, usize::MAX).0;
+        self.index();
+    }
+
+    pub fn row_count(&self) -> usize {
+        self.first_rows.last().copied().unwrap_or(1)
+    }
+
+    pub fn folds(&self) -> &[Range<usize>] {
+        &self.folds
+    }
+
+    pub fn inlays(&self) -> &[Inlay] {
+        &self.inlays
+    }
+
+    /// Hide `range` behind [`FOLD_PLACEHOLDER`]. Folds it overlaps are
+    /// merged into it.
+    pub fn fold(&mut self, rope: &Rope, range: Range<usize>) {
+        let len = rope.len_bytes();
+        let mut range = range.start.min(len)..range.end.min(len);
+        if range.is_empty() {
+            return;
+        }
+        let first = self.folds.partition_point(|f| f.end <= range.start);
+        let last = self.folds.partition_point(|f| f.start < range.end);
+        for fold in &self.folds[first..last] {
+            range = range.start.min(fold.start)..range.end.max(fold.end);
+        }
+        self.folds.splice(first..last, [range.clone()]);
+        let window = self.line_at(range.start)..self.line_at(range.end) + 1;
+        self.relayout(rope, vec![window], |offset, _| offset);
+    }
+
+    /// Fold lines `start_line + 1 ..= end_line` into the end of `start_line`
+    pub fn fold_lines(&mut self, rope: &Rope, start_line: usize, end_line: usize) {
+        if start_line < end_line && end_line < rope.len_lines() {
+            let range = line_content_end(rope, start_line)..line_content_end(rope, end_line);
+            self.fold(rope, range);
+        }
+    }
+
+    /// Show again the folds that touch `range`
+    pub fn unfold(&mut self, rope: &Rope, range: Range<usize>) {
+        let mut windows = Vec::new();
+        let lines = &self.lines;
+        self.folds.retain(|fold| {
+            let touches = fold.start <= range.end && range.start <= fold.end;
+            if touches {
+                let line = lines.partition_point(|l| l.start <= fold.start) - 1;
+                windows.push(line..line + 1);
+            }
+            !touches
+        });
+        if !windows.is_empty() {
+            self.relayout(rope, windows, |offset, _| offset);
+        }
+    }
+
+    pub fn unfold_all(&mut self, rope: &Rope) {
+        self.unfold(rope, 0..rope.len_bytes());
+    }
+
+    /// Replace the inlays of `kind` with `inlays`, given as offsets and text.
+    /// Line breaks in the text are shown as spaces.
+    pub fn set_inlays(
+        &mut self,
+        rope: &Rope,
+        kind: InlayKind,
+        inlays: impl IntoIterator<Item = (usize, String)>,
+    ) {
+        let mut touched = Vec::new();
+        self.inlays.retain(|inlay| {
+            let keep = inlay.kind != kind;
+            if !keep {
+                touched.push(inlay.offset);
+            }
+            keep
+        });
+        for (offset, text) in inlays {
+            let offset = rope.char_to_byte(rope.byte_to_char(offset.min(rope.len_bytes())));
+            touched.push(offset);
+            self.inlays.push(Inlay {
+                offset,
+                text: text.replace(['\r', '\n'], " "),
+                kind,
+            });
+        }
+        self.inlays.sort_by_key(|inlay| inlay.offset);
+
+        let mut windows: Vec<Range<usize>> = touched
+            .into_iter()
+            .map(|offset| {
+                let line = self.line_at(offset);
+                line..line + 1
+            })
+            .collect();
+        windows.sort_unstable_by_key(|w| w.start);
+        windows.dedup();
+        if !windows.is_empty() {
+            self.relayout(rope, windows, |offset, _| offset);
+        }
+    }
+
+    /// Bring the layout up to date with `changes`, which `rope` already has
+    /// applied. Folds and inlays move with the text around them; a fold
+    /// whose text was deleted is gone.
+    pub fn update(&mut self, rope: &Rope, changes: &ChangeSet) {
+        if changes.is_empty() {
+            return;
+        }
+        let map = |offset, assoc| changes.map_position(Position::new(offset), assoc).offset;
+
+        let mut edits: Vec<(usize, usize)> = changes
+            .changes
+            .iter()
+            .map(|c| (c.start.offset, c.end.offset))
+            .collect();
+        edits.sort_unstable();
+        let mut windows: Vec<Range<usize>> = Vec::new();
+        for (start, end) in edits {
+            let window = self.line_at(start)..self.line_at(end) + 1;
+            match windows.last_mut() {
+                Some(last) if window.start < last.end => last.end = last.end.max(window.end),
+                _ => windows.push(window),
+            }
+        }
+
+        // Text typed at either edge of a fold stays visible
+        self.folds = self
+            .folds
+            .iter()
+            .map(|fold| map(fold.start, Assoc::After)..map(fold.end, Assoc::Before))
+            .filter(|fold| fold.start < fold.end)
+            .collect();
+        // A completion stays ahead of what's typed at it; a hint stays put
+        for inlay in &mut self.inlays {
+            let assoc = match inlay.kind {
+                InlayKind::Hint => Assoc::Before,
+                InlayKind::Ghost => Assoc::After,
+            };
+            inlay.offset = map(inlay.offset, assoc);
+        }
+        self.inlays.sort_by_key(|inlay| inlay.offset);
+
+        self.relayout(rope, windows, map);
+    }
+
+    /// Where `offset` is on screen. An offset that has inlays goes before
+    /// them; one inside a fold goes on its placeholder.
+    pub fn to_display(&self, rope: &Rope, offset: usize) -> DisplayPoint {
+        let offset = offset.min(rope.len_bytes());
+        let line = self.line_at(offset);
+        let mut col = 0;
+        for cell in self.cells(rope, self.lines[line].start).0 {
+            let after = match cell.kind {
+                CellKind::Text(start, _) | CellKind::Tab(start) => start >= offset,
+                CellKind::Inlay(index, ..) => self.inlays[index].offset >= offset,
+                CellKind::Fold(index) => self.folds[index].end > offset,
+            };
+            if after {
+                break;
+            }
+            col += cell.width;
+        }
+
+        let wraps = &self.lines[line].wraps;
+        let row = wraps.partition_point(|&w| w <= col);
+        let row_start = row.checked_sub(1).map_or(0, |r| wraps[r]);
+        DisplayPoint::new(self.first_rows[line] + row, col - row_start)
+    }
+
+    /// The buffer offset shown at `point`: the start of what is drawn in
+    /// that column, or the end of the row when it is past it. Inlays and
+    /// fold placeholders give the offset they sit at.
+    pub fn to_buffer(&self, rope: &Rope, point: DisplayPoint) -> usize {
+        let row = point.row.min(self.row_count() - 1);
+        let line = self.first_rows.partition_point(|&r| r <= row) - 1;
+        let layout = &self.lines[line];
+        let index = row - self.first_rows[line];
+        let row_start = index.checked_sub(1).map_or(0, |r| layout.wraps[r]);
+        let mut target = row_start.saturating_add(point.col);
+        // Past the end of a wrapped row is its last column, not the next row
+        if let Some(&next_row) = layout.wraps.get(index) {
+            target = target.min(next_row - 1);
+        }
+
+        let (cells, _) = self.cells(rope, layout.start);
+        let mut col = 0;
+        for cell in &cells {
+            if col + cell.width > target {
+                return match cell.kind {
+                    CellKind::Text(start, _) | CellKind::Tab(start) => start,
+                    CellKind::Inlay(index, ..) => self.inlays[index].offset,
+                    CellKind::Fold(index) => self.folds[index].start,
+                };
+            }
+            col += cell.width;
+        }
+        match cells.last().map(|cell| cell.kind) {
+            Some(CellKind::Text(_, end)) => end,
+            Some(CellKind::Tab(start)) => start + 1,
+            Some(CellKind::Inlay(index, ..)) => self.inlays[index].offset,
+            Some(CellKind::Fold(index)) => self.folds[index].end,
+            None => layout.start,
+        }
+    }
+
+    /// The text of `rows`, as drawn
+    pub fn rows(&self, rope: &Rope, rows: Range<usize>) -> Vec<DisplayRow> {
+        let end = rows.end.min(self.row_count());
+        let mut out = Vec::new();
+        if rows.start >= end {
+            return out;
+        }
+        let mut line = self.first_rows.partition_point(|&r| r <= rows.start) - 1;
+        while line < self.lines.len() && self.first_rows[line] < end {
+            let layout = &self.lines[line];
+            let mut laid = vec![DisplayRow::new(rope.byte_to_line(layout.start), false)];
+            let mut col = 0;
+            for cell in self.cells(rope, layout.start).0 {
+                let offset = match cell.kind {
+                    CellKind::Text(start, _) | CellKind::Tab(start) => start,
+                    CellKind::Inlay(index, ..) => self.inlays[index].offset,
+                    CellKind::Fold(index) => self.folds[index].start,
+                };
+                if layout.wraps.get(laid.len() - 1) == Some(&col) {
+                    laid.push(DisplayRow::new(rope.byte_to_line(offset), true));
+                }
+                let row = laid.last_mut().unwrap();
+                match cell.kind {
+                    CellKind::Text(start, end) => {
+                        let text = rope.byte_slice(start..end).to_string();
+                        row.push(&text, ChunkSource::Text(start..end), cell.width);
+                    }
+                    CellKind::Tab(start) => {
+                        row.push(&" ".repeat(cell.width), ChunkSource::Tab(start), cell.width);
+                    }
+                    CellKind::Inlay(index, from, to) => {
+                        let text = &self.inlays[index].text[from..to];
+                        row.push(text, ChunkSource::Inlay(index), cell.width);
+                    }
+                    CellKind::Fold(index) => {
+                        row.push(FOLD_PLACEHOLDER, ChunkSource::Fold(index), cell.width);
+                    }
+                }
+                col += cell.width;
+            }
+
+            let first = self.first_rows[line];
+            out.extend(
+                laid.into_iter()
+                    .enumerate()
+                    .filter(|(i, _)| (rows.start..end).contains(&(first + i)))
+                    .map(|(_, row)| row),
+            );
+            line += 1;
+        }
+        out
+    }
+
+    /// Index of the display line `offset` is in
+    fn line_at(&self, offset: usize) -> usize {
+        self.lines.partition_point(|l| l.start <= offset).max(1) - 1
+    }
+
+    fn index(&mut self) {
+        self.index_from(0);
+    }
+
+    /// Recount the first rows of the lines from `line` on
+    fn index_from(&mut self, line: usize) {
+        let line = line.min(self.first_rows.len().saturating_sub(1));
+        let mut row = self.first_rows.get(line).copied().unwrap_or(0);
+        self.first_rows.truncate(line);
+        for layout in &self.lines[line..] {
+            self.first_rows.push(row);
+            row += layout.wraps.len() + 1;
+        }
+        self.first_rows.push(row);
+    }
+
+    /// Lay out the lines in each of `windows` again (sorted index ranges of
+    /// `self.lines`), and move the starts of the lines after them with `map`.
+    /// Lines before the first window are left alone.
+    fn relayout(
+        &mut self,
+        rope: &Rope,
+        windows: Vec<Range<usize>>,
+        map: impl Fn(usize, Assoc) -> usize,
+    ) {
+        let Some(first) = windows.first().map(|w| w.start) else {
+            return;
+        };
+        let mut lines = std::mem::take(&mut self.lines);
+        let mut old = lines.split_off(first);
+        // No edit falls between two windows, so the lines there all move by
+        // as much as the first of them
+        let moved = |old: &mut [LineLayout], lines: &mut Vec<LineLayout>| {
+            let Some(head) = old.first() else {
+                return;
+            };
+            let delta = map(head.start, Assoc::After) as isize - head.start as isize;
+            lines.extend(old.iter_mut().map(|l| LineLayout {
+                start: (l.start as isize + delta) as usize,
+                wraps: std::mem::take(&mut l.wraps),
+            }))
+        };
+        let mut next = 0;
+        for window in windows {
+            let window = window.start - first..window.end - first;
+            if window.end <= next {
+                continue;
+            }
+            let start = window.start.max(next);
+            moved(&mut old[next..start], &mut lines);
+            let from = map(old[start].start, Assoc::Before);
+            let to = old
+                .get(window.end)
+                .map_or(usize::MAX, |l| map(l.start, Assoc::After));
+            let (laid, mut stop) = self.layout(rope, from, to);
+            lines.extend(laid);
+            next = window.end;
+
+            // Carry on until a line starts where an old one does
+            loop {
+                while old
+                    .get(next)
+                    .is_some_and(|l| map(l.start, Assoc::After) < stop)
+                {
+                    next += 1;
+                }
+                let resume = old
+                    .get(next)
+                    .map_or(usize::MAX, |l| map(l.start, Assoc::After));
+                if stop >= resume {
+                    break;
+                }
+                let (laid, at) = self.layout(rope, stop, resume);
+                lines.extend(laid);
+                stop = at;
+            }
+        }
+        let len = old.len();
+        moved(&mut old[next..len], &mut lines);
+
+        self.lines = lines;
+        self.index_from(first);
+    }
+
+    /// Lay out the lines from the one starting at `from` to before `to`.
+    /// Returns them and where the next line starts, `usize::MAX` past the end.
+    fn layout(&self, rope: &Rope, from: usize, to: usize) -> (Vec<LineLayout>, usize) {
+        let mut lines = Vec::new();
+        let mut start = Some(from);
+        while let Some(at) = start.filter(|&at| lines.is_empty() || at < to) {
+            let (cells, next) = self.cells(rope, at);
+            lines.push(LineLayout {
+                start: at,
+                wraps: self.wraps(&cells),
+            });
+            start = next;
+        }
+        (lines, start.unwrap_or(usize::MAX))
+    }
+
+    /// The cells of the display line starting at `start`, and where the
+    /// next one starts
+    fn cells(&self, rope: &Rope, start: usize) -> (Vec<Cell>, Option<usize>) {
+        let mut cells = Vec::new();
+        let mut col = 0;
+        let mut pos = start;
+        let mut line = rope.byte_to_line(start);
+        let mut end = line_content_end(rope, line);
+        let mut inlay = self.inlays.partition_point(|i| i.offset < start);
+        let mut fold = self.folds.partition_point(|f| f.end <= start);
+        loop {
+            // Inlays folded away are skipped
+            while let Some(i) = self.inlays.get(inlay).filter(|i| i.offset <= pos) {
+                if i.offset == pos {
+                    for (at, grapheme) in i.text.grapheme_indices(true) {
+                        cells.push(Cell {
+                            kind: CellKind::Inlay(inlay, at, at + grapheme.len()),
+                            width: 1,
+                            space: grapheme.trim().is_empty(),
+                        });
+                        col += 1;
+                    }
+                }
+                inlay += 1;
+            }
+            if let Some(f) = self.folds.get(fold).filter(|f| f.start <= pos) {
+                cells.push(Cell {
+                    kind: CellKind::Fold(fold),
+                    width: 1,
+                    space: false,
+                });
+                col += 1;
+                pos = pos.max(f.end);
+                fold += 1;
+                line = rope.byte_to_line(pos);
+                end = line_content_end(rope, line);
+                continue;
+            }
+            if pos >= end {
+                break;
+            }
+            let next = next_grapheme_boundary(rope, pos);
+            let cell = match rope.byte(pos) {
+                b'\t' => Cell {
+                    kind: CellKind::Tab(pos),
+                    width: self.options.tab_size.max(1) - col % self.options.tab_size.max(1),
+                    space: true,
+                },
+                byte => Cell {
+                    kind: CellKind::Text(pos, next),
+                    width: 1,
+                    space: byte == b' ',
+                },
+            };
+            col += cell.width;
+            cells.push(cell);
+            pos = next;
+        }
+        let next = (line + 1 < rope.len_lines()).then(|| rope.line_to_byte(line + 1));
+        (cells, next)
+    }
+
+    /// Columns to wrap `cells` at: after the last space that fits on the
+    /// row, or mid-word when there is none
+    fn wraps(&self, cells: &[Cell]) -> Vec<usize> {
+        let mut wraps = Vec::new();
+        let Some(width) = self.options.wrap_width.map(|w| w.max(1)) else {
+            return wraps;
+        };
+        let mut row_start = 0;
+        let mut col = 0;
+        let mut last_space = None;
+        for cell in cells {
+            while col + cell.width > row_start + width && col > row_start {
+                let at = last_space
+                    .take()
+                    .filter(|&at| at > row_start)
+                    .unwrap_or(col);
+                wraps.push(at);
+                row_start = at;
+            }
+            col += cell.width;
+            if cell.space {
+                last_space = Some(col);
+            }
+        }
+        wraps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Change;
+
+    fn texts(map: &DisplayMap, rope: &Rope) -> Vec<String> {
+        map.rows(rope, 0..usize::MAX)
+            .into_iter()
+            .map(|row| row.text)
+            .collect()
+    }
+
+    fn wrapped(width: usize) -> DisplayOptions {
+        DisplayOptions {
+            tab_size: 4,
+            wrap_width: Some(width),
+        }
+    }
+
+    #[test]
+    fn tabs_expand_to_tab_stops() {
+        let rope = Rope::from_str("a\tb\n\tc");
+        let map = DisplayMap::new(&rope, DisplayOptions::default());
+        assert_eq!(texts(&map, &rope), ["a   b", "    c"]);
+        assert_eq!(map.to_display(&rope, 2), DisplayPoint::new(0, 4));
+        assert_eq!(map.to_buffer(&rope, DisplayPoint::new(0, 2)), 1);
+        assert_eq!(map.to_buffer(&rope, DisplayPoint::new(1, 4)), 5);
+    }
+
+    #[test]
+    fn long_lines_wrap_after_spaces() {
+        let rope = Rope::from_str("one two three\nabcdefgh");
+        let map = DisplayMap::new(&rope, wrapped(5));
+        assert_eq!(
+            texts(&map, &rope),
+            ["one ", "two ", "three", "abcde", "fgh"]
+        );
+        let rows = map.rows(&rope, 1..4);
+        assert_eq!(
+            rows.iter()
+                .map(|r| (r.buffer_line, r.is_wrap))
+                .collect::<Vec<_>>(),
+            [(0, true), (0, true), (1, false)]
+        );
+        assert_eq!(map.row_count(), 5);
+        assert_eq!(map.to_display(&rope, 8), DisplayPoint::new(2, 0));
+        assert_eq!(map.to_buffer(&rope, DisplayPoint::new(4, 1)), 20);
+        // Past the end of a wrapped row stays on it
+        assert_eq!(map.to_buffer(&rope, DisplayPoint::new(0, 9)), 3);
+        assert_eq!(map.to_buffer(&rope, DisplayPoint::new(2, 9)), 13);
+    }
+
+    #[test]
+    fn folds_show_a_placeholder() {
+        let rope = Rope::from_str("fn a() {\n    1\n}\nfn b() {}\n");
+        let mut map = DisplayMap::new(&rope, DisplayOptions::default());
+        map.fold_lines(&rope, 0, 2);
+        assert_eq!(texts(&map, &rope), ["fn a() {⋯", "fn b() {}", ""]);
+        let row = &map.rows(&rope, 0..1)[0];
+        assert_eq!(row.chunks[1].source, ChunkSource::Fold(0));
+        assert_eq!(row.width, 9);
+
+        // Inside the fold is on the placeholder, past it is after it
+        assert_eq!(map.to_display(&rope, 12), DisplayPoint::new(0, 8));
+        assert_eq!(map.to_display(&rope, 16), DisplayPoint::new(0, 9));
+        assert_eq!(map.to_display(&rope, 17), DisplayPoint::new(1, 0));
+        assert_eq!(map.to_buffer(&rope, DisplayPoint::new(0, 8)), 8);
+        assert_eq!(map.to_buffer(&rope, DisplayPoint::new(1, 3)), 20);
+
+        map.unfold(&rope, 12..12);
+        assert_eq!(map.row_count(), 5);
+        assert!(map.folds().is_empty());
+    }
+
+    #[test]
+    fn inlays_are_shown_but_not_hit() {
+        let rope = Rope::from_str("let x = f(1);");
+        let mut map = DisplayMap::new(&rope, DisplayOptions::default());
+        map.set_inlays(
+            &rope,
+            InlayKind::Hint,
+            [(5, ": i32".into()), (10, "n: ".into())],
+        );
+        assert_eq!(texts(&map, &rope), ["let x: i32 = f(n: 1);"]);
+        assert_eq!(map.to_display(&rope, 5), DisplayPoint::new(0, 5));
+        assert_eq!(map.to_display(&rope, 6), DisplayPoint::new(0, 11));
+        assert_eq!(map.to_buffer(&rope, DisplayPoint::new(0, 7)), 5);
+        assert_eq!(map.to_buffer(&rope, DisplayPoint::new(0, 18)), 10);
+
+        // Setting one kind leaves the others alone
+        map.set_inlays(&rope, InlayKind::Ghost, [(13, " // done".into())]);
+        map.set_inlays(&rope, InlayKind::Hint, []);
+        assert_eq!(texts(&map, &rope), ["let x = f(1); // done"]);
+    }
+
+    #[test]
+    fn everything_composes() {
+        let rope = Rope::from_str("\tif x {\n\t\ty();\n\t}\n\tz(a, b);\n");
+        let mut map = DisplayMap::new(&rope, wrapped(10));
+        map.fold_lines(&rope, 0, 2);
+        map.set_inlays(&rope, InlayKind::Hint, [(24, "first: ".into())]);
+        assert_eq!(
+            texts(&map, &rope),
+            ["    if x ", "{⋯", "    z(a, ", "first: b);", ""]
+        );
+        // Before the hint that wrapped onto the next row
+        let point = map.to_display(&rope, 24);
+        assert_eq!(point, DisplayPoint::new(3, 0));
+        assert_eq!(map.to_buffer(&rope, point), 24);
+        assert_eq!(map.to_display(&rope, 12), DisplayPoint::new(1, 1));
+    }
+
+    #[test]
+    fn edits_update_the_layout_incrementally() {
+        let mut rope = Rope::from_str("fn a() {\n    one two\n}\n\nfn b() {\n\tthree\n}\n");
+        let mut map = DisplayMap::new(&rope, wrapped(8));
+        map.fold_lines(&rope, 4, 6);
+        map.set_inlays(&rope, InlayKind::Hint, [(22, "hint".into())]);
+
+        let edits = [
+            Change::insert(Position::new(13), "zero ".into()),
+            Change::delete(Position::new(8), Position::new(14)),
+            Change::insert(Position::new(0), "// top\n\n".into()),
+            Change::replace(Position::new(10), Position::new(25), "x\ny\tz ".into()),
+            Change::insert(Position::new(0), "a very long first line\n".into()),
+        ];
+        for change in edits {
+            let changes = ChangeSet::with_change(change);
+            changes.apply(&mut rope);
+            map.update(&rope, &changes);
+
+            let mut fresh = DisplayMap::new(&rope, map.options());
+            for fold in map.folds() {
+                fresh.fold(&rope, fold.clone());
+            }
+            let inlays = map.inlays().iter().map(|i| (i.offset, i.text.clone()));
+            fresh.set_inlays(&rope, InlayKind::Hint, inlays);
+            assert_eq!(map.lines, fresh.lines, "{:?}", rope.to_string());
+            assert_eq!(map.first_rows, fresh.first_rows);
+        }
+        assert_eq!(map.folds().len(), 1);
+    }
+
+    #[test]
+    fn lines_between_edits_move_by_the_edits_before_them() {
+        let text: String = (0..20).map(|i| format!("line {} of text\n", i)).collect();
+        let mut rope = Rope::from_str(&text);
+        let mut map = DisplayMap::new(&rope, wrapped(10));
+
+        // Three cursors, each changing the length of its line differently
+        let at = |line, col| Position::new(rope.line_to_byte(line) + col);
+        let mut changes = ChangeSet::new();
+        changes.add(Change::insert(at(2, 0), "a longer start ".into()));
+        changes.add(Change::delete(at(9, 0), at(9, 5)));
+        changes.add(Change::replace(at(15, 0), at(15, 4), "x\ny".into()));
+        changes.apply(&mut rope);
+        map.update(&rope, &changes);
+
+        let fresh = DisplayMap::new(&rope, map.options());
+        assert_eq!(map.lines, fresh.lines);
+        assert_eq!(map.first_rows, fresh.first_rows);
+    }
+
+    #[test]
+    fn deleting_folded_text_removes_the_fold() {
+        let mut rope = Rope::from_str("a {\n  b\n}\nc\n");
+        let mut map = DisplayMap::new(&rope, DisplayOptions::default());
+        map.fold_lines(&rope, 0, 2);
+        let changes = ChangeSet::with_change(Change::delete(Position::new(3), Position::new(9)));
+        changes.apply(&mut rope);
+        map.update(&rope, &changes);
+        assert!(map.folds().is_empty());
+        assert_eq!(texts(&map, &rope), ["a {", "c", ""]);
+    }
+}
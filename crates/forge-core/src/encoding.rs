@@ -0,0 +1,274 @@
+//! Character encoding detection and transcoding for file I/O.
+//!
+//! Buffers always hold UTF-8 text in the rope; this module converts raw file
+//! bytes to and from the on-disk [`Encoding`] so legacy files round-trip.
+
+use crate::Encoding;
+use anyhow::{bail, Result};
+
+const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// How many leading bytes the UTF-16 heuristic inspects.
+const SNIFF_LEN: usize = 4096;
+
+/// Result of decoding a file: the text plus how it was stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    pub text: String,
+    pub encoding: Encoding,
+    /// Whether the file started with a byte order mark
+    pub has_bom: bool,
+}
+
+impl Encoding {
+    /// All supported encodings, in picker order.
+    pub const ALL: [Encoding; 4] = [
+        Encoding::Utf8,
+        Encoding::Utf16Le,
+        Encoding::Utf16Be,
+        Encoding::Latin1,
+    ];
+
+    /// Human-readable label for the status bar and pickers
+    pub fn label(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16 LE",
+            Encoding::Utf16Be => "UTF-16 BE",
+            Encoding::Latin1 => "ISO 8859-1",
+        }
+    }
+
+    /// Stable identifier used in command ids and settings
+    pub fn id(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "utf8",
+            Encoding::Utf16Le => "utf16le",
+            Encoding::Utf16Be => "utf16be",
+            Encoding::Latin1 => "latin1",
+        }
+    }
+
+    /// Parse an identifier produced by [`Encoding::id`]
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|e| e.id() == id)
+    }
+
+    /// The byte order mark for this encoding (Latin-1 has none)
+    pub fn bom(&self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => UTF8_BOM,
+            Encoding::Utf16Le => UTF16LE_BOM,
+            Encoding::Utf16Be => UTF16BE_BOM,
+            Encoding::Latin1 => &[],
+        }
+    }
+
+    /// Detect the encoding of raw file bytes.
+    ///
+    /// A BOM always wins. Without one, valid UTF-8 is assumed UTF-8, text with
+    /// a NUL in every other byte is taken as BOM-less UTF-16, and anything else
+    /// falls back to Latin-1 (which can represent every byte).
+    pub fn detect(bytes: &[u8]) -> (Encoding, bool) {
+        if bytes.starts_with(UTF8_BOM) {
+            return (Encoding::Utf8, true);
+        }
+        if bytes.starts_with(UTF16LE_BOM) {
+            return (Encoding::Utf16Le, true);
+        }
+        if bytes.starts_with(UTF16BE_BOM) {
+            return (Encoding::Utf16Be, true);
+        }
+        if let Some(utf16) = Self::sniff_utf16(bytes) {
+            return (utf16, false);
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            (Encoding::Utf8, false)
+        } else {
+            (Encoding::Latin1, false)
+        }
+    }
+
+    /// Guess BOM-less UTF-16 from the distribution of NUL bytes.
+    fn sniff_utf16(bytes: &[u8]) -> Option<Encoding> {
+        let sample = &bytes[..bytes.len().min(SNIFF_LEN) & !1];
+        if sample.len() < 2 {
+            return None;
+        }
+        let pairs = sample.len() / 2;
+        let even_nuls = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+        let odd_nuls = sample
+            .iter()
+            .skip(1)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count();
+
+        // Mostly-ASCII UTF-16 has a NUL in the high byte of nearly every unit.
+        let threshold = pairs * 3 / 5;
+        if odd_nuls > threshold && even_nuls == 0 {
+            Some(Encoding::Utf16Le)
+        } else if even_nuls > threshold && odd_nuls == 0 {
+            Some(Encoding::Utf16Be)
+        } else {
+            None
+        }
+    }
+
+    /// Detect the encoding and decode in one step
+    pub fn decode_detect(bytes: &[u8]) -> Result<Decoded> {
+        let (encoding, has_bom) = Self::detect(bytes);
+        let body = if has_bom {
+            &bytes[encoding.bom().len()..]
+        } else {
+            bytes
+        };
+        Ok(Decoded {
+            text: encoding.decode(body)?,
+            encoding,
+            has_bom,
+        })
+    }
+
+    /// Decode a whole file as this encoding, stripping a leading BOM that
+    /// matches it (when reopening with an explicit encoding)
+    pub fn decode_file(&self, bytes: &[u8]) -> Result<Decoded> {
+        let body = bytes
+            .strip_prefix(self.bom())
+            .filter(|_| !self.bom().is_empty());
+        Ok(Decoded {
+            text: self.decode(body.unwrap_or(bytes))?,
+            encoding: *self,
+            has_bom: body.is_some(),
+        })
+    }
+
+    /// Decode bytes (without BOM) as this encoding. A U+FEFF at the start
+    /// is kept as part of the text.
+    pub fn decode(&self, bytes: &[u8]) -> Result<String> {
+        match self {
+            Encoding::Utf8 => Ok(std::str::from_utf8(bytes)?.to_string()),
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                let codec = if *self == Encoding::Utf16Le {
+                    encoding_rs::UTF_16LE
+                } else {
+                    encoding_rs::UTF_16BE
+                };
+                let (text, had_errors) = codec.decode_without_bom_handling(bytes);
+                if had_errors {
+                    bail!("Invalid {} data", self.label());
+                }
+                Ok(text.into_owned())
+            }
+            Encoding::Latin1 => Ok(bytes.iter().map(|&b| b as char).collect()),
+        }
+    }
+
+    /// Encode text into this encoding, optionally prefixed with a BOM.
+    ///
+    /// Fails if the text contains characters the encoding can't represent,
+    /// so a save never silently drops data.
+    pub fn encode(&self, text: &str, with_bom: bool) -> Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(text.len() + 3);
+        if with_bom {
+            out.extend_from_slice(self.bom());
+        }
+        match self {
+            Encoding::Utf8 => out.extend_from_slice(text.as_bytes()),
+            Encoding::Utf16Le => {
+                for unit in text.encode_utf16() {
+                    out.extend_from_slice(&unit.to_le_bytes());
+                }
+            }
+            Encoding::Utf16Be => {
+                for unit in text.encode_utf16() {
+                    out.extend_from_slice(&unit.to_be_bytes());
+                }
+            }
+            Encoding::Latin1 => {
+                for c in text.chars() {
+                    let code = c as u32;
+                    if code > 0xFF {
+                        bail!("Character {:?} cannot be encoded as {}", c, self.label());
+                    }
+                    out.push(code as u8);
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+impl std::fmt::Display for Encoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detect_boms() {
+        assert_eq!(Encoding::detect(b"\xEF\xBB\xBFhi"), (Encoding::Utf8, true));
+        assert_eq!(Encoding::detect(b"\xFF\xFEh\0"), (Encoding::Utf16Le, true));
+        assert_eq!(Encoding::detect(b"\xFE\xFF\0h"), (Encoding::Utf16Be, true));
+    }
+
+    #[test]
+    fn detect_without_bom() {
+        assert_eq!(
+            Encoding::detect("héllo".as_bytes()),
+            (Encoding::Utf8, false)
+        );
+        assert_eq!(
+            Encoding::detect(b"h\0e\0l\0l\0o\0"),
+            (Encoding::Utf16Le, false)
+        );
+        assert_eq!(
+            Encoding::detect(b"\0h\0e\0l\0l\0o"),
+            (Encoding::Utf16Be, false)
+        );
+        assert_eq!(Encoding::detect(b"caf\xE9"), (Encoding::Latin1, false));
+    }
+
+    #[test]
+    fn utf16_round_trip() {
+        for enc in [Encoding::Utf16Le, Encoding::Utf16Be] {
+            let bytes = enc.encode("héllo 👋", true).unwrap();
+            let decoded = Encoding::decode_detect(&bytes).unwrap();
+            assert_eq!(decoded.text, "héllo 👋");
+            assert_eq!(decoded.encoding, enc);
+            assert!(decoded.has_bom);
+        }
+    }
+
+    #[test]
+    fn only_one_bom_is_stripped() {
+        // A BOM followed by a zero width no-break space in the text
+        let bytes = b"\xEF\xBB\xBF\xEF\xBB\xBFhi";
+        let decoded = Encoding::decode_detect(bytes).unwrap();
+        assert_eq!(decoded.text, "\u{FEFF}hi");
+        assert!(decoded.has_bom);
+
+        let reopened = Encoding::Utf8.decode_file(bytes).unwrap();
+        assert_eq!(reopened, decoded);
+        let bytes = Encoding::Utf8.encode(&decoded.text, true).unwrap();
+        assert_eq!(bytes, b"\xEF\xBB\xBF\xEF\xBB\xBFhi");
+    }
+
+    #[test]
+    fn latin1_round_trip() {
+        let bytes: Vec<u8> = (0x20..=0xFF).collect();
+        let text = Encoding::Latin1.decode(&bytes).unwrap();
+        assert_eq!(Encoding::Latin1.encode(&text, false).unwrap(), bytes);
+    }
+
+    #[test]
+    fn latin1_rejects_unrepresentable() {
+        assert!(Encoding::Latin1.encode("日本", false).is_err());
+    }
+}
@@ -1,15 +1,151 @@
 use anyhow::Result;
-use std::path::Path;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 
 pub struct FileIO;
 
+/// Options controlling how a file is written to disk.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SaveOptions {
+    /// Keep the previous contents as `<name>.bak` next to the file
+    pub backup: bool,
+}
+
+/// Why a save failed. The original file is left untouched in every case.
+#[derive(Debug, thiserror::Error)]
+pub enum SaveError {
+    #[error("cannot write to {}: permission denied or read-only location", .0.display())]
+    ReadOnly(PathBuf),
+    #[error("not enough disk space to save {}", .0.display())]
+    DiskFull(PathBuf),
+    #[error("failed to save {}: {source}", .path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl SaveError {
+    fn from_io(path: &Path, source: io::Error) -> Self {
+        if matches!(
+            source.kind(),
+            io::ErrorKind::StorageFull | io::ErrorKind::QuotaExceeded
+        ) {
+            SaveError::DiskFull(path.to_path_buf())
+        } else if matches!(
+            source.kind(),
+            io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem
+        ) {
+            SaveError::ReadOnly(path.to_path_buf())
+        } else {
+            SaveError::Io {
+                path: path.to_path_buf(),
+                source,
+            }
+        }
+    }
+}
+
 impl FileIO {
-    /// Atomic save: write to temp, then rename.
-    pub fn save_atomic(path: &Path, content: &str) -> Result<()> {
-        let tmp = path.with_extension("forge-tmp");
-        std::fs::write(&tmp, content)?;
-        std::fs::rename(&tmp, path)?;
-        Ok(())
+    /// Atomic save: write to temp, fsync, then rename over the target.
+    pub fn save_atomic(path: &Path, content: impl AsRef<[u8]>) -> Result<(), SaveError> {
+        Self::save_atomic_with(path, content, &SaveOptions::default())
+    }
+
+    /// Atomic save with options.
+    ///
+    /// The data is written to a temporary file in the same directory, flushed
+    /// to disk and renamed over the target, so a crash leaves either the old
+    /// or the new contents — never a truncated file. Symlinks are resolved so
+    /// the link itself survives, and the original permissions (and ownership,
+    /// on Unix) are carried over to the new file.
+    pub fn save_atomic_with(
+        path: &Path,
+        content: impl AsRef<[u8]>,
+        options: &SaveOptions,
+    ) -> Result<(), SaveError> {
+        let target = Self::resolve_target(path);
+        let existing = fs::metadata(&target).ok();
+        let tmp = Self::temp_path(&target);
+        // A leftover from a previous crash would make create_new fail
+        let _ = fs::remove_file(&tmp);
+
+        let result = (|| {
+            let mut file = OpenOptions::new().write(true).create_new(true).open(&tmp)?;
+            file.write_all(content.as_ref())?;
+            if let Some(meta) = &existing {
+                file.set_permissions(meta.permissions())?;
+                Self::copy_ownership(&file, meta);
+            }
+            file.sync_all()?;
+            drop(file);
+
+            if options.backup && existing.is_some() {
+                fs::copy(&target, Self::backup_path(&target))?;
+            }
+            fs::rename(&tmp, &target)?;
+            Self::sync_parent(&target);
+            Ok(())
+        })();
+
+        result.map_err(|e: io::Error| {
+            let _ = fs::remove_file(&tmp);
+            SaveError::from_io(&target, e)
+        })
+    }
+
+    /// Follow symlinks so saving replaces the link target, not the link.
+    fn resolve_target(path: &Path) -> PathBuf {
+        match fs::symlink_metadata(path) {
+            Ok(meta) if meta.file_type().is_symlink() => {
+                fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+            }
+            _ => path.to_path_buf(),
+        }
+    }
+
+    fn temp_path(target: &Path) -> PathBuf {
+        let name = target
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        target.with_file_name(format!(".{}.{}.forge-tmp", name, std::process::id()))
+    }
+
+    /// Path of the `.bak` copy kept when [`SaveOptions::backup`] is set
+    pub fn backup_path(target: &Path) -> PathBuf {
+        let mut name = target.file_name().unwrap_or_default().to_os_string();
+        name.push(".bak");
+        target.with_file_name(name)
+    }
+
+    #[cfg(unix)]
+    fn copy_ownership(file: &File, meta: &fs::Metadata) {
+        use std::os::unix::fs::MetadataExt;
+        // Only root can give a file away; for everyone else this is a no-op or EPERM.
+        let _ = std::os::unix::fs::fchown(file, Some(meta.uid()), Some(meta.gid()));
+    }
+
+    #[cfg(not(unix))]
+    fn copy_ownership(_file: &File, _meta: &fs::Metadata) {}
+
+    /// Make the rename durable by syncing the directory entry.
+    fn sync_parent(target: &Path) {
+        #[cfg(unix)]
+        if let Some(dir) = target.parent() {
+            let dir = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir
+            };
+            if let Ok(d) = File::open(dir) {
+                let _ = d.sync_all();
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = target;
     }
 
     /// Detect if file is binary (contains null bytes in first 8KB).
@@ -42,6 +178,49 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
     #[test]
+    fn backup_keeps_previous_contents() {
+        let dir = std::env::temp_dir().join("forge_io_backup_test");
+        std::fs::create_dir_all(&dir).ok();
+        let path = dir.join("test.txt");
+        std::fs::write(&path, "old").unwrap();
+        FileIO::save_atomic_with(&path, "new", &SaveOptions { backup: true }).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        assert_eq!(
+            std::fs::read_to_string(FileIO::backup_path(&path)).unwrap(),
+            "old"
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+    #[cfg(unix)]
+    #[test]
+    fn preserves_permissions_and_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join("forge_io_meta_test");
+        std::fs::create_dir_all(&dir).ok();
+        let real = dir.join("real.sh");
+        let link = dir.join("link.sh");
+        std::fs::write(&real, "old").unwrap();
+        std::fs::set_permissions(&real, std::fs::Permissions::from_mode(0o750)).unwrap();
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&real, &link).unwrap();
+
+        FileIO::save_atomic(&link, "new").unwrap();
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&real).unwrap(), "new");
+        let mode = std::fs::metadata(&real).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o750);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+    #[test]
+    fn missing_directory_is_an_error() {
+        let path = std::env::temp_dir().join("forge_io_missing_dir/nested/test.txt");
+        let err = FileIO::save_atomic(&path, "hello").unwrap_err();
+        assert!(matches!(err, SaveError::Io { .. }));
+    }
+    #[test]
     fn detect_lf() {
         assert_eq!(FileIO::detect_line_ending("a\nb\nc"), "\n");
     }
@@ -0,0 +1,156 @@
+//! Noticing when open files change on disk.
+//!
+//! A background thread polls the size and modification time of every watched
+//! path. Polling keeps this dependency-free and behaves the same on network
+//! drives, where native change notifications are unreliable.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// How often watched files are checked by default
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileEvent {
+    /// The file's contents may have changed
+    Changed(PathBuf),
+    /// The file no longer exists
+    Removed(PathBuf),
+}
+
+/// What is compared between polls; `None` when the file is missing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+        })
+    }
+}
+
+type Watched = Arc<Mutex<HashMap<PathBuf, Option<FileStamp>>>>;
+
+pub struct FileWatcher {
+    watched: Watched,
+    events: Receiver<FileEvent>,
+    stop: Arc<AtomicBool>,
+}
+
+impl std::fmt::Debug for FileWatcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FileWatcher")
+            .field("watched", &self.watched.lock().unwrap().len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl FileWatcher {
+    /// Start the polling thread, checking watched files every `interval`
+    pub fn new(interval: Duration) -> std::io::Result<Self> {
+        let watched: Watched = Arc::default();
+        let stop = Arc::new(AtomicBool::new(false));
+        let (tx, events) = mpsc::channel();
+
+        let (thread_watched, thread_stop) = (watched.clone(), stop.clone());
+        std::thread::Builder::new()
+            .name("forge-file-watcher".into())
+            .spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(interval);
+                    let mut watched = thread_watched.lock().unwrap();
+                    for (path, stamp) in watched.iter_mut() {
+                        let current = FileStamp::of(path);
+                        if current == *stamp {
+                            continue;
+                        }
+                        let event = match current {
+                            Some(_) => FileEvent::Changed(path.clone()),
+                            None => FileEvent::Removed(path.clone()),
+                        };
+                        *stamp = current;
+                        if tx.send(event).is_err() {
+                            return;
+                        }
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            watched,
+            events,
+            stop,
+        })
+    }
+
+    /// Start watching `path` from its current state
+    pub fn watch(&self, path: impl Into<PathBuf>) {
+        let path = path.into();
+        let stamp = FileStamp::of(&path);
+        self.watched.lock().unwrap().insert(path, stamp);
+    }
+
+    pub fn unwatch(&self, path: &Path) {
+        self.watched.lock().unwrap().remove(path);
+    }
+
+    pub fn is_watching(&self, path: &Path) -> bool {
+        self.watched.lock().unwrap().contains_key(path)
+    }
+
+    /// Changes seen since the last call
+    pub fn poll(&self) -> Vec<FileEvent> {
+        self.events.try_iter().collect()
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    /// Whether `expected` is reported within a few seconds
+    fn saw_event(watcher: &FileWatcher, expected: FileEvent) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if watcher.poll().contains(&expected) {
+                return true;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        false
+    }
+
+    #[test]
+    fn reports_changes_and_removal() {
+        let path = std::env::temp_dir().join("forge_file_watch.txt");
+        std::fs::write(&path, "one").unwrap();
+        let watcher = FileWatcher::new(Duration::from_millis(10)).unwrap();
+        watcher.watch(&path);
+
+        // A different length is detected even with coarse mtimes
+        std::fs::write(&path, "one two").unwrap();
+        assert!(saw_event(&watcher, FileEvent::Changed(path.clone())));
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(saw_event(&watcher, FileEvent::Removed(path.clone())));
+        watcher.unwatch(&path);
+        assert!(!watcher.is_watching(&path));
+    }
+}
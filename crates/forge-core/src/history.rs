@@ -1,21 +1,47 @@
-use crate::{ChangeSet, Transaction};
-use std::time::{SystemTime, UNIX_EPOCH};
+use crate::{Assoc, ChangeSet, Transaction};
+use ropey::Rope;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+static STAMP: AtomicU64 = AtomicU64::new(1);
+
+/// How many of its latest states a history remembers for
+/// [`History::catch_up`]
+const TRAIL: usize = 16;
+
+fn next_stamp() -> u64 {
+    STAMP.fetch_add(1, Ordering::Relaxed)
+}
+
+fn fresh_trail() -> VecDeque<u64> {
+    VecDeque::from([next_stamp()])
+}
 
 /// A node in the history tree
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryNode {
     /// The transaction that was applied
     pub transaction: Transaction,
+    /// The transaction that reverts it, computed against the document before it ran
+    pub inversion: Transaction,
     /// Timestamp when this transaction was applied
     pub timestamp: u64,
     /// Parent node index (None for root)
     pub parent: Option<usize>,
     /// Child node indices
     pub children: Vec<usize>,
+    /// The child that redo follows (the most recently visited branch)
+    #[serde(default)]
+    pub redo_child: Option<usize>,
+    /// The state of the history in which this node last changed
+    #[serde(skip)]
+    stamp: u64,
 }
 
 impl HistoryNode {
-    fn new(transaction: Transaction, parent: Option<usize>) -> Self {
+    fn new(transaction: Transaction, inversion: Transaction, parent: Option<usize>) -> Self {
         let timestamp = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .unwrap()
@@ -23,52 +49,127 @@ impl HistoryNode {
 
         Self {
             transaction,
+            inversion,
             timestamp,
             parent,
             children: Vec::new(),
+            redo_child: None,
+            stamp: 0,
         }
     }
 }
 
 /// History tree for undo/redo. Unlike a linear undo stack, this preserves all history
 /// even when you undo and make a different edit.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct History {
     /// All history nodes (tree stored as vector)
     pub nodes: Vec<HistoryNode>,
     /// Index of the current position in the history
     pub current: usize,
+    /// The latest states the history went through, oldest first, each named
+    /// by a stamp unique across all histories
+    #[serde(skip, default = "fresh_trail")]
+    trail: VecDeque<u64>,
 }
 
 impl History {
     /// Create a new empty history
     pub fn new() -> Self {
         // Create a dummy root node representing the initial state
-        let root = HistoryNode::new(Transaction::new(ChangeSet::new(), None), None);
+        let empty = Transaction::new(ChangeSet::new(), None);
+        let root = HistoryNode::new(empty.clone(), empty, None);
         Self {
             nodes: vec![root],
             current: 0,
+            trail: fresh_trail(),
         }
     }
 
-    /// Add a new transaction to the history
-    pub fn push(&mut self, transaction: Transaction) -> usize {
+    /// Move to a new state in which `nodes` changed
+    fn touch(&mut self, nodes: &[usize]) {
+        let stamp = next_stamp();
+        for &node in nodes {
+            self.nodes[node].stamp = stamp;
+        }
+        if self.trail.len() == TRAIL {
+            self.trail.pop_front();
+        }
+        self.trail.push_back(stamp);
+    }
+
+    /// Move to a new state no copy of an earlier one can catch up with
+    fn rewrite(&mut self) {
+        self.trail = fresh_trail();
+    }
+
+    /// Become a copy of `other`. If this history is a copy of a state that
+    /// `other` went through lately, only the nodes changed since are copied.
+    pub fn catch_up(&mut self, other: &History) {
+        let at = self.trail.back().copied();
+        if at == other.trail.back().copied() {
+            return;
+        }
+        let Some(at) = at.filter(|at| other.trail.contains(at)) else {
+            *self = other.clone();
+            return;
+        };
+        // Nodes are only ever added, except by a rewrite
+        for (index, node) in other.nodes.iter().enumerate() {
+            if index >= self.nodes.len() {
+                self.nodes.push(node.clone());
+            } else if node.stamp > at {
+                self.nodes[index] = node.clone();
+            }
+        }
+        self.current = other.current;
+        self.trail.clone_from(&other.trail);
+    }
+
+    /// Add a new transaction (and its inverse) to the history
+    pub fn push(&mut self, transaction: Transaction, inversion: Transaction) -> usize {
         // We always have a root node, so parent is always Some(current)
         let parent = Some(self.current);
 
-        let new_node = HistoryNode::new(transaction, parent);
+        let new_node = HistoryNode::new(transaction, inversion, parent);
         let new_idx = self.nodes.len();
         self.nodes.push(new_node);
 
-        // Update parent's children
+        // Update parent's children; the new edit becomes the redo branch
         if let Some(parent_idx) = parent {
             self.nodes[parent_idx].children.push(new_idx);
+            self.nodes[parent_idx].redo_child = Some(new_idx);
+            self.touch(&[parent_idx]);
         }
 
         self.current = new_idx;
+        self.touch(&[new_idx]);
         new_idx
     }
 
+    /// Fold a follow-up edit into the current node instead of adding a new one.
+    ///
+    /// `transaction` must be expressed against the document after the current
+    /// node. The node then undoes both edits in a single step. At the root
+    /// there is nothing to extend, so this falls back to [`History::push`].
+    pub fn merge_into_current(&mut self, transaction: Transaction, inversion: Transaction) {
+        if self.nodes[self.current].parent.is_none() {
+            self.push(transaction, inversion);
+            return;
+        }
+        let node = &mut self.nodes[self.current];
+        node.transaction.changes = node.transaction.changes.compose(&transaction.changes);
+        if transaction.selection.is_some() {
+            node.transaction.selection = transaction.selection;
+        }
+        node.inversion.changes = inversion.changes.compose(&node.inversion.changes);
+        node.timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.touch(&[self.current]);
+    }
+
     /// Undo: move back to the parent node
     pub fn undo(&mut self) -> bool {
         if self.nodes.is_empty() {
@@ -77,26 +178,123 @@ impl History {
 
         let current_node = &self.nodes[self.current];
         if let Some(parent_idx) = current_node.parent {
+            // Redo should come back down the branch we just left
+            self.nodes[parent_idx].redo_child = Some(self.current);
             self.current = parent_idx;
+            self.touch(&[parent_idx]);
             true
         } else {
             false
         }
     }
 
-    /// Redo: move forward to a child node (uses the first child by default)
+    /// Redo: move forward along the current node's redo branch
     pub fn redo(&mut self) -> Option<&Transaction> {
-        if self.nodes.is_empty() {
-            return None;
+        let child = self.redo_branch(self.current)?;
+        self.current = child;
+        self.touch(&[]);
+        Some(&self.nodes[child].transaction)
+    }
+
+    /// The branches (child nodes) leaving `node`, oldest first
+    pub fn branches(&self, node: usize) -> &[usize] {
+        self.nodes
+            .get(node)
+            .map(|n| n.children.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// The child that redo will follow from `node`.
+    ///
+    /// Defaults to the newest branch when none has been chosen.
+    pub fn redo_branch(&self, node: usize) -> Option<usize> {
+        let node = self.nodes.get(node)?;
+        node.redo_child
+            .filter(|child| node.children.contains(child))
+            .or_else(|| node.children.last().copied())
+    }
+
+    /// Make `child` the branch that redo follows from its parent.
+    ///
+    /// Returns false if `child` isn't a node with a parent.
+    pub fn set_redo_branch(&mut self, child: usize) -> bool {
+        match self.nodes.get(child).and_then(|n| n.parent) {
+            Some(parent) => {
+                self.nodes[parent].redo_child = Some(child);
+                self.touch(&[parent]);
+                true
+            }
+            None => false,
         }
+    }
 
-        let current_node = &self.nodes[self.current];
-        if let Some(&first_child) = current_node.children.first() {
-            self.current = first_child;
-            Some(&self.nodes[first_child].transaction)
-        } else {
-            None
+    /// Move to `target`, returning the transactions that take the document there.
+    ///
+    /// The path undoes up to the common ancestor and redoes down the target's
+    /// branch, which also becomes the redo branch at every node along the way.
+    /// Returns an empty list if `target` is already current or doesn't exist.
+    pub fn goto(&mut self, target: usize) -> Vec<Transaction> {
+        if target >= self.nodes.len() || target == self.current {
+            return Vec::new();
+        }
+
+        let target_path = self.ancestors(target);
+        let mut steps = Vec::new();
+        let mut node = self.current;
+        while !target_path.contains(&node) {
+            steps.push(self.nodes[node].inversion.clone());
+            node = match self.nodes[node].parent {
+                Some(parent) => parent,
+                None => break,
+            };
+        }
+
+        let common = target_path.iter().position(|&n| n == node).unwrap_or(0);
+        for &child in target_path[..common].iter().rev() {
+            steps.push(self.nodes[child].transaction.clone());
+            self.set_redo_branch(child);
         }
+        self.current = target;
+        self.touch(&[]);
+        steps
+    }
+
+    /// `node` followed by each of its ancestors up to the root
+    fn ancestors(&self, node: usize) -> Vec<usize> {
+        let mut path = vec![node];
+        let mut cursor = node;
+        while let Some(parent) = self.nodes[cursor].parent {
+            path.push(parent);
+            cursor = parent;
+        }
+        path
+    }
+
+    /// The newest state that existed at `time` (seconds since the Unix epoch).
+    ///
+    /// Nodes are created in chronological order, so this is the last node
+    /// stamped at or before `time`; earlier than everything means the root.
+    pub fn state_at(&self, time: u64) -> usize {
+        self.nodes
+            .iter()
+            .rposition(|n| n.timestamp <= time)
+            .unwrap_or(0)
+    }
+
+    /// The state `duration` before the current one, like Vim's `:earlier`
+    pub fn earlier(&self, duration: Duration) -> usize {
+        let time = self.current_timestamp().saturating_sub(duration.as_secs());
+        self.state_at(time).min(self.current)
+    }
+
+    /// The state `duration` after the current one, like Vim's `:later`
+    pub fn later(&self, duration: Duration) -> usize {
+        let time = self.current_timestamp().saturating_add(duration.as_secs());
+        self.state_at(time).max(self.current)
+    }
+
+    fn current_timestamp(&self) -> u64 {
+        self.nodes.get(self.current).map_or(0, |n| n.timestamp)
     }
 
     /// Get the current transaction
@@ -108,6 +306,11 @@ impl History {
         }
     }
 
+    /// Get the inverse of the transaction at the current position (if any)
+    pub fn current_inversion(&self) -> Option<&Transaction> {
+        self.nodes.get(self.current).map(|node| &node.inversion)
+    }
+
     /// Get the transaction at the current position (if any)
     pub fn get_current(&self) -> Option<&Transaction> {
         if self.nodes.is_empty() {
@@ -125,7 +328,7 @@ impl History {
 
     /// Check if we can redo
     pub fn can_redo(&self) -> bool {
-        !self.nodes.is_empty() && !self.nodes[self.current].children.is_empty()
+        self.redo_branch(self.current).is_some()
     }
 
     /// Get the total number of history nodes
@@ -137,6 +340,154 @@ impl History {
     pub fn is_empty(&self) -> bool {
         self.nodes.is_empty()
     }
+
+    /// How many undo steps lead from the root to the current state
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut node = self.current;
+        while let Some(parent) = self.nodes[node].parent {
+            depth += 1;
+            node = parent;
+        }
+        depth
+    }
+
+    /// Forget the oldest `levels` revisions before the current one.
+    ///
+    /// The revision that many steps down from the root towards the current
+    /// state becomes the new root; branches that split off above it are
+    /// dropped. Returns `false` if there was nothing to drop.
+    pub fn drop_oldest(&mut self, levels: usize) -> bool {
+        let mut path = vec![self.current];
+        while let Some(parent) = self.nodes[*path.last().unwrap()].parent {
+            path.push(parent);
+        }
+        // `path` runs from the current node up to the root
+        if path.len() == 1 || levels == 0 {
+            return false;
+        }
+        let new_root = path[(path.len() - 1).saturating_sub(levels)];
+
+        let mut kept = vec![new_root];
+        let mut i = 0;
+        while i < kept.len() {
+            kept.extend(self.nodes[kept[i]].children.iter().copied());
+            i += 1;
+        }
+        // Children are always newer than their parent, so sorting keeps the
+        // new root first
+        kept.sort_unstable();
+        let mut index = vec![None; self.nodes.len()];
+        for (new, &old) in kept.iter().enumerate() {
+            index[old] = Some(new);
+        }
+        let nodes = kept
+            .iter()
+            .map(|&old| {
+                let node = &self.nodes[old];
+                let mut node = HistoryNode {
+                    parent: node.parent.and_then(|p| index[p]),
+                    children: node.children.iter().filter_map(|&c| index[c]).collect(),
+                    redo_child: node.redo_child.and_then(|c| index[c]),
+                    ..node.clone()
+                };
+                if old == new_root {
+                    // Like the original root, the state it starts from can't
+                    // be undone
+                    let empty = Transaction::new(ChangeSet::new(), None);
+                    node.transaction = empty.clone();
+                    node.inversion = empty;
+                }
+                node
+            })
+            .collect();
+        self.current = index[self.current].unwrap();
+        self.nodes = nodes;
+        self.rewrite();
+        true
+    }
+
+    /// Rebase every revision over `changes`, an edit of the current state
+    /// that the history didn't record, such as a collaborator's.
+    ///
+    /// `text` is the document after `changes`. Undo and redo keep working
+    /// and leave the edit in place wherever it still applies.
+    pub fn rebase(&mut self, changes: &ChangeSet, text: &Rope) {
+        self.rewrite();
+        // Branches hanging off a node are rebased over the edit as it
+        // stands at that node
+        let mut pending = Vec::new();
+        let mut path_child = None;
+        let mut node = self.current;
+        let mut changes = changes.clone();
+        let mut text = text.clone();
+        loop {
+            for &child in &self.nodes[node].children {
+                if Some(child) != path_child {
+                    pending.push((child, changes.clone(), text.clone()));
+                }
+            }
+            let Some(parent) = self.nodes[node].parent else {
+                break;
+            };
+            // Walk up: rebase the step's inversion, then derive the step
+            // itself from it so the two stay exact inverses
+            let entry = &mut self.nodes[node];
+            let inversion = entry.inversion.changes.transform(&changes, Assoc::Before);
+            let before = changes.transform(&entry.inversion.changes, Assoc::After);
+            let mut parent_text = text.clone();
+            inversion.apply(&mut parent_text);
+            let inversion = Transaction::new(
+                inversion,
+                entry
+                    .inversion
+                    .selection
+                    .as_ref()
+                    .map(|s| s.map_through(&before)),
+            );
+            let mut transaction = inversion.invert(&text);
+            transaction.selection = entry
+                .transaction
+                .selection
+                .as_ref()
+                .map(|s| s.map_through(&changes));
+            entry.inversion = inversion;
+            entry.transaction = transaction;
+            path_child = Some(node);
+            node = parent;
+            changes = before;
+            text = parent_text;
+        }
+
+        while let Some((node, changes, text)) = pending.pop() {
+            // Walk down: rebase the step, then derive its inversion from the
+            // rebased step and the text it now applies to
+            let entry = &mut self.nodes[node];
+            let step = entry.transaction.changes.transform(&changes, Assoc::Before);
+            let after = changes.transform(&entry.transaction.changes, Assoc::After);
+            let transaction = Transaction::new(
+                step,
+                entry
+                    .transaction
+                    .selection
+                    .as_ref()
+                    .map(|s| s.map_through(&after)),
+            );
+            let mut inversion = transaction.invert(&text);
+            inversion.selection = entry
+                .inversion
+                .selection
+                .as_ref()
+                .map(|s| s.map_through(&changes));
+            let mut child_text = text;
+            transaction.changes.apply(&mut child_text);
+            entry.transaction = transaction;
+            entry.inversion = inversion;
+            for &child in &self.nodes[node].children {
+                pending.push((child, after.clone(), child_text.clone()));
+            }
+        }
+    }
 }
 
 impl Default for History {
@@ -163,8 +514,8 @@ mod tests {
             None,
         );
 
-        history.push(tx1);
-        history.push(tx2);
+        history.push(tx1, Transaction::new(ChangeSet::new(), None));
+        history.push(tx2, Transaction::new(ChangeSet::new(), None));
 
         assert_eq!(history.len(), 3); // Root + 2 transactions
         assert!(history.can_undo());
@@ -176,6 +527,37 @@ mod tests {
         assert_eq!(history.current, 2);
     }
 
+    #[test]
+    fn a_copy_catches_up_with_the_history_it_came_from() {
+        let step = |text: &str| {
+            Transaction::new(
+                ChangeSet::with_change(Change::insert(Position::new(0), text.to_string())),
+                None,
+            )
+        };
+        let mut history = History::new();
+        history.push(step("a"), step(""));
+        let mut copy = history.clone();
+
+        history.merge_into_current(step("b"), step(""));
+        history.undo();
+        history.push(step("c"), step(""));
+        copy.catch_up(&history);
+        assert_eq!(copy.current, history.current);
+        assert_eq!(copy.nodes.len(), 3);
+        for (copied, node) in copy.nodes.iter().zip(&history.nodes) {
+            assert_eq!(copied.transaction, node.transaction);
+            assert_eq!(copied.children, node.children);
+            assert_eq!(copied.redo_child, node.redo_child);
+        }
+
+        // Once the two have gone separate ways, the copy starts over
+        copy.push(step("d"), step(""));
+        copy.catch_up(&history);
+        assert_eq!(copy.nodes.len(), 3);
+        assert_eq!(copy.current, history.current);
+    }
+
     #[test]
     fn test_history_branching() {
         let mut history = History::new();
@@ -184,13 +566,13 @@ mod tests {
             ChangeSet::with_change(Change::insert(Position::new(0), "branch1".to_string())),
             None,
         );
-        history.push(tx1);
+        history.push(tx1, Transaction::new(ChangeSet::new(), None));
 
         let tx2 = Transaction::new(
             ChangeSet::with_change(Change::insert(Position::new(7), " more".to_string())),
             None,
         );
-        history.push(tx2);
+        history.push(tx2, Transaction::new(ChangeSet::new(), None));
 
         // Undo then make a different edit (creates a branch)
         history.undo();
@@ -199,10 +581,75 @@ mod tests {
             ChangeSet::with_change(Change::insert(Position::new(7), " different".to_string())),
             None,
         );
-        history.push(tx3);
+        history.push(tx3, Transaction::new(ChangeSet::new(), None));
 
         // Now node 1 (tx1) has TWO children: node 2 (tx2) and node 3 (tx3)
         // Node 0 is root.
         assert_eq!(history.nodes[1].children.len(), 2);
     }
+
+    fn push_insert(history: &mut History, text: &str) -> usize {
+        let tx = Transaction::new(
+            ChangeSet::with_change(Change::insert(Position::new(0), text.to_string())),
+            None,
+        );
+        history.push(tx, Transaction::new(ChangeSet::new(), None))
+    }
+
+    #[test]
+    fn test_redo_follows_selected_branch() {
+        let mut history = History::new();
+        let a = push_insert(&mut history, "a");
+        history.undo();
+        let b = push_insert(&mut history, "b");
+        history.undo();
+
+        assert_eq!(history.branches(0), &[a, b]);
+        assert_eq!(history.redo_branch(0), Some(b));
+
+        assert!(history.set_redo_branch(a));
+        history.redo();
+        assert_eq!(history.current, a);
+    }
+
+    #[test]
+    fn test_goto_across_branches() {
+        let mut history = History::new();
+        let a = push_insert(&mut history, "a");
+        let a2 = push_insert(&mut history, "a2");
+        history.undo();
+        history.undo();
+        let b = push_insert(&mut history, "b");
+
+        // Undo b, then redo a and a2
+        let steps = history.goto(a2);
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[1], history.nodes[a].transaction);
+        assert_eq!(history.current, a2);
+        assert_eq!(history.redo_branch(0), Some(a));
+
+        assert!(history.goto(a2).is_empty());
+        assert_eq!(history.goto(b).len(), 3);
+    }
+
+    #[test]
+    fn test_time_travel() {
+        let mut history = History::new();
+        let a = push_insert(&mut history, "a");
+        let b = push_insert(&mut history, "b");
+        let c = push_insert(&mut history, "c");
+        history.nodes[0].timestamp = 1_000;
+        history.nodes[a].timestamp = 1_000;
+        history.nodes[b].timestamp = 1_030;
+        history.nodes[c].timestamp = 1_600;
+
+        assert_eq!(history.state_at(999), 0);
+        assert_eq!(history.state_at(1_100), b);
+        assert_eq!(history.earlier(Duration::from_secs(60)), b);
+        assert_eq!(history.earlier(Duration::from_secs(600)), a);
+
+        history.current = a;
+        assert_eq!(history.later(Duration::from_secs(30)), b);
+        assert_eq!(history.later(Duration::from_secs(3_600)), c);
+    }
 }
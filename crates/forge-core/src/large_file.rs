@@ -0,0 +1,666 @@
+//! Opening multi-gigabyte files without freezing the editor.
+//!
+//! Files over [`LargeFileLimits::threshold_bytes`] are decoded into a rope on
+//! a background thread ([`ChunkedLoad`]) while the UI shows progress. Files
+//! too big to hold in memory at all are viewed read-only through a memory map
+//! ([`MappedFile`]), which indexes lines in the background and can still be
+//! searched and jumped around in. The map is read as UTF-8 split at `\n`, so
+//! files in other encodings or with CR line endings can't be viewed that way.
+
+use crate::undo_store::{content_hash, extend_content_hash};
+use crate::{Encoding, LineEnding};
+use anyhow::{bail, Result};
+use memmap2::Mmap;
+use regex::bytes::Regex;
+use ropey::{Rope, RopeBuilder};
+use std::fs::File;
+use std::io::Read;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+
+/// Bytes read and decoded per step of a background load
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+/// Lines between line-index checkpoints in a mapped file
+const LINES_PER_CHECKPOINT: usize = 1024;
+
+/// Size thresholds that switch a file into large-file mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LargeFileLimits {
+    /// Files at least this big load in the background with syntax
+    /// highlighting, minimap and word wrap disabled
+    pub threshold_bytes: u64,
+    /// Files at least this big are memory-mapped and opened read-only
+    pub mmap_threshold_bytes: u64,
+}
+
+impl Default for LargeFileLimits {
+    fn default() -> Self {
+        Self {
+            threshold_bytes: 64 * 1024 * 1024,
+            mmap_threshold_bytes: 1024 * 1024 * 1024,
+        }
+    }
+}
+
+/// How a file of a given size should be opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpenMode {
+    /// Read and decode synchronously
+    Normal,
+    /// Decode into a rope on a background thread
+    Chunked,
+    /// Memory-map and view read-only
+    Mapped,
+}
+
+impl LargeFileLimits {
+    pub fn mode_for(&self, size: u64) -> OpenMode {
+        if size >= self.mmap_threshold_bytes {
+            OpenMode::Mapped
+        } else if size >= self.threshold_bytes {
+            OpenMode::Chunked
+        } else {
+            OpenMode::Normal
+        }
+    }
+
+    /// The open mode for the file at `path`, based on its size on disk
+    pub fn mode_for_path(&self, path: impl AsRef<Path>) -> Result<OpenMode> {
+        Ok(self.mode_for(std::fs::metadata(path)?.len()))
+    }
+}
+
+/// Text produced by a finished [`ChunkedLoad`]
+#[derive(Debug)]
+pub struct LoadedText {
+    pub rope: Rope,
+    pub encoding: Encoding,
+    pub has_bom: bool,
+    pub line_ending: LineEnding,
+    /// Some bytes didn't decode and were replaced with U+FFFD, so saving the
+    /// text would not reproduce the file
+    pub lossy: bool,
+    /// Hash of the bytes read, to notice when the file changes on disk
+    pub hash: u64,
+}
+
+/// A file being decoded into a rope on a background thread.
+///
+/// Dropping it cancels the load.
+#[derive(Debug)]
+pub struct ChunkedLoad {
+    total: u64,
+    loaded: Arc<AtomicU64>,
+    cancel: Arc<AtomicBool>,
+    result: Receiver<Result<LoadedText>>,
+}
+
+impl ChunkedLoad {
+    pub fn start(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = File::open(&path)?;
+        let total = file.metadata()?.len();
+        let loaded = Arc::new(AtomicU64::new(0));
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (tx, result) = mpsc::channel();
+
+        let (thread_loaded, thread_cancel) = (loaded.clone(), cancel.clone());
+        std::thread::Builder::new()
+            .name("forge-large-file-load".into())
+            .spawn(move || {
+                let _ = tx.send(load_chunks(file, &thread_loaded, &thread_cancel));
+            })?;
+
+        Ok(Self {
+            total,
+            loaded,
+            cancel,
+            result,
+        })
+    }
+
+    /// Fraction of the file decoded so far, from 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.loaded.load(Ordering::Relaxed) as f32 / self.total as f32
+    }
+
+    /// The loaded text, once the background thread has finished
+    pub fn try_finish(&self) -> Option<Result<LoadedText>> {
+        match self.result.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow::anyhow!("File loader stopped"))),
+        }
+    }
+
+    /// Block until the load finishes
+    pub fn wait(self) -> Result<LoadedText> {
+        self.result
+            .recv()
+            .map_err(|_| anyhow::anyhow!("File loader stopped"))?
+    }
+}
+
+impl Drop for ChunkedLoad {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+fn load_chunks(mut file: File, loaded: &AtomicU64, cancel: &AtomicBool) -> Result<LoadedText> {
+    let mut chunk = vec![0; CHUNK_SIZE];
+    let mut builder = RopeBuilder::new();
+    let mut decoder: Option<ChunkDecoder> = None;
+    let mut line_ending = None;
+    let mut text = String::new();
+    let mut hash = content_hash(&[]);
+
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            bail!("Load cancelled");
+        }
+        let read = read_full(&mut file, &mut chunk)?;
+        let last = read < chunk.len();
+        let bytes = &chunk[..read];
+        hash = extend_content_hash(hash, bytes);
+        let decoder = decoder.get_or_insert_with(|| ChunkDecoder::detect(bytes));
+        let skip = std::mem::take(&mut decoder.bom_len);
+
+        text.clear();
+        decoder.decode(&bytes[skip.min(read)..], &mut text, last);
+        if line_ending.is_none() && text.contains(['\n', '\r']) {
+            line_ending = Some(LineEnding::detect_from_str(&text));
+        }
+        builder.append(&text);
+        loaded.fetch_add(read as u64, Ordering::Relaxed);
+        if last {
+            break;
+        }
+    }
+
+    let decoder = decoder.unwrap_or_else(|| ChunkDecoder::detect(&[]));
+    Ok(LoadedText {
+        rope: builder.finish(),
+        encoding: decoder.encoding,
+        has_bom: decoder.has_bom,
+        line_ending: line_ending.unwrap_or_else(LineEnding::detect_system),
+        lossy: decoder.had_errors,
+        hash,
+    })
+}
+
+/// Fill `buf` unless the file ends first; returns the bytes read
+fn read_full(file: &mut File, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Streaming decoder; multi-byte sequences may straddle chunk boundaries
+struct ChunkDecoder {
+    encoding: Encoding,
+    has_bom: bool,
+    /// BOM bytes still to skip at the start of the first chunk
+    bom_len: usize,
+    inner: Option<encoding_rs::Decoder>,
+    /// Malformed data was replaced somewhere in the file
+    had_errors: bool,
+}
+
+impl ChunkDecoder {
+    /// Pick the encoding from the first chunk of the file
+    fn detect(head: &[u8]) -> Self {
+        let (mut encoding, has_bom) = Encoding::detect(head);
+        // A chunk may end mid-character; that alone doesn't make it Latin-1
+        if encoding == Encoding::Latin1 {
+            if let Err(e) = std::str::from_utf8(head) {
+                if e.error_len().is_none() {
+                    encoding = Encoding::Utf8;
+                }
+            }
+        }
+        let codec = match encoding {
+            Encoding::Utf8 => Some(encoding_rs::UTF_8),
+            Encoding::Utf16Le => Some(encoding_rs::UTF_16LE),
+            Encoding::Utf16Be => Some(encoding_rs::UTF_16BE),
+            Encoding::Latin1 => None,
+        };
+        Self {
+            encoding,
+            has_bom,
+            bom_len: if has_bom { encoding.bom().len() } else { 0 },
+            inner: codec.map(|c| c.new_decoder_without_bom_handling()),
+            had_errors: false,
+        }
+    }
+
+    /// Decode `bytes` into `out`. Malformed data becomes U+FFFD rather than
+    /// failing the whole load, since logs are often not perfectly clean; the
+    /// encoding is only sniffed from the first chunk, so later chunks may not
+    /// match it.
+    fn decode(&mut self, bytes: &[u8], out: &mut String, last: bool) {
+        match &mut self.inner {
+            Some(decoder) => {
+                let needed = decoder
+                    .max_utf8_buffer_length(bytes.len())
+                    .unwrap_or(bytes.len() * 3 + 4);
+                out.reserve(needed);
+                let (_, _, had_errors) = decoder.decode_to_string(bytes, out, last);
+                self.had_errors |= had_errors;
+            }
+            None => out.extend(bytes.iter().map(|&b| b as char)),
+        }
+    }
+}
+
+/// Why a file too big to load can't be viewed through a memory map
+#[derive(Debug, thiserror::Error)]
+pub enum UnmappableFile {
+    #[error("{} is too large to open unless it is UTF-8, not {encoding}", .path.display())]
+    Encoding { path: PathBuf, encoding: Encoding },
+    #[error("{} is too large to open with CR line endings", .0.display())]
+    CrLineEndings(PathBuf),
+}
+
+/// Sparse line index, filled in by a background thread
+#[derive(Debug, Default)]
+struct LineIndex {
+    /// Byte offset of every `LINES_PER_CHECKPOINT`th line start
+    checkpoints: Vec<usize>,
+    /// Line breaks seen so far
+    breaks: usize,
+    /// Bytes scanned so far
+    scanned: usize,
+    complete: bool,
+}
+
+/// A read-only, memory-mapped view of a file too large to load.
+///
+/// Lines are located through a sparse index built in the background, so the
+/// first screen is available immediately and jumping to a line only scans a
+/// short stretch of the file once indexing has passed it.
+pub struct MappedFile {
+    path: PathBuf,
+    map: Arc<Mmap>,
+    index: Arc<Mutex<LineIndex>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl std::fmt::Debug for MappedFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MappedFile")
+            .field("path", &self.path)
+            .field("len", &self.map.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl MappedFile {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = File::open(&path)?;
+        // SAFETY: the map is only ever read. If another process truncates the
+        // file while it is mapped, reads past the new end fault; that is the
+        // accepted trade-off for viewing files larger than memory.
+        let map = Arc::new(unsafe { Mmap::map(&file)? });
+        let head = &map[..map.len().min(CHUNK_SIZE)];
+        let encoding = ChunkDecoder::detect(head).encoding;
+        if encoding != Encoding::Utf8 {
+            return Err(UnmappableFile::Encoding { path, encoding }.into());
+        }
+        if memchr::memchr(b'\n', head).is_none() && memchr::memchr(b'\r', head).is_some() {
+            return Err(UnmappableFile::CrLineEndings(path).into());
+        }
+        let index = Arc::new(Mutex::new(LineIndex {
+            checkpoints: vec![0],
+            ..LineIndex::default()
+        }));
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let (thread_map, thread_index, thread_cancel) =
+            (map.clone(), index.clone(), cancel.clone());
+        std::thread::Builder::new()
+            .name("forge-line-index".into())
+            .spawn(move || build_index(&thread_map, &thread_index, &thread_cancel))?;
+
+        Ok(Self {
+            path,
+            map,
+            index,
+            cancel,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// Fraction of the file indexed so far, from 0.0 to 1.0
+    pub fn index_progress(&self) -> f32 {
+        let index = self.index.lock().unwrap();
+        if index.complete || self.map.is_empty() {
+            1.0
+        } else {
+            index.scanned as f32 / self.map.len() as f32
+        }
+    }
+
+    /// Number of lines, counting only what has been indexed so far
+    pub fn len_lines(&self) -> usize {
+        self.index.lock().unwrap().breaks + 1
+    }
+
+    pub fn is_indexed(&self) -> bool {
+        self.index.lock().unwrap().complete
+    }
+
+    /// Byte offset where line `line` starts, if the file has that many lines
+    pub fn line_to_byte(&self, line: usize) -> Option<usize> {
+        let (mut offset, mut current) = {
+            let index = self.index.lock().unwrap();
+            let checkpoint = (line / LINES_PER_CHECKPOINT).min(index.checkpoints.len() - 1);
+            (
+                index.checkpoints[checkpoint],
+                checkpoint * LINES_PER_CHECKPOINT,
+            )
+        };
+        while current < line {
+            let newline = memchr::memchr(b'\n', &self.map[offset..])?;
+            offset += newline + 1;
+            current += 1;
+        }
+        Some(offset)
+    }
+
+    /// Line containing byte `offset`
+    pub fn byte_to_line(&self, offset: usize) -> usize {
+        let offset = offset.min(self.map.len());
+        let (start, line) = {
+            let index = self.index.lock().unwrap();
+            let checkpoint = index
+                .checkpoints
+                .partition_point(|&c| c <= offset)
+                .saturating_sub(1);
+            (
+                index.checkpoints[checkpoint],
+                checkpoint * LINES_PER_CHECKPOINT,
+            )
+        };
+        line + memchr::memchr_iter(b'\n', &self.map[start..offset]).count()
+    }
+
+    /// Text of line `line` without its line break, decoded lossily as UTF-8
+    pub fn line(&self, line: usize) -> Option<String> {
+        let range = self.line_range(line)?;
+        Some(String::from_utf8_lossy(&self.map[range]).into_owned())
+    }
+
+    /// Byte range of line `line`, excluding its line break
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = self.line_to_byte(line)?;
+        let end = memchr::memchr(b'\n', &self.map[start..]).map_or(self.map.len(), |n| start + n);
+        let end = if end > start && self.map[end - 1] == b'\r' {
+            end - 1
+        } else {
+            end
+        };
+        Some(start..end)
+    }
+
+    /// Next match of `pattern` at or after byte `from`, wrapping around to the
+    /// start of the file if nothing follows
+    pub fn find(&self, pattern: &Regex, from: usize) -> Option<Range<usize>> {
+        find_wrapping(&self.map, pattern, from, &AtomicBool::new(false))
+    }
+
+    /// Like [`MappedFile::find`], but on a background thread so a scan of
+    /// the whole file doesn't block the caller
+    pub fn search(&self, pattern: Regex, from: usize) -> Result<MappedSearch> {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let (tx, result) = mpsc::channel();
+        let (map, thread_cancel) = (self.map.clone(), cancel.clone());
+        std::thread::Builder::new()
+            .name("forge-mapped-search".into())
+            .spawn(move || {
+                let found = find_wrapping(&map, &pattern, from, &thread_cancel);
+                let _ = tx.send(found);
+            })?;
+        Ok(MappedSearch { cancel, result })
+    }
+}
+
+/// A search of a [`MappedFile`] running on a background thread.
+///
+/// Dropping it cancels the search.
+#[derive(Debug)]
+pub struct MappedSearch {
+    cancel: Arc<AtomicBool>,
+    result: Receiver<Option<Range<usize>>>,
+}
+
+impl MappedSearch {
+    /// `None` while the search is running, then the match it found (if any)
+    pub fn try_finish(&self) -> Option<Option<Range<usize>>> {
+        match self.result.try_recv() {
+            Ok(found) => Some(found),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(None),
+        }
+    }
+}
+
+impl Drop for MappedSearch {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// First match at or after `from`, wrapping around at the end of `map`
+fn find_wrapping(
+    map: &[u8],
+    pattern: &Regex,
+    from: usize,
+    cancel: &AtomicBool,
+) -> Option<Range<usize>> {
+    let from = from.min(map.len());
+    find_between(map, pattern, from, map.len(), cancel)
+        .or_else(|| find_between(map, pattern, 0, from, cancel))
+}
+
+/// Scan `start..end` about a chunk at a time so a cancel is noticed quickly.
+/// Windows end at line breaks; only a match spanning lines across a window
+/// boundary is missed.
+fn find_between(
+    map: &[u8],
+    pattern: &Regex,
+    start: usize,
+    end: usize,
+    cancel: &AtomicBool,
+) -> Option<Range<usize>> {
+    let mut pos = start;
+    while pos < end {
+        if cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        let min_end = (pos + CHUNK_SIZE).min(end);
+        let window_end = memchr::memchr(b'\n', &map[min_end..end]).map_or(end, |i| min_end + i + 1);
+        if let Some(found) = pattern.find_at(&map[..window_end], pos) {
+            return Some(found.range());
+        }
+        pos = window_end;
+    }
+    None
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+fn build_index(map: &Mmap, index: &Mutex<LineIndex>, cancel: &AtomicBool) {
+    let mut breaks = 0;
+    let mut checkpoints = Vec::new();
+    // Publish progress one chunk at a time so the UI never waits on the lock
+    for (chunk_idx, chunk) in map.chunks(CHUNK_SIZE).enumerate() {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let base = chunk_idx * CHUNK_SIZE;
+        for newline in memchr::memchr_iter(b'\n', chunk) {
+            breaks += 1;
+            if breaks % LINES_PER_CHECKPOINT == 0 {
+                checkpoints.push(base + newline + 1);
+            }
+        }
+        let mut index = index.lock().unwrap();
+        index.checkpoints.append(&mut checkpoints);
+        index.breaks = breaks;
+        index.scanned = base + chunk.len();
+    }
+    index.lock().unwrap().complete = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_temp(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(name);
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn wait_indexed(file: &MappedFile) {
+        while !file.is_indexed() {
+            std::thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn picks_mode_by_size() {
+        let limits = LargeFileLimits {
+            threshold_bytes: 10,
+            mmap_threshold_bytes: 100,
+        };
+        assert_eq!(limits.mode_for(9), OpenMode::Normal);
+        assert_eq!(limits.mode_for(10), OpenMode::Chunked);
+        assert_eq!(limits.mode_for(100), OpenMode::Mapped);
+    }
+
+    #[test]
+    fn chunked_load_handles_split_characters() {
+        // Put a multi-byte character across the first chunk boundary
+        let mut contents = vec![b'a'; CHUNK_SIZE - 1];
+        contents.extend_from_slice("é\r\nend".as_bytes());
+        let path = write_temp("forge_chunked_load.txt", &contents);
+
+        let load = ChunkedLoad::start(&path).unwrap();
+        let loaded = load.wait().unwrap();
+        assert_eq!(loaded.encoding, Encoding::Utf8);
+        assert_eq!(loaded.line_ending, LineEnding::CRLF);
+        assert_eq!(loaded.rope.len_bytes(), contents.len());
+        assert_eq!(loaded.rope.line(1).to_string(), "end");
+        assert!(!loaded.lossy);
+        assert_eq!(loaded.hash, content_hash(&contents));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn chunked_load_reports_malformed_data_after_the_first_chunk() {
+        let mut contents = vec![b'a'; CHUNK_SIZE];
+        contents.extend_from_slice(b"\xff\n");
+        let path = write_temp("forge_chunked_load_lossy.txt", &contents);
+
+        let loaded = ChunkedLoad::start(&path).unwrap().wait().unwrap();
+        assert_eq!(loaded.encoding, Encoding::Utf8);
+        assert!(loaded.lossy);
+        assert!(loaded.rope.to_string().ends_with("\u{FFFD}\n"));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn mapped_file_finds_lines_and_matches() {
+        let contents: String = (0..5000).map(|i| format!("line {}\n", i)).collect();
+        let path = write_temp("forge_mapped_file.txt", contents.as_bytes());
+        let file = MappedFile::open(&path).unwrap();
+        assert_eq!(file.line(0).as_deref(), Some("line 0"));
+        wait_indexed(&file);
+
+        assert_eq!(file.len_lines(), 5001);
+        assert_eq!(file.line(4321).as_deref(), Some("line 4321"));
+        assert_eq!(file.line(5000).as_deref(), Some(""));
+        assert_eq!(file.line(5001), None);
+
+        let pattern = Regex::new(r"line 30\d\d").unwrap();
+        let found = file.find(&pattern, 0).unwrap();
+        assert_eq!(file.byte_to_line(found.start), 3000);
+        let wrapped = file.find(&pattern, file.len_bytes()).unwrap();
+        assert_eq!(wrapped, found);
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn mapped_files_must_be_utf8_split_at_newlines() {
+        let path = write_temp("forge_mapped_utf16.txt", b"\xff\xfea\x00\n\x00");
+        let err = MappedFile::open(&path).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(UnmappableFile::Encoding {
+                encoding: Encoding::Utf16Le,
+                ..
+            })
+        ));
+        std::fs::write(&path, "one\rtwo\r").unwrap();
+        let err = MappedFile::open(&path).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref(),
+            Some(UnmappableFile::CrLineEndings(_))
+        ));
+        std::fs::write(&path, "one\r\ntwo\r\n").unwrap();
+        assert_eq!(
+            MappedFile::open(&path).unwrap().line(1).as_deref(),
+            Some("two")
+        );
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn background_search_scans_past_the_first_window() {
+        let mut contents = "filler line\n".repeat(CHUNK_SIZE / 6);
+        contents.push_str("needle\n");
+        let path = write_temp("forge_mapped_search.txt", contents.as_bytes());
+        let file = MappedFile::open(&path).unwrap();
+
+        let search = file.search(Regex::new("needle").unwrap(), 10).unwrap();
+        let found = loop {
+            if let Some(found) = search.try_finish() {
+                break found;
+            }
+            std::thread::yield_now();
+        };
+        let start = contents.len() - "needle\n".len();
+        assert_eq!(found, Some(start..start + 6));
+        std::fs::remove_file(path).ok();
+    }
+}
@@ -3,28 +3,55 @@
 //! This is the heart of the Forge editor. Every text manipulation flows through this crate.
 
 mod buffer;
+pub mod collab;
+pub mod coords;
+pub mod display_map;
+mod encoding;
 pub mod file_io;
+pub mod file_watch;
 pub mod git;
 mod history;
+pub mod large_file;
 pub mod layout;
+pub mod line_ending;
+pub mod merge;
+pub mod motion;
+pub mod multi_buffer;
 mod position;
 pub mod project;
 pub mod recovery;
+pub mod search;
 mod selection;
 pub mod syntax;
+pub mod syntax_selection;
 pub mod terminal;
 mod transaction;
+mod undo_group;
+pub mod undo_store;
 
 pub use buffer::Buffer;
+pub use collab::{Room, Session};
+pub use coords::{ColumnUnit, LineCol, LineEdit};
+pub use display_map::{DisplayMap, DisplayOptions, DisplayPoint, InlayKind};
+pub use encoding::Decoded;
+pub use file_io::{SaveError, SaveOptions};
+pub use file_watch::{FileEvent, FileWatcher};
 pub use git::GitIntegration;
 pub use history::{History, HistoryNode};
+pub use large_file::{LargeFileLimits, MappedFile, MappedSearch, OpenMode, UnmappableFile};
 pub use layout::Layout;
+pub use line_ending::{LineEndingCounts, LineEndingPolicy};
+pub use merge::{ConflictSide, ExternalChange};
+pub use multi_buffer::{Excerpt, MultiBuffer};
 pub use position::Position;
-pub use project::Project;
+pub use project::{Project, ProjectKind, ProjectLimits, ProjectWatcher, SubProject};
+pub use recovery::{Journal, JournalInfo, RecoveryManager};
+pub use search::{IncrementalSearch, SearchOptions, Searcher};
 pub use selection::{Range, Selection};
-pub use syntax::Syntax;
+pub use syntax::{Syntax, SyntaxChanges};
 pub use terminal::Terminal;
-pub use transaction::{Change, ChangeSet, Transaction};
+pub use transaction::{Assoc, Change, ChangeSet, Transaction};
+pub use undo_store::{UndoRestore, UndoStore, UndoStoreLimits};
 
 /// Line ending styles
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,256 @@
+//! Line ending detection, statistics and conversion.
+
+use crate::{Change, ChangeSet, LineEnding, Position};
+use ropey::{Rope, RopeSlice};
+
+/// How line endings are normalized when a buffer is saved
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineEndingPolicy {
+    /// Write line endings exactly as they appear in the buffer
+    #[default]
+    Preserve,
+    /// Convert every line ending to LF before saving
+    Lf,
+    /// Convert every line ending to CRLF before saving
+    Crlf,
+}
+
+impl LineEndingPolicy {
+    /// All policies, in picker order.
+    pub const ALL: [LineEndingPolicy; 3] = [
+        LineEndingPolicy::Preserve,
+        LineEndingPolicy::Lf,
+        LineEndingPolicy::Crlf,
+    ];
+
+    /// The line ending this policy forces, if any
+    pub fn target(&self) -> Option<LineEnding> {
+        match self {
+            LineEndingPolicy::Preserve => None,
+            LineEndingPolicy::Lf => Some(LineEnding::LF),
+            LineEndingPolicy::Crlf => Some(LineEnding::CRLF),
+        }
+    }
+
+    /// Stable identifier used in command ids and settings
+    pub fn id(&self) -> &'static str {
+        match self {
+            LineEndingPolicy::Preserve => "preserve",
+            LineEndingPolicy::Lf => "lf",
+            LineEndingPolicy::Crlf => "crlf",
+        }
+    }
+
+    /// Parse an identifier produced by [`LineEndingPolicy::id`]
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|p| p.id() == id)
+    }
+}
+
+impl LineEnding {
+    /// Detect the system's default line ending
+    pub(crate) fn detect_system() -> Self {
+        #[cfg(windows)]
+        return LineEnding::CRLF;
+        #[cfg(not(windows))]
+        return LineEnding::LF;
+    }
+
+    /// Detect line ending from a string
+    pub(crate) fn detect_from_str(s: &str) -> Self {
+        if s.contains("\r\n") {
+            LineEnding::CRLF
+        } else if s.contains('\r') {
+            LineEnding::CR
+        } else {
+            LineEnding::LF
+        }
+    }
+
+    /// The character sequence for this line ending
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::LF => "\n",
+            LineEnding::CRLF => "\r\n",
+            LineEnding::CR => "\r",
+        }
+    }
+
+    /// Short label for the status bar
+    pub fn label(&self) -> &'static str {
+        match self {
+            LineEnding::LF => "LF",
+            LineEnding::CRLF => "CRLF",
+            LineEnding::CR => "CR",
+        }
+    }
+
+    /// Build the changes that rewrite every line break in `rope` to this style.
+    ///
+    /// Returns an empty set if nothing needs converting.
+    pub fn conversion_changes(&self, rope: &Rope) -> ChangeSet {
+        let target = self.as_str();
+        let mut changes = ChangeSet::new();
+        for (start, end) in line_breaks(rope.slice(..)) {
+            let len = end - start;
+            let is_target = len == target.len()
+                && rope.byte(start) == target.as_bytes()[0]
+                && rope.byte(end - 1) == target.as_bytes()[len - 1];
+            if !is_target {
+                changes.add(Change::replace(
+                    Position::new(start),
+                    Position::new(end),
+                    target.to_string(),
+                ));
+            }
+        }
+        changes
+    }
+}
+
+/// Counts of each line ending style in a document
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineEndingCounts {
+    pub lf: usize,
+    pub crlf: usize,
+    pub cr: usize,
+}
+
+impl LineEndingCounts {
+    /// Count the line endings in a rope
+    pub fn scan(rope: &Rope) -> Self {
+        Self::scan_slice(rope.slice(..))
+    }
+
+    fn scan_slice(slice: RopeSlice) -> Self {
+        let mut counts = Self::default();
+        for (start, end) in line_breaks(slice) {
+            match (end - start, slice.byte(start)) {
+                (2, _) => counts.crlf += 1,
+                (_, b'\r') => counts.cr += 1,
+                _ => counts.lf += 1,
+            }
+        }
+        counts
+    }
+
+    /// Count the line endings an edit of `start..end` can change: those in
+    /// the range, and a `\r` before it or `\n` after it that may pair up
+    /// with what the edit leaves
+    pub(crate) fn scan_around(rope: &Rope, start: usize, end: usize) -> Self {
+        let start = match start.checked_sub(1) {
+            Some(before) if rope.byte(before) == b'\r' => before,
+            _ => start,
+        };
+        let end = if end < rope.len_bytes() && rope.byte(end) == b'\n' {
+            end + 1
+        } else {
+            end
+        };
+        Self::scan_slice(rope.byte_slice(start..end))
+    }
+
+    pub(crate) fn add(&mut self, other: Self) {
+        self.lf += other.lf;
+        self.crlf += other.crlf;
+        self.cr += other.cr;
+    }
+
+    pub(crate) fn remove(&mut self, other: Self) {
+        self.lf -= other.lf;
+        self.crlf -= other.crlf;
+        self.cr -= other.cr;
+    }
+
+    /// True if more than one line ending style is present
+    pub fn is_mixed(&self) -> bool {
+        [self.lf, self.crlf, self.cr]
+            .iter()
+            .filter(|&&n| n > 0)
+            .count()
+            > 1
+    }
+
+    /// The most common line ending, or `None` for a single-line document
+    pub fn dominant(&self) -> Option<LineEnding> {
+        [
+            (self.lf, LineEnding::LF),
+            (self.crlf, LineEnding::CRLF),
+            (self.cr, LineEnding::CR),
+        ]
+        .into_iter()
+        .filter(|(n, _)| *n > 0)
+        .max_by_key(|(n, _)| *n)
+        .map(|(_, ending)| ending)
+    }
+}
+
+/// Byte ranges of every line break (`\n`, `\r\n` or lone `\r`) in a rope.
+fn line_breaks(rope: RopeSlice) -> Vec<(usize, usize)> {
+    let mut breaks = Vec::new();
+    let mut pending_cr: Option<usize> = None;
+    let mut offset = 0;
+    // Walk chunks directly; a CRLF pair may straddle a chunk boundary.
+    for chunk in rope.chunks() {
+        for &b in chunk.as_bytes() {
+            match (b, pending_cr.take()) {
+                (b'\n', Some(cr)) => breaks.push((cr, offset + 1)),
+                (b'\n', None) => breaks.push((offset, offset + 1)),
+                (b'\r', prev) => {
+                    if let Some(cr) = prev {
+                        breaks.push((cr, cr + 1));
+                    }
+                    pending_cr = Some(offset);
+                }
+                (_, Some(cr)) => breaks.push((cr, cr + 1)),
+                (_, None) => {}
+            }
+            offset += 1;
+        }
+    }
+    if let Some(cr) = pending_cr {
+        breaks.push((cr, cr + 1));
+    }
+    breaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_mixed_endings() {
+        let rope = Rope::from_str("a\r\nb\nc\rd\r\n");
+        let counts = LineEndingCounts::scan(&rope);
+        assert_eq!(
+            counts,
+            LineEndingCounts {
+                lf: 1,
+                crlf: 2,
+                cr: 1
+            }
+        );
+        assert!(counts.is_mixed());
+        assert_eq!(counts.dominant(), Some(LineEnding::CRLF));
+    }
+
+    #[test]
+    fn uniform_endings_are_not_mixed() {
+        let counts = LineEndingCounts::scan(&Rope::from_str("a\nb\n"));
+        assert!(!counts.is_mixed());
+        assert_eq!(
+            LineEndingCounts::scan(&Rope::from_str("abc")).dominant(),
+            None
+        );
+    }
+
+    #[test]
+    fn conversion_to_crlf() {
+        let mut rope = Rope::from_str("a\nb\r\nc\r");
+        let changes = LineEnding::CRLF.conversion_changes(&rope);
+        assert_eq!(changes.changes.len(), 2);
+        changes.apply(&mut rope);
+        assert_eq!(rope.to_string(), "a\r\nb\r\nc\r\n");
+        assert!(LineEnding::CRLF.conversion_changes(&rope).is_empty());
+    }
+}
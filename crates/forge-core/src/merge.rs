@@ -0,0 +1,298 @@
+//! Line-based diffing and three-way merging.
+//!
+//! Used to fold external edits to a file into a buffer that has unsaved
+//! changes of its own: the text last loaded from disk is the common base,
+//! the buffer is "ours" and the new disk contents are "theirs".
+
+use crate::{Change, ChangeSet, Position};
+
+/// Marker lines around an unresolved conflict
+pub const CONFLICT_START: &str = "<<<<<<< buffer";
+pub const CONFLICT_SEPARATOR: &str = "=======";
+pub const CONFLICT_END: &str = ">>>>>>> disk";
+
+/// Above this many line pairs the middle of a diff is treated as one change
+/// instead of running the quadratic LCS
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// Result of a three-way merge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeResult {
+    pub text: String,
+    /// Regions changed on both sides, written out between conflict markers
+    pub conflicts: usize,
+}
+
+/// What happened when a buffer caught up with its file on disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalChange {
+    /// The file matches what the buffer last loaded or saved
+    Unchanged,
+    /// The buffer had no unsaved changes and now shows the new contents
+    Reloaded,
+    /// Unsaved changes and external changes were combined cleanly
+    Merged,
+    /// Both sides changed the same lines; this many conflicts await resolution
+    Conflicts(usize),
+    /// The file was deleted; the buffer is left as it was
+    Deleted,
+    /// The buffer is still loading the file; sync again once it has
+    Loading,
+}
+
+/// Which side of a conflict to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictSide {
+    /// The buffer's unsaved version
+    Buffer,
+    /// The version on disk
+    Disk,
+}
+
+/// Merge `ours` and `theirs`, both derived from `base`.
+///
+/// Regions changed on only one side take that side's text; regions changed
+/// identically on both sides are taken once; anything else becomes a
+/// conflict, with both versions written between conflict markers.
+pub fn merge3(base: &str, ours: &str, theirs: &str) -> MergeResult {
+    let base_lines = lines(base);
+    let our_lines = lines(ours);
+    let their_lines = lines(theirs);
+    let to_ours = match_lines(&base_lines, &our_lines);
+    let to_theirs = match_lines(&base_lines, &their_lines);
+
+    let mut text = String::with_capacity(ours.len().max(theirs.len()));
+    let mut conflicts = 0;
+    let (mut b, mut o, mut t) = (0, 0, 0);
+    while b < base_lines.len() || o < our_lines.len() || t < their_lines.len() {
+        // Lines unchanged on both sides are copied through
+        if b < base_lines.len() && to_ours[b] == Some(o) && to_theirs[b] == Some(t) {
+            text.push_str(base_lines[b]);
+            b += 1;
+            o += 1;
+            t += 1;
+            continue;
+        }
+        // Otherwise find the next base line both sides kept, and merge the
+        // chunk up to it
+        let (next_b, next_o, next_t) = (b..base_lines.len())
+            .find_map(|i| Some((i, to_ours[i]?, to_theirs[i]?)))
+            .unwrap_or((base_lines.len(), our_lines.len(), their_lines.len()));
+        let base_chunk = &base_lines[b..next_b];
+        let our_chunk = &our_lines[o..next_o];
+        let their_chunk = &their_lines[t..next_t];
+
+        if our_chunk == base_chunk || our_chunk == their_chunk {
+            text.extend(their_chunk.iter().copied());
+        } else if their_chunk == base_chunk {
+            text.extend(our_chunk.iter().copied());
+        } else {
+            conflicts += 1;
+            push_conflict(&mut text, our_chunk, their_chunk);
+        }
+        (b, o, t) = (next_b, next_o, next_t);
+    }
+    MergeResult { text, conflicts }
+}
+
+fn push_conflict(text: &mut String, ours: &[&str], theirs: &[&str]) {
+    fn push_side(text: &mut String, side: &[&str]) {
+        side.iter().for_each(|line| text.push_str(line));
+        // Keep the marker on its own line even if the file lacks a final newline
+        if side.last().is_some_and(|l| !l.ends_with('\n')) {
+            text.push('\n');
+        }
+    }
+    text.push_str(CONFLICT_START);
+    text.push('\n');
+    push_side(text, ours);
+    text.push_str(CONFLICT_SEPARATOR);
+    text.push('\n');
+    push_side(text, theirs);
+    text.push_str(CONFLICT_END);
+    text.push('\n');
+}
+
+/// Changes that turn `old` into `new`, touching only the lines that differ.
+///
+/// Offsets are in `old`'s coordinates, so the result can be applied as one
+/// transaction and cursors outside the changed lines stay where they are.
+pub fn diff_changes(old: &str, new: &str) -> ChangeSet {
+    let old_lines = lines(old);
+    let new_lines = lines(new);
+    let matches = match_lines(&old_lines, &new_lines);
+
+    let mut changes = ChangeSet::new();
+    let (mut old_offset, mut new_offset) = (0, 0);
+    let (mut i, mut j) = (0, 0);
+    while i < old_lines.len() || j < new_lines.len() {
+        if i < old_lines.len() && matches[i] == Some(j) {
+            old_offset += old_lines[i].len();
+            new_offset += new_lines[j].len();
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let (next_i, next_j) = (i..old_lines.len())
+            .find_map(|k| Some((k, matches[k]?)))
+            .unwrap_or((old_lines.len(), new_lines.len()));
+        let old_len: usize = old_lines[i..next_i].iter().map(|l| l.len()).sum();
+        let new_len: usize = new_lines[j..next_j].iter().map(|l| l.len()).sum();
+        let inserted = &new[new_offset..new_offset + new_len];
+        changes.add(Change::replace(
+            Position::new(old_offset),
+            Position::new(old_offset + old_len),
+            inserted.to_string(),
+        ));
+        old_offset += old_len;
+        new_offset += new_len;
+        (i, j) = (next_i, next_j);
+    }
+    changes
+}
+
+/// Replace the conflict block containing byte `offset` in `text` with one side.
+///
+/// Returns the change to apply, or `None` if `offset` is not inside a conflict.
+pub fn resolve_conflict(text: &str, offset: usize, side: ConflictSide) -> Option<Change> {
+    let mut line_start = 0;
+    let mut block: Option<(usize, Option<usize>)> = None;
+    for line in text.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        let content = line.trim_end_matches(['\r', '\n']);
+        match (content, block) {
+            (CONFLICT_START, _) => block = Some((line_start, None)),
+            (CONFLICT_SEPARATOR, Some((start, None))) => block = Some((start, Some(line_start))),
+            (CONFLICT_END, Some((start, Some(separator)))) => {
+                if (start..line_end).contains(&offset) {
+                    let ours_start = start + text[start..].find('\n')? + 1;
+                    let theirs_start = separator + text[separator..].find('\n')? + 1;
+                    let kept = match side {
+                        ConflictSide::Buffer => &text[ours_start..separator],
+                        ConflictSide::Disk => &text[theirs_start..line_start],
+                    };
+                    return Some(Change::replace(
+                        Position::new(start),
+                        Position::new(line_end),
+                        kept.to_string(),
+                    ));
+                }
+                block = None;
+            }
+            _ => {}
+        }
+        line_start = line_end;
+    }
+    None
+}
+
+/// Whether `text` still contains a conflict block
+pub fn has_conflicts(text: &str) -> bool {
+    let mut lines = text.lines();
+    lines.any(|l| l == CONFLICT_START)
+        && lines.any(|l| l == CONFLICT_SEPARATOR)
+        && lines.any(|l| l == CONFLICT_END)
+}
+
+/// Lines including their terminators
+fn lines(text: &str) -> Vec<&str> {
+    text.split_inclusive('\n').collect()
+}
+
+/// For each line of `a`, the line of `b` it is matched with in a longest
+/// common subsequence (or `None` if it was removed).
+fn match_lines(a: &[&str], b: &[&str]) -> Vec<Option<usize>> {
+    let mut matches = vec![None; a.len()];
+    let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    for (i, m) in matches.iter_mut().enumerate().take(prefix) {
+        *m = Some(i);
+    }
+    for k in 0..suffix {
+        matches[a.len() - 1 - k] = Some(b.len() - 1 - k);
+    }
+
+    let a_mid = &a[prefix..a.len() - suffix];
+    let b_mid = &b[prefix..b.len() - suffix];
+    if a_mid.is_empty() || b_mid.is_empty() || a_mid.len() * b_mid.len() > MAX_LCS_CELLS {
+        return matches;
+    }
+
+    // lcs[i][j]: LCS length of a_mid[i..] and b_mid[j..]
+    let width = b_mid.len() + 1;
+    let mut lcs = vec![0u32; (a_mid.len() + 1) * width];
+    for i in (0..a_mid.len()).rev() {
+        for j in (0..b_mid.len()).rev() {
+            lcs[i * width + j] = if a_mid[i] == b_mid[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    while i < a_mid.len() && j < b_mid.len() {
+        if a_mid[i] == b_mid[j] {
+            matches[prefix + i] = Some(prefix + j);
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ropey::Rope;
+
+    #[test]
+    fn merges_non_overlapping_edits() {
+        let base = "a\nb\nc\nd\n";
+        let ours = "a\nB\nc\nd\n";
+        let theirs = "a\nb\nc\nD\ne\n";
+        let merged = merge3(base, ours, theirs);
+        assert_eq!(merged.text, "a\nB\nc\nD\ne\n");
+        assert_eq!(merged.conflicts, 0);
+    }
+
+    #[test]
+    fn conflicting_edits_get_markers() {
+        let merged = merge3("a\nb\nc\n", "a\nours\nc\n", "a\ntheirs\nc\n");
+        assert_eq!(merged.conflicts, 1);
+        assert_eq!(
+            merged.text,
+            "a\n<<<<<<< buffer\nours\n=======\ntheirs\n>>>>>>> disk\nc\n"
+        );
+        assert!(has_conflicts(&merged.text));
+
+        let offset = merged.text.find("theirs").unwrap();
+        let change = resolve_conflict(&merged.text, offset, ConflictSide::Disk).unwrap();
+        let mut rope = Rope::from_str(&merged.text);
+        change.apply(&mut rope);
+        assert_eq!(rope.to_string(), "a\ntheirs\nc\n");
+        assert_eq!(resolve_conflict("a\n", 0, ConflictSide::Buffer), None);
+    }
+
+    #[test]
+    fn diff_changes_only_touch_changed_lines() {
+        let old = "one\ntwo\nthree\nfour";
+        let new = "one\n2\nthree\nfour\nfive\n";
+        let changes = diff_changes(old, new);
+        assert_eq!(changes.changes.len(), 2);
+        assert_eq!(changes.changes[0].start.offset, 4);
+
+        let mut rope = Rope::from_str(old);
+        changes.apply(&mut rope);
+        assert_eq!(rope.to_string(), new);
+    }
+}
@@ -0,0 +1,752 @@
+//! Cursor motions and text objects.
+//!
+//! Motions move a position by words, sentences or paragraphs; text objects
+//! find the word, bracket pair, function, ... around a range. Both work on
+//! the rope in byte offsets, like the rest of the buffer, and [`Buffer`]
+//! applies them to every cursor of its selection.
+//!
+//! [`Buffer`]: crate::Buffer
+
+use crate::syntax_selection::Enclosing;
+use crate::{Position, Range};
+use ropey::Rope;
+use tree_sitter::{Node, Tree};
+
+/// What a motion moves over
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Runs of identifier characters, or of punctuation
+    Word,
+    /// Runs of anything but whitespace
+    BigWord,
+    /// Parts of camelCase and snake_case identifiers
+    Subword,
+    /// Text up to a `.`, `!` or `?` followed by whitespace
+    Sentence,
+    /// Lines between blank lines
+    Paragraph,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// To the start of the next unit
+    NextStart(Unit),
+    /// To the start of the unit, or of the previous one when already there
+    PrevStart(Unit),
+    /// To the end of the unit, or of the next one when already there
+    NextEnd(Unit),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextObject {
+    Word,
+    BigWord,
+    Subword,
+    Sentence,
+    Paragraph,
+    /// The innermost pair of the given brackets, e.g. `('(', ')')`
+    Bracket(char, char),
+    /// The innermost pair of `()`, `[]` or `{}`
+    AnyBracket,
+    /// A pair of the given quote on the current line, or the next one
+    Quote(char),
+    /// The innermost pair of `"`, `'` or `` ` `` on the current line
+    AnyQuote,
+    /// Needs a syntax tree
+    Function,
+    /// Needs a syntax tree
+    Class,
+    /// An argument or parameter; needs a syntax tree
+    Argument,
+}
+
+/// How much of a text object to select
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scope {
+    /// Only the contents: a word without surrounding space, the text between
+    /// brackets, a function's body
+    Inside,
+    /// The whole object with its delimiters, plus trailing whitespace or
+    /// separator where that makes sense
+    Around,
+}
+
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+const QUOTES: [char; 3] = ['"', '\'', '`'];
+
+/// Where `motion` takes the cursor at `pos`
+pub fn apply(rope: &Rope, pos: Position, motion: Motion) -> Position {
+    let i = rope.byte_to_char(pos.offset.min(rope.len_bytes()));
+    let len = rope.len_chars();
+    let target = match motion {
+        Motion::NextStart(Unit::Paragraph) => next_paragraph_start(rope, i),
+        Motion::PrevStart(Unit::Paragraph) => prev_paragraph_start(rope, i),
+        Motion::NextEnd(Unit::Paragraph) => next_paragraph_end(rope, i),
+        Motion::NextStart(unit) => (i + 1..=len)
+            .find(|&j| is_start(rope, j, unit))
+            .unwrap_or(len),
+        Motion::PrevStart(unit) => (0..i).rev().find(|&j| is_start(rope, j, unit)).unwrap_or(0),
+        Motion::NextEnd(unit) => (i + 1..=len)
+            .find(|&j| is_end(rope, j, unit))
+            .unwrap_or(len),
+    };
+    Position::new(rope.char_to_byte(target))
+}
+
+/// The `object` around `range`, if there is one.
+///
+/// When `range` already covers exactly that object, the next larger one is
+/// returned, so repeating a text object grows the selection outward.
+pub fn text_object(
+    rope: &Rope,
+    tree: Option<&Tree>,
+    range: &Range,
+    object: TextObject,
+    scope: Scope,
+) -> Option<Range> {
+    let (start, end) = (
+        rope.byte_to_char(range.start().offset),
+        rope.byte_to_char(range.end().offset),
+    );
+    let chars = match object {
+        TextObject::Word => word_object(rope, start, Unit::Word, scope),
+        TextObject::BigWord => word_object(rope, start, Unit::BigWord, scope),
+        TextObject::Subword => word_object(rope, start, Unit::Subword, scope),
+        TextObject::Sentence => sentence_object(rope, start, scope),
+        TextObject::Paragraph => paragraph_object(rope, start, scope),
+        TextObject::Bracket(open, close) => bracket_object(rope, (start, end), open, close, scope),
+        TextObject::AnyBracket => BRACKETS
+            .iter()
+            .filter_map(|&(open, close)| bracket_object(rope, (start, end), open, close, scope))
+            .min_by_key(|(s, e)| e - s),
+        TextObject::Quote(quote) => quote_object(rope, (start, end), quote, scope, false)
+            .or_else(|| quote_object(rope, (start, end), quote, scope, true)),
+        TextObject::AnyQuote => QUOTES
+            .iter()
+            .filter_map(|&quote| quote_object(rope, (start, end), quote, scope, false))
+            .min_by_key(|(s, e)| e - s)
+            .or_else(|| {
+                QUOTES
+                    .iter()
+                    .filter_map(|&quote| quote_object(rope, (start, end), quote, scope, true))
+                    .min_by_key(|&(s, _)| s)
+            }),
+        TextObject::Function | TextObject::Class | TextObject::Argument => {
+            let bytes = syntax_object(tree?, range, object, scope)?;
+            return Some(Range::new(Position::new(bytes.0), Position::new(bytes.1)));
+        }
+    }?;
+    Some(Range::new(
+        Position::new(rope.char_to_byte(chars.0)),
+        Position::new(rope.char_to_byte(chars.1)),
+    ))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Space,
+    Word,
+    Punct,
+}
+
+fn class(c: char, unit: Unit) -> Class {
+    if c.is_whitespace() || (unit == Unit::Subword && c == '_') {
+        Class::Space
+    } else if unit == Unit::BigWord || c.is_alphanumeric() || c == '_' {
+        Class::Word
+    } else {
+        Class::Punct
+    }
+}
+
+fn char_at(rope: &Rope, i: usize) -> Option<char> {
+    rope.get_char(i)
+}
+
+/// Whether a word-like unit may start or end between chars `i - 1` and `i`
+fn boundary(rope: &Rope, i: usize, unit: Unit) -> bool {
+    let (Some(a), Some(b)) = (
+        i.checked_sub(1).and_then(|p| char_at(rope, p)),
+        char_at(rope, i),
+    ) else {
+        return true;
+    };
+    if class(a, unit) != class(b, unit) {
+        return true;
+    }
+    unit == Unit::Subword
+        && class(a, unit) == Class::Word
+        && b.is_uppercase()
+        && (!a.is_uppercase() || char_at(rope, i + 1).is_some_and(char::is_lowercase))
+}
+
+fn is_start(rope: &Rope, i: usize, unit: Unit) -> bool {
+    match unit {
+        Unit::Sentence => is_sentence_start(rope, i),
+        Unit::Paragraph => is_paragraph_start(rope, i),
+        _ => {
+            char_at(rope, i).is_some_and(|c| class(c, unit) != Class::Space)
+                && boundary(rope, i, unit)
+        }
+    }
+}
+
+fn is_end(rope: &Rope, i: usize, unit: Unit) -> bool {
+    match unit {
+        Unit::Sentence => is_sentence_end(rope, i),
+        Unit::Paragraph => is_paragraph_end(rope, i),
+        _ => {
+            i.checked_sub(1)
+                .and_then(|p| char_at(rope, p))
+                .is_some_and(|c| class(c, unit) != Class::Space)
+                && boundary(rope, i, unit)
+        }
+    }
+}
+
+fn is_blank_line(rope: &Rope, line: usize) -> bool {
+    rope.line(line).chars().all(char::is_whitespace)
+}
+
+/// Char index of the end of a line's text, before its line break
+fn line_content_end(rope: &Rope, line: usize) -> usize {
+    let slice = rope.line(line);
+    let trailing = slice
+        .chars_at(slice.len_chars())
+        .reversed()
+        .take_while(|&c| c == '\n' || c == '\r')
+        .count();
+    rope.line_to_char(line) + slice.len_chars() - trailing
+}
+
+/// Lines in the rope, not counting the empty one after a final newline
+fn line_count(rope: &Rope) -> usize {
+    let lines = rope.len_lines();
+    if lines > 1 && rope.line(lines - 1).len_chars() == 0 {
+        lines - 1
+    } else {
+        lines
+    }
+}
+
+fn is_paragraph_first_line(rope: &Rope, line: usize) -> bool {
+    !is_blank_line(rope, line) && (line == 0 || is_blank_line(rope, line - 1))
+}
+
+fn is_paragraph_last_line(rope: &Rope, line: usize) -> bool {
+    !is_blank_line(rope, line) && (line + 1 >= line_count(rope) || is_blank_line(rope, line + 1))
+}
+
+fn is_paragraph_start(rope: &Rope, i: usize) -> bool {
+    if i >= rope.len_chars() {
+        return false;
+    }
+    let line = rope.char_to_line(i);
+    rope.line_to_char(line) == i && is_paragraph_first_line(rope, line)
+}
+
+fn is_paragraph_end(rope: &Rope, i: usize) -> bool {
+    let line = rope.char_to_line(i);
+    line_content_end(rope, line) == i && is_paragraph_last_line(rope, line)
+}
+
+fn next_paragraph_start(rope: &Rope, i: usize) -> usize {
+    (rope.char_to_line(i) + 1..line_count(rope))
+        .find(|&line| is_paragraph_first_line(rope, line))
+        .map_or(rope.len_chars(), |line| rope.line_to_char(line))
+}
+
+fn prev_paragraph_start(rope: &Rope, i: usize) -> usize {
+    (0..=rope.char_to_line(i))
+        .rev()
+        .find(|&line| rope.line_to_char(line) < i && is_paragraph_first_line(rope, line))
+        .map_or(0, |line| rope.line_to_char(line))
+}
+
+fn next_paragraph_end(rope: &Rope, i: usize) -> usize {
+    (rope.char_to_line(i)..line_count(rope))
+        .find(|&line| line_content_end(rope, line) > i && is_paragraph_last_line(rope, line))
+        .map_or(rope.len_chars(), |line| line_content_end(rope, line))
+}
+
+fn is_terminator(c: char) -> bool {
+    matches!(c, '.' | '!' | '?')
+}
+
+/// Closing quotes and brackets that may follow a sentence's full stop
+fn is_closer(c: char) -> bool {
+    matches!(c, ')' | ']' | '"' | '\'')
+}
+
+fn is_sentence_start(rope: &Rope, i: usize) -> bool {
+    let Some(c) = char_at(rope, i) else {
+        return false;
+    };
+    if c.is_whitespace() {
+        return false;
+    }
+    if i == 0 || is_paragraph_start(rope, i) {
+        return true;
+    }
+    let mut j = i;
+    while j > 0 && char_at(rope, j - 1).is_some_and(char::is_whitespace) {
+        j -= 1;
+    }
+    if j == i {
+        return false;
+    }
+    if j == 0 || is_paragraph_end(rope, j) {
+        return true;
+    }
+    while j > 0 && char_at(rope, j - 1).is_some_and(is_closer) {
+        j -= 1;
+    }
+    j > 0 && char_at(rope, j - 1).is_some_and(is_terminator)
+}
+
+fn is_sentence_end(rope: &Rope, i: usize) -> bool {
+    if i == 0 || char_at(rope, i).is_some_and(|c| !c.is_whitespace()) {
+        return false;
+    }
+    if is_paragraph_end(rope, i) {
+        return true;
+    }
+    let mut j = i;
+    while j > 0 && char_at(rope, j - 1).is_some_and(is_closer) {
+        j -= 1;
+    }
+    j > 0 && char_at(rope, j - 1).is_some_and(is_terminator)
+}
+
+/// Extend `end` over spaces and tabs, or failing that `start` backwards
+fn with_blanks(rope: &Rope, (start, mut end): (usize, usize)) -> (usize, usize) {
+    let is_blank = |c: char| c == ' ' || c == '\t';
+    let before = end;
+    while char_at(rope, end).is_some_and(is_blank) {
+        end += 1;
+    }
+    let mut start = start;
+    if end == before {
+        while start > 0 && char_at(rope, start - 1).is_some_and(is_blank) {
+            start -= 1;
+        }
+    }
+    (start, end)
+}
+
+fn word_object(rope: &Rope, i: usize, unit: Unit, scope: Scope) -> Option<(usize, usize)> {
+    let len = rope.len_chars();
+    if len == 0 {
+        return None;
+    }
+    // At the end of the text, or of a word, take the word just before
+    let mut i = i.min(len - 1);
+    if i > 0
+        && char_at(rope, i).is_some_and(|c| class(c, unit) == Class::Space)
+        && char_at(rope, i - 1).is_some_and(|c| class(c, unit) != Class::Space)
+    {
+        i -= 1;
+    }
+    let (mut start, mut end) = (i, i + 1);
+    while start > 0 && !boundary(rope, start, unit) {
+        start -= 1;
+    }
+    while end < len && !boundary(rope, end, unit) {
+        end += 1;
+    }
+    let is_space = char_at(rope, i).is_some_and(|c| class(c, unit) == Class::Space);
+    Some(match scope {
+        Scope::Around if !is_space => with_blanks(rope, (start, end)),
+        _ => (start, end),
+    })
+}
+
+fn sentence_object(rope: &Rope, i: usize, scope: Scope) -> Option<(usize, usize)> {
+    let len = rope.len_chars();
+    let start = (0..=i.min(len))
+        .rev()
+        .find(|&j| is_sentence_start(rope, j))?;
+    let end = (i.max(start) + 1..=len)
+        .find(|&j| is_sentence_end(rope, j))
+        .unwrap_or(len);
+    Some(match scope {
+        Scope::Inside => (start, end),
+        Scope::Around => with_blanks(rope, (start, end)),
+    })
+}
+
+fn paragraph_object(rope: &Rope, i: usize, scope: Scope) -> Option<(usize, usize)> {
+    let lines = line_count(rope);
+    let line = rope.char_to_line(i).min(lines - 1);
+    let blank = is_blank_line(rope, line);
+    let same = |l: usize| is_blank_line(rope, l) == blank;
+    let (mut first, mut last) = (line, line);
+    while first > 0 && same(first - 1) {
+        first -= 1;
+    }
+    while last + 1 < lines && same(last + 1) {
+        last += 1;
+    }
+    if scope == Scope::Around && !blank {
+        if last + 1 < lines {
+            while last + 1 < lines && is_blank_line(rope, last + 1) {
+                last += 1;
+            }
+        } else {
+            while first > 0 && is_blank_line(rope, first - 1) {
+                first -= 1;
+            }
+        }
+    }
+    let end = if last + 1 < rope.len_lines() {
+        rope.line_to_char(last + 1)
+    } else {
+        rope.len_chars()
+    };
+    Some((rope.line_to_char(first), end))
+}
+
+/// Pick the first candidate that covers `range` and is larger than it,
+/// from a list ordered innermost first
+fn grow(
+    range: (usize, usize),
+    candidates: impl IntoIterator<Item = (usize, usize)>,
+) -> Option<(usize, usize)> {
+    candidates
+        .into_iter()
+        .find(|&(s, e)| s <= range.0 && range.1 <= e && (s, e) != range)
+}
+
+fn scoped(pair: (usize, usize), delimiter: usize, scope: Scope) -> (usize, usize) {
+    match scope {
+        Scope::Inside => (pair.0 + delimiter, pair.1 - delimiter),
+        Scope::Around => pair,
+    }
+}
+
+fn bracket_object(
+    rope: &Rope,
+    range: (usize, usize),
+    open: char,
+    close: char,
+    scope: Scope,
+) -> Option<(usize, usize)> {
+    // A cursor on an opening bracket belongs to that pair
+    let mut left = if char_at(rope, range.0) == Some(open) {
+        range.0 + 1
+    } else {
+        range.0
+    };
+    let pairs = std::iter::from_fn(move || {
+        let start = find_unmatched(rope, left, open, close, false)?;
+        let end = find_unmatched(rope, start + 1, open, close, true)?;
+        left = start;
+        Some(scoped((start, end + 1), 1, scope))
+    });
+    grow(range, pairs)
+}
+
+/// The nearest bracket without a partner between it and `from`, scanning
+/// forward for `close` or backward for `open`
+fn find_unmatched(
+    rope: &Rope,
+    from: usize,
+    open: char,
+    close: char,
+    forward: bool,
+) -> Option<usize> {
+    let (want, other) = if forward {
+        (close, open)
+    } else {
+        (open, close)
+    };
+    let mut depth = 0usize;
+    let mut i = from;
+    loop {
+        let c = if forward {
+            let c = char_at(rope, i)?;
+            i += 1;
+            c
+        } else {
+            i = i.checked_sub(1)?;
+            rope.char(i)
+        };
+        if c == other {
+            depth += 1;
+        } else if c == want {
+            if depth == 0 {
+                return Some(if forward { i - 1 } else { i });
+            }
+            depth -= 1;
+        }
+    }
+}
+
+fn quote_object(
+    rope: &Rope,
+    range: (usize, usize),
+    quote: char,
+    scope: Scope,
+    ahead: bool,
+) -> Option<(usize, usize)> {
+    let line = rope.char_to_line(range.0);
+    let line_start = rope.line_to_char(line);
+    let mut quotes = Vec::new();
+    let mut escaped = false;
+    for (offset, c) in rope.line(line).chars().enumerate() {
+        if c == quote && !escaped {
+            quotes.push(line_start + offset);
+        }
+        escaped = c == '\\' && !escaped;
+    }
+    let mut pairs = quotes
+        .chunks_exact(2)
+        .map(|pair| scoped((pair[0], pair[1] + 1), 1, scope));
+    if ahead {
+        // Like vim, a cursor outside any quotes takes the next pair on the line
+        pairs.find(|&(s, _)| s > range.0)
+    } else {
+        grow(range, pairs)
+    }
+}
+
+fn syntax_object(
+    tree: &Tree,
+    range: &Range,
+    object: TextObject,
+    scope: Scope,
+) -> Option<(usize, usize)> {
+    let bounds = (range.start().offset, range.end().offset);
+    let mut node = tree
+        .root_node()
+        .named_descendant_for_byte_range(bounds.0, bounds.1);
+    let candidates = std::iter::from_fn(|| {
+        let found = node?;
+        node = found.parent();
+        Some(found)
+    })
+    .filter_map(|node| match object {
+        TextObject::Function => Enclosing::Function
+            .matches(node)
+            .then(|| definition_range(node, scope)),
+        TextObject::Class => Enclosing::Class
+            .matches(node)
+            .then(|| definition_range(node, scope)),
+        _ => argument_range(node, scope),
+    });
+    grow(bounds, candidates)
+}
+
+fn definition_range(node: Node, scope: Scope) -> (usize, usize) {
+    let body = match scope {
+        Scope::Around => None,
+        Scope::Inside => node.child_by_field_name("body"),
+    };
+    let Some(body) = body else {
+        return (node.start_byte(), node.end_byte());
+    };
+    // Leave out the braces of a block body
+    let first = body.child(0).filter(|c| c.kind() == "{");
+    let last = body
+        .child(body.child_count().saturating_sub(1))
+        .filter(|c| c.kind() == "}");
+    match (first, last) {
+        (Some(first), Some(last)) => (first.end_byte(), last.start_byte()),
+        _ => (body.start_byte(), body.end_byte()),
+    }
+}
+
+fn argument_range(node: Node, scope: Scope) -> Option<(usize, usize)> {
+    let list = node.parent()?.kind();
+    let in_list = ["arguments", "parameters", "argument_list", "parameter_list"]
+        .iter()
+        .any(|suffix| list.ends_with(suffix));
+    if !in_list || !node.is_named() || node.kind().contains("comment") {
+        return None;
+    }
+    let inside = (node.start_byte(), node.end_byte());
+    if scope == Scope::Inside {
+        return Some(inside);
+    }
+    // Take the separator after the argument, or before it for the last one
+    Some(
+        match (node.next_named_sibling(), node.prev_named_sibling()) {
+            (Some(next), _) => (inside.0, next.start_byte()),
+            (None, Some(prev)) => (prev.end_byte(), inside.1),
+            (None, None) => inside,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Syntax;
+
+    fn motions(text: &str, motion: Motion) -> Vec<usize> {
+        let rope = Rope::from_str(text);
+        let mut stops = Vec::new();
+        let mut pos = Position::new(if matches!(motion, Motion::PrevStart(_)) {
+            text.len()
+        } else {
+            0
+        });
+        loop {
+            let next = apply(&rope, pos, motion);
+            if next == pos {
+                return stops;
+            }
+            stops.push(next.offset);
+            pos = next;
+        }
+    }
+
+    fn object(text: &str, at: &str, object: TextObject, scope: Scope) -> Option<String> {
+        let rope = Rope::from_str(text);
+        let offset = text.find(at).unwrap();
+        let range = Range::point(Position::new(offset));
+        let found = text_object(&rope, None, &range, object, scope)?;
+        Some(text[found.start().offset..found.end().offset].to_string())
+    }
+
+    #[test]
+    fn word_motions_stop_at_class_changes() {
+        let text = "let fooBar = snake_case(1);";
+        assert_eq!(
+            motions(text, Motion::NextStart(Unit::Word)),
+            vec![4, 11, 13, 23, 24, 25, 27]
+        );
+        assert_eq!(
+            motions(text, Motion::NextEnd(Unit::BigWord)),
+            vec![3, 10, 12, 27]
+        );
+        assert_eq!(
+            motions(text, Motion::NextStart(Unit::Subword)),
+            vec![4, 7, 11, 13, 19, 23, 24, 25, 27]
+        );
+        assert_eq!(
+            motions("parseHTTPRequest", Motion::PrevStart(Unit::Subword)),
+            vec![9, 5, 0]
+        );
+    }
+
+    #[test]
+    fn sentence_and_paragraph_motions() {
+        let text = "One. Two (three)! Four\nfive.\n\n\nSix.\n";
+        assert_eq!(
+            motions(text, Motion::NextStart(Unit::Sentence)),
+            vec![5, 18, 31, text.len()]
+        );
+        assert_eq!(
+            motions(text, Motion::NextEnd(Unit::Sentence)),
+            vec![4, 17, 28, 35, text.len()]
+        );
+        assert_eq!(
+            motions(text, Motion::NextStart(Unit::Paragraph)),
+            vec![31, text.len()]
+        );
+        assert_eq!(
+            motions(text, Motion::PrevStart(Unit::Paragraph)),
+            vec![31, 0]
+        );
+        assert_eq!(
+            motions(text, Motion::NextEnd(Unit::Paragraph)),
+            vec![28, 35, text.len()]
+        );
+    }
+
+    #[test]
+    fn word_and_paragraph_objects() {
+        let text = "call(foo_bar, baz)\nnext line\n\nlast\n";
+        let word = |at, scope| object(text, at, TextObject::Word, scope);
+        assert_eq!(word("bar", Scope::Inside).unwrap(), "foo_bar");
+        assert_eq!(word("baz", Scope::Around).unwrap(), " baz");
+        assert_eq!(word(", baz", Scope::Inside).unwrap(), ",");
+        assert_eq!(
+            object(text, "bar", TextObject::Subword, Scope::Inside).unwrap(),
+            "bar"
+        );
+        assert_eq!(
+            object(text, "foo", TextObject::BigWord, Scope::Around).unwrap(),
+            "call(foo_bar, "
+        );
+        assert_eq!(
+            object(text, "next", TextObject::Paragraph, Scope::Around).unwrap(),
+            "call(foo_bar, baz)\nnext line\n\n"
+        );
+        assert_eq!(
+            object(text, "last", TextObject::Paragraph, Scope::Around).unwrap(),
+            "\nlast\n"
+        );
+    }
+
+    #[test]
+    fn bracket_and_quote_objects_grow_when_repeated() {
+        let text = r#"f(a, [b, "c \" d"], {e})"#;
+        let rope = Rope::from_str(text);
+        let mut range = Range::point(Position::new(text.find('b').unwrap()));
+        let mut seen = Vec::new();
+        while let Some(found) =
+            text_object(&rope, None, &range, TextObject::AnyBracket, Scope::Inside)
+        {
+            seen.push(&text[found.start().offset..found.end().offset]);
+            range = found;
+        }
+        assert_eq!(seen, vec![r#"b, "c \" d""#, r#"a, [b, "c \" d"], {e}"#]);
+
+        assert_eq!(
+            object(text, "d", TextObject::Quote('"'), Scope::Inside).unwrap(),
+            r#"c \" d"#
+        );
+        assert_eq!(
+            object(text, "f", TextObject::AnyQuote, Scope::Around).unwrap(),
+            r#""c \" d""#
+        );
+        assert_eq!(
+            object(text, "{", TextObject::Bracket('{', '}'), Scope::Around).unwrap(),
+            "{e}"
+        );
+        assert_eq!(
+            object(text, "a", TextObject::Bracket('<', '>'), Scope::Inside),
+            None
+        );
+    }
+
+    #[test]
+    fn syntax_objects_use_the_tree() {
+        let text = "impl S {\n    fn f(a: u8, b: u8) {\n        g(a, b);\n    }\n}\n";
+        let rope = Rope::from_str(text);
+        let mut syntax = Syntax::new(tree_sitter_rust::LANGUAGE.into());
+        syntax.parse(&rope);
+        let tree = syntax.tree();
+        let select = |at: &str, object, scope| {
+            let range = Range::point(Position::new(text.find(at).unwrap()));
+            let found = text_object(&rope, tree, &range, object, scope)?;
+            Some(&text[found.start().offset..found.end().offset])
+        };
+        assert_eq!(
+            select("g(", TextObject::Function, Scope::Inside).unwrap(),
+            "\n        g(a, b);\n    "
+        );
+        assert!(select("g(", TextObject::Class, Scope::Around)
+            .unwrap()
+            .starts_with("impl S"));
+        assert_eq!(
+            select("a: u8", TextObject::Argument, Scope::Around).unwrap(),
+            "a: u8, "
+        );
+        assert_eq!(
+            select("b);", TextObject::Argument, Scope::Around).unwrap(),
+            ", b"
+        );
+        assert_eq!(
+            select("b);", TextObject::Argument, Scope::Inside).unwrap(),
+            "b"
+        );
+        let range = Range::point(Position::zero());
+        assert_eq!(
+            text_object(&rope, None, &range, TextObject::Function, Scope::Inside),
+            None
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Excerpts of many buffers edited as one document.
+//!
+//! A [`MultiBuffer`] lays its excerpts out one after another, each under a
+//! header line naming its file. It doesn't own the buffers: the composite
+//! text is built from them on demand, and an edit to it is split into one
+//! [`ChangeSet`] per file, to be applied to the real buffers as ordinary
+//! transactions.
+
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+use anyhow::{bail, Result};
+use ropey::Rope;
+
+use crate::{Assoc, Change, ChangeSet, Position};
+
+/// Some whole lines of one file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Excerpt {
+    pub path: String,
+    /// Byte range in the file's buffer, starting at a line start and ending
+    /// after a line break (or at the end of the text)
+    range: Range<usize>,
+}
+
+impl Excerpt {
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+}
+
+/// Where an excerpt landed in the composite text
+#[derive(Debug, Clone, Default)]
+struct Segment {
+    header_start: usize,
+    /// The excerpt's text, without the line break added after text that
+    /// doesn't end in one
+    body: Range<usize>,
+    padded: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MultiBuffer {
+    excerpts: Vec<Excerpt>,
+    /// Layout of the text last returned by [`MultiBuffer::build`]
+    segments: Vec<Segment>,
+}
+
+impl MultiBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn excerpts(&self) -> &[Excerpt] {
+        &self.excerpts
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.excerpts.is_empty()
+    }
+
+    /// Add `lines` of `path` with `context` lines around them. Excerpts of
+    /// the same file that overlap or touch are merged.
+    pub fn push(&mut self, path: &str, rope: &Rope, lines: Range<usize>, context: usize) {
+        let start_line = lines.start.saturating_sub(context).min(rope.len_lines());
+        let end_line = (lines.end.max(lines.start + 1) + context).min(rope.len_lines());
+        self.excerpts.push(Excerpt {
+            path: path.to_string(),
+            range: rope.line_to_byte(start_line)..rope.line_to_byte(end_line),
+        });
+        self.merge(self.excerpts.len() - 1);
+    }
+
+    /// Show `above` more lines before excerpt `index` and `below` more after it
+    pub fn expand(&mut self, index: usize, rope: &Rope, above: usize, below: usize) {
+        let Some(excerpt) = self.excerpts.get_mut(index) else {
+            return;
+        };
+        let start = rope.byte_to_line(excerpt.range.start.min(rope.len_bytes()));
+        let end = line_after(rope, excerpt.range.end);
+        let start = start.saturating_sub(above);
+        let end = (end + below).min(rope.len_lines());
+        excerpt.range = rope.line_to_byte(start)..rope.line_to_byte(end);
+        self.merge(index);
+    }
+
+    /// Fold other excerpts of the same file that overlap or touch excerpt
+    /// `index` into it, keeping the place of the earliest
+    fn merge(&mut self, mut index: usize) {
+        let mut i = 0;
+        while i < self.excerpts.len() {
+            let (a, b) = (&self.excerpts[i], &self.excerpts[index]);
+            if i != index
+                && a.path == b.path
+                && a.range.start <= b.range.end
+                && b.range.start <= a.range.end
+            {
+                let range = a.range.start.min(b.range.start)..a.range.end.max(b.range.end);
+                let (keep, drop) = (i.min(index), i.max(index));
+                self.excerpts[keep].range = range;
+                self.excerpts.remove(drop);
+                index = keep;
+                i = 0;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    /// The composite text, reading each excerpt from the rope `rope_for`
+    /// returns for its path. Excerpts of files it has no rope for are empty.
+    pub fn build<'a>(&mut self, rope_for: impl Fn(&str) -> Option<&'a Rope>) -> String {
+        let mut text = String::new();
+        self.segments.clear();
+        for excerpt in &mut self.excerpts {
+            let rope = rope_for(&excerpt.path);
+            if let Some(rope) = rope {
+                // Edits made elsewhere may have moved the text; keep to whole lines
+                let start =
+                    rope.line_to_byte(rope.byte_to_line(excerpt.range.start.min(rope.len_bytes())));
+                let end = rope.line_to_byte(line_after(rope, excerpt.range.end.max(start)));
+                excerpt.range = start..end;
+            }
+            let header_start = text.len();
+            text.push_str(&format!("── {} ──\n", excerpt.path));
+            let body_start = text.len();
+            if let Some(rope) = rope {
+                for chunk in rope.byte_slice(excerpt.range.clone()).chunks() {
+                    text.push_str(chunk);
+                }
+            }
+            let body = body_start..text.len();
+            let padded = !text.ends_with('\n');
+            if padded {
+                text.push('\n');
+            }
+            self.segments.push(Segment {
+                header_start,
+                body,
+                padded,
+            });
+        }
+        text
+    }
+
+    /// The excerpt whose header or text contains composite offset `offset`
+    pub fn excerpt_at(&self, offset: usize) -> Option<usize> {
+        self.segments
+            .partition_point(|s| s.header_start <= offset)
+            .checked_sub(1)
+    }
+
+    /// Whether composite offset `offset` is on a header line
+    pub fn is_header(&self, offset: usize) -> bool {
+        self.segments
+            .iter()
+            .any(|s| (s.header_start..s.body.start).contains(&offset))
+    }
+
+    /// The excerpt and buffer offset of a composite offset in an excerpt's
+    /// text. `None` on header lines.
+    pub fn locate(&self, offset: usize) -> Option<(usize, usize)> {
+        let index = self
+            .segments
+            .iter()
+            .position(|s| s.body.contains(&offset) || (s.padded && s.body.end == offset))?;
+        let segment = &self.segments[index];
+        Some((
+            index,
+            self.excerpts[index].range.start + offset - segment.body.start,
+        ))
+    }
+
+    /// The composite offset of `offset` in excerpt `index`'s buffer
+    pub fn to_composite(&self, index: usize, offset: usize) -> Option<usize> {
+        let (excerpt, segment) = (self.excerpts.get(index)?, self.segments.get(index)?);
+        excerpt
+            .range
+            .contains(&offset)
+            .then(|| segment.body.start + offset - excerpt.range.start)
+            .or_else(|| (offset == excerpt.range.end).then_some(segment.body.end))
+    }
+
+    /// Split an edit of the composite text into edits of each file, keyed
+    /// by path. Fails if the edit touches a header or spans excerpts.
+    pub fn split_edits(&self, changes: &ChangeSet) -> Result<Vec<(String, ChangeSet)>> {
+        let mut per_file: BTreeMap<&str, ChangeSet> = BTreeMap::new();
+        for change in &changes.changes {
+            let (start, end) = (change.start.offset, change.end.offset);
+            let Some((index, buffer_start)) = self.locate(start) else {
+                bail!("Excerpt headers can't be edited");
+            };
+            if end > self.segments[index].body.end {
+                bail!("An edit can't span excerpts or change their headers");
+            }
+            let buffer_end = buffer_start + (end - start);
+            per_file
+                .entry(&self.excerpts[index].path)
+                .or_default()
+                .add(Change {
+                    start: Position::new(buffer_start),
+                    end: Position::new(buffer_end),
+                    text: change.text.clone(),
+                });
+        }
+        Ok(per_file
+            .into_iter()
+            .map(|(path, changes)| (path.to_string(), changes))
+            .collect())
+    }
+
+    /// Move the excerpts of `path` along with `changes`, which were just
+    /// applied to its buffer, leaving `rope`
+    pub fn map_through(&mut self, path: &str, changes: &ChangeSet, rope: &Rope) {
+        let delta: isize = changes.changes.iter().map(Change::len_delta).sum();
+        let old_len = (rope.len_bytes() as isize - delta) as usize;
+        let map = |offset, assoc| changes.map_position(Position::new(offset), assoc).offset;
+        for excerpt in self.excerpts.iter_mut().filter(|e| e.path == path) {
+            // Text typed at the end of a file without a final line break is
+            // still part of the last excerpt
+            let end_assoc = if excerpt.range.end == old_len {
+                Assoc::After
+            } else {
+                Assoc::Before
+            };
+            let start = map(excerpt.range.start, Assoc::Before);
+            excerpt.range = start..map(excerpt.range.end, end_assoc).max(start);
+        }
+    }
+}
+
+/// The line after the one `offset` is on, or the line it starts if it's at
+/// a line start
+fn line_after(rope: &Rope, offset: usize) -> usize {
+    let offset = offset.min(rope.len_bytes());
+    let line = rope.byte_to_line(offset);
+    if rope.line_to_byte(line) == offset {
+        line
+    } else {
+        line + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ropes() -> (Rope, Rope) {
+        let a: String = (1..=20).map(|i| format!("a{}\n", i)).collect();
+        (Rope::from_str(&a), Rope::from_str("b1\nb2"))
+    }
+
+    fn build(multi: &mut MultiBuffer, a: &Rope, b: &Rope) -> String {
+        multi.build(|path| match path {
+            "a" => Some(a),
+            "b" => Some(b),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn lays_out_merged_excerpts_under_headers() {
+        let (a, b) = ropes();
+        let mut multi = MultiBuffer::new();
+        multi.push("a", &a, 4..5, 1);
+        multi.push("b", &b, 1..2, 0);
+        // Touches the first excerpt, so they merge
+        multi.push("a", &a, 7..8, 1);
+        assert_eq!(multi.excerpts().len(), 2);
+
+        let text = build(&mut multi, &a, &b);
+        assert_eq!(text, "── a ──\na4\na5\na6\na7\na8\na9\n── b ──\nb2\n");
+        assert!(multi.is_header(0));
+        assert_eq!(multi.locate(text.find("a5").unwrap()), Some((0, 12)));
+        assert_eq!(multi.locate(text.len() - 1), Some((1, 5)));
+        assert_eq!(multi.to_composite(1, 3), Some(text.find("b2").unwrap()));
+        assert_eq!(multi.excerpt_at(text.find("── b").unwrap()), Some(1));
+
+        multi.expand(1, &b, 1, 1);
+        assert_eq!(
+            build(&mut multi, &a, &b).split("── b ──\n").nth(1),
+            Some("b1\nb2\n")
+        );
+    }
+
+    #[test]
+    fn splits_edits_by_file_and_follows_them() {
+        let (mut a, mut b) = ropes();
+        let mut multi = MultiBuffer::new();
+        multi.push("a", &a, 1..2, 0);
+        multi.push("b", &b, 1..2, 0);
+        multi.push("a", &a, 9..10, 0);
+        let text = build(&mut multi, &a, &b);
+
+        let at = |needle| at_in(&text, needle);
+        let mut changes = ChangeSet::new();
+        changes.add(Change::insert(at("a2"), "x".into()));
+        changes.add(Change::insert(at("\n── a"), "!".into()));
+        changes.add(Change::replace(
+            at("a10"),
+            Position::new(text.len()),
+            "new\nlines\n".into(),
+        ));
+        let per_file = multi.split_edits(&changes).unwrap();
+        assert_eq!(per_file.len(), 2);
+        for (path, changes) in &per_file {
+            let rope = if path == "a" { &mut a } else { &mut b };
+            changes.apply(rope);
+            multi.map_through(path, changes, rope);
+        }
+        assert_eq!(b.to_string(), "b1\nb2!");
+        assert_eq!(
+            build(&mut multi, &a, &b),
+            "── a ──\nxa2\n── b ──\nb2!\n── a ──\nnew\nlines\n"
+        );
+
+        let header = ChangeSet::with_change(Change::delete(Position::new(0), Position::new(2)));
+        assert!(multi.split_edits(&header).is_err());
+        // Right after an excerpt's last line break is the next header
+        let text = build(&mut multi, &a, &b);
+        let between = ChangeSet::with_change(Change::insert(at_in(&text, "── b"), "?".into()));
+        assert!(multi.split_edits(&between).is_err());
+    }
+
+    fn at_in(text: &str, needle: &str) -> Position {
+        Position::new(text.find(needle).unwrap())
+    }
+}
@@ -1,5 +1,7 @@
+use serde::{Deserialize, Serialize};
+
 /// Position in a text buffer. Can be represented as (line, column) or byte offset.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Position {
     /// Byte offset from the start of the buffer
     pub offset: usize,
@@ -1,54 +1,536 @@
+//! Project file index and sub-project detection.
+//!
+//! Files are listed with the `ignore` crate, so `.gitignore`, `.ignore` and
+//! git's global excludes are honored whether or not the folder is a git
+//! repository. The index is kept current incrementally: the modification
+//! time of every indexed directory is remembered and only directories whose
+//! listing changed are read again. [`ProjectWatcher`] does this on a
+//! background thread. [`ProjectLimits`] caps how much of a huge tree (say, a
+//! home directory) is indexed and watched.
+//!
+//! Sub-projects (Cargo workspace members, npm/pnpm workspace packages and
+//! Python packages) give per-member roots to scope search and tasks to.
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard};
+use std::time::{Duration, SystemTime};
+
+/// How often [`ProjectWatcher`] looks for changes by default
+pub const DEFAULT_REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Directory names skipped even when no ignore file mentions them
+const ALWAYS_SKIPPED: &[&str] = &["target", "node_modules"];
+
+/// Files whose rules apply to the whole tree below them
+const IGNORE_FILES: &[&str] = &[".gitignore", ".ignore"];
+
+/// Files that define sub-projects
+const MANIFESTS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pnpm-workspace.yaml",
+    "pyproject.toml",
+    "setup.py",
+];
+
+/// How much of a folder gets indexed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProjectLimits {
+    /// Files indexed before the rest of the tree is left out
+    pub max_files: usize,
+    /// Directory levels below the root that are indexed
+    pub max_depth: usize,
+}
+
+impl Default for ProjectLimits {
+    fn default() -> Self {
+        Self {
+            max_files: 100_000,
+            max_depth: 24,
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct Project {
     /// Root directory of the project
     pub root: PathBuf,
-    /// Known files in the project
+    /// Known files in the project, sorted
     pub files: Vec<PathBuf>,
+    /// Sub-projects found under the root
+    pub members: Vec<SubProject>,
+    /// Indexed directories and their modification times
+    dirs: BTreeMap<PathBuf, Option<SystemTime>>,
+    /// Ignore files and manifests, whose edits don't touch their directory
+    watched: BTreeMap<PathBuf, Option<SystemTime>>,
+    limits: ProjectLimits,
+    /// A limit was hit, so part of the tree is missing from the index
+    truncated: bool,
+}
+
+/// A workspace member or package with its own root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubProject {
+    pub name: String,
+    pub root: PathBuf,
+    pub kind: ProjectKind,
 }
 
 impl Project {
     pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self::with_limits(root, ProjectLimits::default())
+    }
+
+    pub fn with_limits<P: AsRef<Path>>(root: P, limits: ProjectLimits) -> Self {
         Self {
             root: root.as_ref().to_path_buf(),
             files: Vec::new(),
+            members: Vec::new(),
+            dirs: BTreeMap::new(),
+            watched: BTreeMap::new(),
+            limits,
+            truncated: false,
         }
     }
 
-    /// Scan the project directory for files
+    /// Whether part of the tree was left out of the index by the limits
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Index the whole project from scratch
     pub fn scan(&mut self) {
-        self.files.clear();
-        // Basic recursive scan
-        self.visit_dir(&self.root.clone());
-    }
-
-    fn visit_dir(&mut self, dir: &Path) {
-        if let Ok(entries) = std::fs::read_dir(dir) {
-            for entry in entries.flatten() {
-                let path = entry.path();
-                if path.is_dir() {
-                    if !self.is_ignored(&path) {
-                        self.visit_dir(&path);
-                    }
-                } else {
-                    self.files.push(path);
-                }
+        let (files, dirs) = walk(
+            &self.root,
+            Some(self.limits.max_depth),
+            self.limits.max_files,
+        );
+        self.truncated = files.len() >= self.limits.max_files;
+        self.files = files;
+        self.files.sort();
+        self.dirs = std::iter::once(self.root.clone())
+            .chain(dirs)
+            .map(|dir| (dir.clone(), modified(&dir)))
+            .collect();
+        self.restamp_watched();
+        self.members = self.detect_members();
+    }
+
+    /// Whether anything the index was built from changed on disk. Only
+    /// stats files, so it is cheaper than [`Project::refresh`].
+    pub fn is_stale(&self) -> bool {
+        self.dirs
+            .iter()
+            .chain(&self.watched)
+            .any(|(path, stamp)| modified(path) != *stamp)
+    }
+
+    /// Bring the index up to date with the file system, re-reading only the
+    /// directories that changed. Returns whether anything changed.
+    pub fn refresh(&mut self) -> bool {
+        // New ignore rules can hide or reveal files anywhere below them
+        let ignore_rules_changed = self
+            .watched
+            .iter()
+            .any(|(path, stamp)| is_ignore_file(path) && modified(path) != *stamp);
+        if ignore_rules_changed {
+            self.scan();
+            return true;
+        }
+
+        let changed_dirs: Vec<PathBuf> = self
+            .dirs
+            .iter()
+            .filter(|(dir, stamp)| modified(dir) != **stamp)
+            .map(|(dir, _)| dir.clone())
+            .collect();
+        for dir in &changed_dirs {
+            // Skip directories already dropped along with their parent
+            if self.dirs.contains_key(dir) && self.rescan_dir(dir) {
+                self.scan();
+                return true;
             }
         }
+
+        let manifests_changed = self
+            .watched
+            .iter()
+            .any(|(path, stamp)| modified(path) != *stamp);
+        if changed_dirs.is_empty() && !manifests_changed {
+            return false;
+        }
+        self.files.sort();
+        self.files.dedup();
+        if self.files.len() > self.limits.max_files {
+            self.files.truncate(self.limits.max_files);
+            self.truncated = true;
+        }
+        self.restamp_watched();
+        self.members = self.detect_members();
+        true
+    }
+
+    /// The innermost sub-project containing `path`
+    pub fn member_for(&self, path: &Path) -> Option<&SubProject> {
+        self.members
+            .iter()
+            .filter(|member| path.starts_with(&member.root))
+            .max_by_key(|member| member.root.components().count())
     }
 
-    fn is_ignored(&self, path: &Path) -> bool {
-        // Basic ignore logic
-        if let Some(name) = path.file_name() {
-            let name_str = name.to_string_lossy();
-            return name_str.starts_with('.') || name_str == "target" || name_str == "node_modules";
+    /// Indexed entries directly inside `dir` as `(path, is_dir)`,
+    /// directories first, each group sorted by name
+    pub fn list_dir(&self, dir: &Path) -> Vec<(PathBuf, bool)> {
+        let dirs = self
+            .dirs
+            .keys()
+            .filter(|d| d.parent() == Some(dir))
+            .map(|d| (d.clone(), true));
+        let files = self
+            .files
+            .iter()
+            .filter(|f| f.parent() == Some(dir))
+            .map(|f| (f.clone(), false));
+        dirs.chain(files).collect()
+    }
+
+    /// Re-read one directory whose listing changed. Returns true if its
+    /// ignore files appeared or disappeared, which needs a full scan.
+    fn rescan_dir(&mut self, dir: &Path) -> bool {
+        if !dir.is_dir() {
+            self.remove_tree(dir);
+            return false;
+        }
+        let ignore_files_changed = IGNORE_FILES.iter().any(|name| {
+            let path = dir.join(name);
+            path.exists() != self.watched.contains_key(&path)
+        });
+        if ignore_files_changed {
+            return true;
+        }
+        // Directories at the depth limit are listed but not looked into
+        let depth = self.depth(dir);
+        if depth >= self.limits.max_depth {
+            self.dirs.insert(dir.to_path_buf(), modified(dir));
+            return false;
+        }
+
+        let (files, subdirs) = walk(dir, Some(1), usize::MAX);
+        self.files.retain(|f| f.parent() != Some(dir));
+        self.files.extend(files);
+
+        let removed: Vec<PathBuf> = self
+            .dirs
+            .keys()
+            .filter(|d| d.parent() == Some(dir) && !subdirs.contains(d))
+            .cloned()
+            .collect();
+        for gone in &removed {
+            self.remove_tree(gone);
+        }
+        for subdir in subdirs {
+            if self.dirs.contains_key(&subdir) {
+                continue;
+            }
+            let budget = self.limits.max_files.saturating_sub(self.files.len());
+            let (files, dirs) = walk(&subdir, Some(self.limits.max_depth - depth - 1), budget);
+            self.truncated |= files.len() >= budget;
+            self.files.extend(files);
+            for new_dir in std::iter::once(subdir).chain(dirs) {
+                let stamp = modified(&new_dir);
+                self.dirs.insert(new_dir, stamp);
+            }
         }
+        self.dirs.insert(dir.to_path_buf(), modified(dir));
         false
     }
+
+    /// Directory levels between the root and `path`
+    fn depth(&self, path: &Path) -> usize {
+        path.strip_prefix(&self.root)
+            .map_or(0, |relative| relative.components().count())
+    }
+
+    fn remove_tree(&mut self, dir: &Path) {
+        self.dirs.retain(|d, _| !d.starts_with(dir));
+        self.files.retain(|f| !f.starts_with(dir));
+        self.watched.retain(|f, _| !f.starts_with(dir));
+    }
+
+    fn restamp_watched(&mut self) {
+        let ignore_files = self
+            .dirs
+            .keys()
+            .flat_map(|dir| IGNORE_FILES.iter().map(move |name| dir.join(name)))
+            .filter(|path| path.exists());
+        let manifests = self
+            .files
+            .iter()
+            .filter(|f| {
+                f.file_name()
+                    .is_some_and(|n| MANIFESTS.iter().any(|m| n == *m))
+            })
+            .cloned();
+        self.watched = ignore_files
+            .chain(manifests)
+            .map(|path| (path.clone(), modified(&path)))
+            .collect();
+    }
+
+    fn detect_members(&self) -> Vec<SubProject> {
+        let mut members = Vec::new();
+
+        if let Some(workspace) = read_toml(&self.root.join("Cargo.toml"))
+            .and_then(|manifest| manifest.get("workspace").cloned())
+        {
+            let patterns = |key: &str| -> Vec<String> {
+                workspace
+                    .get(key)
+                    .and_then(|v| v.as_array())
+                    .map(|a| {
+                        a.iter()
+                            .filter_map(|p| Some(p.as_str()?.to_string()))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            };
+            let (include, exclude) = (
+                glob_set(&patterns("members")),
+                glob_set(&patterns("exclude")),
+            );
+            for dir in self.manifest_dirs("Cargo.toml") {
+                if self.matches(&dir, &include) && !self.matches(&dir, &exclude) {
+                    let name = read_toml(&dir.join("Cargo.toml"))
+                        .and_then(|m| Some(m.get("package")?.get("name")?.as_str()?.to_string()));
+                    members.push(SubProject::new(name, dir, ProjectKind::Rust));
+                }
+            }
+        }
+
+        let js_patterns = js_workspace_patterns(&self.root);
+        if !js_patterns.is_empty() {
+            let (excluded, included): (Vec<String>, Vec<String>) =
+                js_patterns.into_iter().partition(|p| p.starts_with('!'));
+            let excluded: Vec<String> = excluded.iter().map(|p| p[1..].to_string()).collect();
+            let (include, exclude) = (glob_set(&included), glob_set(&excluded));
+            for dir in self.manifest_dirs("package.json") {
+                if self.matches(&dir, &include) && !self.matches(&dir, &exclude) {
+                    let name = read_json(&dir.join("package.json"))
+                        .and_then(|p| Some(p.get("name")?.as_str()?.to_string()));
+                    members.push(SubProject::new(name, dir, ProjectKind::Node));
+                }
+            }
+        }
+
+        let mut python_dirs: Vec<PathBuf> = self
+            .manifest_dirs("pyproject.toml")
+            .chain(self.manifest_dirs("setup.py"))
+            .collect();
+        python_dirs.sort();
+        python_dirs.dedup();
+        for dir in python_dirs {
+            let name = read_toml(&dir.join("pyproject.toml")).and_then(|p| {
+                let name = match p.get("project") {
+                    Some(project) => project.get("name"),
+                    None => p.get("tool")?.get("poetry")?.get("name"),
+                };
+                Some(name?.as_str()?.to_string())
+            });
+            members.push(SubProject::new(name, dir, ProjectKind::Python));
+        }
+
+        members.sort_by(|a, b| a.root.cmp(&b.root));
+        members
+    }
+
+    /// Directories below the root containing a file called `manifest`
+    fn manifest_dirs<'a>(&'a self, manifest: &'a str) -> impl Iterator<Item = PathBuf> + 'a {
+        self.files
+            .iter()
+            .filter(move |f| f.file_name().is_some_and(|n| n == manifest))
+            .filter_map(|f| f.parent())
+            .filter(|dir| *dir != self.root)
+            .map(Path::to_path_buf)
+    }
+
+    /// Whether `dir`, relative to the root, matches a workspace glob
+    fn matches(&self, dir: &Path, globs: &GlobSet) -> bool {
+        dir.strip_prefix(&self.root)
+            .is_ok_and(|relative| globs.is_match(relative))
+    }
+}
+
+impl SubProject {
+    fn new(name: Option<String>, root: PathBuf, kind: ProjectKind) -> Self {
+        let name = name.unwrap_or_else(|| {
+            root.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        Self { name, root, kind }
+    }
+}
+
+/// Keeps a [`Project`] index current on a background thread
+pub struct ProjectWatcher {
+    project: Arc<RwLock<Project>>,
+    generation: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+}
+
+impl ProjectWatcher {
+    /// Scan `root` in the background, then look for changes every `interval`
+    pub fn start(
+        root: impl Into<PathBuf>,
+        limits: ProjectLimits,
+        interval: Duration,
+    ) -> std::io::Result<Self> {
+        let root = root.into();
+        let project = Arc::new(RwLock::new(Project::with_limits(&root, limits)));
+        let generation = Arc::new(AtomicU64::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let (thread_project, thread_generation, thread_stop) =
+            (project.clone(), generation.clone(), stop.clone());
+        std::thread::Builder::new()
+            .name("forge-project-index".into())
+            .spawn(move || {
+                // Walk without holding the lock so readers aren't blocked
+                let mut index = Project::with_limits(root, limits);
+                index.scan();
+                *thread_project.write().unwrap() = index;
+                thread_generation.fetch_add(1, Ordering::Release);
+                while !thread_stop.load(Ordering::Relaxed) {
+                    std::thread::sleep(interval);
+                    // Only the directories that changed are re-read under the
+                    // write lock
+                    let stale = thread_project.read().unwrap().is_stale();
+                    if stale && thread_project.write().unwrap().refresh() {
+                        thread_generation.fetch_add(1, Ordering::Release);
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            project,
+            generation,
+            stop,
+        })
+    }
+
+    /// The current index; empty until the first scan finishes
+    pub fn project(&self) -> RwLockReadGuard<'_, Project> {
+        self.project.read().unwrap()
+    }
+
+    /// Bumped every time the index changes; 0 until the first scan finishes
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for ProjectWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Files and directories under `dir` (excluding `dir` itself) that aren't
+/// ignored, stopping after `max_files` files
+fn walk(dir: &Path, max_depth: Option<usize>, max_files: usize) -> (Vec<PathBuf>, Vec<PathBuf>) {
+    let mut files = Vec::new();
+    let mut dirs = Vec::new();
+    let walker = WalkBuilder::new(dir)
+        .max_depth(max_depth)
+        .require_git(false)
+        .filter_entry(|entry| {
+            let skipped = ALWAYS_SKIPPED.iter().any(|name| entry.file_name() == *name);
+            !(skipped && entry.file_type().is_some_and(|t| t.is_dir()))
+        })
+        .build();
+    for entry in walker.flatten() {
+        if files.len() >= max_files {
+            break;
+        }
+        if entry.depth() == 0 {
+            continue;
+        }
+        match entry.file_type() {
+            Some(t) if t.is_dir() => dirs.push(entry.into_path()),
+            Some(t) if t.is_file() => files.push(entry.into_path()),
+            _ => {}
+        }
+    }
+    (files, dirs)
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn is_ignore_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| IGNORE_FILES.iter().any(|f| name == *f))
+}
+
+fn read_toml(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()
+}
+
+/// Package globs from `package.json` workspaces and `pnpm-workspace.yaml`
+fn js_workspace_patterns(root: &Path) -> Vec<String> {
+    let mut patterns = Vec::new();
+    if let Some(package) = read_json(&root.join("package.json")) {
+        // Either a list, or `{ "packages": [...] }` as used by Yarn
+        let workspaces = package.get("workspaces");
+        let list = workspaces
+            .and_then(|w| w.get("packages"))
+            .or(workspaces)
+            .and_then(|w| w.as_array());
+        patterns.extend(
+            list.into_iter()
+                .flatten()
+                .filter_map(|p| Some(p.as_str()?.to_string())),
+        );
+    }
+    if let Ok(yaml) = std::fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        patterns.extend(pnpm_packages(&yaml));
+    }
+    patterns
+}
+
+/// Entries of the top-level `packages:` list in `pnpm-workspace.yaml`
+fn pnpm_packages(yaml: &str) -> Vec<String> {
+    yaml.lines()
+        .skip_while(|line| line.trim_end() != "packages:")
+        .skip(1)
+        .map(|line| line.split(" #").next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map_while(|line| line.strip_prefix('-'))
+        .map(|item| item.trim().trim_matches(['\'', '"']).to_string())
+        .collect()
+}
+
+fn glob_set(patterns: &[String]) -> GlobSet {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        if let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() {
+            builder.add(glob);
+        }
+    }
+    builder.build().unwrap_or_else(|_| GlobSet::empty())
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ProjectKind {
     Rust,
     Node,
@@ -70,3 +552,164 @@ pub fn detect_project_kind(root: &Path) -> ProjectKind {
         ProjectKind::Generic
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn project_dir(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(name);
+        let _ = fs::remove_dir_all(&root);
+        for (path, contents) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, contents).unwrap();
+        }
+        root
+    }
+
+    fn relative(project: &Project) -> Vec<String> {
+        project
+            .files
+            .iter()
+            .map(|f| {
+                f.strip_prefix(&project.root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn honors_gitignore_and_updates_incrementally() {
+        let root = project_dir(
+            "forge_project_index",
+            &[
+                (".gitignore", "*.log\nbuild/\n"),
+                ("src/main.rs", ""),
+                ("debug.log", ""),
+                ("build/out.o", ""),
+                ("target/debug/forge", ""),
+            ],
+        );
+        let mut project = Project::new(&root);
+        project.scan();
+        assert_eq!(relative(&project), vec!["src/main.rs"]);
+        assert!(!project.refresh());
+
+        fs::write(root.join("src/lib.rs"), "").unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/guide.md"), "").unwrap();
+        fs::write(root.join("src/trace.log"), "").unwrap();
+        assert!(project.refresh());
+        assert_eq!(
+            relative(&project),
+            vec!["docs/guide.md", "src/lib.rs", "src/main.rs"]
+        );
+        assert_eq!(project.list_dir(&root).len(), 2);
+
+        fs::remove_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        assert!(project.refresh());
+        assert_eq!(
+            relative(&project),
+            vec!["debug.log", "src/lib.rs", "src/main.rs", "src/trace.log"]
+        );
+    }
+
+    #[test]
+    fn limits_files_and_depth() {
+        let root = project_dir(
+            "forge_project_limits",
+            &[
+                ("a.rs", ""),
+                ("b.rs", ""),
+                ("src/c.rs", ""),
+                ("src/deep/d.rs", ""),
+            ],
+        );
+        let mut project = Project::with_limits(
+            &root,
+            ProjectLimits {
+                max_files: 10,
+                max_depth: 2,
+            },
+        );
+        project.scan();
+        assert_eq!(relative(&project), vec!["a.rs", "b.rs", "src/c.rs"]);
+        assert!(!project.is_truncated());
+
+        // New files below the depth limit stay out of the index
+        fs::write(root.join("src/deep/e.rs"), "").unwrap();
+        fs::create_dir_all(root.join("src/more")).unwrap();
+        fs::write(root.join("src/more/f.rs"), "").unwrap();
+        assert!(project.is_stale());
+        assert!(project.refresh());
+        assert_eq!(relative(&project), vec!["a.rs", "b.rs", "src/c.rs"]);
+        assert!(!project.is_stale());
+
+        let mut project = Project::with_limits(
+            &root,
+            ProjectLimits {
+                max_files: 2,
+                max_depth: 8,
+            },
+        );
+        project.scan();
+        assert_eq!(project.files.len(), 2);
+        assert!(project.is_truncated());
+    }
+
+    #[test]
+    fn detects_workspace_members() {
+        let root = project_dir(
+            "forge_project_members",
+            &[
+                (
+                    "Cargo.toml",
+                    "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/skip\"]\n",
+                ),
+                ("crates/core/Cargo.toml", "[package]\nname = \"app-core\"\n"),
+                ("crates/skip/Cargo.toml", "[package]\nname = \"skip\"\n"),
+                ("package.json", "{\"workspaces\": [\"web/*\"]}"),
+                ("web/ui/package.json", "{\"name\": \"@app/ui\"}"),
+                (
+                    "pnpm-workspace.yaml",
+                    "packages:\n  - 'tools/**'\n  - '!tools/old'\n",
+                ),
+                ("tools/gen/cli/package.json", "{}"),
+                ("tools/old/package.json", "{}"),
+                ("py/lib/pyproject.toml", "[project]\nname = \"applib\"\n"),
+                ("crates/core/src/lib.rs", ""),
+            ],
+        );
+        let mut project = Project::new(&root);
+        project.scan();
+
+        let names: Vec<(&str, ProjectKind)> = project
+            .members
+            .iter()
+            .map(|m| (m.name.as_str(), m.kind))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("app-core", ProjectKind::Rust),
+                ("applib", ProjectKind::Python),
+                ("cli", ProjectKind::Node),
+                ("@app/ui", ProjectKind::Node),
+            ]
+        );
+        let member = project.member_for(&root.join("crates/core/src/lib.rs"));
+        assert_eq!(member.map(|m| m.name.as_str()), Some("app-core"));
+        assert_eq!(project.member_for(&root.join("README.md")), None);
+    }
+
+    #[test]
+    fn reads_pnpm_package_list() {
+        let yaml = "packages:\n  # apps\n  - \"apps/*\" # all apps\n  - libs/**\ncatalog:\n  - x\n";
+        assert_eq!(pnpm_packages(yaml), vec!["apps/*", "libs/**"]);
+    }
+}
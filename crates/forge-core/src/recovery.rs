@@ -1,8 +1,88 @@
-use anyhow::Result;
-use std::path::PathBuf;
+//! Crash recovery journals.
+//!
+//! Every transaction applied to a buffer with unsaved changes is appended to
+//! a journal for its file. The journal starts with a header naming the file,
+//! the hash of the contents the edits apply to and the encoding they were
+//! decoded with, followed by one JSON line per entry. If the editor dies,
+//! replaying the entries over the file on disk brings back the unsaved text.
+//! Long journals are compacted into a single snapshot of the text, and a
+//! journal is deleted once its buffer is saved.
+
+use crate::collab::check_changes;
+use crate::undo_store::content_hash;
+use crate::{ChangeSet, Encoding};
+use anyhow::{Context, Result};
+use ropey::Rope;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Entries appended before a journal is rewritten as one snapshot
+pub const DEFAULT_COMPACT_AFTER: usize = 500;
+
+const JOURNAL_EXTENSION: &str = "journal";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalHeader {
+    path: String,
+    base_hash: u64,
+    /// [`Encoding::id`] of the buffer's encoding
+    encoding: String,
+    created_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum JournalOp {
+    /// Edits in the coordinates of the text produced by the entries before
+    Changes(ChangeSet),
+    /// The whole text, replacing everything before it
+    Snapshot(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct JournalEntry {
+    at: u64,
+    op: JournalOp,
+}
+
+/// A journal left behind by a previous session
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JournalInfo {
+    /// The file the unsaved edits belong to
+    pub path: String,
+    /// Hash of the file contents the edits were made against
+    pub base_hash: u64,
+    /// Encoding the buffer decoded the file with
+    pub encoding: Encoding,
+    /// Seconds since the epoch when the first unsaved edit was made
+    pub created_at: u64,
+    /// Seconds since the epoch of the last entry
+    pub updated_at: u64,
+    /// Number of entries after the header
+    pub entries: usize,
+    journal_file: PathBuf,
+}
+
+impl JournalInfo {
+    /// Whether the file on disk still matches the text the edits were made
+    /// against (a missing file never does)
+    pub fn base_matches_disk(&self) -> bool {
+        std::fs::read(&self.path).is_ok_and(|bytes| content_hash(&bytes) == self.base_hash)
+    }
+
+    /// The file on disk, decoded the way the buffer decoded it
+    pub fn read_disk_text(&self) -> Result<String> {
+        let bytes =
+            std::fs::read(&self.path).with_context(|| format!("Cannot read {}", self.path))?;
+        Ok(self.encoding.decode_file(&bytes)?.text)
+    }
+}
 
 pub struct RecoveryManager {
     recovery_dir: PathBuf,
+    compact_after: usize,
 }
 
 impl Default for RecoveryManager {
@@ -17,58 +97,364 @@ impl RecoveryManager {
             .unwrap_or_else(|| PathBuf::from("."))
             .join("forge")
             .join("recovery");
-        Self { recovery_dir: dir }
+        Self::with_dir(dir)
     }
-    pub fn save_buffer(&self, file_path: &str, content: &str) -> Result<()> {
+
+    pub fn with_dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            recovery_dir: dir.into(),
+            compact_after: DEFAULT_COMPACT_AFTER,
+        }
+    }
+
+    /// Set how many entries a journal may collect before it is compacted
+    pub fn set_compact_after(&mut self, entries: usize) {
+        self.compact_after = entries.max(1);
+    }
+
+    /// Start a fresh journal for `file_path`, whose on-disk bytes hash to
+    /// `base_hash` and decode with `encoding`. Any older journal for the file
+    /// is replaced.
+    pub fn start(&self, file_path: &str, base_hash: u64, encoding: Encoding) -> Result<Journal> {
         std::fs::create_dir_all(&self.recovery_dir)?;
-        let hash = Self::path_hash(file_path);
-        std::fs::write(self.recovery_dir.join(hash), content)?;
-        Ok(())
+        let header = JournalHeader {
+            path: file_path.to_string(),
+            base_hash,
+            encoding: encoding.id().to_string(),
+            created_at: now_secs(),
+        };
+        let journal_file = self.journal_path(file_path);
+        let file = write_journal(&journal_file, &header, None)?;
+        Ok(Journal {
+            file,
+            journal_file,
+            header,
+            entries: 0,
+            compact_after: self.compact_after,
+        })
+    }
+
+    /// Journals found in the recovery directory, oldest first. Unreadable
+    /// journals are skipped.
+    pub fn list(&self) -> Result<Vec<JournalInfo>> {
+        let Ok(dir) = std::fs::read_dir(&self.recovery_dir) else {
+            return Ok(Vec::new());
+        };
+        let mut journals: Vec<JournalInfo> = dir
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == JOURNAL_EXTENSION))
+            .filter_map(|path| read_journal(&path).ok().map(|(info, _)| info))
+            .collect();
+        journals.sort_by(|a, b| (a.created_at, &a.path).cmp(&(b.created_at, &b.path)));
+        Ok(journals)
     }
-    pub fn recover_buffer(&self, file_path: &str) -> Result<Option<String>> {
-        let hash = Self::path_hash(file_path);
-        let path = self.recovery_dir.join(hash);
-        if path.exists() {
-            Ok(Some(std::fs::read_to_string(path)?))
-        } else {
-            Ok(None)
+
+    /// Rebuild the unsaved text recorded in a journal.
+    ///
+    /// Entries after the last snapshot are replayed over that snapshot, or
+    /// over the file on disk if the journal was never compacted; in that
+    /// case the file must not have changed since the edits were made.
+    pub fn replay(&self, info: &JournalInfo) -> Result<String> {
+        let (_, entries) = read_journal(&info.journal_file)?;
+        let snapshot = entries
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(i, e)| match &e.op {
+                JournalOp::Snapshot(text) => Some((i, text)),
+                JournalOp::Changes(_) => None,
+            });
+        let (mut rope, rest) = match snapshot {
+            Some((i, text)) => (Rope::from_str(text), &entries[i + 1..]),
+            None => {
+                if !info.base_matches_disk() {
+                    anyhow::bail!("{} changed on disk since the edits were made", info.path);
+                }
+                (Rope::from_str(&info.read_disk_text()?), &entries[..])
+            }
+        };
+        for entry in rest {
+            if let JournalOp::Changes(changes) = &entry.op {
+                check_changes(changes, &rope)
+                    .with_context(|| format!("Journal for {} is corrupt", info.path))?;
+                changes.apply(&mut rope);
+            }
         }
+        Ok(rope.to_string())
     }
+
+    /// Delete a journal left by a previous session
+    pub fn discard(&self, info: &JournalInfo) -> Result<()> {
+        remove_if_exists(&info.journal_file)
+    }
+
+    /// Delete the journal for `file_path`, if any
     pub fn clear(&self, file_path: &str) -> Result<()> {
-        let path = self.recovery_dir.join(Self::path_hash(file_path));
-        if path.exists() {
-            std::fs::remove_file(path)?;
+        remove_if_exists(&self.journal_path(file_path))
+    }
+
+    fn journal_path(&self, file_path: &str) -> PathBuf {
+        self.recovery_dir.join(format!(
+            "{:016x}.{}",
+            content_hash(file_path.as_bytes()),
+            JOURNAL_EXTENSION
+        ))
+    }
+}
+
+/// An open journal being appended to
+#[derive(Debug)]
+pub struct Journal {
+    file: File,
+    journal_file: PathBuf,
+    header: JournalHeader,
+    entries: usize,
+    compact_after: usize,
+}
+
+impl Journal {
+    pub fn path(&self) -> &str {
+        &self.header.path
+    }
+
+    /// Hash of the file contents the journal's edits apply to
+    pub fn base_hash(&self) -> u64 {
+        self.header.base_hash
+    }
+
+    /// Append an edit, syncing it to disk before returning
+    pub fn record(&mut self, changes: &ChangeSet) -> Result<()> {
+        self.record_all(std::slice::from_ref(changes))
+    }
+
+    /// Append edits in one write, syncing them to disk once before returning
+    pub fn record_all(&mut self, changes: &[ChangeSet]) -> Result<()> {
+        if changes.is_empty() {
+            return Ok(());
         }
+        let at = now_secs();
+        let mut lines = Vec::new();
+        for changes in changes {
+            let entry = JournalEntry {
+                at,
+                op: JournalOp::Changes(changes.clone()),
+            };
+            serde_json::to_writer(&mut lines, &entry)?;
+            lines.push(b'\n');
+        }
+        self.file.write_all(&lines)?;
+        self.file.sync_data()?;
+        self.entries += changes.len();
+        Ok(())
+    }
+
+    /// Whether enough entries have piled up to be worth compacting
+    pub fn needs_compaction(&self) -> bool {
+        self.entries >= self.compact_after
+    }
+
+    /// Replace every entry with a snapshot of the current `text`.
+    ///
+    /// The new journal is written beside the old one and renamed over it, so
+    /// a crash mid-way leaves one of the two intact.
+    pub fn compact(&mut self, text: &str) -> Result<()> {
+        let snapshot = JournalEntry {
+            at: now_secs(),
+            op: JournalOp::Snapshot(text.to_string()),
+        };
+        self.file = write_journal(&self.journal_file, &self.header, Some(&snapshot))?;
+        self.entries = 1;
         Ok(())
     }
-    fn path_hash(path: &str) -> String {
-        format!("{:x}", md5_simple(path))
+
+    /// Delete the journal, e.g. after the buffer was saved
+    pub fn remove(self) -> Result<()> {
+        drop(self.file);
+        remove_if_exists(&self.journal_file)
     }
 }
 
-fn md5_simple(input: &str) -> u64 {
-    input
-        .bytes()
-        .fold(0u64, |acc, b| acc.wrapping_mul(31).wrapping_add(b as u64))
+/// Write a journal with `header` and an optional first entry, returning it
+/// open for appending
+fn write_journal(
+    path: &Path,
+    header: &JournalHeader,
+    entry: Option<&JournalEntry>,
+) -> Result<File> {
+    let mut contents = serde_json::to_vec(header)?;
+    contents.push(b'\n');
+    if let Some(entry) = entry {
+        contents.extend(serde_json::to_vec(entry)?);
+        contents.push(b'\n');
+    }
+    crate::file_io::FileIO::save_atomic(path, contents)?;
+    Ok(OpenOptions::new().append(true).open(path)?)
+}
+
+fn read_journal(path: &Path) -> Result<(JournalInfo, Vec<JournalEntry>)> {
+    let mut lines = BufReader::new(File::open(path)?).lines();
+    let header: JournalHeader = serde_json::from_str(
+        &lines
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Empty journal"))??,
+    )?;
+    let encoding = Encoding::from_id(&header.encoding)
+        .ok_or_else(|| anyhow::anyhow!("Unknown encoding {}", header.encoding))?;
+    let mut entries = Vec::new();
+    for line in lines {
+        // A crash mid-append leaves a partial last line; stop there
+        match serde_json::from_str::<JournalEntry>(&line?) {
+            Ok(entry) => entries.push(entry),
+            Err(_) => break,
+        }
+    }
+    let info = JournalInfo {
+        updated_at: entries.last().map_or(header.created_at, |e| e.at),
+        entries: entries.len(),
+        path: header.path,
+        base_hash: header.base_hash,
+        encoding,
+        created_at: header.created_at,
+        journal_file: path.to_path_buf(),
+    };
+    Ok((info, entries))
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{Change, Position};
+
+    fn manager(name: &str) -> RecoveryManager {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        RecoveryManager::with_dir(dir)
+    }
+
+    fn insert(at: usize, text: &str) -> ChangeSet {
+        ChangeSet::with_change(Change::insert(Position::new(at), text.to_string()))
+    }
+
     #[test]
-    fn save_and_recover() {
-        let rm = RecoveryManager {
-            recovery_dir: std::env::temp_dir().join("forge_test_recovery"),
-        };
-        if rm.recovery_dir.exists() {
-            std::fs::remove_dir_all(&rm.recovery_dir).unwrap();
-        }
-        rm.save_buffer("test.rs", "fn main() {}").unwrap();
-        assert_eq!(
-            rm.recover_buffer("test.rs").unwrap(),
-            Some("fn main() {}".into())
-        );
-        rm.clear("test.rs").unwrap();
-        assert_eq!(rm.recover_buffer("test.rs").unwrap(), None);
+    fn replays_journal_over_file() {
+        let rm = manager("forge_test_recovery_replay");
+        let file = std::env::temp_dir().join("forge_test_recovery_replay.rs");
+        std::fs::write(&file, "fn main() {}").unwrap();
+        let path = file.to_string_lossy().to_string();
+
+        let mut journal = rm
+            .start(&path, content_hash(b"fn main() {}"), Encoding::Utf8)
+            .unwrap();
+        journal
+            .record_all(&[insert(0, "pub "), insert(15, " ")])
+            .unwrap();
+        drop(journal);
+
+        let journals = rm.list().unwrap();
+        assert_eq!(journals.len(), 1);
+        assert_eq!(journals[0].path, path);
+        assert_eq!(journals[0].entries, 2);
+        assert!(journals[0].base_matches_disk());
+        assert_eq!(rm.replay(&journals[0]).unwrap(), "pub fn main() { }");
+
+        // Edits can't be replayed onto text they weren't made against
+        std::fs::write(&file, "fn other() {}").unwrap();
+        assert!(rm.replay(&journals[0]).is_err());
+
+        rm.discard(&journals[0]).unwrap();
+        assert!(rm.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn compaction_keeps_text_and_survives_torn_writes() {
+        let mut rm = manager("forge_test_recovery_compact");
+        rm.set_compact_after(2);
+        let mut journal = rm
+            .start("/nonexistent/forge/a.txt", 0, Encoding::Utf8)
+            .unwrap();
+        journal.record(&insert(0, "a")).unwrap();
+        journal.record(&insert(1, "b")).unwrap();
+        assert!(journal.needs_compaction());
+        journal.compact("ab").unwrap();
+        assert!(!journal.needs_compaction());
+        journal.record(&insert(2, "c")).unwrap();
+        let journal_file = journal.journal_file.clone();
+        drop(journal);
+
+        // Simulate a crash in the middle of appending an entry
+        let mut file = OpenOptions::new().append(true).open(&journal_file).unwrap();
+        file.write_all(b"{\"at\":1,\"op\":{\"Chan").unwrap();
+
+        let info = rm.list().unwrap().remove(0);
+        assert_eq!(info.entries, 2);
+        assert!(!info.base_matches_disk());
+        assert_eq!(rm.replay(&info).unwrap(), "abc");
+
+        rm.clear("/nonexistent/forge/a.txt").unwrap();
+        assert!(rm.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn refuses_edits_that_dont_fit_the_text() {
+        let rm = manager("forge_test_recovery_corrupt");
+        let mut journal = rm
+            .start("/nonexistent/forge/b.txt", 0, Encoding::Utf8)
+            .unwrap();
+        journal.compact("0123456789").unwrap();
+        let delete = |start, end| Change::delete(Position::new(start), Position::new(end));
+        journal
+            .record(&ChangeSet::from_changes(vec![delete(0, 5), delete(2, 8)]))
+            .unwrap();
+        drop(journal);
+        let info = rm.list().unwrap().remove(0);
+        assert!(rm.replay(&info).is_err());
+
+        let mut journal = rm
+            .start("/nonexistent/forge/b.txt", 0, Encoding::Utf8)
+            .unwrap();
+        journal.compact("héllo").unwrap();
+        journal.record(&insert(2, "x")).unwrap();
+        drop(journal);
+        let info = rm.list().unwrap().remove(0);
+        assert!(rm.replay(&info).is_err());
+
+        rm.clear("/nonexistent/forge/b.txt").unwrap();
+    }
+
+    #[test]
+    fn replays_over_the_recorded_encoding() {
+        let rm = manager("forge_test_recovery_encoding");
+        let file = std::env::temp_dir().join("forge_test_recovery_encoding.txt");
+        // Also valid UTF-8, so sniffing the bytes would pick the wrong encoding
+        let bytes = b"caf\xc3\xa9";
+        std::fs::write(&file, bytes).unwrap();
+        let path = file.to_string_lossy().to_string();
+
+        let mut journal = rm
+            .start(&path, content_hash(bytes), Encoding::Latin1)
+            .unwrap();
+        // Decoded as Latin-1 the text is "cafÃ©", seven bytes of UTF-8
+        journal.record(&insert(7, "!")).unwrap();
+        drop(journal);
+
+        let info = rm.list().unwrap().remove(0);
+        assert_eq!(info.encoding, Encoding::Latin1);
+        assert_eq!(rm.replay(&info).unwrap(), "caf\u{c3}\u{a9}!");
+        rm.discard(&info).unwrap();
+        std::fs::remove_file(file).ok();
     }
 }
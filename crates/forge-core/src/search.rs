@@ -0,0 +1,655 @@
+//! Regex and literal search over a rope.
+//!
+//! Patterns run on a lazy DFA fed straight from the rope's chunks, so the
+//! text is never copied into a `String` and matches can span lines. A
+//! forward pass finds where the leftmost match ends, then a reverse pass
+//! from there finds where it starts. Matches are byte ranges, produced one
+//! at a time by [`Searcher::find_iter`].
+
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::Result;
+use regex_automata::hybrid::dfa::{Cache, DFA};
+use regex_automata::nfa::thompson;
+use regex_automata::util::{start, syntax};
+use regex_automata::{Anchored, MatchKind};
+use regex_syntax::hir::{Class, Hir, HirKind};
+use ropey::Rope;
+
+use crate::{Assoc, ChangeSet, Position};
+
+/// How a query is interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchOptions {
+    /// The query is a regular expression rather than literal text
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Only match text starting and ending on a word boundary
+    pub whole_word: bool,
+}
+
+/// A compiled search query
+#[derive(Debug, Clone)]
+pub struct Searcher {
+    query: String,
+    options: SearchOptions,
+    forward: DFA,
+    reverse: DFA,
+    /// For when the lazy DFA gives up, which it does at non-ASCII text next
+    /// to a Unicode word boundary
+    fallback: regex::Regex,
+    /// Most line breaks a match can contain; `None` if there is no limit
+    line_breaks: Option<usize>,
+}
+
+/// The lazy DFA couldn't continue; search with the fallback regex instead
+struct GaveUp;
+
+/// Lines the fallback search copies out of the rope at a time, besides those
+/// a match starting on them may run on into
+const FALLBACK_LINES: usize = 64;
+
+impl Searcher {
+    /// Compile `query`. Fails only for an invalid regular expression.
+    pub fn new(query: &str, options: SearchOptions) -> Result<Self> {
+        let pattern = if options.regex {
+            query.to_string()
+        } else {
+            regex::escape(query)
+        };
+        let pattern = if options.whole_word {
+            format!(r"\b(?:{})\b", pattern)
+        } else {
+            pattern
+        };
+        let syntax = syntax::Config::new()
+            .case_insensitive(!options.case_sensitive)
+            .multi_line(true);
+
+        let forward = DFA::builder()
+            .configure(DFA::config().unicode_word_boundary(true))
+            .syntax(syntax)
+            .build(&pattern)?;
+        // Anchored at the match end and run backwards, the longest match is
+        // the leftmost start
+        let reverse = DFA::builder()
+            .configure(
+                DFA::config()
+                    .unicode_word_boundary(true)
+                    .match_kind(MatchKind::All)
+                    .prefilter(None)
+                    .specialize_start_states(false),
+            )
+            .syntax(syntax)
+            .thompson(thompson::Config::new().reverse(true))
+            .build(&pattern)?;
+        let fallback = regex::RegexBuilder::new(&pattern)
+            .case_insensitive(!options.case_sensitive)
+            .multi_line(true)
+            .build()?;
+        let line_breaks = max_line_breaks(&syntax::parse_with(&pattern, &syntax)?);
+
+        Ok(Self {
+            query: query.to_string(),
+            options,
+            forward,
+            reverse,
+            fallback,
+            line_breaks,
+        })
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn options(&self) -> SearchOptions {
+        self.options
+    }
+
+    /// Matches lying within `range` of the rope, front to back and never
+    /// overlapping
+    pub fn find_iter<'a>(&'a self, rope: &'a Rope, range: Range<usize>) -> Matches<'a> {
+        let len = rope.len_bytes();
+        let end = range.end.min(len);
+        let start = rope.char_to_byte(rope.byte_to_char(range.start.min(end)));
+        Matches {
+            searcher: self,
+            rope,
+            pos: start,
+            end,
+            last_end: None,
+            forward_cache: self.forward.create_cache(),
+            reverse_cache: self.reverse.create_cache(),
+        }
+    }
+
+    /// The first match starting at or after byte `from`
+    pub fn find_at(&self, rope: &Rope, from: usize) -> Option<Range<usize>> {
+        self.find_iter(rope, from..rope.len_bytes()).next()
+    }
+}
+
+/// Lazy iterator over the matches of a [`Searcher`]
+pub struct Matches<'a> {
+    searcher: &'a Searcher,
+    rope: &'a Rope,
+    /// Where the next search starts
+    pos: usize,
+    end: usize,
+    /// End of the previous match; an empty match there is skipped
+    last_end: Option<usize>,
+    forward_cache: Cache,
+    reverse_cache: Cache,
+}
+
+impl Iterator for Matches<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        loop {
+            if self.pos > self.end {
+                return None;
+            }
+            let found = match self.find_end() {
+                Ok(Some(end)) => match self.find_start(end) {
+                    Ok(start) => start..end,
+                    Err(GaveUp) => self.find_fallback()?,
+                },
+                Ok(None) => return None,
+                Err(GaveUp) => self.find_fallback()?,
+            };
+            if found.is_empty() && self.last_end == Some(found.end) {
+                // Step past an empty match touching the previous one
+                self.pos = if found.end >= self.end {
+                    self.end + 1
+                } else {
+                    self.rope
+                        .char_to_byte(self.rope.byte_to_char(found.end) + 1)
+                };
+                continue;
+            }
+            self.pos = found.end;
+            self.last_end = Some(found.end);
+            return Some(found);
+        }
+    }
+}
+
+impl Matches<'_> {
+    /// End of the leftmost match starting at or after `pos`
+    fn find_end(&mut self) -> Result<Option<usize>, GaveUp> {
+        let dfa = &self.searcher.forward;
+        let cache = &mut self.forward_cache;
+        let look_behind = self.pos.checked_sub(1).map(|i| self.rope.byte(i));
+        let config = start::Config::new()
+            .anchored(Anchored::No)
+            .look_behind(look_behind);
+        let mut sid = dfa.start_state(cache, &config).map_err(|_| GaveUp)?;
+
+        let mut found = None;
+        let mut at = self.pos;
+        let (chunks, chunk_start, _, _) = self.rope.chunks_at_byte(self.pos);
+        let mut skip = self.pos - chunk_start;
+        'chunks: for chunk in chunks {
+            for &byte in &chunk.as_bytes()[skip..] {
+                if at == self.end {
+                    break 'chunks;
+                }
+                sid = dfa.next_state(cache, sid, byte).map_err(|_| GaveUp)?;
+                // Match states are entered one byte late, so a match seen
+                // here ends before `byte`
+                if sid.is_tagged() {
+                    if sid.is_match() {
+                        found = Some(at);
+                    } else if sid.is_dead() {
+                        return Ok(found);
+                    } else if sid.is_quit() {
+                        return Err(GaveUp);
+                    }
+                }
+                at += 1;
+            }
+            skip = 0;
+        }
+
+        // The byte after the range still decides `$` and `\b`
+        sid = if self.end < self.rope.len_bytes() {
+            dfa.next_state(cache, sid, self.rope.byte(self.end))
+        } else {
+            dfa.next_eoi_state(cache, sid)
+        }
+        .map_err(|_| GaveUp)?;
+        if sid.is_quit() {
+            return Err(GaveUp);
+        }
+        if sid.is_match() {
+            found = Some(self.end);
+        }
+        Ok(found)
+    }
+
+    /// Start of the match ending at `end`
+    fn find_start(&mut self, end: usize) -> Result<usize, GaveUp> {
+        let dfa = &self.searcher.reverse;
+        let cache = &mut self.reverse_cache;
+        let look_behind = (end < self.rope.len_bytes()).then(|| self.rope.byte(end));
+        let config = start::Config::new()
+            .anchored(Anchored::Yes)
+            .look_behind(look_behind);
+        let mut sid = dfa.start_state(cache, &config).map_err(|_| GaveUp)?;
+
+        let mut found = end;
+        let mut at = end;
+        let mut bytes = self.rope.bytes_at(end);
+        while at > self.pos {
+            let Some(byte) = bytes.prev() else { break };
+            at -= 1;
+            sid = dfa.next_state(cache, sid, byte).map_err(|_| GaveUp)?;
+            if sid.is_tagged() {
+                if sid.is_match() {
+                    found = at + 1;
+                } else if sid.is_dead() {
+                    return Ok(found);
+                } else if sid.is_quit() {
+                    return Err(GaveUp);
+                }
+            }
+        }
+
+        sid = match self.pos.checked_sub(1) {
+            Some(before) => dfa.next_state(cache, sid, self.rope.byte(before)),
+            None => dfa.next_eoi_state(cache, sid),
+        }
+        .map_err(|_| GaveUp)?;
+        if sid.is_quit() {
+            return Err(GaveUp);
+        }
+        if sid.is_match() {
+            found = self.pos;
+        }
+        Ok(found)
+    }
+
+    /// Search with the fallback regex, copying the text out of the rope a
+    /// few lines at a time. A pattern that can span any number of lines
+    /// copies the rest of the rope instead.
+    fn find_fallback(&mut self) -> Option<Range<usize>> {
+        let rope = self.rope;
+        let line_start = |line: usize| rope.line_to_byte(line.min(rope.len_lines()));
+        // Just past the `count`th `\n` from `from` on, or the end of the rope
+        let past_line_feeds = |from: usize, count: usize| {
+            let mut feeds = rope.bytes_at(from).enumerate().filter(|&(_, b)| b == b'\n');
+            feeds
+                .nth(count - 1)
+                .map_or(rope.len_bytes(), |(i, _)| from + i + 1)
+        };
+        let mut from = self.pos;
+        let mut line = rope.byte_to_line(from);
+        loop {
+            // A match starting before line `next` ends inside the window
+            let next = line + FALLBACK_LINES;
+            let end = match self.searcher.line_breaks {
+                Some(breaks) => past_line_feeds(line_start(next), breaks + 1),
+                None => rope.len_bytes(),
+            };
+            // The character before the window still decides `^` and `\b`
+            let start = rope.char_to_byte(rope.line_to_char(line).saturating_sub(1));
+            let text = rope.byte_slice(start..end).to_string();
+            let found = self.searcher.fallback.find_at(&text, from - start);
+            let found = found.map(|m| start + m.start()..start + m.end());
+            let complete = end == rope.len_bytes();
+            match found {
+                Some(found) if complete || found.start < line_start(next) => {
+                    return (found.end <= self.end).then_some(found);
+                }
+                _ if complete || line_start(next) > self.end => return None,
+                _ => {
+                    from = line_start(next);
+                    line = next;
+                }
+            }
+        }
+    }
+}
+
+/// Most line breaks a match of `hir` can contain; `None` if there is no
+/// limit
+fn max_line_breaks(hir: &Hir) -> Option<usize> {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => Some(0),
+        HirKind::Literal(literal) => Some(literal.0.iter().filter(|&&b| b == b'\n').count()),
+        HirKind::Class(Class::Unicode(class)) => Some(usize::from(
+            class
+                .ranges()
+                .iter()
+                .any(|r| (r.start()..=r.end()).contains(&'\n')),
+        )),
+        HirKind::Class(Class::Bytes(class)) => Some(usize::from(
+            class
+                .ranges()
+                .iter()
+                .any(|r| (r.start()..=r.end()).contains(&b'\n')),
+        )),
+        HirKind::Repetition(repetition) => {
+            match (max_line_breaks(&repetition.sub)?, repetition.max) {
+                (0, _) => Some(0),
+                (breaks, Some(max)) => Some(breaks * max as usize),
+                (_, None) => None,
+            }
+        }
+        HirKind::Capture(capture) => max_line_breaks(&capture.sub),
+        HirKind::Concat(subs) => subs.iter().map(max_line_breaks).sum(),
+        HirKind::Alternation(subs) => subs
+            .iter()
+            .try_fold(0, |most, sub| Some(most.max(max_line_breaks(sub)?))),
+    }
+}
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// The matches of a query in a rope, kept up to date as the rope is edited.
+///
+/// An edit only re-scans the lines it touched, carrying on past them until
+/// the matches found line up with the old ones again.
+#[derive(Debug, Clone)]
+pub struct IncrementalSearch {
+    searcher: Searcher,
+    matches: Vec<Range<usize>>,
+    generation: u64,
+}
+
+impl IncrementalSearch {
+    pub fn new(searcher: Searcher, rope: &Rope) -> Self {
+        let mut search = Self {
+            searcher,
+            matches: Vec::new(),
+            generation: 0,
+        };
+        search.rescan(rope);
+        search
+    }
+
+    pub fn searcher(&self) -> &Searcher {
+        &self.searcher
+    }
+
+    /// Byte ranges of every match, in order
+    pub fn matches(&self) -> &[Range<usize>] {
+        &self.matches
+    }
+
+    /// Changes whenever the matches may have. Unique across searches, so a
+    /// view showing the matches of different buffers can tell them apart.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Search the whole rope again
+    pub fn rescan(&mut self, rope: &Rope) {
+        self.matches = self.searcher.find_iter(rope, 0..rope.len_bytes()).collect();
+        self.generation = GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Bring the matches up to date with `changes`, which `rope` already has
+    /// applied
+    pub fn update(&mut self, rope: &Rope, changes: &ChangeSet) {
+        if changes.is_empty() {
+            return;
+        }
+        // A match could start any number of lines before the edit
+        let Some(line_breaks) = self.searcher.line_breaks else {
+            return self.rescan(rope);
+        };
+        let mut edits: Vec<(usize, usize)> = changes
+            .changes
+            .iter()
+            .map(|c| (c.start.offset, c.end.offset))
+            .collect();
+        edits.sort_unstable();
+        let map = |offset, assoc| changes.map_position(Position::new(offset), assoc).offset;
+
+        // Regions to re-scan, in the edited text: every edit, and every old
+        // match an edit touched
+        let mut dirty: Vec<Range<usize>> = edits
+            .iter()
+            .map(|&(start, end)| map(start, Assoc::Before)..map(end, Assoc::After))
+            .collect();
+        let mut kept = Vec::with_capacity(self.matches.len());
+        for m in self.matches.drain(..) {
+            let next_edit = edits.partition_point(|&(_, end)| end < m.start);
+            let touched = edits
+                .get(next_edit)
+                .is_some_and(|&(start, _)| start <= m.end);
+            let start = map(m.start, Assoc::Before);
+            let end = map(m.end, Assoc::After).max(start);
+            if touched {
+                dirty.push(start..end);
+            } else {
+                kept.push(start..end);
+            }
+        }
+
+        let mut matches = Vec::with_capacity(kept.len());
+        let mut kept = kept.into_iter().peekable();
+        for window in line_windows(rope, dirty, line_breaks) {
+            while let Some(m) = kept.next_if(|m| m.start < window.start) {
+                matches.push(m);
+            }
+            let from = matches
+                .last()
+                .map_or(0, |m: &Range<usize>| m.end)
+                .max(window.start);
+            let mut found_iter = self.searcher.find_iter(rope, from..rope.len_bytes());
+            found_iter.last_end = matches.last().map(|m| m.end).filter(|&end| end == from);
+
+            let mut in_step = false;
+            for found in found_iter {
+                if found.start >= window.end {
+                    // Past the edit, the old matches from here on still hold
+                    // once one of them turns up unchanged
+                    while kept.next_if(|m| m.start < found.start).is_some() {}
+                    if kept.peek() == Some(&found) {
+                        in_step = true;
+                        break;
+                    }
+                }
+                while kept
+                    .next_if(|m| m.start < found.end || *m == found)
+                    .is_some()
+                {}
+                matches.push(found);
+            }
+            if !in_step {
+                // Scanned to the end, so anything not found again is gone
+                kept.by_ref().for_each(drop);
+            }
+        }
+        matches.extend(kept);
+
+        self.matches = matches;
+        self.generation = GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Ranges widened to whole lines, sorted and merged. Each also takes in the
+/// `line_breaks` lines before it (at least one), where a match of a
+/// multi-line pattern running into the edit may start.
+fn line_windows(
+    rope: &Rope,
+    mut ranges: Vec<Range<usize>>,
+    line_breaks: usize,
+) -> Vec<Range<usize>> {
+    ranges.sort_unstable_by_key(|r| r.start);
+    let before = line_breaks.max(1);
+    let mut windows: Vec<Range<usize>> = Vec::new();
+    for range in ranges {
+        let start = rope.line_to_byte(rope.byte_to_line(range.start).saturating_sub(before));
+        let end_line = rope.byte_to_line(range.end.max(range.start)) + 1;
+        let end = rope.line_to_byte(end_line.min(rope.len_lines()));
+        match windows.last_mut() {
+            Some(last) if start <= last.end => last.end = last.end.max(end),
+            _ => windows.push(start..end),
+        }
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Change;
+
+    fn find_all<'t>(query: &str, options: SearchOptions, text: &'t str) -> Vec<&'t str> {
+        let rope = Rope::from_str(text);
+        let searcher = Searcher::new(query, options).unwrap();
+        searcher
+            .find_iter(&rope, 0..rope.len_bytes())
+            .map(|m| &text[m])
+            .collect()
+    }
+
+    #[test]
+    fn matches_literals_and_regexes() {
+        let text = "Foo foo food\nfoo.bar f\u{F6}\u{F6} x";
+        let literal = SearchOptions::default();
+        assert_eq!(find_all("foo", literal, text).len(), 4);
+        assert_eq!(find_all("foo.", literal, text), vec!["foo."]);
+        let case = SearchOptions {
+            case_sensitive: true,
+            ..literal
+        };
+        assert_eq!(find_all("Foo", case, text), vec!["Foo"]);
+        let word = SearchOptions {
+            whole_word: true,
+            ..literal
+        };
+        // Unicode word boundaries fall back to the regex crate
+        assert_eq!(find_all("foo", word, text).len(), 3);
+        assert_eq!(find_all("f\u{F6}\u{F6}", word, text), vec!["f\u{F6}\u{F6}"]);
+
+        let regex = SearchOptions {
+            regex: true,
+            ..literal
+        };
+        assert_eq!(find_all(r"o+d?$", regex, text), vec!["ood"]);
+        assert_eq!(find_all(r"d\nf", regex, text), vec!["d\nf"]);
+        assert_eq!(find_all("^", regex, text).len(), 2);
+        assert!(Searcher::new("(", regex).is_err());
+    }
+
+    #[test]
+    fn matches_across_chunk_boundaries() {
+        let line = "lorem ipsum dolor sit amet\n";
+        let text = format!(
+            "{}needle\nin a\nhaystack{}",
+            line.repeat(300),
+            line.repeat(300)
+        );
+        let rope = Rope::from_str(&text);
+        assert!(rope.chunks().count() > 2);
+        let options = SearchOptions {
+            regex: true,
+            ..Default::default()
+        };
+        let searcher = Searcher::new(r"needle\n.*\nhay", options).unwrap();
+        let found = searcher.find_at(&rope, 0).unwrap();
+        assert_eq!(found.start, text.find("needle").unwrap());
+        assert_eq!(&text[found], "needle\nin a\nhay");
+
+        let amet = Searcher::new("amet", SearchOptions::default()).unwrap();
+        assert_eq!(amet.find_iter(&rope, 0..rope.len_bytes()).count(), 600);
+        // A range cuts off matches running past its end
+        assert_eq!(amet.find_iter(&rope, 0..line.len() - 1).count(), 1);
+        assert_eq!(amet.find_iter(&rope, 0..line.len() - 2).count(), 0);
+    }
+
+    #[test]
+    fn fallback_finds_matches_across_its_windows() {
+        let text = "f\u{F6}\u{F6} bar\nbaz\n".repeat(100);
+        let word = SearchOptions {
+            whole_word: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(find_all("f\u{F6}\u{F6}", word, &text).len(), 100);
+        let regex = SearchOptions {
+            regex: true,
+            ..word
+        };
+        assert_eq!(find_all("bar\nbaz\nf\u{F6}\u{F6}", regex, &text).len(), 99);
+    }
+
+    #[test]
+    fn incremental_updates_match_a_full_rescan() {
+        let text = "let a = 1;\nlet b = a + a;\n\nfn a() {}\nlet ab = 2;\n";
+        let queries = [
+            ("a", false, false),
+            (r"\ba\b", true, false),
+            (r";\n\n?fn", true, false),
+            ("^", true, false),
+            ("let", false, true),
+        ];
+        let edits = [
+            Change::insert(Position::new(4), "x".into()),
+            Change::delete(Position::new(10), Position::new(12)),
+            Change::replace(Position::new(20), Position::new(30), "aa\n\nfn".into()),
+            Change::insert(Position::new(0), "a\n".into()),
+            Change::delete(Position::new(0), Position::new(40)),
+        ];
+        let mut cases: Vec<(&str, _, &[Change])> = queries
+            .into_iter()
+            .map(|query| (text, query, &edits[..]))
+            .collect();
+        // The match starts three lines before the edit that completes it
+        let completed = [Change::replace(
+            Position::new(6),
+            Position::new(7),
+            "y".into(),
+        )];
+        cases.push(("x\na\nb\nz\n", (r"x\n.*\n.*\ny", true, false), &completed));
+
+        for (text, (query, regex, whole_word), edits) in cases {
+            let options = SearchOptions {
+                regex,
+                whole_word,
+                case_sensitive: true,
+            };
+            let searcher = Searcher::new(query, options).unwrap();
+            let mut rope = Rope::from_str(text);
+            let mut search = IncrementalSearch::new(searcher.clone(), &rope);
+            for edit in edits {
+                let changes = ChangeSet::with_change(edit.clone());
+                changes.apply(&mut rope);
+                let generation = search.generation();
+                search.update(&rope, &changes);
+                assert_ne!(search.generation(), generation);
+                let expected: Vec<_> = searcher.find_iter(&rope, 0..rope.len_bytes()).collect();
+                assert_eq!(search.matches(), expected, "{query:?} in {rope:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn finds_multi_line_matches_starting_before_the_edit() {
+        let options = SearchOptions {
+            regex: true,
+            ..SearchOptions::default()
+        };
+        let searcher = Searcher::new(r";\nf", options).unwrap();
+        let mut rope = Rope::from_str("a;\nxn");
+        let mut search = IncrementalSearch::new(searcher, &rope);
+        assert!(search.matches().is_empty());
+
+        let changes = ChangeSet::with_change(Change::replace(
+            Position::new(3),
+            Position::new(4),
+            "f".into(),
+        ));
+        changes.apply(&mut rope);
+        search.update(&rope, &changes);
+        let expected: Vec<_> = search.searcher().find_iter(&rope, 0..6).collect();
+        assert_eq!(search.matches(), expected);
+        assert_eq!(search.matches().first(), Some(&(1..4)));
+    }
+}
@@ -1,8 +1,9 @@
-use crate::Position;
+use crate::{Assoc, ChangeSet, Position};
+use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 
 /// A range in the text buffer with an anchor (immovable) and head (moving cursor).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Range {
     /// The immovable end of the selection
     pub anchor: Position,
@@ -47,10 +48,28 @@ impl Range {
     pub fn is_empty(&self) -> bool {
         self.is_point()
     }
+
+    /// Map this range through an edit so it keeps covering the same text.
+    ///
+    /// Cursors move past text typed at them; a selection doesn't grow to
+    /// include text inserted right at either edge.
+    pub fn map_through(&self, changes: &ChangeSet) -> Self {
+        if self.is_point() {
+            return Self::point(changes.map_position(self.head, Assoc::After));
+        }
+        let forward = self.anchor <= self.head;
+        let start = changes.map_position(self.start(), Assoc::After);
+        let end = changes.map_position(self.end(), Assoc::Before).max(start);
+        if forward {
+            Self::new(start, end)
+        } else {
+            Self::new(end, start)
+        }
+    }
 }
 
 /// A set of selections (can be multiple cursors/selections)
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Selection {
     /// The selection ranges. Using SmallVec for single-cursor optimization.
     ranges: SmallVec<[Range; 1]>,
@@ -120,6 +139,53 @@ impl Selection {
             primary_index: self.primary_index,
         }
     }
+
+    /// Map every range through an edit, so cursors follow the text they were on
+    pub fn map_through(&self, changes: &ChangeSet) -> Self {
+        self.map(|range| range.map_through(changes))
+    }
+
+    /// Map each range through a function, then merge ranges that overlap
+    /// or coincide. The merged range keeps primary if any part of it was.
+    pub fn map_merged<F>(&self, f: F) -> Self
+    where
+        F: Fn(&Range) -> Range,
+    {
+        let mut ranges: Vec<(Range, bool)> = self
+            .ranges
+            .iter()
+            .enumerate()
+            .map(|(i, range)| (f(range), i == self.primary_index))
+            .collect();
+        ranges.sort_by_key(|(range, _)| range.start());
+
+        let mut merged: SmallVec<[Range; 1]> = SmallVec::new();
+        let mut primary_index = 0;
+        for (range, is_primary) in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start() < last.end() || range == *last => {
+                    // Keep the direction, so extending a backward selection
+                    // still moves its head
+                    let end = last.end().max(range.end());
+                    let backward = if last.is_point() {
+                        range.head < range.anchor
+                    } else {
+                        last.head < last.anchor
+                    };
+                    *last = if backward {
+                        Range::new(end, last.start())
+                    } else {
+                        Range::new(last.start(), end)
+                    };
+                }
+                _ => merged.push(range),
+            }
+            if is_primary {
+                primary_index = merged.len() - 1;
+            }
+        }
+        Self::new(merged, primary_index)
+    }
 
     /// Add a range to the selection
     pub fn push(&mut self, range: Range) {
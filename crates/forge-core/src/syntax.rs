@@ -1,10 +1,67 @@
 use crate::transaction::Change;
 use ropey::Rope;
+use std::ops::Range;
 use tree_sitter::{InputEdit, Language, Parser, Point, Tree};
 
 pub struct Syntax {
     parser: Parser,
     tree: Option<Tree>,
+    /// Regions edited since the last reparse, in current coordinates
+    edited: Vec<Range<usize>>,
+    /// Everything consumers have not caught up with yet
+    pending: SyntaxChanges,
+}
+
+/// Edits and re-parsed regions accumulated since a consumer last caught up.
+///
+/// Consumers holding byte-offset data derived from the tree (highlight spans,
+/// folds, outline entries) map it through [`SyntaxChanges::map_range`] and
+/// recompute only what falls in [`SyntaxChanges::ranges`].
+#[derive(Debug, Clone, Default)]
+pub struct SyntaxChanges {
+    /// Edits applied to the tree, in the order they were made
+    pub edits: Vec<InputEdit>,
+    /// Sorted, disjoint byte ranges of the current text whose syntax may differ
+    pub ranges: Vec<Range<usize>>,
+}
+
+impl SyntaxChanges {
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty() && self.ranges.is_empty()
+    }
+
+    /// Map a range from before the edits to the current text.
+    ///
+    /// Returns `None` if an edit overlapped the range, in which case it lies
+    /// inside one of [`SyntaxChanges::ranges`] and must be recomputed.
+    pub fn map_range(&self, range: Range<usize>) -> Option<Range<usize>> {
+        self.edits.iter().try_fold(range, |range, edit| {
+            if range.end <= edit.start_byte {
+                Some(range)
+            } else if range.start >= edit.old_end_byte {
+                let shift = |offset: usize| offset - edit.old_end_byte + edit.new_end_byte;
+                Some(shift(range.start)..shift(range.end))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Whether `range` (in current coordinates) overlaps or touches a changed region
+    pub fn touches(&self, range: &Range<usize>) -> bool {
+        self.ranges
+            .iter()
+            .any(|r| r.start <= range.end && range.start <= r.end)
+    }
+
+    fn push_edit(&mut self, edit: InputEdit) {
+        map_ranges(&mut self.ranges, &edit);
+        self.edits.push(edit);
+    }
+
+    fn add_range(&mut self, range: Range<usize>) {
+        add_range(&mut self.ranges, range);
+    }
 }
 
 impl Syntax {
@@ -14,21 +71,23 @@ impl Syntax {
             .set_language(&language)
             .expect("Error loading language");
 
-        Self { parser, tree: None }
+        Self {
+            parser,
+            tree: None,
+            edited: Vec::new(),
+            pending: SyntaxChanges::default(),
+        }
     }
 
     /// Parse the entire buffer from scratch
     pub fn parse(&mut self, rope: &Rope) {
-        self.tree = self.parser.parse_with(
-            &mut |byte_offset, _| {
-                if byte_offset >= rope.len_bytes() {
-                    return &[] as &[u8];
-                }
-                let (chunk, chunk_byte_idx, _, _) = rope.chunk_at_byte(byte_offset);
-                &chunk.as_bytes()[byte_offset - chunk_byte_idx..]
-            },
-            None,
-        );
+        self.tree = parse_rope(&mut self.parser, rope, None);
+        self.edited.clear();
+        // Nothing derived from the old tree can be reused
+        self.pending = SyntaxChanges {
+            edits: Vec::new(),
+            ranges: std::iter::once(0..rope.len_bytes()).collect(),
+        };
     }
 
     /// Update the syntax tree with a change
@@ -73,26 +132,94 @@ impl Syntax {
             };
 
             tree.edit(&edit);
+            map_ranges(&mut self.edited, &edit);
+            add_range(&mut self.edited, start_byte..new_end_byte);
+            self.pending.push_edit(edit);
         }
     }
 
-    /// Re-parse the tree using the previous tree as a base
-    pub fn reparse(&mut self, rope: &Rope) {
-        self.tree = self.parser.parse_with(
-            &mut |byte_offset, _| {
-                if byte_offset >= rope.len_bytes() {
-                    return &[] as &[u8];
+    /// Re-parse incrementally, reusing the edited tree.
+    ///
+    /// Returns the byte ranges whose syntax may have changed: the edited text
+    /// plus whatever tree-sitter reports as structurally different. Without
+    /// pending edits this is a no-op and returns nothing.
+    pub fn reparse(&mut self, rope: &Rope) -> Vec<Range<usize>> {
+        let Some(old_tree) = self.tree.take() else {
+            self.parse(rope);
+            return self.pending.ranges.clone();
+        };
+        if self.edited.is_empty() {
+            self.tree = Some(old_tree);
+            return Vec::new();
+        }
+        let mut changed = std::mem::take(&mut self.edited);
+        self.tree = parse_rope(&mut self.parser, rope, Some(&old_tree));
+        match &self.tree {
+            Some(new_tree) => {
+                for range in old_tree.changed_ranges(new_tree) {
+                    add_range(&mut changed, range.start_byte..range.end_byte);
                 }
-                let (chunk, chunk_byte_idx, _, _) = rope.chunk_at_byte(byte_offset);
-                &chunk.as_bytes()[byte_offset - chunk_byte_idx..]
-            },
-            self.tree.as_ref(),
-        );
+            }
+            None => changed = std::iter::once(0..rope.len_bytes()).collect(),
+        }
+        for range in &changed {
+            self.pending.add_range(range.clone());
+        }
+        changed
     }
 
     pub fn tree(&self) -> Option<&Tree> {
         self.tree.as_ref()
     }
+
+    /// Edits and changed ranges since the last call, resetting the record
+    pub fn take_changes(&mut self) -> SyntaxChanges {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Parse `rope` chunk by chunk, without flattening it into a string
+fn parse_rope(parser: &mut Parser, rope: &Rope, old_tree: Option<&Tree>) -> Option<Tree> {
+    parser.parse_with(
+        &mut |byte_offset, _| {
+            if byte_offset >= rope.len_bytes() {
+                return &[] as &[u8];
+            }
+            let (chunk, chunk_byte_idx, _, _) = rope.chunk_at_byte(byte_offset);
+            &chunk.as_bytes()[byte_offset - chunk_byte_idx..]
+        },
+        old_tree,
+    )
+}
+
+/// Move `ranges` to where their text ended up after `edit`
+fn map_ranges(ranges: &mut Vec<Range<usize>>, edit: &InputEdit) {
+    let map = |offset: usize| {
+        if offset <= edit.start_byte {
+            offset
+        } else if offset >= edit.old_end_byte {
+            offset - edit.old_end_byte + edit.new_end_byte
+        } else {
+            edit.new_end_byte
+        }
+    };
+    // Deletions can bring ranges together, so merge them again
+    for range in std::mem::take(ranges) {
+        add_range(ranges, map(range.start)..map(range.end));
+    }
+}
+
+/// Insert `range` into a sorted list, merging anything it overlaps or touches
+fn add_range(ranges: &mut Vec<Range<usize>>, mut range: Range<usize>) {
+    ranges.retain(|r| {
+        let merge = r.start <= range.end && range.start <= r.end;
+        if merge {
+            range = range.start.min(r.start)..range.end.max(r.end);
+        }
+        !merge
+    });
+    let at = ranges.partition_point(|r| r.start < range.start);
+    ranges.insert(at, range);
 }
 
 // Implement manual Debug for Parser wrapper if needed, but the struct derive works if Parser implements Debug
@@ -139,3 +266,75 @@ fn count_lines_cols(text: &str) -> (usize, usize) {
     }
     (lines, last_line_len)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Position;
+
+    fn rust(text: &str) -> (Syntax, Rope) {
+        let rope = Rope::from_str(text);
+        let mut syntax = Syntax::new(tree_sitter_rust::LANGUAGE.into());
+        syntax.parse(&rope);
+        syntax.take_changes();
+        (syntax, rope)
+    }
+
+    fn edit(syntax: &mut Syntax, rope: &mut Rope, change: Change) -> Vec<Range<usize>> {
+        syntax.update(rope, &change);
+        change.apply(rope);
+        syntax.reparse(rope)
+    }
+
+    #[test]
+    fn reparse_reports_only_the_edited_region() {
+        let text = "fn a() { 1 }\nfn b() { 2 }\nfn c() { 3 }\n";
+        let (mut syntax, mut rope) = rust(text);
+
+        let changed = edit(
+            &mut syntax,
+            &mut rope,
+            Change::replace(Position::new(22), Position::new(23), "42".into()),
+        );
+        assert!(!changed.is_empty());
+        assert!(changed.iter().all(|r| r.start >= 13 && r.end <= 27));
+        assert_eq!(syntax.reparse(&rope), Vec::<Range<usize>>::new());
+
+        let tree = syntax.tree().unwrap();
+        assert_eq!(
+            tree.root_node().to_sexp(),
+            rust(&rope.to_string())
+                .0
+                .tree()
+                .unwrap()
+                .root_node()
+                .to_sexp()
+        );
+    }
+
+    #[test]
+    fn changes_map_ranges_through_edits() {
+        let (mut syntax, mut rope) = rust("fn a() {}\nfn b() {}\n");
+        edit(
+            &mut syntax,
+            &mut rope,
+            Change::insert(Position::new(0), "pub ".into()),
+        );
+        edit(
+            &mut syntax,
+            &mut rope,
+            Change::delete(Position::new(17), Position::new(18)),
+        );
+        assert_eq!(rope.to_string(), "pub fn a() {}\nfn () {}\n");
+
+        let changes = syntax.take_changes();
+        assert_eq!(changes.edits.len(), 2);
+        // `fn` of the second function moved past the inserted `pub `
+        assert_eq!(changes.map_range(10..12), Some(14..16));
+        // `b` was deleted
+        assert_eq!(changes.map_range(13..14), None);
+        assert!(changes.touches(&(0..4)));
+        assert!(!changes.touches(&(6..8)));
+        assert!(syntax.take_changes().is_empty());
+    }
+}
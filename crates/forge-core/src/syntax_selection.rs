@@ -0,0 +1,304 @@
+//! Selecting by syntax node.
+//!
+//! Each function maps every range of a [`Selection`] through the tree-sitter
+//! tree independently, so they work the same with one cursor or many. Ranges
+//! that end up overlapping are merged.
+
+use crate::{Position, Range, Selection};
+use tree_sitter::{Node, Tree};
+
+/// Kind of enclosing definition to select
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Enclosing {
+    /// Functions, methods and closures
+    Function,
+    /// Classes, structs, enums, traits, interfaces and impl blocks
+    Class,
+}
+
+/// Node kinds of definitions with a body, by grammar. Matching exact kinds
+/// keeps out parts of a definition such as `class_body` or `function_type`.
+const FUNCTION_KINDS: &[&str] = &[
+    // Rust
+    "function_item",
+    "closure_expression",
+    // JavaScript and TypeScript
+    "function_declaration",
+    "function_expression",
+    "function",
+    "generator_function_declaration",
+    "generator_function",
+    "arrow_function",
+    "method_definition",
+    // Python, C, C++ and Bash
+    "function_definition",
+    "lambda",
+    "lambda_expression",
+    // Go
+    "method_declaration",
+    "func_literal",
+    // Java
+    "constructor_declaration",
+    // Ruby
+    "method",
+    "singleton_method",
+];
+
+const CLASS_KINDS: &[&str] = &[
+    // Rust
+    "struct_item",
+    "enum_item",
+    "union_item",
+    "trait_item",
+    "impl_item",
+    // JavaScript, TypeScript and Java
+    "class_declaration",
+    "class",
+    "abstract_class_declaration",
+    "interface_declaration",
+    "enum_declaration",
+    "record_declaration",
+    // Python
+    "class_definition",
+    // Go
+    "type_declaration",
+    // C and C++
+    "struct_specifier",
+    "union_specifier",
+    "enum_specifier",
+    "class_specifier",
+    // Ruby
+    "module",
+];
+
+impl Enclosing {
+    /// Whether `node` is such a definition. The root is the whole file even
+    /// when its kind is a definition's elsewhere, as Python's `module` is.
+    pub(crate) fn matches(self, node: Node) -> bool {
+        let kinds = match self {
+            Self::Function => FUNCTION_KINDS,
+            Self::Class => CLASS_KINDS,
+        };
+        node.parent().is_some() && kinds.contains(&node.kind())
+    }
+}
+
+/// Grow each range to the smallest syntax node strictly containing it
+pub fn expand(tree: &Tree, selection: &Selection) -> Selection {
+    map_ranges(selection, |range| {
+        let mut node = smallest_node(tree, range)?;
+        while node_range(node) == (range.start().offset, range.end().offset) {
+            node = node.parent()?;
+        }
+        Some(select_node(node, range))
+    })
+}
+
+/// Shrink each range to the first named child of the node it covers.
+/// Ranges that can't shrink any further are left alone.
+pub fn shrink(tree: &Tree, selection: &Selection) -> Selection {
+    map_ranges(selection, |range| {
+        if range.is_empty() {
+            return None;
+        }
+        let node = outermost_node(tree, range)?;
+        let mut cursor = node.walk();
+        let child = node
+            .named_children(&mut cursor)
+            .next()
+            .or_else(|| node.child(0))?;
+        Some(select_node(child, range))
+    })
+}
+
+/// Move each range to the next (or previous) named sibling of the node it covers
+pub fn select_sibling(tree: &Tree, selection: &Selection, forward: bool) -> Selection {
+    map_ranges(selection, |range| {
+        let mut node = outermost_node(tree, range)?;
+        // Climb until there is a sibling in that direction, like moving out
+        // of the last argument to the next statement
+        loop {
+            let sibling = if forward {
+                node.next_named_sibling()
+            } else {
+                node.prev_named_sibling()
+            };
+            match sibling {
+                Some(sibling) => return Some(select_node(sibling, range)),
+                None => node = node.parent()?,
+            }
+        }
+    })
+}
+
+/// Select the nearest enclosing function or class of each range.
+///
+/// A range already covering exactly such a definition moves out to the next
+/// one around it.
+pub fn select_enclosing(tree: &Tree, selection: &Selection, kind: Enclosing) -> Selection {
+    map_ranges(selection, |range| {
+        let bounds = (range.start().offset, range.end().offset);
+        let mut node = smallest_node(tree, range)?;
+        while !(kind.matches(node) && node_range(node) != bounds) {
+            node = node.parent()?;
+        }
+        Some(select_node(node, range))
+    })
+}
+
+/// Apply `f` to every range, keeping those it returns `None` for
+fn map_ranges(selection: &Selection, f: impl Fn(&Range) -> Option<Range>) -> Selection {
+    selection.map_merged(|range| f(range).unwrap_or(*range))
+}
+
+/// The smallest named node covering the range
+fn smallest_node<'t>(tree: &'t Tree, range: &Range) -> Option<Node<'t>> {
+    tree.root_node()
+        .named_descendant_for_byte_range(range.start().offset, range.end().offset)
+}
+
+/// The largest node spanning exactly the same text as the smallest one
+/// covering the range; siblings are looked for at that level
+fn outermost_node<'t>(tree: &'t Tree, range: &Range) -> Option<Node<'t>> {
+    let mut node = smallest_node(tree, range)?;
+    while let Some(parent) = node.parent().filter(|p| node_range(*p) == node_range(node)) {
+        node = parent;
+    }
+    Some(node)
+}
+
+fn node_range(node: Node) -> (usize, usize) {
+    (node.start_byte(), node.end_byte())
+}
+
+/// A range covering `node`, facing the same way as `like`
+fn select_node(node: Node, like: &Range) -> Range {
+    let (start, end) = (
+        Position::new(node.start_byte()),
+        Position::new(node.end_byte()),
+    );
+    if like.head < like.anchor {
+        Range::new(end, start)
+    } else {
+        Range::new(start, end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Buffer, Syntax};
+    use ropey::Rope;
+
+    const SOURCE: &str =
+        "struct S;\nimpl S {\n    fn a(x: u8, y: u8) -> u8 {\n        x + y\n    }\n}\n";
+
+    fn tree() -> Tree {
+        let mut syntax = Syntax::new(tree_sitter_rust::LANGUAGE.into());
+        syntax.parse(&Rope::from_str(SOURCE));
+        syntax.tree().unwrap().clone()
+    }
+
+    fn text(selection: &Selection) -> Vec<&'static str> {
+        selection
+            .ranges()
+            .iter()
+            .map(|r| &SOURCE[r.start().offset..r.end().offset])
+            .collect()
+    }
+
+    fn cursor_at(needle: &str) -> Range {
+        Range::point(Position::new(SOURCE.find(needle).unwrap()))
+    }
+
+    #[test]
+    fn expands_and_shrinks_through_the_tree() {
+        let tree = tree();
+        let selection = Selection::single(cursor_at("x + y"));
+        let once = expand(&tree, &selection);
+        assert_eq!(text(&once), vec!["x"]);
+        let twice = expand(&tree, &once);
+        assert_eq!(text(&twice), vec!["x + y"]);
+        assert_eq!(text(&shrink(&tree, &twice)), vec!["x"]);
+    }
+
+    #[test]
+    fn moves_between_siblings_with_multiple_cursors() {
+        let tree = tree();
+        let mut selection = Selection::single(cursor_at("S;"));
+        selection.push(cursor_at("y: u8"));
+        let nodes = expand(&tree, &expand(&tree, &selection));
+        assert_eq!(text(&nodes), vec!["struct S;", "y: u8"]);
+
+        let prev = select_sibling(&tree, &nodes, false);
+        assert_eq!(text(&prev), vec!["struct S;", "x: u8"]);
+        assert_eq!(prev.primary().start().offset, SOURCE.find("x: u8").unwrap());
+        // The struct moves on to the impl block, which swallows the parameter
+        let next = select_sibling(&tree, &prev, true);
+        assert_eq!(
+            text(&next),
+            vec!["impl S {\n    fn a(x: u8, y: u8) -> u8 {\n        x + y\n    }\n}"]
+        );
+    }
+
+    #[test]
+    fn selects_enclosing_definitions_and_merges_overlaps() {
+        let tree = tree();
+        let mut selection = Selection::single(cursor_at("x + y"));
+        selection.push(cursor_at("y: u8"));
+        let function = select_enclosing(&tree, &selection, Enclosing::Function);
+        assert_eq!(
+            text(&function),
+            vec!["fn a(x: u8, y: u8) -> u8 {\n        x + y\n    }"]
+        );
+
+        let class = select_enclosing(&tree, &function, Enclosing::Class);
+        assert!(text(&class)[0].starts_with("impl S {"));
+        // Nothing encloses the impl block, so it stays selected
+        assert_eq!(select_enclosing(&tree, &class, Enclosing::Class), class);
+
+        let mut buffer = Buffer::from_str(SOURCE);
+        buffer.set_selection(class);
+        assert_eq!(buffer.selection().len(), 1);
+    }
+
+    #[test]
+    fn python_modules_are_not_classes() {
+        let source = "x = 1\n\nclass A:\n    y = 2\n";
+        let mut syntax = Syntax::new(tree_sitter_python::LANGUAGE.into());
+        syntax.parse(&Rope::from_str(source));
+        let tree = syntax.tree().unwrap();
+        let at = |needle: &str| {
+            Selection::single(Range::point(Position::new(source.find(needle).unwrap())))
+        };
+
+        let top_level = at("x = 1");
+        assert_eq!(
+            select_enclosing(tree, &top_level, Enclosing::Class),
+            top_level
+        );
+        let class = select_enclosing(tree, &at("y = 2"), Enclosing::Class);
+        let range = class.primary();
+        assert_eq!(
+            &source[range.start().offset..range.end().offset],
+            "class A:\n    y = 2"
+        );
+    }
+
+    #[test]
+    fn skips_nodes_that_only_name_a_definition() {
+        let source = "fn apply(f: fn(u8) -> u8) -> u8 {\n    f(1)\n}\n";
+        let mut syntax = Syntax::new(tree_sitter_rust::LANGUAGE.into());
+        syntax.parse(&Rope::from_str(source));
+        let tree = syntax.tree().unwrap();
+
+        // The cursor sits in a `function_type`, which is not a function
+        let cursor = Range::point(Position::new(source.find("u8)").unwrap()));
+        let function = select_enclosing(tree, &Selection::single(cursor), Enclosing::Function);
+        let range = function.primary();
+        assert_eq!(
+            &source[range.start().offset..range.end().offset],
+            source.trim_end()
+        );
+    }
+}
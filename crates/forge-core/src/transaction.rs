@@ -1,8 +1,10 @@
 use crate::{Position, Selection};
 use ropey::Rope;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
 
 /// A single change in a transaction: delete range and/or insert text
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Change {
     /// Start position of the change
     pub start: Position,
@@ -44,15 +46,21 @@ impl Change {
     pub fn apply(&self, rope: &mut Rope) {
         // First remove the range if it's not empty
         if self.start != self.end {
-            rope.remove(self.start.offset..self.end.offset);
+            let start = rope.byte_to_char(self.start.offset);
+            rope.remove(start..rope.byte_to_char(self.end.offset));
         }
 
         // Then insert the text if any
         if let Some(ref text) = self.text {
-            rope.insert(self.start.offset, text);
+            rope.insert(rope.byte_to_char(self.start.offset), text);
         }
     }
 
+    /// Byte length of the inserted text
+    pub fn inserted_len(&self) -> usize {
+        self.text.as_ref().map_or(0, |t| t.len())
+    }
+
     /// Get the byte length change (negative for deletions, positive for insertions)
     pub fn len_delta(&self) -> isize {
         let deleted = (self.end.offset - self.start.offset) as isize;
@@ -61,32 +69,363 @@ impl Change {
     }
 }
 
-/// A set of changes (for composing multiple changes)
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Which side of an insertion a mapped position sticks to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    /// Stay before text inserted at the position
+    Before,
+    /// Move past text inserted at the position (like a cursor while typing)
+    After,
+}
+
+/// A set of changes applied as one edit.
+///
+/// Every change is expressed in the coordinates of the document *before* the
+/// set is applied, so multi-cursor edits and batched LSP edits don't shift
+/// each other. Changes must not overlap, though they may touch: an insertion
+/// at the offset where a deletion or replacement starts goes before the
+/// replacement text. Insertions at the same offset land in the order they
+/// were added.
+///
+/// Add changes with [`ChangeSet::add`] rather than through `changes`, which
+/// would leave the cached order stale.
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChangeSet {
     pub changes: Vec<Change>,
+    /// Indices of `changes` in [`ChangeSet::sorted`] order, worked out once
+    #[serde(skip)]
+    order: OnceLock<Vec<usize>>,
 }
 
+impl PartialEq for ChangeSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.changes == other.changes
+    }
+}
+
+impl Eq for ChangeSet {}
+
 impl ChangeSet {
     pub fn new() -> Self {
-        Self {
-            changes: Vec::new(),
-        }
+        Self::from_changes(Vec::new())
     }
 
     pub fn with_change(change: Change) -> Self {
+        Self::from_changes(vec![change])
+    }
+
+    pub fn from_changes(changes: Vec<Change>) -> Self {
         Self {
-            changes: vec![change],
+            changes,
+            order: OnceLock::new(),
         }
     }
 
     pub fn add(&mut self, change: Change) {
         self.changes.push(change);
+        self.order.take();
     }
 
     pub fn is_empty(&self) -> bool {
         self.changes.is_empty()
     }
+
+    /// Changes sorted by start offset, with pure insertions before the
+    /// deletions and replacements starting at the same offset; other ties
+    /// keep the order they were added in
+    pub(crate) fn sorted(&self) -> impl DoubleEndedIterator<Item = &Change> {
+        let order = self.order.get_or_init(|| {
+            let mut order: Vec<usize> = (0..self.changes.len()).collect();
+            order.sort_by_key(|&i| {
+                let change = &self.changes[i];
+                (change.start.offset, change.end != change.start)
+            });
+            order
+        });
+        order.iter().map(|&i| &self.changes[i])
+    }
+
+    /// Changes in the order they can be applied one at a time.
+    ///
+    /// Going back to front keeps every change's original offsets valid when
+    /// it is reached, so each can be applied (or fed to an incremental
+    /// parser) directly against the partially edited document.
+    pub fn application_order(&self) -> impl Iterator<Item = &Change> {
+        self.sorted().rev()
+    }
+
+    /// Apply every change to a rope
+    pub fn apply(&self, rope: &mut Rope) {
+        for change in self.application_order() {
+            change.apply(rope);
+        }
+    }
+
+    /// Map a position in the original document to the edited one.
+    ///
+    /// Text inserted exactly at the position ends up before or after it
+    /// according to `assoc`; a position inside a deleted or replaced range
+    /// collapses to the matching edge of the replacement.
+    pub fn map_position(&self, pos: Position, assoc: Assoc) -> Position {
+        let pos = pos.offset;
+        let mut delta: isize = 0;
+        for change in self.sorted() {
+            let (start, end) = (change.start.offset, change.end.offset);
+            if start > pos {
+                break;
+            }
+            if start == pos {
+                // Replaced text starts after the position, so only a pure
+                // insertion can push it along
+                if start == end && assoc == Assoc::After {
+                    delta += change.inserted_len() as isize;
+                }
+            } else if end <= pos {
+                delta += change.len_delta();
+            } else {
+                let edge = match assoc {
+                    Assoc::Before => start,
+                    Assoc::After => start + change.inserted_len(),
+                };
+                return Position::new((edge as isize + delta) as usize);
+            }
+        }
+        Position::new((pos as isize + delta) as usize)
+    }
+
+    /// Combine this set with `next`, which is expressed in the coordinates of
+    /// the document after `self`. The result performs both in one step.
+    pub fn compose(&self, next: &ChangeSet) -> ChangeSet {
+        let mut first = self.ops().into_iter();
+        let mut second = next.ops().into_iter();
+        let mut a = first.next();
+        let mut b = second.next();
+        let mut out = OpBuilder::default();
+
+        loop {
+            match (a.take(), b.take()) {
+                (None, None) => break,
+                // Deletions from the first set and insertions from the second
+                // don't interact with the other side
+                (Some(Op::Delete(n)), other) => {
+                    out.delete(n);
+                    a = first.next();
+                    b = other;
+                }
+                (other, Some(Op::Insert(text))) => {
+                    out.insert(&text);
+                    a = other;
+                    b = second.next();
+                }
+                // Past the last op each set leaves the rest of the text alone
+                (None, Some(Op::Retain(n))) => {
+                    out.retain(n);
+                    b = second.next();
+                }
+                (None, Some(Op::Delete(n))) => {
+                    out.delete(n);
+                    b = second.next();
+                }
+                (Some(Op::Retain(n)), None) => {
+                    out.retain(n);
+                    a = first.next();
+                }
+                (Some(Op::Insert(text)), None) => {
+                    out.insert(&text);
+                    a = first.next();
+                }
+                (Some(Op::Retain(i)), Some(Op::Retain(j))) => {
+                    let n = i.min(j);
+                    out.retain(n);
+                    a = Op::rest(Op::Retain(i), n).or_else(|| first.next());
+                    b = Op::rest(Op::Retain(j), n).or_else(|| second.next());
+                }
+                (Some(Op::Retain(i)), Some(Op::Delete(j))) => {
+                    let n = i.min(j);
+                    out.delete(n);
+                    a = Op::rest(Op::Retain(i), n).or_else(|| first.next());
+                    b = Op::rest(Op::Delete(j), n).or_else(|| second.next());
+                }
+                (Some(Op::Insert(text)), Some(Op::Retain(j))) => {
+                    let n = text.len().min(j);
+                    out.insert(&text[..n]);
+                    a = Op::rest(Op::Insert(text), n).or_else(|| first.next());
+                    b = Op::rest(Op::Retain(j), n).or_else(|| second.next());
+                }
+                // Text inserted by the first set and deleted by the second
+                (Some(Op::Insert(text)), Some(Op::Delete(j))) => {
+                    let n = text.len().min(j);
+                    a = Op::rest(Op::Insert(text), n).or_else(|| first.next());
+                    b = Op::rest(Op::Delete(j), n).or_else(|| second.next());
+                }
+            }
+        }
+        out.finish()
+    }
+
+    /// Rebase this set over `other`, a concurrent edit of the same document,
+    /// so it applies to the document after `other`.
+    ///
+    /// Text both sets insert at the same offset is ordered by `side`: with
+    /// [`Assoc::Before`] this set's text goes first. Transforming the two
+    /// sets over each other with opposite sides gives edits that leave the
+    /// document the same whichever was applied first.
+    pub fn transform(&self, other: &ChangeSet, side: Assoc) -> ChangeSet {
+        let mut mine = self.ops().into_iter();
+        let mut theirs = other.ops().into_iter();
+        let mut a = mine.next();
+        let mut b = theirs.next();
+        let mut out = OpBuilder::default();
+
+        loop {
+            match (a.take(), b.take()) {
+                (None, None) => break,
+                (Some(Op::Insert(text)), Some(Op::Insert(other))) => {
+                    if side == Assoc::Before {
+                        out.insert(&text);
+                        a = mine.next();
+                        b = Some(Op::Insert(other));
+                    } else {
+                        out.retain(other.len());
+                        a = Some(Op::Insert(text));
+                        b = theirs.next();
+                    }
+                }
+                (Some(Op::Insert(text)), other) => {
+                    out.insert(&text);
+                    a = mine.next();
+                    b = other;
+                }
+                // Text the other set inserts is kept as it is
+                (other, Some(Op::Insert(text))) => {
+                    out.retain(text.len());
+                    a = other;
+                    b = theirs.next();
+                }
+                // Past this set's last op there is nothing left to rebase
+                (None, Some(_)) => b = theirs.next(),
+                (Some(Op::Retain(n)), None) => {
+                    out.retain(n);
+                    a = mine.next();
+                }
+                (Some(Op::Delete(n)), None) => {
+                    out.delete(n);
+                    a = mine.next();
+                }
+                (Some(Op::Retain(i)), Some(Op::Retain(j))) => {
+                    let n = i.min(j);
+                    out.retain(n);
+                    a = Op::rest(Op::Retain(i), n).or_else(|| mine.next());
+                    b = Op::rest(Op::Retain(j), n).or_else(|| theirs.next());
+                }
+                (Some(Op::Delete(i)), Some(Op::Retain(j))) => {
+                    let n = i.min(j);
+                    out.delete(n);
+                    a = Op::rest(Op::Delete(i), n).or_else(|| mine.next());
+                    b = Op::rest(Op::Retain(j), n).or_else(|| theirs.next());
+                }
+                // Text the other set deletes is gone whatever this set did to it
+                (Some(Op::Retain(i)), Some(Op::Delete(j))) => {
+                    let n = i.min(j);
+                    a = Op::rest(Op::Retain(i), n).or_else(|| mine.next());
+                    b = Op::rest(Op::Delete(j), n).or_else(|| theirs.next());
+                }
+                (Some(Op::Delete(i)), Some(Op::Delete(j))) => {
+                    let n = i.min(j);
+                    a = Op::rest(Op::Delete(i), n).or_else(|| mine.next());
+                    b = Op::rest(Op::Delete(j), n).or_else(|| theirs.next());
+                }
+            }
+        }
+        out.finish()
+    }
+
+    /// The set as a retain/delete/insert sequence over the original document
+    fn ops(&self) -> Vec<Op> {
+        let mut ops = Vec::new();
+        let mut pos = 0;
+        for change in self.sorted() {
+            debug_assert!(change.start.offset >= pos, "overlapping changes");
+            if change.start.offset > pos {
+                ops.push(Op::Retain(change.start.offset - pos));
+            }
+            if change.end.offset > change.start.offset {
+                ops.push(Op::Delete(change.end.offset - change.start.offset));
+            }
+            if let Some(text) = change.text.as_ref().filter(|t| !t.is_empty()) {
+                ops.push(Op::Insert(text.clone()));
+            }
+            pos = pos.max(change.end.offset);
+        }
+        ops
+    }
+}
+
+/// One step of a change set walked left to right, used by [`ChangeSet::compose`]
+/// and [`ChangeSet::transform`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Op {
+    Retain(usize),
+    Delete(usize),
+    Insert(String),
+}
+
+impl Op {
+    /// What remains of this op after consuming `n` bytes of it
+    fn rest(self, n: usize) -> Option<Op> {
+        match self {
+            Op::Retain(len) if len > n => Some(Op::Retain(len - n)),
+            Op::Delete(len) if len > n => Some(Op::Delete(len - n)),
+            Op::Insert(text) if text.len() > n => Some(Op::Insert(text[n..].to_string())),
+            _ => None,
+        }
+    }
+}
+
+/// Collects ops back into changes in original-document coordinates
+#[derive(Default)]
+struct OpBuilder {
+    pos: usize,
+    pending: Option<Change>,
+    changes: Vec<Change>,
+}
+
+impl OpBuilder {
+    fn retain(&mut self, n: usize) {
+        if n > 0 {
+            self.flush();
+            self.pos += n;
+        }
+    }
+
+    fn delete(&mut self, n: usize) {
+        let change = self.pending_at();
+        change.end.offset += n;
+        self.pos += n;
+    }
+
+    fn insert(&mut self, text: &str) {
+        if !text.is_empty() {
+            let change = self.pending_at();
+            change.text.get_or_insert_with(String::new).push_str(text);
+        }
+    }
+
+    fn pending_at(&mut self) -> &mut Change {
+        let pos = Position::new(self.pos);
+        self.pending.get_or_insert_with(|| Change::delete(pos, pos))
+    }
+
+    fn flush(&mut self) {
+        if let Some(change) = self.pending.take() {
+            self.changes.push(change);
+        }
+    }
+
+    fn finish(mut self) -> ChangeSet {
+        self.flush();
+        ChangeSet::from_changes(self.changes)
+    }
 }
 
 impl Default for ChangeSet {
@@ -96,7 +435,7 @@ impl Default for ChangeSet {
 }
 
 /// An atomic, invertible transaction that can be applied to a buffer
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Transaction {
     /// The changes to apply
     pub changes: ChangeSet,
@@ -120,42 +459,40 @@ impl Transaction {
 
     /// Apply this transaction to a rope, returning the selection if the transaction has one
     pub fn apply(&self, rope: &mut Rope) -> Option<Selection> {
-        for change in &self.changes.changes {
-            change.apply(rope);
-        }
+        self.changes.apply(rope);
         self.selection.clone()
     }
 
-    /// Create an inverted transaction that undoes this one
+    /// Create an inverted transaction that undoes this one.
+    ///
+    /// `rope` must be the document *before* this transaction was applied, since
+    /// deleted text is only recoverable from there. The inverse is expressed in
+    /// the coordinates of the edited document, like any other change set.
     pub fn invert(&self, rope: &Rope) -> Transaction {
-        let mut inverted_changes = Vec::new();
+        let mut inverted_changes = Vec::with_capacity(self.changes.changes.len());
+        let mut delta: isize = 0;
+
+        for change in self.changes.sorted() {
+            let start = Position::new((change.start.offset as isize + delta) as usize);
+            let end = Position::new(start.offset + change.inserted_len());
+            let deleted = (change.start != change.end).then(|| {
+                rope.byte_slice(change.start.offset..change.end.offset)
+                    .to_string()
+            });
 
-        for change in self.changes.changes.iter().rev() {
-            let inverted = if change.start == change.end {
+            inverted_changes.push(match deleted {
                 // Was an insertion, invert to deletion
-                let end = Position::new(change.start.offset + change.text.as_ref().unwrap().len());
-                Change::delete(change.start, end)
-            } else if change.text.is_none() {
+                None => Change::delete(start, end),
                 // Was a deletion, invert to insertion
-                let deleted_text = rope
-                    .slice(change.start.offset..change.end.offset)
-                    .to_string();
-                Change::insert(change.start, deleted_text)
-            } else {
+                Some(text) if start == end => Change::insert(start, text),
                 // Was a replacement, invert both parts
-                let deleted_text = rope
-                    .slice(change.start.offset..change.end.offset)
-                    .to_string();
-                let end = Position::new(change.start.offset + change.text.as_ref().unwrap().len());
-                Change::replace(change.start, end, deleted_text)
-            };
-            inverted_changes.push(inverted);
+                Some(text) => Change::replace(start, end, text),
+            });
+            delta += change.len_delta();
         }
 
         Transaction {
-            changes: ChangeSet {
-                changes: inverted_changes,
-            },
+            changes: ChangeSet::from_changes(inverted_changes),
             selection: None,
         }
     }
@@ -186,4 +523,208 @@ mod tests {
         tx.apply(&mut rope);
         assert_eq!(rope.to_string(), "hello Forge");
     }
+
+    #[test]
+    fn test_invert_compound_changes() {
+        let original = Rope::from_str("a\r\nb\r\nc");
+        let mut rope = original.clone();
+        let mut changes = ChangeSet::new();
+        changes.add(Change::replace(
+            Position::new(4),
+            Position::new(6),
+            "\n".into(),
+        ));
+        changes.add(Change::replace(
+            Position::new(1),
+            Position::new(3),
+            "\n".into(),
+        ));
+        changes.add(Change::delete(Position::new(0), Position::new(1)));
+        let tx = Transaction::new(changes, None);
+
+        let inverse = tx.invert(&original);
+        tx.apply(&mut rope);
+        assert_eq!(rope.to_string(), "\nb\nc");
+
+        inverse.apply(&mut rope);
+        assert_eq!(rope, original);
+    }
+
+    fn insert(at: usize, text: &str) -> Change {
+        Change::insert(Position::new(at), text.to_string())
+    }
+
+    #[test]
+    fn test_changes_use_original_coordinates() {
+        // Three cursors typing at once, listed front to back
+        let mut rope = Rope::from_str("a b c");
+        let mut changes = ChangeSet::new();
+        changes.add(insert(1, "1"));
+        changes.add(insert(3, "2"));
+        changes.add(insert(5, "3"));
+        let tx = Transaction::new(changes, None);
+        let inverse = tx.invert(&rope);
+
+        tx.apply(&mut rope);
+        assert_eq!(rope.to_string(), "a1 b2 c3");
+        inverse.apply(&mut rope);
+        assert_eq!(rope.to_string(), "a b c");
+    }
+
+    #[test]
+    fn test_insert_at_start_of_delete_survives() {
+        let original = Rope::from_str("abcdef");
+        let mut changes = ChangeSet::new();
+        changes.add(Change::delete(Position::new(2), Position::new(4)));
+        changes.add(insert(2, "XY"));
+        let tx = Transaction::new(changes.clone(), None);
+        let inverse = tx.invert(&original);
+
+        let mut rope = original.clone();
+        tx.apply(&mut rope);
+        assert_eq!(rope.to_string(), "abXYef");
+        assert_eq!(
+            changes.map_position(Position::new(3), Assoc::Before).offset,
+            4
+        );
+
+        // Composing walks the set front to back
+        let mut composed = original.clone();
+        changes
+            .compose(&ChangeSet::with_change(insert(6, "!")))
+            .apply(&mut composed);
+        assert_eq!(composed.to_string(), "abXYef!");
+
+        inverse.apply(&mut rope);
+        assert_eq!(rope, original);
+    }
+
+    #[test]
+    fn test_map_position() {
+        let mut changes = ChangeSet::new();
+        changes.add(insert(2, "xx"));
+        changes.add(Change::replace(
+            Position::new(4),
+            Position::new(7),
+            "y".into(),
+        ));
+
+        let map = |offset, assoc| changes.map_position(Position::new(offset), assoc).offset;
+        assert_eq!(map(1, Assoc::After), 1);
+        assert_eq!(map(2, Assoc::Before), 2);
+        assert_eq!(map(2, Assoc::After), 4);
+        assert_eq!(map(5, Assoc::Before), 6);
+        assert_eq!(map(5, Assoc::After), 7);
+        assert_eq!(map(9, Assoc::After), 9);
+    }
+
+    #[test]
+    fn test_compose_matches_sequential_application() {
+        let original = Rope::from_str("hello world");
+        let mut first = ChangeSet::new();
+        first.add(Change::replace(
+            Position::new(0),
+            Position::new(5),
+            "goodbye".into(),
+        ));
+        first.add(insert(11, "!"));
+        // In the coordinates of "goodbye world!"
+        let mut second = ChangeSet::new();
+        second.add(Change::delete(Position::new(4), Position::new(8)));
+        second.add(insert(14, "?"));
+
+        let mut expected = original.clone();
+        first.apply(&mut expected);
+        second.apply(&mut expected);
+        assert_eq!(expected.to_string(), "goodworld!?");
+
+        let composed = first.compose(&second);
+        let mut rope = original.clone();
+        composed.apply(&mut rope);
+        assert_eq!(rope, expected);
+
+        let inverse = Transaction::new(composed, None).invert(&original);
+        inverse.apply(&mut rope);
+        assert_eq!(rope, original);
+    }
+
+    #[test]
+    fn test_compose_deletes_inserted_text() {
+        let first = ChangeSet::with_change(insert(3, "abc"));
+        let second = ChangeSet::with_change(Change::delete(Position::new(2), Position::new(5)));
+        let composed = first.compose(&second);
+        assert_eq!(
+            composed.changes,
+            vec![Change::replace(
+                Position::new(2),
+                Position::new(3),
+                "c".into(),
+            )]
+        );
+    }
+
+    #[test]
+    fn test_offsets_are_bytes_in_non_ascii_text() {
+        let original = Rope::from_str("h\u{e9}llo w\u{f6}rld");
+        // Replace "w\u{f6}rld" (bytes 7..13) and insert after "h\u{e9}"
+        let changes = ChangeSet::from_changes(vec![
+            Change::replace(Position::new(7), Position::new(13), "\u{1F30D}".into()),
+            insert(3, "-"),
+        ]);
+        let mut rope = original.clone();
+        changes.apply(&mut rope);
+        assert_eq!(rope.to_string(), "h\u{e9}-llo \u{1F30D}");
+
+        Transaction::new(changes, None)
+            .invert(&original)
+            .apply(&mut rope);
+        assert_eq!(rope, original);
+    }
+
+    #[test]
+    fn test_transform_converges() {
+        let original = Rope::from_str("abcdef");
+        let edits = [
+            ChangeSet::with_change(insert(0, "x")),
+            ChangeSet::with_change(insert(3, "yy")),
+            ChangeSet::with_change(insert(6, "z")),
+            ChangeSet::with_change(Change::delete(Position::new(1), Position::new(4))),
+            ChangeSet::with_change(Change::delete(Position::new(2), Position::new(6))),
+            ChangeSet::with_change(Change::replace(
+                Position::new(3),
+                Position::new(5),
+                "Q".into(),
+            )),
+            ChangeSet::from_changes(vec![insert(1, "1"), insert(5, "5")]),
+        ];
+        for a in &edits {
+            for b in &edits {
+                let mut ab = original.clone();
+                a.apply(&mut ab);
+                b.transform(a, Assoc::After).apply(&mut ab);
+
+                let mut ba = original.clone();
+                b.apply(&mut ba);
+                a.transform(b, Assoc::Before).apply(&mut ba);
+                assert_eq!(ab, ba, "{:?} / {:?}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_transform_orders_insertions_by_side() {
+        let mine = ChangeSet::with_change(insert(1, "a"));
+        let theirs = ChangeSet::with_change(insert(1, "b"));
+        let mut rope = Rope::from_str("__");
+        theirs.apply(&mut rope);
+        mine.transform(&theirs, Assoc::Before).apply(&mut rope);
+        assert_eq!(rope.to_string(), "_ab_");
+
+        // Deleting around an insertion keeps the inserted text
+        let mut rope = Rope::from_str("__");
+        theirs.apply(&mut rope);
+        let delete = ChangeSet::with_change(Change::delete(Position::new(0), Position::new(2)));
+        delete.transform(&theirs, Assoc::Before).apply(&mut rope);
+        assert_eq!(rope.to_string(), "b");
+    }
 }
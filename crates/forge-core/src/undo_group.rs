@@ -0,0 +1,280 @@
+//! Decides when consecutive edits share one undo step.
+//!
+//! Typing and deleting are coalesced while the cursor keeps moving in step
+//! with the edits. A pause, a cursor jump, a change of edit kind or the start
+//! of a new word ends the step. Explicit groups merge everything between
+//! [`crate::Buffer::begin_undo_group`] and [`crate::Buffer::end_undo_group`].
+
+use crate::Transaction;
+use ropey::Rope;
+use std::time::{Duration, Instant};
+
+/// Pause after which the next keystroke starts a new undo step
+pub(crate) const DEFAULT_COALESCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EditKind {
+    Insert,
+    Delete,
+}
+
+/// A single-change edit that may be coalesced with its neighbours
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Edit {
+    kind: EditKind,
+    start: usize,
+    end: usize,
+    /// First and last character inserted or deleted
+    first: char,
+    last: char,
+    len: usize,
+}
+
+impl Edit {
+    /// Classify a transaction against the document it is about to modify.
+    ///
+    /// Only plain single-line typing and deletion qualify; anything else
+    /// (multi-cursor edits, replacements, newlines) is always its own step.
+    pub(crate) fn classify(transaction: &Transaction, rope: &Rope) -> Option<Self> {
+        let [change] = transaction.changes.changes.as_slice() else {
+            return None;
+        };
+        let (start, end) = (change.start.offset, change.end.offset);
+        let inserted = change.text.as_deref().unwrap_or("");
+        let (kind, text) = match (start == end, inserted.is_empty()) {
+            (true, false) => (EditKind::Insert, inserted.to_string()),
+            (false, true) => (EditKind::Delete, rope.byte_slice(start..end).to_string()),
+            _ => return None,
+        };
+        if text.contains(['\n', '\r']) {
+            return None;
+        }
+        Some(Self {
+            kind,
+            start,
+            end,
+            first: text.chars().next()?,
+            last: text.chars().last()?,
+            len: text.len(),
+        })
+    }
+}
+
+/// The step new edits may still be merged into
+#[derive(Debug, Clone, Copy)]
+struct OpenStep {
+    node: usize,
+    kind: Option<EditKind>,
+    /// Offset the next edit must touch to continue the step
+    at: usize,
+    /// The character most recently typed or deleted
+    last: char,
+    time: Instant,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct UndoCoalescer {
+    open: Option<OpenStep>,
+    group_depth: usize,
+    pub(crate) timeout: Duration,
+}
+
+impl Default for UndoCoalescer {
+    fn default() -> Self {
+        Self {
+            open: None,
+            group_depth: 0,
+            timeout: DEFAULT_COALESCE_TIMEOUT,
+        }
+    }
+}
+
+impl UndoCoalescer {
+    /// Whether `edit` should be folded into history node `current`
+    pub(crate) fn should_merge(&self, current: usize, edit: Option<&Edit>) -> bool {
+        let Some(open) = self.open.filter(|open| open.node == current) else {
+            return false;
+        };
+        if self.group_depth > 0 {
+            return true;
+        }
+        let Some(edit) = edit else {
+            return false;
+        };
+        if open.kind != Some(edit.kind) || open.time.elapsed() > self.timeout {
+            return false;
+        }
+        // The character next to the previous edit, in editing order
+        let edge = match edit.kind {
+            EditKind::Insert if edit.start == open.at => edit.first,
+            EditKind::Delete if edit.end == open.at => edit.last,
+            EditKind::Delete if edit.start == open.at => edit.first,
+            _ => return false,
+        };
+        // Starting a new word after whitespace begins a new step
+        !open.last.is_whitespace() || edge.is_whitespace()
+    }
+
+    /// Remember the edit just recorded at history node `node`
+    pub(crate) fn record(&mut self, node: usize, edit: Option<&Edit>) {
+        // Forward deletes (the Delete key) eat text to the right of the cursor
+        let forward = self
+            .open
+            .zip(edit)
+            .is_some_and(|(open, e)| e.kind == EditKind::Delete && e.start == open.at);
+        self.open = match edit {
+            Some(edit) => Some(OpenStep {
+                node,
+                kind: Some(edit.kind),
+                at: match edit.kind {
+                    EditKind::Insert => edit.start + edit.len,
+                    EditKind::Delete => edit.start,
+                },
+                last: match edit.kind {
+                    EditKind::Delete if !forward => edit.first,
+                    _ => edit.last,
+                },
+                time: Instant::now(),
+            }),
+            // Inside a group any edit keeps the step open
+            None if self.group_depth > 0 => Some(OpenStep {
+                node,
+                kind: None,
+                at: 0,
+                last: ' ',
+                time: Instant::now(),
+            }),
+            None => None,
+        };
+    }
+
+    /// End the current step; the next edit starts a new one
+    pub(crate) fn break_step(&mut self) {
+        if self.group_depth == 0 {
+            self.open = None;
+        }
+    }
+
+    pub(crate) fn begin_group(&mut self) {
+        if self.group_depth == 0 {
+            self.open = None;
+        }
+        self.group_depth += 1;
+    }
+
+    pub(crate) fn end_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+        if self.group_depth == 0 {
+            self.open = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Buffer, Change, ChangeSet, Position, Selection, Transaction};
+    use std::time::Duration;
+
+    fn type_text(buffer: &mut Buffer, text: &str) {
+        for c in text.chars() {
+            let offset = buffer.selection().primary().head.offset;
+            let change = Change::insert(Position::new(offset), c.to_string());
+            let cursor = Selection::point(Position::new(offset + c.len_utf8()));
+            buffer.apply(Transaction::new(
+                ChangeSet::with_change(change),
+                Some(cursor),
+            ));
+        }
+    }
+
+    fn backspace(buffer: &mut Buffer, times: usize) {
+        for _ in 0..times {
+            let offset = buffer.selection().primary().head.offset;
+            let change = Change::delete(Position::new(offset - 1), Position::new(offset));
+            let cursor = Selection::point(Position::new(offset - 1));
+            buffer.apply(Transaction::new(
+                ChangeSet::with_change(change),
+                Some(cursor),
+            ));
+        }
+    }
+
+    #[test]
+    fn typing_undoes_word_by_word() {
+        let mut buffer = Buffer::new();
+        type_text(&mut buffer, "hello world");
+        assert_eq!(buffer.history().len(), 3);
+
+        buffer.undo();
+        assert_eq!(buffer.text(), "hello ");
+        buffer.undo();
+        assert_eq!(buffer.text(), "");
+        buffer.redo();
+        buffer.redo();
+        assert_eq!(buffer.text(), "hello world");
+    }
+
+    #[test]
+    fn backspacing_coalesces_separately_from_typing() {
+        let mut buffer = Buffer::new();
+        type_text(&mut buffer, "abc");
+        backspace(&mut buffer, 2);
+        assert_eq!(buffer.text(), "a");
+
+        buffer.undo();
+        assert_eq!(buffer.text(), "abc");
+        buffer.undo();
+        assert_eq!(buffer.text(), "");
+    }
+
+    #[test]
+    fn cursor_jump_starts_a_new_step() {
+        let mut buffer = Buffer::new();
+        type_text(&mut buffer, "ab");
+        buffer.set_selection(Selection::point(Position::new(0)));
+        type_text(&mut buffer, "x");
+
+        buffer.undo();
+        assert_eq!(buffer.text(), "ab");
+    }
+
+    #[test]
+    fn pause_starts_a_new_step() {
+        let mut buffer = Buffer::new();
+        buffer.set_undo_coalesce_timeout(Duration::ZERO);
+        type_text(&mut buffer, "ab");
+        std::thread::sleep(Duration::from_millis(5));
+        type_text(&mut buffer, "c");
+
+        buffer.undo();
+        assert_eq!(buffer.text(), "ab");
+    }
+
+    #[test]
+    fn explicit_group_is_one_step() {
+        let mut buffer = Buffer::from_str("fn main() {}");
+        buffer.begin_undo_group();
+        let mut changes = ChangeSet::new();
+        changes.add(Change::insert(Position::new(11), "\n".into()));
+        changes.add(Change::insert(Position::new(12), "\n".into()));
+        buffer.apply(Transaction::new(changes, None));
+        buffer.begin_undo_group();
+        buffer.apply(Transaction::from_change(Change::insert(
+            Position::new(0),
+            "pub ".into(),
+        )));
+        buffer.end_undo_group();
+        buffer.apply(Transaction::from_change(Change::delete(
+            Position::new(0),
+            Position::new(4),
+        )));
+        buffer.end_undo_group();
+        assert_eq!(buffer.text(), "fn main() {\n}\n");
+        assert_eq!(buffer.history().len(), 2);
+
+        buffer.undo();
+        assert_eq!(buffer.text(), "fn main() {}");
+        buffer.redo();
+        assert_eq!(buffer.text(), "fn main() {\n}\n");
+    }
+}
@@ -0,0 +1,285 @@
+//! Persistent undo history across editor restarts.
+//!
+//! Each file's [`History`] tree is stored as JSON under the local data dir,
+//! keyed by its path and tagged with a hash of the file contents it belongs to.
+//! History is only restored when the file on disk still matches that hash.
+
+use crate::History;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Limits on what gets persisted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndoStoreLimits {
+    /// Largest serialized history kept per file, in bytes
+    pub max_bytes: u64,
+    /// Histories not touched for this long are discarded
+    pub max_age: Duration,
+}
+
+impl Default for UndoStoreLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 * 1024 * 1024,
+            max_age: Duration::from_secs(30 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// Outcome of looking up persisted history for a file
+#[derive(Debug)]
+pub enum UndoRestore {
+    /// History matches the file contents and can be used as-is
+    Restored(History),
+    /// History existed but the file changed on disk since; it was discarded
+    Stale,
+    /// Nothing stored (or it expired)
+    Missing,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UndoEntry {
+    path: String,
+    content_hash: u64,
+    saved_at: u64,
+    history: History,
+}
+
+pub struct UndoStore {
+    dir: PathBuf,
+    limits: UndoStoreLimits,
+}
+
+impl Default for UndoStore {
+    fn default() -> Self {
+        Self::new(UndoStoreLimits::default())
+    }
+}
+
+impl UndoStore {
+    pub fn new(limits: UndoStoreLimits) -> Self {
+        let dir = dirs_next::data_local_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join("forge")
+            .join("undo");
+        Self::with_dir(dir, limits)
+    }
+
+    pub fn with_dir(dir: impl Into<PathBuf>, limits: UndoStoreLimits) -> Self {
+        Self {
+            dir: dir.into(),
+            limits,
+        }
+    }
+
+    /// Persist `history` for `file_path`, whose on-disk contents are `content`.
+    ///
+    /// Histories over the size limit lose their oldest revisions until they
+    /// fit. If even the current state alone doesn't, nothing is written (and
+    /// any older entry is removed so it can't be restored against the wrong
+    /// text).
+    pub fn save(&self, file_path: &str, content: &[u8], history: &History) -> Result<()> {
+        let mut entry = UndoEntry {
+            path: file_path.to_string(),
+            content_hash: content_hash(content),
+            saved_at: now_secs(),
+            history: history.clone(),
+        };
+        let path = self.entry_path(file_path);
+        let json = loop {
+            let json = serde_json::to_vec(&entry)?;
+            let len = json.len() as u64;
+            if len <= self.limits.max_bytes {
+                break json;
+            }
+            // Drop about the share of revisions that is over the limit
+            let depth = entry.history.depth();
+            let excess = (len - self.limits.max_bytes) as f64 / len as f64;
+            let levels = ((depth as f64 * excess).ceil() as usize).max(1);
+            if !entry.history.drop_oldest(levels) {
+                let _ = std::fs::remove_file(&path);
+                return Ok(());
+            }
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        crate::file_io::FileIO::save_atomic(&path, json)?;
+        Ok(())
+    }
+
+    /// Look up the history for `file_path` given its current on-disk `content`.
+    pub fn load(&self, file_path: &str, content: &[u8]) -> Result<UndoRestore> {
+        let path = self.entry_path(file_path);
+        if !path.exists() {
+            return Ok(UndoRestore::Missing);
+        }
+        let entry: UndoEntry = match serde_json::from_slice(&std::fs::read(&path)?) {
+            Ok(entry) => entry,
+            Err(_) => {
+                // Corrupt or from an incompatible version
+                std::fs::remove_file(&path)?;
+                return Ok(UndoRestore::Missing);
+            }
+        };
+        if entry.path != file_path || self.is_expired(entry.saved_at) {
+            std::fs::remove_file(&path)?;
+            return Ok(UndoRestore::Missing);
+        }
+        if entry.content_hash != content_hash(content) {
+            std::fs::remove_file(&path)?;
+            return Ok(UndoRestore::Stale);
+        }
+        Ok(UndoRestore::Restored(entry.history))
+    }
+
+    /// Forget the stored history for a file
+    pub fn clear(&self, file_path: &str) -> Result<()> {
+        let path = self.entry_path(file_path);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Delete every entry older than the age limit. Returns how many were removed.
+    pub fn prune(&self) -> Result<usize> {
+        let mut removed = 0;
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Ok(0);
+        };
+        for entry in entries.flatten() {
+            let modified = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::now());
+            let age = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default();
+            if age > self.limits.max_age {
+                std::fs::remove_file(entry.path())?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn is_expired(&self, saved_at: u64) -> bool {
+        now_secs().saturating_sub(saved_at) > self.limits.max_age.as_secs()
+    }
+
+    fn entry_path(&self, file_path: &str) -> PathBuf {
+        self.dir
+            .join(format!("{:016x}.json", content_hash(file_path.as_bytes())))
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+/// 64-bit FNV-1a hash. Stable across Rust versions, unlike `DefaultHasher`.
+pub(crate) fn content_hash(bytes: &[u8]) -> u64 {
+    extend_content_hash(0xcbf2_9ce4_8422_2325, bytes)
+}
+
+/// Continue [`content_hash`] over bytes that follow those hashed into `hash`
+pub(crate) fn extend_content_hash(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Change, ChangeSet, Position, Transaction};
+
+    fn store(name: &str, limits: UndoStoreLimits) -> UndoStore {
+        let dir = std::env::temp_dir().join(name);
+        let _ = std::fs::remove_dir_all(&dir);
+        UndoStore::with_dir(dir, limits)
+    }
+
+    fn sample_history() -> History {
+        let mut history = History::new();
+        let tx = Transaction::from_change(Change::insert(Position::new(0), "hi".into()));
+        history.push(tx, Transaction::new(ChangeSet::new(), None));
+        history
+    }
+
+    #[test]
+    fn restores_matching_content() {
+        let store = store("forge_undo_store_restore", UndoStoreLimits::default());
+        store.save("a.rs", b"hi", &sample_history()).unwrap();
+
+        match store.load("a.rs", b"hi").unwrap() {
+            UndoRestore::Restored(history) => {
+                assert_eq!(history.len(), 2);
+                assert_eq!(history.current, 1);
+            }
+            other => panic!("expected restore, got {:?}", other),
+        }
+        std::fs::remove_dir_all(&store.dir).ok();
+    }
+
+    #[test]
+    fn discards_stale_history() {
+        let store = store("forge_undo_store_stale", UndoStoreLimits::default());
+        store.save("a.rs", b"hi", &sample_history()).unwrap();
+
+        assert!(matches!(
+            store.load("a.rs", b"changed").unwrap(),
+            UndoRestore::Stale
+        ));
+        assert!(matches!(
+            store.load("a.rs", b"hi").unwrap(),
+            UndoRestore::Missing
+        ));
+        std::fs::remove_dir_all(&store.dir).ok();
+    }
+
+    #[test]
+    fn trims_oldest_revisions_to_fit() {
+        let mut history = History::new();
+        for i in 0..50 {
+            let tx = Transaction::from_change(Change::insert(Position::new(i), "x".into()));
+            history.push(tx, Transaction::new(ChangeSet::new(), None));
+        }
+        let full = serde_json::to_vec(&history).unwrap().len() as u64;
+        let limits = UndoStoreLimits {
+            max_bytes: full / 2,
+            ..UndoStoreLimits::default()
+        };
+        let store = store("forge_undo_store_trim", limits);
+        store.save("a.rs", b"hi", &history).unwrap();
+
+        let UndoRestore::Restored(mut trimmed) = store.load("a.rs", b"hi").unwrap() else {
+            panic!("expected a trimmed history");
+        };
+        assert!(trimmed.len() > 10 && trimmed.len() < 50);
+        // The newest edit is still there to undo
+        let newest = trimmed.current_transaction().unwrap().clone();
+        assert_eq!(newest, history.nodes[50].transaction);
+        while trimmed.undo() {}
+        assert!(trimmed.current_transaction().unwrap().changes.is_empty());
+        std::fs::remove_dir_all(&store.dir).ok();
+    }
+
+    #[test]
+    fn respects_size_limit() {
+        let limits = UndoStoreLimits {
+            max_bytes: 16,
+            ..UndoStoreLimits::default()
+        };
+        let store = store("forge_undo_store_limit", limits);
+        store.save("a.rs", b"hi", &sample_history()).unwrap();
+        assert!(matches!(
+            store.load("a.rs", b"hi").unwrap(),
+            UndoRestore::Missing
+        ));
+    }
+}
@@ -3,20 +3,24 @@ use crate::transport::Transport;
 use anyhow::{Context, Result};
 use lsp_types::{
     ClientCapabilities, CompletionItem, CompletionParams, CompletionResponse,
-    DidChangeTextDocumentParams, DidOpenTextDocumentParams, Hover, HoverParams, InitializeParams,
-    InitializeResult, InitializedParams, Position, TextDocumentContentChangeEvent,
+    DidChangeTextDocumentParams, DidOpenTextDocumentParams, GeneralClientCapabilities, Hover,
+    HoverParams, InitializeParams, InitializeResult, InitializedParams, Position,
+    PositionEncodingKind, ReferenceContext, ReferenceParams, TextDocumentContentChangeEvent,
     TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, Uri,
     VersionedTextDocumentIdentifier, WorkspaceFolder,
 };
 use serde_json::json;
 use std::str::FromStr;
 use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::RwLock;
 use url::Url;
 
 pub struct LspClient {
     server: LspServer,
     transport: Option<Transport>,
     next_id: AtomicI64,
+    /// What `character` in positions counts, as agreed with the server
+    position_encoding: RwLock<PositionEncodingKind>,
 }
 
 impl LspClient {
@@ -25,6 +29,7 @@ impl LspClient {
             server,
             transport: None,
             next_id: AtomicI64::new(1),
+            position_encoding: RwLock::new(PositionEncodingKind::UTF16),
         }
     }
 
@@ -54,7 +59,19 @@ impl LspClient {
 
         let params = InitializeParams {
             process_id: Some(std::process::id()),
-            capabilities: ClientCapabilities::default(),
+            capabilities: ClientCapabilities {
+                general: Some(GeneralClientCapabilities {
+                    // In order of preference: UTF-8 matches the buffer's byte
+                    // offsets, UTF-16 is what servers must support anyway
+                    position_encodings: Some(vec![
+                        PositionEncodingKind::UTF8,
+                        PositionEncodingKind::UTF32,
+                        PositionEncodingKind::UTF16,
+                    ]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
             workspace_folders: Some(vec![WorkspaceFolder {
                 uri: root_uri,
                 name: workspace_name,
@@ -64,6 +81,17 @@ impl LspClient {
 
         let response = self.request("initialize", params).await?;
         let result: InitializeResult = serde_json::from_value(response)?;
+        // Servers that don't say use UTF-16, the protocol default
+        let encoding = result
+            .capabilities
+            .position_encoding
+            .clone()
+            .unwrap_or(PositionEncodingKind::UTF16);
+        tracing::info!("LSP: using {} positions", encoding.as_str());
+        *self
+            .position_encoding
+            .write()
+            .unwrap_or_else(|e| e.into_inner()) = encoding;
 
         // Send initialized notification
         self.notify("initialized", InitializedParams {}).await?;
@@ -71,6 +99,15 @@ impl LspClient {
         Ok(result)
     }
 
+    /// What the `character` of positions sent to and received from the
+    /// server counts. UTF-16 code units until initialization says otherwise.
+    pub fn position_encoding(&self) -> PositionEncodingKind {
+        self.position_encoding
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
     pub async fn did_open(&self, uri: Url, text: String, language_id: String) -> Result<()> {
         let uri = Uri::from_str(uri.as_str()).map_err(|e| anyhow::anyhow!("Invalid URI: {}", e))?;
 
@@ -188,6 +225,34 @@ impl LspClient {
         Ok(None)
     }
 
+    /// Every reference to the symbol at the position, including its declaration
+    pub async fn references(
+        &self,
+        uri: Url,
+        line: u32,
+        character: u32,
+    ) -> Result<Vec<lsp_types::Location>> {
+        let uri = Uri::from_str(uri.as_str()).map_err(|e| anyhow::anyhow!("Invalid URI: {}", e))?;
+
+        let params = ReferenceParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position: Position { line, character },
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+            context: ReferenceContext {
+                include_declaration: true,
+            },
+        };
+
+        let response = self.request("textDocument/references", params).await?;
+        if response.is_null() {
+            return Ok(Vec::new());
+        }
+        Ok(serde_json::from_value(response)?)
+    }
+
     async fn request<T: serde::Serialize>(
         &self,
         method: &str,
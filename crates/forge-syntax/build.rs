@@ -0,0 +1,24 @@
+//! Compiles the grammars vendored under `grammars/`, for languages whose
+//! crates don't build against our tree-sitter version.
+//!
+//! `grammars/toml` is tree-sitter-toml 0.20.0 (MIT,
+//! https://github.com/Mathspy/tree-sitter-toml).
+
+fn main() {
+    for name in ["toml"] {
+        let dir = std::path::Path::new("grammars").join(name);
+        let mut build = cc::Build::new();
+        build
+            .include(&dir)
+            .warnings(false)
+            .flag_if_supported("-std=c11");
+        for file in ["parser.c", "scanner.c"] {
+            let path = dir.join(file);
+            if path.exists() {
+                println!("cargo:rerun-if-changed={}", path.display());
+                build.file(path);
+            }
+        }
+        build.compile(&format!("tree-sitter-{}", name));
+    }
+}
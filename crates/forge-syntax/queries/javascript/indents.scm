@@ -0,0 +1,7 @@
+(_ "{" "}" @end) @indent
+(_ "(" ")" @end) @indent
+(_ "[" "]" @end) @indent
+
+(ERROR "{" @indent)
+(ERROR "(" @indent)
+(ERROR "[" @indent)
@@ -0,0 +1,16 @@
+(string) @string
+
+(pair
+  key: (_) @string.special.key)
+
+(number) @number
+
+[
+  (null)
+  (true)
+  (false)
+] @constant.builtin
+
+(escape_sequence) @escape
+
+(comment) @comment
@@ -0,0 +1,12 @@
+; Fenced code blocks, in the language named after the opening fence. The
+; grammar marks brackets inside them, which are part of the code
+
+(fenced_code_block
+  (info_string
+    (language) @injection.language)
+  (code_fence_content) @injection.content
+  (#set! injection.include-unnamed-children))
+
+((html_block) @injection.content
+ (#set! injection.language "html")
+ (#set! injection.include-unnamed-children))
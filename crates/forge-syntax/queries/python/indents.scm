@@ -0,0 +1,32 @@
+; Blocks have no closing token: a line after one ends is indented like the
+; line the block's statement starts on. Clauses marked @outdent line up with
+; the statement they continue.
+
+(_ "(" ")" @end) @indent
+(_ "[" "]" @end) @indent
+(_ "{" "}" @end) @indent
+
+[
+  (function_definition)
+  (class_definition)
+  (if_statement)
+  (elif_clause)
+  (else_clause)
+  (for_statement)
+  (while_statement)
+  (try_statement)
+  (except_clause)
+  (finally_clause)
+  (with_statement)
+] @indent
+
+[
+  (elif_clause)
+  (else_clause)
+  (except_clause)
+  (finally_clause)
+] @outdent
+
+(ERROR "(" @indent)
+(ERROR "[" @indent)
+(ERROR "{" @indent)
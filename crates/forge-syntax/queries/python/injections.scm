@@ -0,0 +1,6 @@
+; SQL in string literals, recognised by the statement they start with
+
+((string
+  (string_content) @injection.content)
+ (#match? @injection.content "^\\s*(?i:select|insert|update|delete|create|alter|drop|with)\\s")
+ (#set! injection.language "sql"))
@@ -0,0 +1,11 @@
+; Lines inside an @indent node are one level deeper than the line it starts
+; on, and the line starting with its @end token lines up with that line again.
+; Unclosed brackets show up as tokens in an ERROR, and indent until its end.
+
+(_ "{" "}" @end) @indent
+(_ "(" ")" @end) @indent
+(_ "[" "]" @end) @indent
+
+(ERROR "{" @indent)
+(ERROR "(" @indent)
+(ERROR "[" @indent)
@@ -0,0 +1,5 @@
+(array "[" "]" @end) @indent
+(inline_table "{" "}" @end) @indent
+
+(ERROR "[" @indent)
+(ERROR "{" @indent)
@@ -0,0 +1,206 @@
+//! Tree-sitter grammars by name.
+//!
+//! Grammars compiled into forge are registered up front. Any other grammar,
+//! for a language forge doesn't bundle, is loaded on first use from
+//! `<grammar dir>/<name>.<so|dylib|dll>`, a library exporting
+//! `tree_sitter_<name>` as built by `tree-sitter build`.
+//! [`Grammars::register`] adds grammars from code, so new ones need no
+//! change to [`Language`](crate::Language).
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tree_sitter_language::LanguageFn;
+
+// Vendored grammars, compiled by build.rs
+extern "C" {
+    fn tree_sitter_toml() -> *const ();
+}
+
+pub struct Grammars {
+    dir: Mutex<Option<PathBuf>>,
+    /// `None` for names already looked up without success
+    grammars: Mutex<HashMap<String, Option<tree_sitter::Language>>>,
+}
+
+impl Grammars {
+    /// The built-in grammars, loading others from `dir`
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        let grammars = Self {
+            dir: Mutex::new(dir),
+            grammars: Mutex::new(HashMap::new()),
+        };
+        grammars.register("rust", tree_sitter_rust::LANGUAGE.into());
+        grammars.register("javascript", tree_sitter_javascript::LANGUAGE.into());
+        grammars.register("python", tree_sitter_python::LANGUAGE.into());
+        grammars.register("json", tree_sitter_json::LANGUAGE.into());
+        grammars.register("html", tree_sitter_html::LANGUAGE.into());
+        grammars.register("css", tree_sitter_css::LANGUAGE.into());
+        grammars.register("markdown", tree_sitter_md::LANGUAGE.into());
+        grammars.register("sql", tree_sitter_sequel::LANGUAGE.into());
+        grammars.register("go", tree_sitter_go::LANGUAGE.into());
+        grammars.register("c", tree_sitter_c::LANGUAGE.into());
+        grammars.register("cpp", tree_sitter_cpp::LANGUAGE.into());
+        grammars.register("yaml", tree_sitter_yaml::LANGUAGE.into());
+        grammars.register("bash", tree_sitter_bash::LANGUAGE.into());
+        grammars.register(
+            "typescript",
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        );
+        grammars.register("tsx", tree_sitter_typescript::LANGUAGE_TSX.into());
+        // SAFETY: generated by the tree-sitter CLI, for ABI 13, which this
+        // runtime still reads
+        let toml = unsafe { LanguageFn::from_raw(tree_sitter_toml) };
+        grammars.register("toml", toml.into());
+        grammars
+    }
+
+    /// The grammars [`Language`](crate::Language) uses
+    pub fn global() -> &'static Grammars {
+        static GLOBAL: OnceLock<Grammars> = OnceLock::new();
+        GLOBAL.get_or_init(|| Grammars::new(None))
+    }
+
+    /// Look for grammar libraries in `dir`, retrying names not found so far
+    pub fn set_dir(&self, dir: Option<PathBuf>) {
+        *self.dir.lock().unwrap() = dir;
+        self.grammars
+            .lock()
+            .unwrap()
+            .retain(|_, grammar| grammar.is_some());
+    }
+
+    pub fn register(&self, name: &str, grammar: tree_sitter::Language) {
+        self.grammars
+            .lock()
+            .unwrap()
+            .insert(name.to_string(), Some(grammar));
+    }
+
+    /// `None` if no grammar by that name is built in, registered or
+    /// installed
+    pub fn get(&self, name: &str) -> Option<tree_sitter::Language> {
+        if let Some(grammar) = self.grammars.lock().unwrap().get(name) {
+            return grammar.clone();
+        }
+        let dir = self.dir.lock().unwrap().clone();
+        let grammar = dir.and_then(|dir| match load(&dir, name) {
+            Ok(grammar) => grammar,
+            Err(e) => {
+                tracing::warn!("Failed to load the {} grammar: {:#}", name, e);
+                None
+            }
+        });
+        self.grammars
+            .lock()
+            .unwrap()
+            .entry(name.to_string())
+            .or_insert(grammar)
+            .clone()
+    }
+
+    /// Names of the grammars available so far, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .grammars
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(_, grammar)| grammar.is_some())
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+}
+
+/// `Ok(None)` if `dir` has no library for `name`
+fn load(dir: &Path, name: &str) -> Result<Option<tree_sitter::Language>> {
+    let path = dir
+        .join(name)
+        .with_extension(std::env::consts::DLL_EXTENSION);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let symbol = format!("tree_sitter_{}", name.replace('-', "_"));
+    // SAFETY: grammar libraries are trusted like the rest of the config
+    // directory; `language` is checked against the runtime's ABI below
+    let grammar = unsafe {
+        let library = libloading::Library::new(&path)
+            .with_context(|| format!("opening {}", path.display()))?;
+        let language = *library
+            .get::<unsafe extern "C" fn() -> *const ()>(symbol.as_bytes())
+            .with_context(|| format!("finding {} in {}", symbol, path.display()))?;
+        // Languages point into the library, so it stays loaded for good
+        std::mem::forget(library);
+        tree_sitter::Language::new(LanguageFn::from_raw(language))
+    };
+    let version = grammar.version();
+    if !(tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION..=tree_sitter::LANGUAGE_VERSION)
+        .contains(&version)
+    {
+        bail!(
+            "{} was generated for ABI {}, but forge supports {} to {}",
+            path.display(),
+            version,
+            tree_sitter::MIN_COMPATIBLE_LANGUAGE_VERSION,
+            tree_sitter::LANGUAGE_VERSION
+        );
+    }
+    Ok(Some(grammar))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_grammars_parse() {
+        let grammars = Grammars::new(None);
+        let mut parser = tree_sitter::Parser::new();
+        parser.set_language(&grammars.get("toml").unwrap()).unwrap();
+        let tree = parser.parse("[package]\nname = \"forge\"\n", None).unwrap();
+        assert_eq!(tree.root_node().kind(), "document");
+        assert!(!tree.root_node().has_error());
+
+        assert!(grammars.get("cobol").is_none());
+        assert_eq!(
+            grammars.names(),
+            vec![
+                "bash",
+                "c",
+                "cpp",
+                "css",
+                "go",
+                "html",
+                "javascript",
+                "json",
+                "markdown",
+                "python",
+                "rust",
+                "sql",
+                "toml",
+                "tsx",
+                "typescript",
+                "yaml"
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_libraries_are_skipped() {
+        let grammars = Grammars::new(Some(std::env::temp_dir().join("forge-no-grammars")));
+        assert!(grammars.get("zig").is_none());
+
+        let dir = std::env::temp_dir().join(format!("forge-grammars-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let library = dir
+            .join("zig")
+            .with_extension(std::env::consts::DLL_EXTENSION);
+        std::fs::write(&library, b"not a library").unwrap();
+        grammars.set_dir(Some(dir.clone()));
+        assert!(grammars.get("zig").is_none());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
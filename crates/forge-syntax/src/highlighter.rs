@@ -1,4 +1,10 @@
 use crate::language::Language;
+use crate::queries::Queries;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::ops::Range;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{InputEdit, Node, Parser, Point, QueryCursor, Tree};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TokenType {
@@ -22,152 +28,524 @@ pub enum TokenType {
     Plain,
 }
 
-#[derive(Debug, Clone)]
+impl TokenType {
+    /// The token type of a highlight query capture such as
+    /// `function.method`, falling back to its prefixes (`function`)
+    pub fn from_capture(name: &str) -> Option<Self> {
+        let mut name = name;
+        loop {
+            if let Some(token_type) = Self::from_capture_exact(name) {
+                return Some(token_type);
+            }
+            name = &name[..name.rfind('.')?];
+        }
+    }
+
+    /// The token type of a TextMate scope such as
+    /// `entity.name.function.rust`, from its longest known prefix
+    pub fn from_scope(scope: &str) -> Option<Self> {
+        let mut scope = scope;
+        loop {
+            if let Some(token_type) = Self::from_scope_exact(scope) {
+                return Some(token_type);
+            }
+            scope = &scope[..scope.rfind('.')?];
+        }
+    }
+
+    fn from_scope_exact(scope: &str) -> Option<Self> {
+        Some(match scope {
+            "comment" | "punctuation.definition.comment" => Self::Comment,
+            "string" | "constant.character" | "punctuation.definition.string" => Self::String,
+            "constant.numeric" => Self::Number,
+            "constant" | "support.constant" | "variable.other.constant" => Self::Constant,
+            "keyword.operator" => Self::Operator,
+            "keyword" | "storage" | "entity.name.tag" | "markup.heading" => Self::Keyword,
+            "entity.name.function.macro" | "entity.name.function.preprocessor" => Self::Macro,
+            "entity.name.function" | "meta.function-call.generic" => Self::Function,
+            "support.function" | "variable.language" => Self::Builtin,
+            "entity.name.type"
+            | "entity.name.class"
+            | "entity.other.inherited-class"
+            | "support.type"
+            | "support.class" => Self::Type,
+            "support.type.property-name" | "variable.other.property" | "variable.other.member" => {
+                Self::Property
+            }
+            "entity.name.namespace" | "entity.name.module" => Self::Namespace,
+            "entity.other.attribute-name" => Self::Attribute,
+            "entity.name.label" | "markup.underline.link" => Self::Label,
+            "variable.parameter" => Self::Parameter,
+            "variable" => Self::Variable,
+            "punctuation" => Self::Punctuation,
+            "markup.raw" | "markup.inline.raw" => Self::String,
+            _ => return None,
+        })
+    }
+
+    fn from_capture_exact(name: &str) -> Option<Self> {
+        Some(match name {
+            "keyword" | "conditional" | "repeat" | "include" | "exception" | "tag" => Self::Keyword,
+            "function.macro" | "macro" => Self::Macro,
+            "function.builtin" | "variable.builtin" => Self::Builtin,
+            "function" | "method" => Self::Function,
+            "type" | "constructor" => Self::Type,
+            "string.special.key" => Self::Property,
+            "string" | "escape" | "character" | "markup.raw" | "text.literal" => Self::String,
+            "number" | "float" => Self::Number,
+            "comment" => Self::Comment,
+            "operator" => Self::Operator,
+            "punctuation" => Self::Punctuation,
+            "variable.parameter" | "parameter" => Self::Parameter,
+            "variable" => Self::Variable,
+            "constant" | "boolean" => Self::Constant,
+            "namespace" | "module" => Self::Namespace,
+            "property" | "field" | "variable.member" => Self::Property,
+            "attribute" | "decorator" => Self::Attribute,
+            "label" | "markup.link" | "text.uri" | "text.reference" => Self::Label,
+            "markup.heading" | "text.title" => Self::Keyword,
+            // Text of another language, or nothing to color
+            "embedded" | "none" => Self::Plain,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HighlightSpan {
     pub start_byte: usize,
     pub end_byte: usize,
     pub token_type: TokenType,
 }
 
-pub struct Highlighter;
+/// How deep injections may nest, e.g. SQL in a string in a code block
+const MAX_INJECTION_DEPTH: usize = 3;
+
+/// How many injected trees a highlighter keeps
+const MAX_LAYERS: usize = 64;
+
+/// Highlights a document, keeping the trees of injected languages so they
+/// are reparsed incrementally as the document is edited.
+///
+/// Use one per document.
+#[derive(Default)]
+pub struct Highlighter {
+    parser: Parser,
+    layers: Vec<Layer>,
+    /// Counts highlight calls, to find layers that weren't used
+    generation: u64,
+}
+
+/// An injected language's tree
+struct Layer {
+    language: Language,
+    depth: usize,
+    ranges: Vec<tree_sitter::Range>,
+    /// The source from the start of the first range to the end of the last
+    text: Vec<u8>,
+    tree: Tree,
+    used: u64,
+}
+
+impl Layer {
+    fn span(&self) -> Range<usize> {
+        span(&self.ranges)
+    }
+}
 
 impl Highlighter {
-    /// Walk the tree-sitter CST and classify nodes by token type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Highlight the whole tree with the language's highlight queries.
     pub fn highlight(
         tree: &tree_sitter::Tree,
         source: &[u8],
         lang: Language,
     ) -> Vec<HighlightSpan> {
+        Self::new().highlight_range(tree, |offset| &source[offset..], lang, 0..source.len())
+    }
+
+    /// Highlight only the tokens overlapping or touching `range`.
+    ///
+    /// `chunk(offset)` is the source from byte `offset` to the end of the
+    /// chunk holding it, empty at the end, so a rope is read in place.
+    ///
+    /// Used after an incremental reparse to refresh just the changed regions;
+    /// captures entirely outside the range are skipped.
+    pub fn highlight_range<'a>(
+        &mut self,
+        tree: &tree_sitter::Tree,
+        chunk: impl Fn(usize) -> &'a [u8],
+        lang: Language,
+        range: Range<usize>,
+    ) -> Vec<HighlightSpan> {
+        self.highlight_range_with(Queries::global(), tree, chunk, lang, range)
+    }
+
+    /// [`Highlighter::highlight_range`] with a given set of queries.
+    ///
+    /// Spans are sorted and don't overlap: a capture nested in another wins
+    /// over it, and injected languages win over the text around them.
+    pub fn highlight_range_with<'a>(
+        &mut self,
+        queries: &Queries,
+        tree: &tree_sitter::Tree,
+        chunk: impl Fn(usize) -> &'a [u8],
+        lang: Language,
+        range: Range<usize>,
+    ) -> Vec<HighlightSpan> {
+        self.generation += 1;
         let mut spans = Vec::new();
-        let mut cursor = tree.walk();
-        Self::walk_node(&mut cursor, source, lang, &mut spans);
-        spans.sort_by_key(|s| s.start_byte);
+        self.highlight_layer(
+            queries,
+            tree.root_node(),
+            &chunk,
+            lang,
+            &range,
+            0,
+            &mut spans,
+        );
+        // Layers in the range that weren't found again are gone from the
+        // document
+        let generation = self.generation;
+        self.layers.retain(|layer| {
+            let span = layer.span();
+            layer.used == generation || span.end < range.start || span.start > range.end
+        });
+        if self.layers.len() > MAX_LAYERS {
+            self.layers.sort_by_key(|layer| Reverse(layer.used));
+            self.layers.truncate(MAX_LAYERS);
+        }
+        spans.retain(|s| {
+            s.token_type != TokenType::Plain
+                && s.end_byte >= range.start
+                && s.start_byte <= range.end
+        });
         spans
     }
 
-    fn walk_node(
-        cursor: &mut tree_sitter::TreeCursor,
-        source: &[u8],
+    /// The tree of an injected layer, reusing the one parsed last time for
+    /// the same layer
+    fn parse_layer<'a>(
+        &mut self,
+        language: Language,
+        depth: usize,
+        ranges: Vec<tree_sitter::Range>,
+        source: &dyn Fn(usize) -> &'a [u8],
+    ) -> Option<Tree> {
+        let grammar = language.tree_sitter_language()?;
+        let span = span(&ranges);
+        let text = slice(source, span.clone());
+        let cached = self.layers.iter().position(|layer| {
+            let old = layer.span();
+            layer.language == language
+                && layer.depth == depth
+                && old.start < span.end
+                && span.start < old.end
+        });
+        let old_tree = match cached.map(|i| self.layers.swap_remove(i)) {
+            Some(layer) if layer.ranges == ranges && layer.text == *text => {
+                let tree = layer.tree.clone();
+                self.layers.push(Layer {
+                    used: self.generation,
+                    ..layer
+                });
+                return Some(tree);
+            }
+            // Edited inside: tell the old tree what changed
+            Some(layer)
+                if layer.ranges[0].start_byte == span.start
+                    && layer.ranges[0].start_point == ranges[0].start_point =>
+            {
+                let mut tree = layer.tree;
+                tree.edit(&text_edit(
+                    &layer.text,
+                    &text,
+                    span.start,
+                    ranges[0].start_point,
+                ));
+                Some(tree)
+            }
+            _ => None,
+        };
+        self.parser.set_language(&grammar).ok()?;
+        self.parser.set_included_ranges(&ranges).ok()?;
+        let tree = self
+            .parser
+            .parse_with(&mut |offset, _| source(offset), old_tree.as_ref())?;
+        self.layers.push(Layer {
+            language,
+            depth,
+            ranges,
+            text: text.into_owned(),
+            tree: tree.clone(),
+            used: self.generation,
+        });
+        Some(tree)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn highlight_layer<'a>(
+        &mut self,
+        queries: &Queries,
+        root: Node,
+        source: &dyn Fn(usize) -> &'a [u8],
         lang: Language,
+        range: &Range<usize>,
+        depth: usize,
         spans: &mut Vec<HighlightSpan>,
     ) {
-        let node = cursor.node();
-        let kind = node.kind();
-
-        // tree-sitter cursors iterate all nodes. Leaf nodes are usually the tokens.
-        if node.child_count() == 0 {
-            let token_type =
-                Self::classify_node(kind, lang, source, node.start_byte(), node.end_byte());
-            if token_type != TokenType::Plain {
-                spans.push(HighlightSpan {
-                    start_byte: node.start_byte(),
-                    end_byte: node.end_byte(),
-                    token_type,
-                });
+        let Some(compiled) = queries.get(lang) else {
+            return;
+        };
+        let touches =
+            |node: &Node| node.end_byte() >= range.start && node.start_byte() <= range.end;
+        // Tokens touching the range count: one ending where an edit starts
+        // may have changed
+        let byte_range = range.start.saturating_sub(1)..range.end.saturating_add(1);
+
+        if let Some(query) = &compiled.highlights {
+            let mut captures = Vec::new();
+            let mut cursor = QueryCursor::new();
+            cursor.set_byte_range(byte_range.clone());
+            let mut matches = cursor.captures(query, root, |node: Node| {
+                std::iter::once(slice(source, node.byte_range()))
+            });
+            while let Some((m, index)) = matches.next() {
+                let capture = m.captures[*index];
+                let token_type = compiled.token_types[capture.index as usize];
+                if let Some(token_type) = token_type.filter(|_| touches(&capture.node)) {
+                    let node = capture.node;
+                    captures.push((
+                        node.start_byte(),
+                        node.end_byte(),
+                        m.pattern_index,
+                        token_type,
+                    ));
+                }
+            }
+            // Outer nodes first, then the captures of each node in pattern
+            // order, so nested captures and later patterns are painted last
+            captures.sort_by_key(|&(start, end, pattern, _)| (start, Reverse(end), pattern));
+            for (start_byte, end_byte, _, token_type) in captures {
+                paint(
+                    spans,
+                    HighlightSpan {
+                        start_byte,
+                        end_byte,
+                        token_type,
+                    },
+                );
             }
         }
 
-        if cursor.goto_first_child() {
-            loop {
-                Self::walk_node(cursor, source, lang, spans);
-                if !cursor.goto_next_sibling() {
-                    break;
+        let Some(injection) = compiled
+            .injections
+            .as_ref()
+            .filter(|_| depth < MAX_INJECTION_DEPTH)
+        else {
+            return;
+        };
+        // Content of each injection, by pattern for combined injections
+        let mut layers: Vec<(Option<usize>, Language, Vec<tree_sitter::Range>)> = Vec::new();
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(byte_range);
+        let mut matches = cursor.matches(&injection.query, root, |node: Node| {
+            std::iter::once(slice(source, node.byte_range()))
+        });
+        while let Some(m) = matches.next() {
+            let mut language = None;
+            let mut content = Vec::new();
+            for capture in m.captures {
+                if Some(capture.index) == injection.language {
+                    let name = slice(source, capture.node.byte_range());
+                    language = std::str::from_utf8(&name).ok().map(Language::from_name);
+                } else if Some(capture.index) == injection.content && touches(&capture.node) {
+                    content.push(capture.node);
+                }
+            }
+            let mut children = Children::Excluded;
+            let mut combined = None;
+            for property in injection.query.property_settings(m.pattern_index) {
+                match (&*property.key, property.value.as_deref()) {
+                    ("injection.language", Some(name)) => {
+                        language.get_or_insert(Language::from_name(name));
+                    }
+                    ("injection.include-children", _) => children = Children::Included,
+                    ("injection.include-unnamed-children", _) => children = Children::Unnamed,
+                    ("injection.combined", _) => combined = Some(m.pattern_index),
+                    _ => {}
                 }
             }
-            cursor.goto_parent();
+            let Some(language) = language.filter(|l| l.tree_sitter_language().is_some()) else {
+                continue;
+            };
+            let ranges = content
+                .iter()
+                .flat_map(|node| content_ranges(node, children));
+            match layers.iter_mut().find(|(pattern, lang, _)| {
+                combined.is_some() && *pattern == combined && *lang == language
+            }) {
+                Some((_, _, layer)) => layer.extend(ranges),
+                None => layers.push((combined, language, ranges.collect())),
+            }
         }
-    }
 
-    fn classify_node(
-        kind: &str,
-        _lang: Language,
-        source: &[u8],
-        start: usize,
-        end: usize,
-    ) -> TokenType {
-        // Basic heuristic classification based on node kind strings common in tree-sitter grammars
-        // A real implementation would use queries (.scm files)
-        match kind {
-            "line_comment" | "block_comment" | "comment" => TokenType::Comment,
-            "string_literal" | "string" | "string_content" | "raw_string_literal"
-            | "char_literal" | "template_string" => TokenType::String,
-            "integer_literal" | "float_literal" | "number" => TokenType::Number,
-            "true" | "false" | "none" | "None" | "null" => TokenType::Constant,
-            "type_identifier" | "primitive_type" | "builtin_type" => TokenType::Type,
-            "identifier" => {
-                let text = std::str::from_utf8(&source[start..end]).unwrap_or("");
-                if Self::is_keyword(text) {
-                    TokenType::Keyword
-                } else {
-                    TokenType::Variable
-                }
+        for (_, language, ranges) in layers {
+            if ranges.is_empty() {
+                continue;
+            }
+            let Some(tree) = self.parse_layer(language, depth, ranges.clone(), source) else {
+                continue;
+            };
+            // The injected language's colors replace the host's
+            for content in &ranges {
+                paint(
+                    spans,
+                    HighlightSpan {
+                        start_byte: content.start_byte,
+                        end_byte: content.end_byte,
+                        token_type: TokenType::Plain,
+                    },
+                );
             }
-            // Keywords often appear as their own node types or anonymous nodes
-            "fn" | "let" | "mut" | "pub" | "use" | "mod" | "struct" | "enum" | "impl" | "trait"
-            | "const" | "static" | "if" | "else" | "match" | "for" | "while" | "loop"
-            | "return" | "async" | "await" | "self" | "super" | "crate" | "where" | "as"
-            | "function" | "var" | "class" | "import" | "export" | "from" | "def" | "lambda"
-            | "yield" | "try" | "except" | "finally" | "raise" | "with" => TokenType::Keyword,
+            self.highlight_layer(
+                queries,
+                tree.root_node(),
+                source,
+                language,
+                range,
+                depth + 1,
+                spans,
+            );
+        }
+    }
+}
 
-            "(" | ")" | "[" | "]" | "{" | "}" | ";" | "," | "." | "::" => TokenType::Punctuation,
+/// Put `span` over the sorted, non-overlapping `spans`, cutting back or
+/// removing what it covers
+pub(crate) fn paint(spans: &mut Vec<HighlightSpan>, span: HighlightSpan) {
+    if span.start_byte >= span.end_byte {
+        return;
+    }
+    let first = spans.partition_point(|s| s.end_byte <= span.start_byte);
+    let last = spans.partition_point(|s| s.start_byte < span.end_byte);
+    let mut pieces = Vec::with_capacity(3);
+    if let Some(before) = spans[first..last]
+        .first()
+        .filter(|s| s.start_byte < span.start_byte)
+    {
+        pieces.push(HighlightSpan {
+            end_byte: span.start_byte,
+            ..before.clone()
+        });
+    }
+    let after = spans[first..last]
+        .last()
+        .filter(|s| s.end_byte > span.end_byte)
+        .map(|s| HighlightSpan {
+            start_byte: span.end_byte,
+            ..s.clone()
+        });
+    pieces.push(span);
+    pieces.extend(after);
+    spans.splice(first..last, pieces);
+}
 
-            "+" | "-" | "*" | "/" | "%" | "=" | "!" | "<" | ">" | "&" | "|" | "^" | "~" | "=="
-            | "!=" | "<=" | ">=" | "&&" | "||" | "->" | "=>" => TokenType::Operator,
+/// The bytes of `range`, borrowed when they're in one chunk
+fn slice<'a>(source: &dyn Fn(usize) -> &'a [u8], range: Range<usize>) -> Cow<'a, [u8]> {
+    let first = source(range.start);
+    if first.len() >= range.len() {
+        return Cow::Borrowed(&first[..range.len()]);
+    }
+    let mut bytes = Vec::with_capacity(range.len());
+    while bytes.len() < range.len() {
+        let chunk = source(range.start + bytes.len());
+        if chunk.is_empty() {
+            break;
+        }
+        bytes.extend_from_slice(&chunk[..chunk.len().min(range.len() - bytes.len())]);
+    }
+    Cow::Owned(bytes)
+}
 
-            "attribute_item" | "decorator" => TokenType::Attribute,
-            "macro_invocation" => TokenType::Macro,
+/// From the start of the first of `ranges` to the end of the last
+fn span(ranges: &[tree_sitter::Range]) -> Range<usize> {
+    match (ranges.first(), ranges.last()) {
+        (Some(first), Some(last)) => first.start_byte..last.end_byte,
+        _ => 0..0,
+    }
+}
 
-            _ => TokenType::Plain,
-        }
+/// The edit turning `old` into `new`, both starting at byte `start` and
+/// point `point` of the document
+fn text_edit(old: &[u8], new: &[u8], start: usize, point: Point) -> InputEdit {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let advance = |text: &[u8]| {
+        text.iter().fold(point, |p, &byte| match byte {
+            b'\n' => Point::new(p.row + 1, 0),
+            _ => Point::new(p.row, p.column + 1),
+        })
+    };
+    InputEdit {
+        start_byte: start + prefix,
+        old_end_byte: start + old.len() - suffix,
+        new_end_byte: start + new.len() - suffix,
+        start_position: advance(&new[..prefix]),
+        old_end_position: advance(&old[..old.len() - suffix]),
+        new_end_position: advance(&new[..new.len() - suffix]),
     }
+}
 
-    fn is_keyword(text: &str) -> bool {
-        matches!(
-            text,
-            "fn" | "let"
-                | "mut"
-                | "pub"
-                | "use"
-                | "mod"
-                | "struct"
-                | "enum"
-                | "impl"
-                | "trait"
-                | "const"
-                | "static"
-                | "if"
-                | "else"
-                | "match"
-                | "for"
-                | "while"
-                | "loop"
-                | "return"
-                | "async"
-                | "await"
-                | "self"
-                | "super"
-                | "crate"
-                | "where"
-                | "as"
-                | "in"
-                | "ref"
-                | "move"
-                | "type"
-                | "unsafe"
-                | "extern"
-                | "dyn"
-                | "true"
-                | "false"
-                | "function"
-                | "var"
-                | "class"
-                | "import"
-                | "export"
-                | "from"
-                | "def"
-                | "yield"
-        )
+/// Which children of an injection's content node are part of the injection
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Children {
+    Excluded,
+    Included,
+    /// Only anonymous ones, such as punctuation
+    Unnamed,
+}
+
+/// The text of an injection's content node, without its children unless
+/// the query asks for them
+fn content_ranges(node: &Node, children: Children) -> Vec<tree_sitter::Range> {
+    if children == Children::Included || node.child_count() == 0 {
+        return vec![node.range()];
+    }
+    let mut ranges = Vec::new();
+    let (mut start_byte, mut start_point) = (node.start_byte(), node.start_position());
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        if children == Children::Unnamed && !child.is_named() {
+            continue;
+        }
+        if child.start_byte() > start_byte {
+            ranges.push(tree_sitter::Range {
+                start_byte,
+                end_byte: child.start_byte(),
+                start_point,
+                end_point: child.start_position(),
+            });
+        }
+        (start_byte, start_point) = (child.end_byte(), child.end_position());
     }
+    if node.end_byte() > start_byte {
+        ranges.push(tree_sitter::Range {
+            start_byte,
+            end_byte: node.end_byte(),
+            start_point,
+            end_point: node.end_position(),
+        });
+    }
+    ranges
 }
 
 #[cfg(test)]
@@ -189,4 +567,228 @@ mod tests {
         // Check for 'x' variable
         assert!(spans.iter().any(|s| s.token_type == TokenType::Variable));
     }
+
+    #[test]
+    fn highlight_range_limits_to_touched_tokens() {
+        let code = "fn a() { 1 }\nfn b() { 2 }";
+        let mut parser = SyntaxParser::new(Language::Rust).unwrap();
+        let tree = parser.parse(code).unwrap();
+        let spans = Highlighter::new().highlight_range(&tree, chunks(code), Language::Rust, 22..23);
+
+        let covered: Vec<&str> = spans
+            .iter()
+            .map(|s| &code[s.start_byte..s.end_byte])
+            .collect();
+        assert_eq!(covered, vec!["2"]);
+        assert_eq!(spans[0].token_type, TokenType::Number);
+    }
+
+    /// The source read a few bytes at a time, like a rope's chunks
+    fn chunks<'a>(code: &'a str) -> impl Fn(usize) -> &'a [u8] {
+        |offset| &code.as_bytes()[offset..(offset + 7).min(code.len())]
+    }
+
+    fn spans_by_text<'a>(code: &'a str, spans: &[HighlightSpan]) -> Vec<(&'a str, TokenType)> {
+        spans
+            .iter()
+            .map(|s| (&code[s.start_byte..s.end_byte], s.token_type))
+            .collect()
+    }
+
+    #[test]
+    fn capture_names_map_to_token_types() {
+        assert_eq!(
+            TokenType::from_capture("function.method"),
+            Some(TokenType::Function)
+        );
+        assert_eq!(
+            TokenType::from_capture("function.macro"),
+            Some(TokenType::Macro)
+        );
+        assert_eq!(
+            TokenType::from_capture("punctuation.bracket"),
+            Some(TokenType::Punctuation)
+        );
+        assert_eq!(
+            TokenType::from_capture("string.special.key"),
+            Some(TokenType::Property)
+        );
+        assert_eq!(
+            TokenType::from_capture("constant.builtin"),
+            Some(TokenType::Constant)
+        );
+        assert_eq!(
+            TokenType::from_capture("variable.builtin"),
+            Some(TokenType::Builtin)
+        );
+        assert_eq!(TokenType::from_capture("spell"), None);
+    }
+
+    #[test]
+    fn later_and_nested_captures_win() {
+        let code = "const MAX: u32 = 1;\nlet s = \"a\\n\";";
+        let mut parser = SyntaxParser::new(Language::Rust).unwrap();
+        let tree = parser.parse(code).unwrap();
+        let spans = Highlighter::highlight(&tree, code.as_bytes(), Language::Rust);
+
+        let by_text = spans_by_text(code, &spans);
+        assert!(by_text.contains(&("MAX", TokenType::Constant)));
+        assert!(by_text.contains(&("u32", TokenType::Type)));
+        assert!(by_text.contains(&("s", TokenType::Variable)));
+        assert!(by_text.contains(&("\\n", TokenType::String)));
+        assert!(spans.windows(2).all(|w| w[0].end_byte <= w[1].start_byte));
+    }
+
+    #[test]
+    fn injections_use_the_injected_grammar() {
+        let dir = std::env::temp_dir().join(format!("forge-queries-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("python")).unwrap();
+        std::fs::write(
+            dir.join("python/injections.scm"),
+            "((string (string_content) @injection.content)\n \
+             (#match? @injection.content \"^\\\\{\")\n \
+             (#set! injection.language \"json\"))",
+        )
+        .unwrap();
+        let queries = Queries::new(Some(dir.clone()));
+
+        let code = "x = '{\"a\": 1}'";
+        let mut parser = SyntaxParser::new(Language::Python).unwrap();
+        let tree = parser.parse(code).unwrap();
+        let spans = Highlighter::new().highlight_range_with(
+            &queries,
+            &tree,
+            chunks(code),
+            Language::Python,
+            0..code.len(),
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let by_text = spans_by_text(code, &spans);
+        assert!(
+            by_text.contains(&("\"a\"", TokenType::Property)),
+            "{:?}",
+            by_text
+        );
+        assert!(by_text.contains(&("1", TokenType::Number)));
+        assert!(by_text.contains(&("'", TokenType::String)));
+    }
+
+    fn highlight_text(code: &str, lang: Language) -> Vec<(&str, TokenType)> {
+        let tree = SyntaxParser::new(lang).unwrap().parse(code).unwrap();
+        spans_by_text(code, &Highlighter::highlight(&tree, code.as_bytes(), lang))
+    }
+
+    #[test]
+    fn sql_strings_are_injected() {
+        let by_text = highlight_text("let q = \"SELECT id FROM users\";", Language::Rust);
+        assert!(
+            by_text.contains(&("SELECT", TokenType::Keyword)),
+            "{:?}",
+            by_text
+        );
+        assert!(
+            by_text.contains(&("users", TokenType::Type)),
+            "{:?}",
+            by_text
+        );
+
+        let by_text = highlight_text("q = 'select id from users'", Language::Python);
+        assert!(
+            by_text.contains(&("select", TokenType::Keyword)),
+            "{:?}",
+            by_text
+        );
+
+        let by_text = highlight_text("const q = 'SELECT id FROM users';", Language::JavaScript);
+        assert!(
+            by_text.contains(&("SELECT", TokenType::Keyword)),
+            "{:?}",
+            by_text
+        );
+    }
+
+    #[test]
+    fn tagged_templates_are_injected() {
+        let by_text = highlight_text("const s = css`p { color: red; }`;", Language::JavaScript);
+        assert!(
+            by_text.contains(&("color", TokenType::Property)),
+            "{:?}",
+            by_text
+        );
+    }
+
+    #[test]
+    fn html_scripts_and_styles_are_injected() {
+        let code = "<script>let x = 1;</script>\n<style>p { color: red; }</style>";
+        let by_text = highlight_text(code, Language::Html);
+        assert!(
+            by_text.contains(&("script", TokenType::Keyword)),
+            "{:?}",
+            by_text
+        );
+        assert!(
+            by_text.contains(&("let", TokenType::Keyword)),
+            "{:?}",
+            by_text
+        );
+        assert!(by_text.contains(&("1", TokenType::Number)), "{:?}", by_text);
+        assert!(
+            by_text.contains(&("color", TokenType::Property)),
+            "{:?}",
+            by_text
+        );
+    }
+
+    #[test]
+    fn markdown_code_blocks_and_html_are_injected() {
+        let code = "# Title\n\n```rust\nfn main() {}\n```\n\n<div>\n</div>\n";
+        let by_text = highlight_text(code, Language::Markdown);
+        assert!(
+            by_text.contains(&("fn", TokenType::Keyword)),
+            "{:?}",
+            by_text
+        );
+        assert!(
+            by_text.contains(&("main", TokenType::Function)),
+            "{:?}",
+            by_text
+        );
+        assert!(
+            by_text.contains(&("div", TokenType::Keyword)),
+            "{:?}",
+            by_text
+        );
+    }
+
+    #[test]
+    fn injected_trees_are_reused_across_edits() {
+        let mut highlighter = Highlighter::new();
+        let mut parser = SyntaxParser::new(Language::Rust).unwrap();
+        let code = "let q = \"SELECT id FROM users\";";
+        let tree = parser.parse(code).unwrap();
+        highlighter.highlight_range(&tree, chunks(code), Language::Rust, 0..code.len());
+        assert_eq!(highlighter.layers.len(), 1);
+
+        // Unchanged: the same tree comes back
+        let before = highlighter.layers[0].tree.root_node().id();
+        highlighter.highlight_range(&tree, chunks(code), Language::Rust, 10..12);
+        assert_eq!(highlighter.layers[0].tree.root_node().id(), before);
+
+        // Edited inside the string: reparsed, and highlighted like a fresh parse
+        let code = "let q = \"SELECT id, name FROM users\";";
+        let tree = parser.parse(code).unwrap();
+        let spans = highlighter.highlight_range(&tree, chunks(code), Language::Rust, 0..code.len());
+        assert_eq!(highlighter.layers.len(), 1);
+        assert_eq!(
+            spans,
+            Highlighter::highlight(&tree, code.as_bytes(), Language::Rust)
+        );
+
+        // Gone from the document: dropped
+        let code = "let q = 1;";
+        let tree = parser.parse(code).unwrap();
+        highlighter.highlight_range(&tree, chunks(code), Language::Rust, 0..code.len());
+        assert!(highlighter.layers.is_empty());
+    }
 }
@@ -0,0 +1,442 @@
+//! Indentation for new and re-indented lines.
+//!
+//! Languages with an `indents.scm` query are indented from the syntax tree:
+//!
+//! - `@indent` on a node indents the lines after the one it starts on, up to
+//!   the line its `@end` token starts. On a token in an `ERROR`, such as an
+//!   unclosed bracket, it runs to the end of the error.
+//! - `@end` is the token closing an `@indent` node; a line starting with it
+//!   lines up with the line the node starts on.
+//! - `@outdent` on a node, like Python's `else`, lines it up with the start
+//!   of the `@indent` node around it.
+//!
+//! Other languages use the indent patterns from their
+//! [`LanguageConfig`].
+
+use crate::language::Language;
+use crate::language_config::LanguageConfig;
+use crate::queries::Queries;
+use std::borrow::Cow;
+use std::ops::Range;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{Node, Point, QueryCursor, Tree};
+
+/// Where a line's indentation comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndentSuggestion {
+    /// The line whose indentation it builds on, `None` for no indentation.
+    /// A line inside a multi-line string or comment is its own basis.
+    pub basis: Option<usize>,
+    /// Levels deeper than the basis line, or shallower when negative
+    pub delta: isize,
+}
+
+impl IndentSuggestion {
+    /// The indentation, given the basis line's and one level's
+    pub fn indent(&self, basis_indent: &str, unit: &str) -> String {
+        let mut indent = match self.basis {
+            Some(_) => basis_indent.to_string(),
+            None => String::new(),
+        };
+        for _ in 0..self.delta.max(0) {
+            indent.push_str(unit);
+        }
+        for _ in 0..(-self.delta).max(0) {
+            if indent.ends_with('\t') {
+                indent.pop();
+            } else {
+                let spaces = indent.len() - indent.trim_end_matches(' ').len();
+                indent.truncate(indent.len() - spaces.min(unit.len().max(1)));
+            }
+        }
+        indent
+    }
+}
+
+/// Suggestions for each row in `rows`: from the language's indent query when
+/// it has one and there is a tree, otherwise from the config's patterns.
+///
+/// `line(i)` is line `i` of the source, with or without its line ending. Only
+/// the rows in `rows` and the lines they build on are read.
+pub fn suggest_indents<'a>(
+    tree: Option<&Tree>,
+    line: impl Fn(usize) -> Cow<'a, str>,
+    lang: Language,
+    config: &LanguageConfig,
+    rows: Range<usize>,
+) -> Vec<IndentSuggestion> {
+    let lines = Lines { line };
+    tree.and_then(|tree| query_suggestions(Queries::global(), tree, &lines, lang, rows.clone()))
+        .unwrap_or_else(|| from_patterns(config, &lines, rows))
+}
+
+/// Suggestions from the indent query in `queries`, `None` when the language
+/// has none
+pub fn suggest_indents_with<'a>(
+    queries: &Queries,
+    tree: &Tree,
+    line: impl Fn(usize) -> Cow<'a, str>,
+    lang: Language,
+    rows: Range<usize>,
+) -> Option<Vec<IndentSuggestion>> {
+    query_suggestions(queries, tree, &Lines { line }, lang, rows)
+}
+
+fn query_suggestions<'a, F: Fn(usize) -> Cow<'a, str>>(
+    queries: &Queries,
+    tree: &Tree,
+    lines: &Lines<F>,
+    lang: Language,
+    rows: Range<usize>,
+) -> Option<Vec<IndentSuggestion>> {
+    let compiled = queries.get(lang)?;
+    let query = compiled.indents.as_ref()?;
+    let first = lines.prev_non_blank(rows.start).unwrap_or(rows.start);
+
+    let capture = |name| query.capture_index_for_name(name);
+    let (indent, end, outdent) = (capture("indent"), capture("end"), capture("outdent"));
+    let mut spans = Vec::new();
+    let mut outdents = Vec::new();
+    let mut cursor = QueryCursor::new();
+    cursor.set_point_range(Point::new(first, 0)..Point::new(rows.end, 0));
+    let mut matches = cursor.matches(query, tree.root_node(), |node: Node| lines.node_text(node));
+    while let Some(m) = matches.next() {
+        let mut node = None;
+        let mut end_token = None;
+        for capture in m.captures {
+            if Some(capture.index) == indent {
+                node = Some(capture.node);
+            } else if Some(capture.index) == end {
+                end_token = Some(capture.node);
+            } else if Some(capture.index) == outdent {
+                outdents.push(capture.node.start_position());
+            }
+        }
+        let Some(node) = node else {
+            continue;
+        };
+        let end = match end_token {
+            Some(token) => token.start_position(),
+            None if node.child_count() == 0 => node
+                .parent()
+                .map_or(node.end_position(), |parent| parent.end_position()),
+            None => node.end_position(),
+        };
+        spans.push(Span {
+            start: node.start_position(),
+            end,
+            closed: end_token.is_some(),
+        });
+    }
+
+    Some(
+        rows.map(|row| from_spans(&spans, &outdents, tree, lines, row))
+            .collect(),
+    )
+}
+
+/// An `@indent` node: rows after `start`'s are inside it until `end`
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    start: Point,
+    end: Point,
+    /// Ends at an `@end` token rather than with the node
+    closed: bool,
+}
+
+fn from_spans<'a, F: Fn(usize) -> Cow<'a, str>>(
+    spans: &[Span],
+    outdents: &[Point],
+    tree: &Tree,
+    lines: &Lines<F>,
+    row: usize,
+) -> IndentSuggestion {
+    let blank = lines.is_blank(row);
+    let here = Point::new(row, lines.indent_len(row));
+    if !blank && in_multiline_literal(tree, here) {
+        return IndentSuggestion {
+            basis: Some(row),
+            delta: 0,
+        };
+    }
+
+    // Closing tokens and clauses like `else` line up with what they close
+    let closing = spans
+        .iter()
+        .filter(|s| s.closed && s.end == here && s.start.row < row)
+        .map(|s| s.start.row)
+        .max();
+    let outdent = || {
+        outdents.contains(&here).then(|| {
+            spans
+                .iter()
+                .filter(|s| s.start.row < row && s.start < here && here < s.end)
+                .map(|s| s.start.row)
+                .max()
+        })?
+    };
+    if let Some(basis) = closing.or_else(outdent) {
+        return IndentSuggestion {
+            basis: Some(basis),
+            delta: 0,
+        };
+    }
+
+    let Some(prev) = lines.prev_non_blank(row) else {
+        return IndentSuggestion {
+            basis: None,
+            delta: 0,
+        };
+    };
+    // A line closing brackets opened on earlier lines continues the line
+    // that opened them
+    let basis = spans
+        .iter()
+        .filter(|s| s.closed && s.end.row == prev && s.start.row < prev)
+        .map(|s| s.start.row)
+        .min()
+        .unwrap_or(prev);
+    let contains = |s: &Span, at: Point| {
+        s.start.row < at.row
+            && (s.end > at
+                // While typing, a new line after a block without a closing
+                // token is still in it
+                || (blank && !s.closed && s.end.row >= prev))
+    };
+    if spans
+        .iter()
+        .any(|s| (basis..=prev).contains(&s.start.row) && contains(s, here))
+    {
+        return IndentSuggestion {
+            basis: Some(basis),
+            delta: 1,
+        };
+    }
+
+    // Leaving blocks without a closing token, like Python's
+    let prev_start = Point::new(prev, lines.indent_len(prev));
+    let left = spans
+        .iter()
+        .filter(|s| !s.closed && s.end > prev_start && !contains(s, here))
+        .filter(|s| s.start.row < prev)
+        .map(|s| s.start.row)
+        .min();
+    IndentSuggestion {
+        basis: Some(left.unwrap_or(basis)),
+        delta: 0,
+    }
+}
+
+/// Whether `point` is in a string or comment that starts on an earlier line
+fn in_multiline_literal(tree: &Tree, point: Point) -> bool {
+    let mut node = tree.root_node().descendant_for_point_range(point, point);
+    while let Some(n) = node {
+        if n.start_position().row < point.row
+            && (n.kind().contains("string") || n.kind().contains("comment"))
+        {
+            return true;
+        }
+        node = n.parent();
+    }
+    false
+}
+
+fn from_patterns<'a, F: Fn(usize) -> Cow<'a, str>>(
+    config: &LanguageConfig,
+    lines: &Lines<F>,
+    rows: Range<usize>,
+) -> Vec<IndentSuggestion> {
+    let matches = |pattern: &Option<regex::Regex>, row: usize| {
+        pattern
+            .as_ref()
+            .is_some_and(|pattern| pattern.is_match(&lines.text(row)))
+    };
+    rows.map(|row| match lines.prev_non_blank(row) {
+        Some(prev) => IndentSuggestion {
+            basis: Some(prev),
+            delta: matches(&config.increase_indent_pattern, prev) as isize
+                - matches(&config.decrease_indent_pattern, row) as isize,
+        },
+        None => IndentSuggestion {
+            basis: None,
+            delta: 0,
+        },
+    })
+    .collect()
+}
+
+/// Row access to the source, one line at a time
+struct Lines<F> {
+    line: F,
+}
+
+impl<'a, F: Fn(usize) -> Cow<'a, str>> Lines<F> {
+    /// The row's text without its line ending
+    fn text(&self, row: usize) -> Cow<'a, str> {
+        match (self.line)(row) {
+            Cow::Borrowed(text) => Cow::Borrowed(text.trim_end_matches(['\n', '\r'])),
+            Cow::Owned(mut text) => {
+                text.truncate(text.trim_end_matches(['\n', '\r']).len());
+                Cow::Owned(text)
+            }
+        }
+    }
+
+    /// The text a node spans, for query predicates
+    fn node_text(&self, node: Node) -> std::vec::IntoIter<Cow<'a, [u8]>> {
+        let (start, end) = (node.start_position(), node.end_position());
+        let pieces: Vec<_> = (start.row..=end.row)
+            .map(|row| {
+                let text = match (self.line)(row) {
+                    Cow::Borrowed(text) => Cow::Borrowed(text.as_bytes()),
+                    Cow::Owned(text) => Cow::Owned(text.into_bytes()),
+                };
+                let to = if row == end.row {
+                    end.column
+                } else {
+                    text.len()
+                };
+                let to = to.min(text.len());
+                let from = if row == start.row { start.column } else { 0 };
+                let range = from.min(to)..to;
+                match text {
+                    Cow::Borrowed(text) => Cow::Borrowed(&text[range]),
+                    Cow::Owned(text) => Cow::Owned(text[range].to_vec()),
+                }
+            })
+            .collect();
+        pieces.into_iter()
+    }
+
+    fn indent_len(&self, row: usize) -> usize {
+        let text = self.text(row);
+        text.len() - text.trim_start_matches([' ', '\t']).len()
+    }
+
+    fn is_blank(&self, row: usize) -> bool {
+        self.text(row).trim().is_empty()
+    }
+
+    fn prev_non_blank(&self, row: usize) -> Option<usize> {
+        (0..row).rev().find(|&r| !self.is_blank(r))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LanguageConfigs, SyntaxParser};
+
+    fn lines<'a>(source: &'a str) -> impl Fn(usize) -> Cow<'a, str> {
+        |row| Cow::Borrowed(source.split_inclusive('\n').nth(row).unwrap_or(""))
+    }
+
+    /// Re-indent every line, as its tree has it
+    fn reindent(lang: Language, source: &str) -> String {
+        let tree = SyntaxParser::new(lang).unwrap().parse(source).unwrap();
+        let config = LanguageConfigs::global().for_language(lang);
+        let rows = source.lines().count();
+        let suggestions = suggest_indents(Some(&tree), lines(source), lang, &config, 0..rows);
+        let leading = |line: &str| line[..line.len() - line.trim_start().len()].to_string();
+        let mut out: Vec<String> = Vec::new();
+        for (line, suggestion) in source.lines().zip(suggestions) {
+            let indent = match suggestion.basis {
+                Some(row) if row == out.len() => leading(line),
+                Some(row) => suggestion.indent(&leading(&out[row]), "    "),
+                None => suggestion.indent("", "    "),
+            };
+            out.push(indent + line.trim_start());
+        }
+        out.join("\n") + "\n"
+    }
+
+    #[test]
+    fn rust_indents_by_brackets() {
+        let source = "fn main() {
+    let x = foo(
+        1,
+        bar(2),
+    );
+    if x {
+        y();
+    }
+    let s = \"a
+  b\";
+}
+";
+        // Lines in the string are left alone
+        let flat: String = source
+            .lines()
+            .map(|l| l.trim_start().to_owned() + "\n")
+            .collect();
+        assert_eq!(
+            reindent(Language::Rust, &flat),
+            source.replace("\n  b", "\nb")
+        );
+    }
+
+    #[test]
+    fn python_dedents_after_blocks() {
+        let source = "class A:
+    def f(self):
+        if x:
+            return [
+                1,
+            ]
+        else:
+            pass
+    y = 2
+z = 3
+";
+        assert_eq!(reindent(Language::Python, source), source);
+        // Python's own indentation decides where a block ends, so only
+        // lines that stay in their block can be fixed
+        let misaligned = source.replace("\n            pass", "\n          pass");
+        assert_eq!(reindent(Language::Python, &misaligned), source);
+    }
+
+    #[test]
+    fn new_lines_indent_inside_unclosed_brackets() {
+        let suggest = |lang, source: &str| {
+            let tree = SyntaxParser::new(lang).unwrap().parse(source).unwrap();
+            let row = source.lines().count();
+            let config = LanguageConfigs::global().for_language(lang);
+            suggest_indents(Some(&tree), lines(source), lang, &config, row..row + 1)[0]
+        };
+        let indent = |basis, delta| IndentSuggestion {
+            basis: Some(basis),
+            delta,
+        };
+        assert_eq!(suggest(Language::Rust, "fn main() {\n"), indent(0, 1));
+        assert_eq!(
+            suggest(Language::Rust, "fn main() {\n    let x = foo(\n"),
+            indent(1, 1)
+        );
+        assert_eq!(
+            suggest(Language::Rust, "fn main() {\n    x();\n"),
+            indent(1, 0)
+        );
+        assert_eq!(suggest(Language::Python, "def f():\n"), indent(0, 1));
+        assert_eq!(
+            suggest(Language::Python, "def f():\n    x = 1\n"),
+            indent(1, 0)
+        );
+        assert_eq!(suggest(Language::Python, "foo(a,\n"), indent(0, 1));
+    }
+
+    #[test]
+    fn patterns_cover_languages_without_queries() {
+        let config = LanguageConfigs::global().get("css");
+        let suggestions = from_patterns(
+            &config,
+            &Lines {
+                line: lines("a {\ncolor: red;\n}\n"),
+            },
+            0..3,
+        );
+        assert_eq!(suggestions[1].delta, 1);
+        assert_eq!(suggestions[2].delta, -1);
+        assert_eq!(suggestions[2].indent("    ", "    "), "");
+        assert!(suggest_indents(None, lines("a {\n"), Language::Css, &config, 1..2)[0].delta == 1);
+    }
+}
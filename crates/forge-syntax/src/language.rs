@@ -1,8 +1,11 @@
+use crate::grammars::Grammars;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Language {
     Rust,
     JavaScript,
     TypeScript,
+    Tsx,
     Python,
     Go,
     C,
@@ -14,6 +17,7 @@ pub enum Language {
     Css,
     Markdown,
     Shell,
+    Sql,
     Unknown,
 }
 
@@ -22,7 +26,8 @@ impl Language {
         match ext.to_lowercase().as_str() {
             "rs" => Self::Rust,
             "js" | "mjs" | "cjs" | "jsx" => Self::JavaScript,
-            "ts" | "tsx" => Self::TypeScript,
+            "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
             "py" | "pyw" => Self::Python,
             "go" => Self::Go,
             "c" | "h" => Self::C,
@@ -34,6 +39,54 @@ impl Language {
             "css" | "scss" => Self::Css,
             "md" | "markdown" => Self::Markdown,
             "sh" | "bash" | "zsh" => Self::Shell,
+            "sql" => Self::Sql,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// The language's id, as used for query directories and settings
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Rust => "rust",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Tsx => "tsx",
+            Self::Python => "python",
+            Self::Go => "go",
+            Self::C => "c",
+            Self::Cpp => "cpp",
+            Self::Json => "json",
+            Self::Toml => "toml",
+            Self::Yaml => "yaml",
+            Self::Html => "html",
+            Self::Css => "css",
+            Self::Markdown => "markdown",
+            Self::Shell => "shell",
+            Self::Sql => "sql",
+            Self::Unknown => "plaintext",
+        }
+    }
+
+    /// The language for an id or common alias, such as a Markdown code
+    /// fence's info string
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "rust" | "rs" => Self::Rust,
+            "javascript" | "js" | "jsx" | "node" => Self::JavaScript,
+            "typescript" | "ts" | "mts" | "cts" => Self::TypeScript,
+            "tsx" => Self::Tsx,
+            "python" | "py" => Self::Python,
+            "go" | "golang" => Self::Go,
+            "c" => Self::C,
+            "cpp" | "c++" => Self::Cpp,
+            "json" => Self::Json,
+            "toml" => Self::Toml,
+            "yaml" | "yml" => Self::Yaml,
+            "html" => Self::Html,
+            "css" => Self::Css,
+            "markdown" | "md" => Self::Markdown,
+            "shell" | "sh" | "bash" | "zsh" => Self::Shell,
+            "sql" => Self::Sql,
             _ => Self::Unknown,
         }
     }
@@ -46,14 +99,16 @@ impl Language {
             .unwrap_or(Self::Unknown)
     }
 
-    pub fn tree_sitter_language(&self) -> Option<tree_sitter::Language> {
+    /// The name of the language's grammar in [`Grammars`]
+    pub fn grammar_name(&self) -> Option<&'static str> {
         match self {
-            Self::Rust => Some(tree_sitter_rust::LANGUAGE.into()),
-            Self::JavaScript | Self::TypeScript => Some(tree_sitter_javascript::LANGUAGE.into()),
-            Self::Python => Some(tree_sitter_python::LANGUAGE.into()),
-            Self::Json => Some(tree_sitter_json::LANGUAGE.into()),
-            // Self::Toml => Some(tree_sitter_toml::language()), // Disable TOML for now due to version mismatch
-            _ => None,
+            Self::Shell => Some("bash"),
+            Self::Unknown => None,
+            _ => Some(self.name()),
         }
     }
+
+    pub fn tree_sitter_language(&self) -> Option<tree_sitter::Language> {
+        Grammars::global().get(self.grammar_name()?)
+    }
 }
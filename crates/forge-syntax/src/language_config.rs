@@ -0,0 +1,578 @@
+//! Per-language editing behavior: comment tokens, brackets, word and indent
+//! patterns, and which files a language is used for.
+//!
+//! The bundled `languages.toml` covers the built-in languages; user and
+//! workspace files in the same format override it key by key, and may map
+//! globs straight to languages in an `[associations]` table.
+
+use crate::Language;
+use anyhow::{Context, Result};
+use globset::{Glob, GlobBuilder, GlobMatcher, GlobSet, GlobSetBuilder};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+const BUNDLED: &str = include_str!("../languages.toml");
+
+/// A `languages.toml` as written
+#[derive(Debug, Deserialize)]
+struct RawFile {
+    /// Globs mapped to language ids, checked before every other rule
+    #[serde(default)]
+    associations: BTreeMap<String, String>,
+    #[serde(flatten)]
+    languages: BTreeMap<String, RawConfig>,
+}
+
+/// One language's table as written, before it is merged with the tables
+/// loaded before it
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawConfig {
+    aliases: Option<Vec<String>>,
+    line_comment: Option<String>,
+    block_comment: Option<(String, String)>,
+    brackets: Option<Vec<(String, String)>>,
+    auto_closing_pairs: Option<Vec<(String, String)>>,
+    word_pattern: Option<String>,
+    increase_indent_pattern: Option<String>,
+    decrease_indent_pattern: Option<String>,
+    indent_unit: Option<String>,
+    file_types: Option<Vec<String>>,
+    shebangs: Option<Vec<String>>,
+    first_line: Option<String>,
+}
+
+impl RawConfig {
+    fn merge(self, over: RawConfig) -> RawConfig {
+        RawConfig {
+            aliases: over.aliases.or(self.aliases),
+            line_comment: over.line_comment.or(self.line_comment),
+            block_comment: over.block_comment.or(self.block_comment),
+            brackets: over.brackets.or(self.brackets),
+            auto_closing_pairs: over.auto_closing_pairs.or(self.auto_closing_pairs),
+            word_pattern: over.word_pattern.or(self.word_pattern),
+            increase_indent_pattern: over
+                .increase_indent_pattern
+                .or(self.increase_indent_pattern),
+            decrease_indent_pattern: over
+                .decrease_indent_pattern
+                .or(self.decrease_indent_pattern),
+            indent_unit: over.indent_unit.or(self.indent_unit),
+            file_types: over.file_types.or(self.file_types),
+            shebangs: over.shebangs.or(self.shebangs),
+            first_line: over.first_line.or(self.first_line),
+        }
+    }
+
+    fn compile(&self, id: &str) -> Result<LanguageConfig> {
+        let regex = |pattern: &Option<String>, key: &str| -> Result<Option<Regex>> {
+            pattern
+                .as_deref()
+                // An empty pattern in an override turns the behavior off
+                .filter(|p| !p.is_empty())
+                .map(|p| Regex::new(p).with_context(|| format!("{}.{}", id, key)))
+                .transpose()
+        };
+        let file_types = self.file_types.clone().unwrap_or_default();
+        let mut globs = GlobSetBuilder::new();
+        for pattern in &file_types {
+            let glob = GlobBuilder::new(pattern)
+                .literal_separator(true)
+                .build()
+                .with_context(|| format!("{}.file_types", id))?;
+            globs.add(glob);
+        }
+        Ok(LanguageConfig {
+            id: id.to_string(),
+            aliases: self.aliases.clone().unwrap_or_default(),
+            line_comment: self.line_comment.clone().filter(|t| !t.is_empty()),
+            block_comment: self.block_comment.clone(),
+            brackets: self.brackets.clone().unwrap_or_default(),
+            auto_closing_pairs: self.auto_closing_pairs.clone().unwrap_or_default(),
+            word_pattern: regex(&self.word_pattern, "word_pattern")?
+                .unwrap_or_else(|| Regex::new(r"\w+").unwrap()),
+            increase_indent_pattern: regex(
+                &self.increase_indent_pattern,
+                "increase_indent_pattern",
+            )?,
+            decrease_indent_pattern: regex(
+                &self.decrease_indent_pattern,
+                "decrease_indent_pattern",
+            )?,
+            indent_unit: self.indent_unit.clone().filter(|u| !u.is_empty()),
+            globs: globs
+                .build()
+                .with_context(|| format!("{}.file_types", id))?,
+            file_types,
+            shebangs: self.shebangs.clone().unwrap_or_default(),
+            first_line: regex(&self.first_line, "first_line")?,
+        })
+    }
+}
+
+/// How a language is edited
+#[derive(Debug, Clone)]
+pub struct LanguageConfig {
+    /// Language id, such as `rust` or `dockerfile`
+    pub id: String,
+    /// Other names for the language, the first of which is shown to users
+    pub aliases: Vec<String>,
+    pub line_comment: Option<String>,
+    pub block_comment: Option<(String, String)>,
+    /// Pairs that are matched and highlighted together
+    pub brackets: Vec<(String, String)>,
+    /// Pairs whose closing half is inserted when the opening one is typed
+    pub auto_closing_pairs: Vec<(String, String)>,
+    pub word_pattern: Regex,
+    /// Lines matching it indent the line after them
+    pub increase_indent_pattern: Option<Regex>,
+    /// Lines matching it are indented one level less
+    pub decrease_indent_pattern: Option<Regex>,
+    /// One level of indentation when the language requires one, like Go's
+    /// tab; otherwise the editor settings decide
+    pub indent_unit: Option<String>,
+    /// Globs for file names, or for whole paths when they contain a `/`
+    pub file_types: Vec<String>,
+    /// Interpreters that a `#!` line runs the file with
+    pub shebangs: Vec<String>,
+    /// Matches the first line of files in the language
+    pub first_line: Option<Regex>,
+    globs: GlobSet,
+}
+
+impl LanguageConfig {
+    /// The language's variant, `Unknown` for ones only configured here
+    pub fn language(&self) -> Language {
+        Language::from_name(&self.id)
+    }
+
+    /// The name shown to users
+    pub fn display_name(&self) -> &str {
+        self.aliases.first().unwrap_or(&self.id)
+    }
+
+    fn is_named(&self, name: &str) -> bool {
+        std::iter::once(&self.id)
+            .chain(&self.aliases)
+            .any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// The byte range of the word in `line` containing or touching `offset`
+    pub fn word_at(&self, line: &str, offset: usize) -> Option<Range<usize>> {
+        self.word_pattern
+            .find_iter(line)
+            .map(|m| m.range())
+            .find(|range| range.start <= offset && offset <= range.end && !range.is_empty())
+    }
+
+    fn matches_path(&self, path: &Path) -> bool {
+        let file_name = path.file_name().map(Path::new).unwrap_or(path);
+        self.globs.is_match(file_name) || self.globs.is_match(path)
+    }
+
+    fn matches_first_line(&self, line: &str) -> bool {
+        self.first_line.as_ref().is_some_and(|re| re.is_match(line))
+    }
+
+    fn matches_shebang(&self, interpreter: &str) -> bool {
+        self.shebangs.iter().any(|name| {
+            // `python3.12` runs a `python3` script
+            interpreter
+                .strip_prefix(name.as_str())
+                .is_some_and(|version| version.chars().all(|c| c.is_ascii_digit() || c == '.'))
+        })
+    }
+}
+
+/// The interpreter a `#!` line runs, looking through `env`
+fn shebang_interpreter(first_line: &str) -> Option<&str> {
+    let mut words = first_line.strip_prefix("#!")?.split_whitespace();
+    let program = words.next()?;
+    let program = program.rsplit('/').next().unwrap_or(program);
+    if program != "env" {
+        return Some(program);
+    }
+    words.find(|word| !word.starts_with('-') && !word.contains('='))
+}
+
+/// The language a Vim (`vim: set ft=python:`) or Emacs
+/// (`-*- mode: python -*-`) modeline in the first or last five lines sets
+fn modeline_language(text: &str) -> Option<&str> {
+    static VIM: OnceLock<Regex> = OnceLock::new();
+    static VIM_OPTION: OnceLock<Regex> = OnceLock::new();
+    static EMACS: OnceLock<Regex> = OnceLock::new();
+    static EMACS_MODE: OnceLock<Regex> = OnceLock::new();
+    let vim = VIM.get_or_init(|| Regex::new(r"(?:^|\s)(?:vi|vim\d*|ex):\s*(.*)").unwrap());
+    let vim_option = VIM_OPTION
+        .get_or_init(|| Regex::new(r"(?:^|[\s:])(?:ft|filetype|syn|syntax)=([\w+.-]+)").unwrap());
+    let emacs = EMACS.get_or_init(|| Regex::new(r"-\*-\s*(.*?)\s*-\*-").unwrap());
+    let emacs_mode =
+        EMACS_MODE.get_or_init(|| Regex::new(r"(?:^|;)\s*mode:\s*([\w+.-]+)").unwrap());
+
+    let lines: Vec<&str> = text.lines().collect();
+    let tail = lines.len().saturating_sub(5).max(5.min(lines.len()));
+    lines[..5.min(lines.len())]
+        .iter()
+        .chain(&lines[tail..])
+        .find_map(|line| {
+            if let Some(options) = vim.captures(line) {
+                let ft = vim_option.captures(options.get(1)?.as_str());
+                return ft.map(|c| c.get(1).unwrap().as_str());
+            }
+            let vars = emacs.captures(line)?.get(1)?.as_str();
+            if !vars.contains(':') {
+                return Some(vars);
+            }
+            emacs_mode
+                .captures(vars)
+                .map(|c| c.get(1).unwrap().as_str())
+        })
+}
+
+struct Entry {
+    raw: RawConfig,
+    config: Arc<LanguageConfig>,
+}
+
+/// A glob from an `[associations]` table
+struct Association {
+    glob: GlobMatcher,
+    id: String,
+    /// The file it was loaded from
+    source: Option<PathBuf>,
+}
+
+impl Association {
+    fn matches(&self, path: &Path) -> bool {
+        let file_name = path.file_name().map(Path::new).unwrap_or(path);
+        self.glob.is_match(file_name) || self.glob.is_match(path)
+    }
+}
+
+/// Every language's configuration; languages configured later take
+/// precedence when matching files
+pub struct LanguageConfigs {
+    entries: Mutex<Vec<Entry>>,
+    associations: Mutex<Vec<Association>>,
+}
+
+impl Default for LanguageConfigs {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguageConfigs {
+    /// The bundled configuration
+    pub fn new() -> Self {
+        let configs = Self {
+            entries: Mutex::new(Vec::new()),
+            associations: Mutex::new(Vec::new()),
+        };
+        configs
+            .load_str(BUNDLED)
+            .expect("bundled languages.toml is valid");
+        configs
+    }
+
+    /// The configuration the editor uses
+    pub fn global() -> &'static LanguageConfigs {
+        static GLOBAL: OnceLock<LanguageConfigs> = OnceLock::new();
+        GLOBAL.get_or_init(LanguageConfigs::new)
+    }
+
+    /// Merge in a `languages.toml`. Nothing changes if any table in it is
+    /// invalid.
+    pub fn load_str(&self, text: &str) -> Result<()> {
+        self.load(text, None)
+    }
+
+    /// Merge in `text`, read from `source`. The associations loaded from
+    /// `source` before are replaced.
+    fn load(&self, text: &str, source: Option<&Path>) -> Result<()> {
+        let file: RawFile = toml::from_str(text)?;
+        let mut entries = self.entries.lock().unwrap();
+        let known =
+            |id: &str| entries.iter().any(|e| e.config.id == id) || file.languages.contains_key(id);
+        let associations = file
+            .associations
+            .iter()
+            .map(|(pattern, id)| {
+                let glob =
+                    Glob::new(pattern).with_context(|| format!("associations.\"{}\"", pattern))?;
+                if !known(id) {
+                    anyhow::bail!("associations.\"{}\": no language {:?}", pattern, id);
+                }
+                Ok(Association {
+                    glob: glob.compile_matcher(),
+                    id: id.clone(),
+                    source: source.map(Path::to_path_buf),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut merged = Vec::new();
+        for (id, over) in file.languages {
+            let raw = match entries.iter().position(|e| e.config.id == id) {
+                Some(i) => entries[i].raw.clone().merge(over),
+                None => over,
+            };
+            let config = Arc::new(raw.compile(&id)?);
+            merged.push(Entry { raw, config });
+        }
+        for entry in merged {
+            entries.retain(|e| e.config.id != entry.config.id);
+            entries.push(entry);
+        }
+        let mut loaded = self.associations.lock().unwrap();
+        if source.is_some() {
+            loaded.retain(|a| a.source.as_deref() != source);
+        }
+        loaded.extend(associations);
+        Ok(())
+    }
+
+    /// Merge in a `languages.toml` file, if it exists
+    pub fn load_file(&self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        self.load(&text, Some(path))
+            .with_context(|| format!("loading {}", path.display()))
+    }
+
+    /// The configuration for a language id, or for plain text when there is
+    /// none
+    pub fn get(&self, id: &str) -> Arc<LanguageConfig> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .find(|e| e.config.id == id)
+            .or_else(|| entries.iter().find(|e| e.config.id == "plaintext"))
+            .map(|e| e.config.clone())
+            .unwrap_or_else(|| Arc::new(RawConfig::default().compile("plaintext").unwrap()))
+    }
+
+    pub fn for_language(&self, language: Language) -> Arc<LanguageConfig> {
+        self.get(language.name())
+    }
+
+    /// The language with an id or alias, ignoring case
+    pub fn by_name(&self, name: &str) -> Option<Arc<LanguageConfig>> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .find(|e| e.config.is_named(name))
+            .map(|e| e.config.clone())
+    }
+
+    /// The language a file is in. `text` is its content, or at least its
+    /// first and last five lines.
+    ///
+    /// A modeline decides first, then the associations, the file name, the
+    /// `#!` line and last the first line's content.
+    pub fn detect(&self, path: &str, text: &str) -> Option<Arc<LanguageConfig>> {
+        if let Some(config) = modeline_language(text).and_then(|name| self.by_name(name)) {
+            return Some(config);
+        }
+        let path = Path::new(path);
+        let associated = self
+            .associations
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|a| a.matches(path))
+            .map(|a| a.id.clone());
+        let entries = self.entries.lock().unwrap();
+        let first_line = text.lines().next().unwrap_or("");
+        let by_association = || {
+            let id = associated.as_deref()?;
+            entries.iter().find(|e| e.config.id == id)
+        };
+        let by_path = || entries.iter().rev().find(|e| e.config.matches_path(path));
+        let by_shebang = || {
+            let interpreter = shebang_interpreter(first_line)?;
+            entries
+                .iter()
+                .rev()
+                .find(|e| e.config.matches_shebang(interpreter))
+        };
+        let by_first_line = || {
+            entries
+                .iter()
+                .rev()
+                .find(|e| e.config.matches_first_line(first_line))
+        };
+        by_association()
+            .or_else(by_path)
+            .or_else(by_shebang)
+            .or_else(by_first_line)
+            .map(|e| e.config.clone())
+    }
+
+    /// Every configured language id
+    pub fn ids(&self) -> Vec<String> {
+        let entries = self.entries.lock().unwrap();
+        entries.iter().map(|e| e.config.id.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_languages_cover_every_variant() {
+        let configs = LanguageConfigs::new();
+        let rust = configs.for_language(Language::Rust);
+        assert_eq!(rust.line_comment.as_deref(), Some("//"));
+        assert!(rust
+            .increase_indent_pattern
+            .as_ref()
+            .unwrap()
+            .is_match("fn main() {"));
+        assert_eq!(
+            configs.get("html").block_comment.as_ref().unwrap().0,
+            "<!--"
+        );
+        assert_eq!(configs.get("go").indent_unit.as_deref(), Some("\t"));
+        assert_eq!(rust.indent_unit, None);
+        assert_eq!(configs.get("no-such-language").id, "plaintext");
+        for language in [
+            Language::Tsx,
+            Language::Shell,
+            Language::Sql,
+            Language::Yaml,
+        ] {
+            assert_eq!(configs.for_language(language).language(), language);
+        }
+    }
+
+    #[test]
+    fn files_are_detected_by_glob_and_shebang() {
+        let configs = LanguageConfigs::new();
+        let detect =
+            |path: &str, first_line: &str| configs.detect(path, first_line).map(|c| c.id.clone());
+        assert_eq!(detect("/src/main.rs", "").as_deref(), Some("rust"));
+        assert_eq!(
+            detect("deploy/Dockerfile", "").as_deref(),
+            Some("dockerfile")
+        );
+        assert_eq!(detect("Dockerfile.dev", "").as_deref(), Some("dockerfile"));
+        assert_eq!(
+            detect("bin/tool", "#!/usr/bin/env -S python3.12 -u").as_deref(),
+            Some("python")
+        );
+        assert_eq!(detect("bin/run", "#!/bin/bash").as_deref(), Some("shell"));
+        assert_eq!(detect("bin/run", "#!/usr/bin/pythonic").as_deref(), None);
+        assert_eq!(detect("notes", "").as_deref(), None);
+    }
+
+    #[test]
+    fn modelines_associations_and_first_lines() {
+        let configs = LanguageConfigs::new();
+        let detect = |path: &str, text: &str| configs.detect(path, text).map(|c| c.id.clone());
+        assert_eq!(
+            detect("a.txt", "x = 1\n# vim: set ts=4 ft=python :\n").as_deref(),
+            Some("python")
+        );
+        assert_eq!(
+            detect("build.rs", "// -*- mode: makefile; tab-width: 8 -*-").as_deref(),
+            Some("makefile")
+        );
+        assert_eq!(
+            detect("x", "# -*- Shell-Script -*-").as_deref(),
+            Some("shell")
+        );
+        assert_eq!(
+            detect("x.rs", "// -*- coding: utf-8 -*-").as_deref(),
+            Some("rust")
+        );
+        assert_eq!(
+            detect("build", "# syntax=docker/dockerfile:1\nFROM alpine").as_deref(),
+            Some("dockerfile")
+        );
+        assert_eq!(
+            detect("index", "<!DOCTYPE html>\n<html>").as_deref(),
+            Some("html")
+        );
+
+        configs
+            .load_str(
+                r#"
+                [associations]
+                "*.conf" = "shell"
+                "**/templates/*.txt" = "html"
+                "#,
+            )
+            .unwrap();
+        assert_eq!(detect("/etc/app.conf", "").as_deref(), Some("shell"));
+        assert_eq!(
+            detect("/repo/templates/page.txt", "").as_deref(),
+            Some("html")
+        );
+        assert_eq!(detect("/repo/page.txt", "").as_deref(), None);
+        assert_eq!(configs.by_name("C++").unwrap().id, "cpp");
+        assert_eq!(configs.get("cpp").display_name(), "C++");
+        assert!(configs.load_str("[associations]\n'a[' = 'rust'").is_err());
+        assert!(configs
+            .load_str("[associations]\n'*.cfg' = 'shel'")
+            .is_err());
+        assert_eq!(detect("/etc/app.cfg", "").as_deref(), None);
+    }
+
+    #[test]
+    fn reloading_a_file_replaces_its_associations() {
+        let dir = std::env::temp_dir().join("forge_language_associations");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("languages.toml");
+        let configs = LanguageConfigs::new();
+        let detect = |path: &str| configs.detect(path, "").map(|c| c.id.clone());
+
+        std::fs::write(&path, "[associations]\n'*.conf' = 'shell'\n").unwrap();
+        configs.load_file(&path).unwrap();
+        assert_eq!(detect("app.conf").as_deref(), Some("shell"));
+        std::fs::write(&path, "[associations]\n'*.cnf' = 'shell'\n").unwrap();
+        configs.load_file(&path).unwrap();
+        assert_eq!(detect("app.conf").as_deref(), None);
+        assert_eq!(detect("app.cnf").as_deref(), Some("shell"));
+        assert_eq!(configs.associations.lock().unwrap().len(), 1);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn overrides_merge_key_by_key() {
+        let configs = LanguageConfigs::new();
+        configs
+            .load_str(
+                r###"
+                [python]
+                line_comment = "##"
+
+                [jsonnet]
+                line_comment = "//"
+                file_types = ["*.jsonnet", "*.json"]
+                "###,
+            )
+            .unwrap();
+        let python = configs.get("python");
+        assert_eq!(python.line_comment.as_deref(), Some("##"));
+        assert!(python.increase_indent_pattern.is_some());
+        // Languages configured later win
+        assert_eq!(configs.detect("a.json", "").unwrap().id, "jsonnet");
+
+        assert!(configs.load_str("[python]\nword_pattern = '('").is_err());
+        assert!(configs.load_str("[python]\nline_coment = '#'").is_err());
+        assert_eq!(configs.get("python").word_pattern.as_str(), r"\w+");
+
+        let word = configs.get("css").word_at("a { --main-color: red }", 6);
+        assert_eq!(word, Some(4..16));
+    }
+}
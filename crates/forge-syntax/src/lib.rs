@@ -1,11 +1,21 @@
 pub mod colors;
+pub mod grammars;
 pub mod highlighter;
+pub mod indent;
 pub mod language;
+pub mod language_config;
 pub mod parser;
+pub mod queries;
+pub mod textmate;
 
+pub use grammars::Grammars;
 pub use highlighter::{HighlightSpan, Highlighter, TokenType};
+pub use indent::{suggest_indents, IndentSuggestion};
 pub use language::Language;
+pub use language_config::{LanguageConfig, LanguageConfigs};
 pub use parser::SyntaxParser;
+pub use queries::Queries;
+pub use textmate::{TextMateGrammars, TextMateHighlighter};
 
 #[cfg(test)]
 mod tests {
@@ -28,12 +38,39 @@ mod tests {
         assert_eq!(tree.root_node().kind(), "document");
     }
 
+    #[test]
+    fn parse_toml_file() {
+        let code = "[package]\nname = \"forge\"\n";
+        let mut parser = SyntaxParser::new(Language::Toml).unwrap();
+        let tree = parser.parse(code).unwrap();
+        assert_eq!(tree.root_node().kind(), "document");
+        assert!(!tree.root_node().has_error());
+    }
+
+    #[test]
+    fn parse_vendored_grammars() {
+        for (lang, code) in [
+            (Language::Go, "package main\n\nfunc main() {}\n"),
+            (Language::C, "int main(void) { return 0; }\n"),
+            (Language::Cpp, "class A { public: A() = default; };\n"),
+            (Language::Yaml, "name: forge\nitems: [1, 2]\n"),
+            (Language::Shell, "if [ -f x ]; then echo \"$x\"; fi\n"),
+            (Language::TypeScript, "let x: number = 1;\n"),
+            (Language::Tsx, "const a = <div>{x as number}</div>;\n"),
+        ] {
+            let mut parser = SyntaxParser::new(lang).unwrap();
+            let tree = parser.parse(code).unwrap();
+            assert!(!tree.root_node().has_error(), "{:?}", lang);
+        }
+    }
+
     #[test]
     fn language_detection() {
         assert_eq!(Language::from_extension("rs"), Language::Rust);
         assert_eq!(Language::from_extension("js"), Language::JavaScript);
         assert_eq!(Language::from_extension("py"), Language::Python);
         assert_eq!(Language::from_extension("json"), Language::Json);
+        assert_eq!(Language::from_extension("tsx"), Language::Tsx);
         assert_eq!(Language::from_extension("unknown"), Language::Unknown);
     }
 }
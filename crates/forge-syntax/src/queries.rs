@@ -0,0 +1,283 @@
+//! Highlight, injection and indent queries for each language.
+//!
+//! Queries are bundled from `queries/<language>/` and compiled on first use.
+//! A `highlights.scm`, `injections.scm` or `indents.scm` in
+//! `<user dir>/<language>/` replaces the bundled one; if it doesn't compile,
+//! the bundled one is used.
+//!
+//! When several patterns capture the same node, the last one in the query
+//! wins, so queries go from general patterns to specific ones.
+
+use crate::highlighter::TokenType;
+use crate::language::Language;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use tree_sitter::Query;
+
+/// Bundled `highlights.scm`, `injections.scm` and `indents.scm`
+struct Bundled {
+    highlights: Option<&'static str>,
+    injections: Option<&'static str>,
+    indents: Option<&'static str>,
+}
+
+fn bundled(lang: Language) -> Bundled {
+    let none = Bundled {
+        highlights: None,
+        injections: None,
+        indents: None,
+    };
+    match lang {
+        Language::Rust => Bundled {
+            highlights: Some(include_str!("../queries/rust/highlights.scm")),
+            injections: Some(include_str!("../queries/rust/injections.scm")),
+            indents: Some(include_str!("../queries/rust/indents.scm")),
+        },
+        Language::JavaScript => Bundled {
+            highlights: Some(concat!(
+                include_str!("../queries/javascript/highlights.scm"),
+                include_str!("../queries/javascript/highlights-jsx.scm"),
+            )),
+            injections: Some(include_str!("../queries/javascript/injections.scm")),
+            indents: Some(include_str!("../queries/javascript/indents.scm")),
+        },
+        // The TypeScript grammars extend the JavaScript one, and so do their
+        // queries
+        Language::TypeScript => Bundled {
+            highlights: Some(concat!(
+                include_str!("../queries/javascript/highlights.scm"),
+                include_str!("../queries/typescript/highlights.scm"),
+            )),
+            injections: Some(include_str!("../queries/javascript/injections.scm")),
+            indents: Some(include_str!("../queries/javascript/indents.scm")),
+        },
+        Language::Tsx => Bundled {
+            highlights: Some(concat!(
+                include_str!("../queries/javascript/highlights.scm"),
+                include_str!("../queries/javascript/highlights-jsx.scm"),
+                include_str!("../queries/typescript/highlights.scm"),
+            )),
+            injections: Some(include_str!("../queries/javascript/injections.scm")),
+            indents: Some(include_str!("../queries/javascript/indents.scm")),
+        },
+        Language::Python => Bundled {
+            highlights: Some(include_str!("../queries/python/highlights.scm")),
+            injections: Some(include_str!("../queries/python/injections.scm")),
+            indents: Some(include_str!("../queries/python/indents.scm")),
+        },
+        Language::Json => Bundled {
+            highlights: Some(include_str!("../queries/json/highlights.scm")),
+            indents: Some(include_str!("../queries/json/indents.scm")),
+            ..none
+        },
+        Language::Toml => Bundled {
+            highlights: Some(include_str!("../queries/toml/highlights.scm")),
+            indents: Some(include_str!("../queries/toml/indents.scm")),
+            ..none
+        },
+        Language::Markdown => Bundled {
+            highlights: Some(include_str!("../queries/markdown/highlights.scm")),
+            injections: Some(include_str!("../queries/markdown/injections.scm")),
+            ..none
+        },
+        Language::Html => Bundled {
+            highlights: Some(include_str!("../queries/html/highlights.scm")),
+            injections: Some(include_str!("../queries/html/injections.scm")),
+            ..none
+        },
+        Language::Css => Bundled {
+            highlights: Some(include_str!("../queries/css/highlights.scm")),
+            ..none
+        },
+        Language::Sql => Bundled {
+            highlights: Some(include_str!("../queries/sql/highlights.scm")),
+            ..none
+        },
+        Language::Go => Bundled {
+            highlights: Some(include_str!("../queries/go/highlights.scm")),
+            ..none
+        },
+        Language::C => Bundled {
+            highlights: Some(include_str!("../queries/c/highlights.scm")),
+            ..none
+        },
+        // The C++ grammar extends the C one, and so do its queries
+        Language::Cpp => Bundled {
+            highlights: Some(concat!(
+                include_str!("../queries/c/highlights.scm"),
+                include_str!("../queries/cpp/highlights.scm"),
+            )),
+            ..none
+        },
+        Language::Yaml => Bundled {
+            highlights: Some(include_str!("../queries/yaml/highlights.scm")),
+            ..none
+        },
+        Language::Shell => Bundled {
+            highlights: Some(include_str!("../queries/shell/highlights.scm")),
+            ..none
+        },
+        _ => none,
+    }
+}
+
+/// Where an injection's language and content come from in the query
+pub(crate) struct Injection {
+    pub query: Query,
+    pub content: Option<u32>,
+    pub language: Option<u32>,
+}
+
+/// One language's compiled queries
+pub struct LanguageQueries {
+    pub(crate) highlights: Option<Query>,
+    /// Token type of each capture of `highlights`, by capture index
+    pub(crate) token_types: Vec<Option<TokenType>>,
+    pub(crate) injections: Option<Injection>,
+    pub(crate) indents: Option<Query>,
+}
+
+impl LanguageQueries {
+    fn compile(lang: Language, user_dir: Option<&Path>) -> Option<Self> {
+        let grammar = lang.tree_sitter_language()?;
+        let bundled = bundled(lang);
+        let load = |file: &str, bundled: Option<&str>| -> Option<Query> {
+            if let Some(path) = user_dir.map(|dir| dir.join(lang.name()).join(file)) {
+                if let Ok(source) = std::fs::read_to_string(&path) {
+                    match Query::new(&grammar, &source) {
+                        Ok(query) => return Some(query),
+                        Err(e) => tracing::warn!("Ignoring {}: {}", path.display(), e),
+                    }
+                }
+            }
+            match Query::new(&grammar, bundled?) {
+                Ok(query) => Some(query),
+                Err(e) => {
+                    tracing::error!("Bundled {} for {:?} is broken: {}", file, lang, e);
+                    None
+                }
+            }
+        };
+
+        let highlights = load("highlights.scm", bundled.highlights);
+        let token_types = highlights
+            .as_ref()
+            .map(|q| {
+                q.capture_names()
+                    .iter()
+                    .map(|name| TokenType::from_capture(name))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let injections = load("injections.scm", bundled.injections).map(|query| Injection {
+            content: query.capture_index_for_name("injection.content"),
+            language: query.capture_index_for_name("injection.language"),
+            query,
+        });
+        Some(Self {
+            highlights,
+            token_types,
+            injections,
+            indents: load("indents.scm", bundled.indents),
+        })
+    }
+}
+
+/// Compiled queries for every language, with the user's overrides
+pub struct Queries {
+    user_dir: Mutex<Option<PathBuf>>,
+    compiled: Mutex<HashMap<Language, Option<Arc<LanguageQueries>>>>,
+}
+
+impl Queries {
+    pub fn new(user_dir: Option<PathBuf>) -> Self {
+        Self {
+            user_dir: Mutex::new(user_dir),
+            compiled: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The queries [`Highlighter`](crate::Highlighter) uses
+    pub fn global() -> &'static Queries {
+        static GLOBAL: OnceLock<Queries> = OnceLock::new();
+        GLOBAL.get_or_init(|| Queries::new(None))
+    }
+
+    /// Look for overrides in `dir`, compiling every query again
+    pub fn set_user_dir(&self, dir: Option<PathBuf>) {
+        *self.user_dir.lock().unwrap() = dir;
+        self.compiled.lock().unwrap().clear();
+    }
+
+    /// `None` for languages without a grammar
+    pub fn get(&self, lang: Language) -> Option<Arc<LanguageQueries>> {
+        if let Some(queries) = self.compiled.lock().unwrap().get(&lang) {
+            return queries.clone();
+        }
+        let user_dir = self.user_dir.lock().unwrap().clone();
+        let queries = LanguageQueries::compile(lang, user_dir.as_deref()).map(Arc::new);
+        self.compiled
+            .lock()
+            .unwrap()
+            .entry(lang)
+            .or_insert(queries)
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_queries_compile() {
+        let queries = Queries::new(None);
+        for lang in [
+            Language::Rust,
+            Language::JavaScript,
+            Language::Python,
+            Language::Json,
+            Language::Toml,
+            Language::TypeScript,
+            Language::Tsx,
+        ] {
+            let compiled = queries.get(lang).unwrap();
+            assert!(compiled.highlights.is_some(), "{:?}", lang);
+            assert!(compiled.indents.is_some(), "{:?}", lang);
+            assert!(
+                compiled.token_types.iter().all(Option::is_some),
+                "{:?}",
+                lang
+            );
+        }
+        // Highlighted, and indented by patterns rather than queries
+        for lang in [
+            Language::Html,
+            Language::Css,
+            Language::Markdown,
+            Language::Sql,
+            Language::Go,
+            Language::C,
+            Language::Cpp,
+            Language::Yaml,
+            Language::Shell,
+        ] {
+            let compiled = queries.get(lang).unwrap();
+            assert!(compiled.highlights.is_some(), "{:?}", lang);
+            assert!(
+                compiled.token_types.iter().all(Option::is_some),
+                "{:?}",
+                lang
+            );
+        }
+        for lang in [Language::Html, Language::Markdown] {
+            assert!(
+                queries.get(lang).unwrap().injections.is_some(),
+                "{:?}",
+                lang
+            );
+        }
+        assert!(queries.get(Language::Unknown).is_none());
+    }
+}
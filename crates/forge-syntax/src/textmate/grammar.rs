@@ -0,0 +1,437 @@
+//! Loading TextMate grammars and compiling their rules.
+
+use super::pattern::Pattern;
+use crate::highlighter::TokenType;
+use anyhow::{anyhow, bail, Context, Result};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+pub(crate) type RuleId = usize;
+
+/// Token type of each capture group, by index
+pub(crate) type Captures = Vec<Option<TokenType>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CloseKind {
+    End,
+    While,
+}
+
+/// How a begin rule's region closes
+#[derive(Debug)]
+pub(crate) struct Close {
+    pub kind: CloseKind,
+    pub source: String,
+    /// `None` if the pattern refers to the begin match's captures, and is
+    /// compiled for each begin match instead
+    pub pattern: Option<Arc<Pattern>>,
+    pub captures: Captures,
+}
+
+#[derive(Debug)]
+pub(crate) enum RuleKind {
+    /// Only `patterns` to try, e.g. an include or a repository entry
+    Group,
+    Match {
+        pattern: Pattern,
+        captures: Captures,
+    },
+    Begin {
+        begin: Pattern,
+        captures: Captures,
+        close: Close,
+        content: Option<TokenType>,
+        end_pattern_last: bool,
+    },
+}
+
+#[derive(Debug)]
+pub(crate) struct Rule {
+    pub kind: RuleKind,
+    pub name: Option<TokenType>,
+    pub patterns: Vec<RuleId>,
+    /// `patterns` with groups expanded, in the order they're tried
+    pub candidates: Vec<RuleId>,
+}
+
+impl Rule {
+    fn group(patterns: Vec<RuleId>) -> Self {
+        Self {
+            kind: RuleKind::Group,
+            name: None,
+            patterns,
+            candidates: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Grammar {
+    pub name: String,
+    pub scope_name: String,
+    /// File extensions or whole file names the grammar is for
+    pub file_types: Vec<String>,
+    pub(crate) rules: Vec<Rule>,
+}
+
+impl Grammar {
+    pub(crate) const ROOT: RuleId = 0;
+
+    /// Load a `.tmLanguage.json`, or a plist `.tmLanguage`
+    pub fn load(path: &Path) -> Result<Self> {
+        let text =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        let grammar = if path.extension().is_some_and(|e| e == "json") {
+            Self::from_json(&text)
+        } else {
+            Self::from_plist(&text)
+        };
+        grammar.with_context(|| format!("loading {}", path.display()))
+    }
+
+    pub fn from_json(text: &str) -> Result<Self> {
+        Self::compile(&serde_json::from_str(text)?)
+    }
+
+    pub fn from_plist(text: &str) -> Result<Self> {
+        Self::compile(&plist_to_json(text)?)
+    }
+
+    fn compile(grammar: &Value) -> Result<Self> {
+        let scope_name = grammar
+            .get("scopeName")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("grammar has no scopeName"))?;
+        let mut compiler = Compiler::default();
+        compiler.rules.push(Rule::group(Vec::new()));
+        let repositories: Vec<_> = grammar
+            .get("repository")
+            .and_then(Value::as_object)
+            .into_iter()
+            .collect();
+        let patterns = compiler.patterns(grammar, &repositories);
+        compiler.rules[Self::ROOT].patterns = patterns;
+
+        let mut rules = compiler.rules;
+        for id in 0..rules.len() {
+            let mut candidates = Vec::new();
+            let mut seen = vec![false; rules.len()];
+            expand(&rules, &rules[id].patterns, &mut seen, &mut candidates);
+            rules[id].candidates = candidates;
+        }
+        Ok(Self {
+            name: grammar
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or(scope_name)
+                .to_string(),
+            scope_name: scope_name.to_string(),
+            file_types: grammar
+                .get("fileTypes")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect(),
+            rules,
+        })
+    }
+}
+
+fn expand(rules: &[Rule], patterns: &[RuleId], seen: &mut [bool], out: &mut Vec<RuleId>) {
+    for &id in patterns {
+        if std::mem::replace(&mut seen[id], true) {
+            continue;
+        }
+        match rules[id].kind {
+            RuleKind::Group => expand(rules, &rules[id].patterns, seen, out),
+            _ => out.push(id),
+        }
+    }
+}
+
+/// The token type of a rule's `name` or `contentName`, which may list
+/// several scopes
+fn scope_type(value: Option<&Value>) -> Option<TokenType> {
+    value?
+        .as_str()?
+        .split_whitespace()
+        .rev()
+        .find_map(TokenType::from_scope)
+}
+
+fn captures(value: Option<&Value>) -> Captures {
+    let Some(map) = value.and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let mut captures = Vec::new();
+    for (index, capture) in map {
+        let Ok(index) = index.parse::<usize>() else {
+            continue;
+        };
+        if captures.len() <= index {
+            captures.resize(index + 1, None);
+        }
+        captures[index] = scope_type(capture.get("name"));
+    }
+    captures
+}
+
+/// Whether an end or while pattern refers to the begin match's captures
+pub(crate) fn has_backreferences(source: &str) -> bool {
+    let mut chars = source.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' && chars.next().is_some_and(|c| c.is_ascii_digit()) {
+            return true;
+        }
+    }
+    false
+}
+
+#[derive(Default)]
+struct Compiler {
+    rules: Vec<Rule>,
+    /// Rules already compiled, by the JSON object they came from
+    ids: HashMap<*const Value, RuleId>,
+}
+
+impl Compiler {
+    fn patterns<'a>(
+        &mut self,
+        rule: &'a Value,
+        repositories: &[&'a Map<String, Value>],
+    ) -> Vec<RuleId> {
+        rule.get("patterns")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|pattern| self.rule(pattern, repositories))
+            .collect()
+    }
+
+    fn rule<'a>(&mut self, value: &'a Value, repositories: &[&'a Map<String, Value>]) -> RuleId {
+        if let Some(&id) = self.ids.get(&(value as *const Value)) {
+            return id;
+        }
+        let id = self.rules.len();
+        self.rules.push(Rule::group(Vec::new()));
+        self.ids.insert(value as *const Value, id);
+
+        let mut repositories = repositories.to_vec();
+        repositories.extend(value.get("repository").and_then(Value::as_object));
+        if let Some(include) = value.get("include").and_then(Value::as_str) {
+            let target = match include {
+                "$self" | "$base" => Some(Grammar::ROOT),
+                _ => include.strip_prefix('#').and_then(|key| {
+                    let entry = repositories.iter().rev().find_map(|repo| repo.get(key))?;
+                    Some(self.rule(entry, &repositories))
+                }),
+            };
+            if target.is_none() {
+                tracing::debug!("Unresolved TextMate include {}", include);
+            }
+            self.rules[id].patterns = target.into_iter().collect();
+            return id;
+        }
+
+        let patterns = self.patterns(value, &repositories);
+        let name = scope_type(value.get("name"));
+        let kind = match compile_kind(value) {
+            Ok(kind) => kind,
+            Err(e) => {
+                tracing::debug!("Skipping TextMate rule: {:#}", e);
+                self.rules[id].patterns = Vec::new();
+                return id;
+            }
+        };
+        self.rules[id] = Rule {
+            kind,
+            name,
+            patterns,
+            candidates: Vec::new(),
+        };
+        id
+    }
+}
+
+fn compile_kind(rule: &Value) -> Result<RuleKind> {
+    let source = |key: &str| rule.get(key).and_then(Value::as_str);
+    if let Some(pattern) = source("match") {
+        return Ok(RuleKind::Match {
+            pattern: Pattern::new(pattern)?,
+            captures: captures(rule.get("captures")),
+        });
+    }
+    let Some(begin) = source("begin") else {
+        return Ok(RuleKind::Group);
+    };
+    let (kind, close) = match (source("end"), source("while")) {
+        (Some(end), _) => (CloseKind::End, end),
+        (None, Some(condition)) => (CloseKind::While, condition),
+        (None, None) => bail!("begin rule without end or while"),
+    };
+    let pattern = if has_backreferences(close) {
+        None
+    } else {
+        Some(Arc::new(Pattern::new(close)?))
+    };
+    let close_captures = match kind {
+        CloseKind::End => "endCaptures",
+        CloseKind::While => "whileCaptures",
+    };
+    Ok(RuleKind::Begin {
+        begin: Pattern::new(begin)?,
+        captures: captures(rule.get("beginCaptures").or(rule.get("captures"))),
+        close: Close {
+            kind,
+            source: close.to_string(),
+            pattern,
+            captures: captures(rule.get(close_captures).or(rule.get("captures"))),
+        },
+        content: scope_type(rule.get("contentName")),
+        end_pattern_last: rule
+            .get("applyEndPatternLast")
+            .is_some_and(|v| v.as_bool() == Some(true) || v.as_i64() == Some(1)),
+    })
+}
+
+/// Convert an XML property list to the JSON it's equivalent to
+fn plist_to_json(text: &str) -> Result<Value> {
+    let mut plist = Plist {
+        events: xml::EventReader::from_str(text).into_iter(),
+    };
+    while let Some(tag) = plist.next_element()? {
+        if tag != "plist" {
+            return plist.value(&tag);
+        }
+    }
+    bail!("empty property list")
+}
+
+struct Plist<I> {
+    events: I,
+}
+
+impl<I: Iterator<Item = xml::reader::Result<xml::reader::XmlEvent>>> Plist<I> {
+    /// The next child element's name, or `None` at the end of the parent
+    fn next_element(&mut self) -> Result<Option<String>> {
+        use xml::reader::XmlEvent;
+        for event in self.events.by_ref() {
+            match event? {
+                XmlEvent::StartElement { name, .. } => return Ok(Some(name.local_name)),
+                XmlEvent::EndElement { .. } | XmlEvent::EndDocument => return Ok(None),
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+
+    /// The text up to the end of the current element
+    fn text(&mut self) -> Result<String> {
+        use xml::reader::XmlEvent;
+        let mut text = String::new();
+        for event in self.events.by_ref() {
+            match event? {
+                XmlEvent::Characters(s) | XmlEvent::CData(s) | XmlEvent::Whitespace(s) => {
+                    text.push_str(&s)
+                }
+                XmlEvent::EndElement { .. } => return Ok(text),
+                XmlEvent::StartElement { name, .. } => {
+                    bail!("unexpected <{}> in text", name.local_name)
+                }
+                _ => {}
+            }
+        }
+        bail!("unterminated element")
+    }
+
+    fn value(&mut self, tag: &str) -> Result<Value> {
+        Ok(match tag {
+            "dict" => {
+                let mut map = Map::new();
+                while let Some(tag) = self.next_element()? {
+                    if tag != "key" {
+                        bail!("expected <key> in <dict>, found <{}>", tag);
+                    }
+                    let key = self.text()?;
+                    let tag = self
+                        .next_element()?
+                        .ok_or_else(|| anyhow!("no value for key {}", key))?;
+                    map.insert(key, self.value(&tag)?);
+                }
+                Value::Object(map)
+            }
+            "array" => {
+                let mut items = Vec::new();
+                while let Some(tag) = self.next_element()? {
+                    items.push(self.value(&tag)?);
+                }
+                Value::Array(items)
+            }
+            "true" | "false" => {
+                self.text()?;
+                Value::Bool(tag == "true")
+            }
+            "integer" => Value::from(self.text()?.trim().parse::<i64>()?),
+            "real" => Value::from(self.text()?.trim().parse::<f64>()?),
+            _ => Value::String(self.text()?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plist_and_json_grammars_compile_alike() {
+        let plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+<plist version="1.0">
+<dict>
+  <key>scopeName</key><string>source.demo</string>
+  <key>fileTypes</key><array><string>demo</string></array>
+  <key>patterns</key>
+  <array>
+    <dict><key>include</key><string>#comment</string></dict>
+    <dict>
+      <key>match</key><string>\b(let)\s+(\w+)</string>
+      <key>captures</key>
+      <dict>
+        <key>1</key><dict><key>name</key><string>keyword.other</string></dict>
+        <key>2</key><dict><key>name</key><string>variable.other</string></dict>
+      </dict>
+    </dict>
+  </array>
+  <key>repository</key>
+  <dict>
+    <key>comment</key>
+    <dict>
+      <key>begin</key><string>/\*</string>
+      <key>end</key><string>\*/</string>
+      <key>name</key><string>comment.block</string>
+      <key>patterns</key><array><dict><key>include</key><string>#comment</string></dict></array>
+    </dict>
+  </dict>
+</dict>
+</plist>"#;
+        let grammar = Grammar::from_plist(plist).unwrap();
+        assert_eq!(grammar.scope_name, "source.demo");
+        assert_eq!(grammar.file_types, vec!["demo"]);
+        let root = &grammar.rules[Grammar::ROOT];
+        assert_eq!(root.candidates.len(), 2);
+        let comment = &grammar.rules[root.candidates[0]];
+        assert_eq!(comment.name, Some(TokenType::Comment));
+        // Nested comments include the rule itself
+        assert_eq!(comment.candidates, vec![root.candidates[0]]);
+        let RuleKind::Match { captures, .. } = &grammar.rules[root.candidates[1]].kind else {
+            panic!("expected a match rule");
+        };
+        assert_eq!(
+            captures,
+            &vec![None, Some(TokenType::Keyword), Some(TokenType::Variable)]
+        );
+    }
+}
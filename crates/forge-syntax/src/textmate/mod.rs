@@ -0,0 +1,228 @@
+//! TextMate grammars, for languages without a tree-sitter grammar.
+//!
+//! Grammars come from `.tmLanguage.json` and plist `.tmLanguage` files in a
+//! user directory, and from extension packages that contribute them in
+//! their `package.json` the way VS Code extensions do. Tokens map to the
+//! same [`HighlightSpan`](crate::HighlightSpan)s tree-sitter highlighting
+//! produces.
+
+mod grammar;
+mod pattern;
+mod tokenizer;
+
+pub use grammar::Grammar;
+pub use tokenizer::TextMateHighlighter;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Arc, Mutex, OnceLock};
+
+struct Entry {
+    grammar: Arc<Grammar>,
+    /// Language id from the contributing extension
+    language: Option<String>,
+    /// Extensions (with the dot) and file names the grammar is used for
+    file_names: Vec<String>,
+}
+
+impl Entry {
+    fn matches_file(&self, file_name: &str) -> bool {
+        let types = self.grammar.file_types.iter().map(|t| (t.as_str(), true));
+        let names = self.file_names.iter().map(|n| (n.as_str(), false));
+        types.chain(names).any(|(name, bare_extension)| {
+            file_name == name
+                || (bare_extension
+                    && file_name
+                        .strip_suffix(name)
+                        .is_some_and(|s| s.ends_with('.')))
+                || (name.starts_with('.') && file_name.ends_with(name))
+        })
+    }
+}
+
+/// Loaded TextMate grammars; ones loaded later take precedence
+#[derive(Default)]
+pub struct TextMateGrammars {
+    entries: Mutex<Vec<Entry>>,
+}
+
+impl TextMateGrammars {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The grammars the editor uses
+    pub fn global() -> &'static TextMateGrammars {
+        static GLOBAL: OnceLock<TextMateGrammars> = OnceLock::new();
+        GLOBAL.get_or_init(TextMateGrammars::new)
+    }
+
+    pub fn add(
+        &self,
+        grammar: Grammar,
+        language: Option<&str>,
+        file_names: Vec<String>,
+    ) -> Arc<Grammar> {
+        let grammar = Arc::new(grammar);
+        self.entries.lock().unwrap().push(Entry {
+            grammar: grammar.clone(),
+            language: language.map(String::from),
+            file_names,
+        });
+        grammar
+    }
+
+    /// Load every grammar file in `dir`, returning how many loaded
+    pub fn load_dir(&self, dir: &Path) -> usize {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return 0;
+        };
+        let mut paths: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+                name.ends_with(".tmLanguage.json") || name.ends_with(".tmLanguage")
+            })
+            .collect();
+        paths.sort();
+        paths
+            .iter()
+            .filter(|path| match Grammar::load(path) {
+                Ok(grammar) => {
+                    self.add(grammar, None, Vec::new());
+                    true
+                }
+                Err(e) => {
+                    tracing::warn!("{:#}", e);
+                    false
+                }
+            })
+            .count()
+    }
+
+    /// Load the grammars an extension package contributes, returning how
+    /// many loaded
+    pub fn load_extension(&self, dir: &Path) -> Result<usize> {
+        let manifest = dir.join("package.json");
+        let manifest: Value = serde_json::from_str(
+            &std::fs::read_to_string(&manifest)
+                .with_context(|| format!("reading {}", manifest.display()))?,
+        )?;
+        let contributes = |key: &str| {
+            manifest
+                .pointer(&format!("/contributes/{}", key))
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default()
+        };
+        let languages = contributes("languages");
+        let mut loaded = 0;
+        for contribution in contributes("grammars") {
+            // Grammars without a language are injected into others
+            let (Some(language), Some(path)) = (
+                contribution.get("language").and_then(Value::as_str),
+                contribution.get("path").and_then(Value::as_str),
+            ) else {
+                continue;
+            };
+            let grammar = match Grammar::load(&dir.join(path)) {
+                Ok(grammar) => grammar,
+                Err(e) => {
+                    tracing::warn!("{:#}", e);
+                    continue;
+                }
+            };
+            let file_names = languages
+                .iter()
+                .filter(|l| l.get("id").and_then(Value::as_str) == Some(language))
+                .flat_map(|l| {
+                    ["extensions", "filenames"]
+                        .map(|key| l.get(key).and_then(Value::as_array).cloned())
+                })
+                .flatten()
+                .flatten()
+                .filter_map(|name| name.as_str().map(String::from))
+                .collect();
+            self.add(grammar, Some(language), file_names);
+            loaded += 1;
+        }
+        Ok(loaded)
+    }
+
+    /// The grammar for a file, by its name
+    pub fn for_path(&self, path: &str) -> Option<Arc<Grammar>> {
+        let file_name = Path::new(path).file_name()?.to_str()?;
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .rev()
+            .find(|entry| entry.matches_file(file_name))
+            .map(|entry| entry.grammar.clone())
+    }
+
+    /// The grammar for a language id such as `go`, or a scope name such as
+    /// `source.go`
+    pub fn for_language(&self, id: &str) -> Option<Arc<Grammar>> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .iter()
+            .rev()
+            .find(|entry| {
+                entry.language.as_deref() == Some(id)
+                    || entry.grammar.scope_name == id
+                    || entry.grammar.scope_name.strip_prefix("source.") == Some(id)
+            })
+            .map(|entry| entry.grammar.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extensions_contribute_grammars_by_file_name() {
+        let dir = std::env::temp_dir().join(format!("forge-textmate-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("syntaxes")).unwrap();
+        std::fs::write(
+            dir.join("package.json"),
+            r#"{"contributes": {
+                "languages": [{"id": "dockerfile", "extensions": [".dockerfile"], "filenames": ["Dockerfile"]}],
+                "grammars": [{"language": "dockerfile", "scopeName": "source.dockerfile", "path": "./syntaxes/docker.tmLanguage.json"}]
+            }}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("syntaxes/docker.tmLanguage.json"),
+            r#"{"scopeName": "source.dockerfile", "patterns": [{"match": "^FROM\\b", "name": "keyword.other"}]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("syntaxes/terraform.tmLanguage.json"),
+            r#"{"scopeName": "source.hcl.terraform", "fileTypes": ["tf"], "patterns": []}"#,
+        )
+        .unwrap();
+
+        let grammars = TextMateGrammars::new();
+        assert_eq!(grammars.load_extension(&dir).unwrap(), 1);
+        assert_eq!(grammars.load_dir(&dir.join("syntaxes")), 2);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let docker = grammars.for_path("/src/app/Dockerfile").unwrap();
+        assert_eq!(docker.scope_name, "source.dockerfile");
+        assert!(grammars.for_path("web.dockerfile").is_some());
+        assert_eq!(
+            grammars.for_path("main.tf").unwrap().scope_name,
+            "source.hcl.terraform"
+        );
+        assert!(grammars.for_path("maintf").is_none());
+        assert!(grammars.for_language("dockerfile").is_some());
+        assert!(grammars.for_language("hcl.terraform").is_some());
+
+        let mut highlighter = TextMateHighlighter::new(docker);
+        highlighter.update("FROM rust\n");
+        assert_eq!(highlighter.spans().len(), 1);
+    }
+}
@@ -0,0 +1,330 @@
+//! TextMate's Oniguruma patterns on top of the `regex` crate.
+//!
+//! Most grammar patterns only need syntax `regex` lacks in a few places, so
+//! patterns are rewritten: possessive quantifiers and atomic groups become
+//! plain ones, `\h` becomes a hex digit class, and lookarounds at either end
+//! of the pattern are checked separately around each match. `\G` is only
+//! honored at the start of a pattern. Anything else `regex` can't express,
+//! such as backreferences, fails to compile and the rule is skipped.
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use std::ops::Range;
+
+/// A lookaround checked next to a match rather than inside the regex
+#[derive(Debug)]
+struct Assertion {
+    regex: Regex,
+    negate: bool,
+}
+
+impl Assertion {
+    fn ahead(text: &str, negate: bool) -> Result<Self> {
+        Ok(Self {
+            regex: compile(&format!(r"\A(?:{})", text))?,
+            negate,
+        })
+    }
+
+    fn behind(text: &str, negate: bool) -> Result<Self> {
+        Ok(Self {
+            regex: compile(&format!(r"(?:{})\z", text))?,
+            negate,
+        })
+    }
+
+    fn holds(&self, haystack: &str) -> bool {
+        self.regex.is_match(haystack) != self.negate
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Pattern {
+    regex: Regex,
+    /// Starts with `\G`: only matches where the last match ended
+    anchored: bool,
+    behind: Vec<Assertion>,
+    /// Lookaheads at the start, checked where the match starts
+    ahead_start: Vec<Assertion>,
+    /// Lookaheads at the end, checked where the match ends
+    ahead_end: Vec<Assertion>,
+}
+
+impl Pattern {
+    pub fn new(source: &str) -> Result<Self> {
+        let mut rest = rewrite(source);
+        let mut anchored = false;
+        let mut behind = Vec::new();
+        let mut ahead_start = Vec::new();
+        let mut ahead_end = Vec::new();
+        let top_level_alternation = has_top_level_alternation(&rest);
+
+        loop {
+            if let Some(tail) = rest.strip_prefix(r"\G") {
+                anchored = true;
+                rest = tail.to_string();
+                continue;
+            }
+            if top_level_alternation {
+                break;
+            }
+            let Some((kind, end)) = lookaround_at(&rest, 0) else {
+                break;
+            };
+            let body = &rest[kind.len()..end];
+            match kind {
+                "(?=" => ahead_start.push(Assertion::ahead(body, false)?),
+                "(?!" => ahead_start.push(Assertion::ahead(body, true)?),
+                "(?<=" => behind.push(Assertion::behind(body, false)?),
+                _ => behind.push(Assertion::behind(body, true)?),
+            }
+            rest = rest[end + 1..].to_string();
+        }
+        if !top_level_alternation {
+            while let Some(start) = group_start_ending_at(&rest, rest.len()) {
+                let Some((kind, end)) = lookaround_at(&rest, start) else {
+                    break;
+                };
+                if kind.starts_with("(?<") {
+                    break;
+                }
+                ahead_end.push(Assertion::ahead(
+                    &rest[start + kind.len()..end],
+                    kind == "(?!",
+                )?);
+                rest.truncate(start);
+            }
+        }
+
+        Ok(Self {
+            regex: compile(&rest)?,
+            anchored,
+            behind,
+            ahead_start,
+            ahead_end,
+        })
+    }
+
+    /// The leftmost match starting at or after `start`, as the range of each
+    /// capture group. `anchor` is where `\G` matches.
+    pub fn find(
+        &self,
+        text: &str,
+        start: usize,
+        anchor: usize,
+    ) -> Option<Vec<Option<Range<usize>>>> {
+        if self.anchored && start > anchor {
+            return None;
+        }
+        let mut from = if self.anchored { anchor } else { start };
+        while from <= text.len() {
+            let captures = self.regex.captures_at(text, from)?;
+            let whole = captures.get(0)?.range();
+            if self.anchored && whole.start != anchor {
+                return None;
+            }
+            let holds = self.behind.iter().all(|a| a.holds(&text[..whole.start]))
+                && self
+                    .ahead_start
+                    .iter()
+                    .all(|a| a.holds(&text[whole.start..]))
+                && self.ahead_end.iter().all(|a| a.holds(&text[whole.end..]));
+            if holds {
+                return Some(captures.iter().map(|c| c.map(|c| c.range())).collect());
+            }
+            if self.anchored {
+                return None;
+            }
+            from = whole.start + text[whole.start..].chars().next().map_or(1, char::len_utf8);
+        }
+        None
+    }
+}
+
+fn compile(source: &str) -> Result<Regex> {
+    // `$` matches before the newline each line is tokenized with
+    Regex::new(&format!("(?m){}", source)).map_err(|e| anyhow!("{}", e))
+}
+
+/// Rewrite Oniguruma-only syntax that has a `regex` equivalent
+fn rewrite(source: &str) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    // Nesting of character classes, which both engines allow
+    let mut classes = 0;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some('h') if classes > 0 => out.push_str("0-9a-fA-F"),
+                Some('h') => out.push_str("[0-9a-fA-F]"),
+                Some('H') if classes == 0 => out.push_str("[^0-9a-fA-F]"),
+                Some('e') => out.push_str(r"\x1B"),
+                Some('Z') => out.push('$'),
+                Some('G') if !out.is_empty() => {}
+                Some(escaped) => {
+                    out.push('\\');
+                    out.push(escaped);
+                }
+                None => out.push('\\'),
+            },
+            '[' if classes > 0 && chars.peek() == Some(&':') => {
+                // A POSIX class like `[:alpha:]`
+                out.push(c);
+                for next in chars.by_ref() {
+                    out.push(next);
+                    if next == ']' {
+                        break;
+                    }
+                }
+            }
+            '[' => {
+                classes += 1;
+                out.push(c);
+                if chars.peek() == Some(&'^') {
+                    out.push('^');
+                    chars.next();
+                }
+                // A `]` right after the opening bracket is literal
+                if chars.peek() == Some(&']') {
+                    out.push_str(r"\]");
+                    chars.next();
+                }
+            }
+            ']' if classes > 0 => {
+                classes -= 1;
+                out.push(c);
+            }
+            '(' if classes == 0 && source_continues(&mut chars, "?>") => out.push_str("(?:"),
+            '*' | '+' | '?' | '}' if classes == 0 => {
+                out.push(c);
+                if c == '}' && !ends_with_counted_repetition(&out) {
+                    continue;
+                }
+                // Possessive quantifiers match like greedy ones, minus
+                // backtracking `regex` doesn't need
+                if chars.peek() == Some(&'+') {
+                    chars.next();
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// Whether `out` ends with a `{n}`, `{n,}` or `{n,m}` quantifier
+fn ends_with_counted_repetition(out: &str) -> bool {
+    out.rfind('{').is_some_and(|open| {
+        let inner = &out[open + 1..out.len() - 1];
+        !inner.is_empty()
+            && inner.starts_with(|c: char| c.is_ascii_digit())
+            && inner.chars().all(|c| c.is_ascii_digit() || c == ',')
+    })
+}
+
+/// Consume `expected` if the input continues with it
+fn source_continues(chars: &mut std::iter::Peekable<std::str::Chars>, expected: &str) -> bool {
+    let rest: String = chars.clone().take(expected.len()).collect();
+    if rest == expected {
+        for _ in 0..expected.chars().count() {
+            chars.next();
+        }
+        true
+    } else {
+        false
+    }
+}
+
+/// Byte offset of each unescaped `(`, `)` and `|` outside character
+/// classes, with its nesting depth
+fn structure(source: &str) -> Vec<(usize, char, usize)> {
+    let mut out = Vec::new();
+    let mut depth: usize = 0;
+    let mut classes = 0;
+    let mut escaped = false;
+    for (i, c) in source.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' => escaped = true,
+            '[' => classes += 1,
+            ']' if classes > 0 => classes -= 1,
+            '(' if classes == 0 => {
+                out.push((i, c, depth));
+                depth += 1;
+            }
+            ')' if classes == 0 => {
+                depth = depth.saturating_sub(1);
+                out.push((i, c, depth));
+            }
+            '|' if classes == 0 => out.push((i, c, depth)),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn has_top_level_alternation(source: &str) -> bool {
+    structure(source)
+        .iter()
+        .any(|&(_, c, depth)| c == '|' && depth == 0)
+}
+
+/// The lookaround opening at `start`, and the offset of its closing paren
+fn lookaround_at(source: &str, start: usize) -> Option<(&'static str, usize)> {
+    let kind = ["(?<=", "(?<!", "(?=", "(?!"]
+        .into_iter()
+        .find(|kind| source[start..].starts_with(kind))?;
+    let marks = structure(source);
+    let open = marks.iter().position(|&(i, _, _)| i == start)?;
+    let depth = marks[open].2;
+    let end = marks[open + 1..]
+        .iter()
+        .find(|&&(_, c, d)| c == ')' && d == depth)?
+        .0;
+    Some((kind, end))
+}
+
+/// Where the group closed by the `)` just before `end` opens
+fn group_start_ending_at(source: &str, end: usize) -> Option<usize> {
+    let marks = structure(source);
+    let &(close, c, depth) = marks.last()?;
+    if c != ')' || close + 1 != end {
+        return None;
+    }
+    marks
+        .iter()
+        .rev()
+        .find(|&&(i, c, d)| c == '(' && d == depth && i < close)
+        .map(|&(i, _, _)| i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find(pattern: &str, text: &str) -> Option<Range<usize>> {
+        Pattern::new(pattern).unwrap().find(text, 0, 0)?[0].clone()
+    }
+
+    #[test]
+    fn lookarounds_at_the_edges_are_emulated() {
+        assert_eq!(find(r"\bfoo(?=\()", "foo foo("), Some(4..7));
+        assert_eq!(find(r"\d+(?!px|\d)", "10px 20em"), Some(5..7));
+        assert_eq!(find(r"(?<=\.)\w+", "a.b"), Some(2..3));
+        assert_eq!(find(r"(?<!\$)\{", "${ {"), Some(3..4));
+        assert_eq!(find(r"(?=[;}])", "ab;"), Some(2..2));
+        assert_eq!(find(r"(?i:select)\s++(?>\*)", "x SELECT  *"), Some(2..11));
+        assert_eq!(find(r"0x\h+", "n = 0xfF;"), Some(4..8));
+        assert!(Pattern::new(r"(a)\1").is_err());
+    }
+
+    #[test]
+    fn g_anchors_at_the_previous_match() {
+        let pattern = Pattern::new(r"\G\s*,").unwrap();
+        assert_eq!(pattern.find("a , b", 1, 1).unwrap()[0], Some(1..3));
+        assert!(pattern.find("a , b", 0, 0).is_none());
+    }
+}
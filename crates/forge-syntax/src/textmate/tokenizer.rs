@@ -0,0 +1,540 @@
+//! Line-by-line tokenizing with a TextMate grammar.
+//!
+//! The tokenizer's state at the end of each line is cached. After an edit,
+//! tokenizing resumes at the first changed line and stops as soon as a line
+//! after the edit starts in the same state as before, so only the lines
+//! whose tokens can have changed are tokenized again.
+
+use super::grammar::{has_backreferences, CloseKind, Grammar, RuleId, RuleKind};
+use super::pattern::Pattern;
+use crate::highlighter::{paint, HighlightSpan, TokenType};
+use std::borrow::Cow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Begin rules nested deeper than this match like single-line rules
+const MAX_DEPTH: usize = 100;
+
+/// Zero-width matches in a row before the tokenizer skips a character
+const MAX_STALLS: usize = 50;
+
+/// An open begin rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Frame {
+    rule: RuleId,
+    /// The end or while pattern with the begin match's captures filled in
+    close: Option<Arc<str>>,
+    /// Token type of the text between the begin and end matches
+    inner: Option<TokenType>,
+    /// Token type of the begin and end matches
+    outer: Option<TokenType>,
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    hash: u64,
+    /// Length including the line ending
+    len: usize,
+    /// Spans relative to the start of the line
+    spans: Vec<HighlightSpan>,
+    /// Open rules at the end of the line
+    end: Vec<Frame>,
+}
+
+/// What matched during tokenizing
+enum Matched {
+    Close,
+    Rule(RuleId),
+}
+
+#[derive(Clone)]
+pub struct TextMateHighlighter {
+    grammar: Arc<Grammar>,
+    lines: Vec<Line>,
+    /// Close patterns compiled with a begin match's captures
+    closes: HashMap<Arc<str>, Option<Arc<Pattern>>>,
+}
+
+impl TextMateHighlighter {
+    pub fn new(grammar: Arc<Grammar>) -> Self {
+        Self {
+            grammar,
+            lines: Vec::new(),
+            closes: HashMap::new(),
+        }
+    }
+
+    pub fn grammar(&self) -> &Arc<Grammar> {
+        &self.grammar
+    }
+
+    /// Tokenize the lines of `text` that changed since the last update.
+    ///
+    /// Returns the lines tokenized again, or `None` if nothing changed.
+    pub fn update(&mut self, text: &str) -> Option<Range<usize>> {
+        let texts: Vec<&str> = text.split_inclusive('\n').collect();
+        let hashes: Vec<u64> = texts.iter().map(|line| hash(line)).collect();
+        let old = &self.lines;
+        let prefix = old
+            .iter()
+            .zip(&hashes)
+            .take_while(|(line, hash)| line.hash == **hash)
+            .count();
+        if prefix == old.len() && prefix == hashes.len() {
+            return None;
+        }
+        let suffix = old
+            .iter()
+            .rev()
+            .zip(hashes.iter().rev())
+            .take(old.len().min(hashes.len()) - prefix)
+            .take_while(|(line, hash)| line.hash == **hash)
+            .count();
+        let old_end = old.len() - suffix;
+        let new_end = hashes.len() - suffix;
+        Some(self.retokenize(prefix..old_end, new_end, |i| Cow::Borrowed(texts[i])))
+    }
+
+    /// Tokenize again after an edit replaced lines `old` with lines
+    /// `old.start..new_end`, where `line(i)` is line `i` of the edited text
+    /// with its line ending.
+    ///
+    /// Returns the lines tokenized again.
+    pub fn edit<'a>(
+        &mut self,
+        old: Range<usize>,
+        new_end: usize,
+        line: impl FnMut(usize) -> Cow<'a, str>,
+    ) -> Range<usize> {
+        // Lines past the end were never tokenized
+        let old_end = old.end.min(self.lines.len());
+        let start = old.start.min(old_end);
+        let new_end = new_end.saturating_sub(old.end - old_end).max(start);
+        self.retokenize(start..old_end, new_end, line)
+    }
+
+    fn retokenize<'a>(
+        &mut self,
+        old: Range<usize>,
+        new_end: usize,
+        mut line: impl FnMut(usize) -> Cow<'a, str>,
+    ) -> Range<usize> {
+        let prefix = old.start;
+        let mut tail = self.lines.split_off(old.end);
+        let mut tail_start = self
+            .lines
+            .last()
+            .map_or_else(|| self.initial(), |line| line.end.clone());
+        self.lines.truncate(prefix);
+
+        let mut state = self
+            .lines
+            .last()
+            .map_or_else(|| self.initial(), |line| line.end.clone());
+        let tail_from = new_end;
+        let len = new_end + tail.len();
+        let mut end = len;
+        for i in prefix..len {
+            if i >= tail_from {
+                // An unchanged line starting as before ends as before, and
+                // so does everything after it
+                let k = i - tail_from;
+                if k > 0 {
+                    tail_start = tail[k - 1].end.clone();
+                }
+                if state == tail_start {
+                    self.lines.extend(tail.drain(k..));
+                    end = i;
+                    break;
+                }
+            }
+            let text = line(i);
+            let (spans, next) = self.tokenize_line(&text, state);
+            self.lines.push(Line {
+                hash: hash(&text),
+                len: text.len(),
+                spans,
+                end: next.clone(),
+            });
+            state = next;
+        }
+        prefix..end
+    }
+
+    /// Spans for the whole text, as of the last update
+    pub fn spans(&self) -> Vec<HighlightSpan> {
+        let mut spans = Vec::new();
+        let mut offset = 0;
+        for line in &self.lines {
+            spans.extend(line.spans.iter().map(|span| HighlightSpan {
+                start_byte: offset + span.start_byte,
+                end_byte: offset + span.end_byte,
+                token_type: span.token_type,
+            }));
+            offset += line.len;
+        }
+        spans
+    }
+
+    fn initial(&self) -> Vec<Frame> {
+        vec![Frame {
+            rule: Grammar::ROOT,
+            close: None,
+            inner: None,
+            outer: None,
+        }]
+    }
+
+    fn close_pattern(&mut self, frame: &Frame) -> Option<Arc<Pattern>> {
+        if let Some(source) = &frame.close {
+            return self
+                .closes
+                .entry(source.clone())
+                .or_insert_with(|| Pattern::new(source).ok().map(Arc::new))
+                .clone();
+        }
+        match &self.grammar.rules[frame.rule].kind {
+            RuleKind::Begin { close, .. } => close.pattern.clone(),
+            _ => None,
+        }
+    }
+
+    fn tokenize_line(
+        &mut self,
+        line: &str,
+        mut stack: Vec<Frame>,
+    ) -> (Vec<HighlightSpan>, Vec<Frame>) {
+        let grammar = self.grammar.clone();
+        let content = line.trim_end_matches('\n').trim_end_matches('\r');
+        // Grammars expect each line to end with a newline
+        let text = format!("{}\n", content);
+        let mut spans = Vec::new();
+        let mut pos = 0;
+
+        // A while rule stays open as long as each line starts with a match
+        let mut depth = 1;
+        while depth < stack.len() {
+            let frame = stack[depth].clone();
+            if let RuleKind::Begin { close, .. } = &grammar.rules[frame.rule].kind {
+                if close.kind == CloseKind::While {
+                    let matched = self
+                        .close_pattern(&frame)
+                        .and_then(|pattern| pattern.find(&text, pos, pos))
+                        .filter(|captures| captures[0].as_ref().is_some_and(|m| m.start == pos));
+                    let Some(captures) = matched else {
+                        stack.truncate(depth);
+                        break;
+                    };
+                    emit_match(&mut spans, &captures, &close.captures, frame.outer);
+                    pos = captures[0].as_ref().map_or(pos, |m| m.end);
+                }
+            }
+            depth += 1;
+        }
+
+        let mut stalls = 0;
+        while pos < text.len() {
+            let top = stack.last().unwrap().clone();
+            let rule = &grammar.rules[top.rule];
+            let (close, end_pattern_last) = match &rule.kind {
+                RuleKind::Begin {
+                    close,
+                    end_pattern_last,
+                    ..
+                } if close.kind == CloseKind::End => (self.close_pattern(&top), *end_pattern_last),
+                _ => (None, false),
+            };
+
+            let mut best: Option<(Vec<Option<Range<usize>>>, Matched)> = None;
+            let mut consider =
+                |captures: Option<Vec<Option<Range<usize>>>>, matched: Matched, ties_win: bool| {
+                    let Some(start) = captures
+                        .as_ref()
+                        .and_then(|c| c[0].as_ref())
+                        .map(|m| m.start)
+                    else {
+                        return;
+                    };
+                    let best_start = best
+                        .as_ref()
+                        .and_then(|(c, _)| c[0].as_ref())
+                        .map(|m| m.start);
+                    if best_start.is_none_or(|best| start < best || (ties_win && start == best)) {
+                        best = Some((captures.unwrap(), matched));
+                    }
+                };
+            if !end_pattern_last {
+                if let Some(close) = &close {
+                    consider(close.find(&text, pos, pos), Matched::Close, false);
+                }
+            }
+            for &id in &rule.candidates {
+                let pattern = match &grammar.rules[id].kind {
+                    RuleKind::Match { pattern, .. } => pattern,
+                    RuleKind::Begin { begin, .. } => begin,
+                    RuleKind::Group => continue,
+                };
+                consider(pattern.find(&text, pos, pos), Matched::Rule(id), false);
+            }
+            if end_pattern_last {
+                if let Some(close) = &close {
+                    consider(close.find(&text, pos, pos), Matched::Close, true);
+                }
+            }
+
+            let Some((captures, matched)) = best else {
+                emit(&mut spans, pos..text.len(), top.inner);
+                break;
+            };
+            let whole = captures[0].clone().unwrap();
+            emit(&mut spans, pos..whole.start, top.inner);
+            match matched {
+                Matched::Close => {
+                    if let RuleKind::Begin { close, .. } = &rule.kind {
+                        emit_match(&mut spans, &captures, &close.captures, top.outer);
+                    }
+                    stack.pop();
+                }
+                Matched::Rule(id) => {
+                    let matched = &grammar.rules[id];
+                    let outer = matched.name.or(top.inner);
+                    match &matched.kind {
+                        RuleKind::Match {
+                            captures: types, ..
+                        } => emit_match(&mut spans, &captures, types, outer),
+                        RuleKind::Begin {
+                            captures: types,
+                            close,
+                            content,
+                            ..
+                        } => {
+                            emit_match(&mut spans, &captures, types, outer);
+                            if stack.len() < MAX_DEPTH {
+                                stack.push(Frame {
+                                    rule: id,
+                                    close: has_backreferences(&close.source).then(|| {
+                                        fill_backreferences(&close.source, &text, &captures).into()
+                                    }),
+                                    inner: content.or(outer),
+                                    outer,
+                                });
+                            }
+                        }
+                        RuleKind::Group => {}
+                    }
+                }
+            }
+
+            if whole.end > pos {
+                pos = whole.end;
+                stalls = 0;
+            } else {
+                stalls += 1;
+                if stalls > MAX_STALLS {
+                    let next = pos + text[pos..].chars().next().map_or(1, char::len_utf8);
+                    emit(&mut spans, pos..next, stack.last().unwrap().inner);
+                    pos = next;
+                    stalls = 0;
+                }
+            }
+        }
+
+        let mut clipped: Vec<HighlightSpan> = Vec::with_capacity(spans.len());
+        for mut span in spans {
+            span.end_byte = span.end_byte.min(content.len());
+            if span.token_type == TokenType::Plain || span.start_byte >= span.end_byte {
+                continue;
+            }
+            match clipped.last_mut() {
+                Some(last)
+                    if last.end_byte == span.start_byte && last.token_type == span.token_type =>
+                {
+                    last.end_byte = span.end_byte
+                }
+                _ => clipped.push(span),
+            }
+        }
+        (clipped, stack)
+    }
+}
+
+fn hash(line: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    line.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn emit(spans: &mut Vec<HighlightSpan>, range: Range<usize>, token_type: Option<TokenType>) {
+    if range.start < range.end {
+        spans.push(HighlightSpan {
+            start_byte: range.start,
+            end_byte: range.end,
+            token_type: token_type.unwrap_or(TokenType::Plain),
+        });
+    }
+}
+
+/// Emit a match, with its capture groups over it
+fn emit_match(
+    spans: &mut Vec<HighlightSpan>,
+    captures: &[Option<Range<usize>>],
+    types: &[Option<TokenType>],
+    token_type: Option<TokenType>,
+) {
+    let Some(whole) = captures[0].clone() else {
+        return;
+    };
+    emit(spans, whole, token_type);
+    for (range, token_type) in captures.iter().zip(types) {
+        if let (Some(range), Some(token_type)) = (range, token_type) {
+            paint(
+                spans,
+                HighlightSpan {
+                    start_byte: range.start,
+                    end_byte: range.end,
+                    token_type: *token_type,
+                },
+            );
+        }
+    }
+}
+
+/// Replace `\1`-style references in a close pattern with the escaped text
+/// the begin match captured
+fn fill_backreferences(source: &str, text: &str, captures: &[Option<Range<usize>>]) -> String {
+    let mut out = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(digit) if digit.is_ascii_digit() => {
+                let index = digit.to_digit(10).unwrap() as usize;
+                if let Some(Some(range)) = captures.get(index) {
+                    out.push_str(&regex::escape(&text[range.clone()]));
+                }
+            }
+            Some(escaped) => {
+                out.push('\\');
+                out.push(escaped);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRAMMAR: &str = r##"{
+        "scopeName": "source.demo",
+        "patterns": [
+            { "include": "#comment" },
+            { "match": "\\b(if|else)(?=\\s)", "name": "keyword.control" },
+            { "match": "\\b\\d+\\b", "name": "constant.numeric" },
+            {
+                "begin": "(<<)(\\w+)", "end": "^\\2$",
+                "beginCaptures": { "1": { "name": "keyword.operator" } },
+                "contentName": "string.unquoted.heredoc"
+            }
+        ],
+        "repository": {
+            "comment": { "begin": "/\\*", "end": "\\*/", "name": "comment.block" }
+        }
+    }"##;
+
+    fn tokens<'a>(text: &'a str, highlighter: &TextMateHighlighter) -> Vec<(&'a str, TokenType)> {
+        highlighter
+            .spans()
+            .iter()
+            .map(|s| (&text[s.start_byte..s.end_byte], s.token_type))
+            .collect()
+    }
+
+    #[test]
+    fn tokenizes_across_lines() {
+        let grammar = Arc::new(Grammar::from_json(GRAMMAR).unwrap());
+        let mut highlighter = TextMateHighlighter::new(grammar);
+        let text = "if x /* a\nb */ 42\n<<EOF\nif 1\nEOF\nelse ";
+        highlighter.update(text);
+        assert_eq!(
+            tokens(text, &highlighter),
+            vec![
+                ("if", TokenType::Keyword),
+                ("/* a", TokenType::Comment),
+                ("b */", TokenType::Comment),
+                ("42", TokenType::Number),
+                ("<<", TokenType::Operator),
+                ("if 1", TokenType::String),
+                ("else", TokenType::Keyword),
+            ]
+        );
+    }
+
+    #[test]
+    fn updates_only_lines_whose_tokens_can_change() {
+        let grammar = Arc::new(Grammar::from_json(GRAMMAR).unwrap());
+        let mut highlighter = TextMateHighlighter::new(grammar);
+        let text: String = (0..100).map(|i| format!("if {}\n", i)).collect();
+        assert_eq!(highlighter.update(&text), Some(0..100));
+        assert_eq!(highlighter.update(&text), None);
+
+        // An edit inside a line leaves the next one starting as before
+        let edited = text.replacen("if 50", "else 50", 1);
+        assert_eq!(highlighter.update(&edited), Some(50..51));
+        let full = {
+            let mut fresh = TextMateHighlighter::new(highlighter.grammar().clone());
+            fresh.update(&edited);
+            fresh.spans()
+        };
+        assert_eq!(highlighter.spans(), full);
+
+        // Opening a comment changes every line after it
+        let commented = edited.replacen("if 10", "/* 10", 1);
+        assert_eq!(highlighter.update(&commented), Some(10..100));
+        let comment_start = commented.find("/*").unwrap();
+        assert!(highlighter
+            .spans()
+            .iter()
+            .filter(|s| s.start_byte >= comment_start)
+            .all(|s| s.token_type == TokenType::Comment));
+    }
+
+    #[test]
+    fn edits_retokenize_only_the_lines_they_name() {
+        let grammar = Arc::new(Grammar::from_json(GRAMMAR).unwrap());
+        let mut highlighter = TextMateHighlighter::new(grammar.clone());
+        let text: String = (0..100).map(|i| format!("if {}\n", i)).collect();
+        highlighter.update(&text);
+
+        let fresh = |text: &str| {
+            let mut fresh = TextMateHighlighter::new(grammar.clone());
+            fresh.update(text);
+            fresh.spans()
+        };
+        let edit = |highlighter: &mut TextMateHighlighter, text: &str, old, new_end| {
+            let lines: Vec<&str> = text.split_inclusive('\n').collect();
+            highlighter.edit(old, new_end, |i| Cow::Borrowed(lines[i]))
+        };
+
+        let edited = text.replacen("if 50", "else 50", 1);
+        assert_eq!(edit(&mut highlighter, &edited, 50..51, 51), 50..51);
+        assert_eq!(highlighter.spans(), fresh(&edited));
+
+        // One line split in two, opening a comment
+        let split = edited.replacen("if 10\n", "/*\nif 10\n", 1);
+        assert_eq!(edit(&mut highlighter, &split, 10..11, 12), 10..101);
+        assert_eq!(highlighter.spans(), fresh(&split));
+
+        // And joined again, closing it
+        assert_eq!(edit(&mut highlighter, &edited, 10..12, 11), 10..100);
+        assert_eq!(highlighter.spans(), fresh(&edited));
+    }
+}